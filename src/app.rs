@@ -1,4 +1,20 @@
-use std::{collections::HashMap, ffi::OsString};
+use crate::batch::{self, Batch};
+use crate::bookmark::{self, Bookmark};
+use crate::columns::{self, Column};
+use crate::diff::{self, Comparison};
+use crate::ethereum::{self, Abi};
+use crate::profile;
+use crate::query::{self, Query};
+use crate::search::Search;
+use crate::subscription;
+use crate::timeline::{self, Window};
+use crate::tree::{Fold, Folds};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
 use tokio::sync::{mpsc, oneshot};
 
 #[derive(Debug, Clone)]
@@ -12,6 +28,106 @@ pub struct JsonRpcMessage {
     pub direction: MessageDirection,
     pub transport: TransportType,
     pub headers: Option<HashMap<String, String>>,
+    pub connection: Option<Connection>,
+    pub batch: Option<BatchPosition>,
+    pub timing: Option<Timing>,
+    /// The `seq` of a DAP response or event. A DAP request's `seq` is its `id`.
+    pub seq: Option<serde_json::Value>,
+}
+
+/// Where a message sat in a batch body. Requests from one wire batch share an `id`; a
+/// response's `position` is its place in the batch response, which may differ from its
+/// request's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchPosition {
+    pub id: String,
+    pub position: usize,
+    pub size: usize,
+}
+
+/// Where the proxy spent an HTTP exchange's time, recorded on its response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timing {
+    /// Held for a decision while interception was paused.
+    pub queued: Option<Duration>,
+    /// Name lookup for a new upstream connection. `None` when a pooled connection was reused
+    /// or the target is an IP address.
+    pub dns: Option<Duration>,
+    /// Opening the TCP connection, as HAR captures record it. The proxy's HTTP client does
+    /// not report it, so proxied exchanges count it in `first_byte`.
+    pub connect: Option<Duration>,
+    /// The TLS handshake, as HAR captures record it. Proxied exchanges count it in
+    /// `first_byte`.
+    pub tls: Option<Duration>,
+    /// From sending upstream until the response headers arrived, after any name lookup.
+    pub first_byte: Option<Duration>,
+    /// From the response headers until the whole body arrived.
+    pub download: Option<Duration>,
+    /// Until the client's connection took the response. Filled in after the response was
+    /// logged, from a [`Delivery`].
+    pub delivery: Option<Duration>,
+}
+
+impl Timing {
+    /// The recorded phases in the order they happen.
+    pub fn phases(&self) -> Vec<(&'static str, Duration)> {
+        [
+            ("queued", self.queued),
+            ("dns", self.dns),
+            ("connect", self.connect),
+            ("tls", self.tls),
+            ("first byte", self.first_byte),
+            ("download", self.download),
+            ("delivery", self.delivery),
+        ]
+        .into_iter()
+        .filter_map(|(name, duration)| Some((name, duration?)))
+        .collect()
+    }
+}
+
+/// The client connection or stream that carried a message. Concurrent clients often reuse
+/// the same ids, so responses only pair with requests from their own connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    /// Identifies one request/response channel, such as a single proxied HTTP request.
+    pub id: String,
+    /// Who is on the other end, such as the peer address, shown in the client column.
+    pub client: String,
+    /// The listener that carried it when the debugger runs several, shown in the source
+    /// column.
+    pub source: Option<String>,
+}
+
+impl Connection {
+    pub fn stdio() -> Self {
+        Self {
+            id: "stdio".to_string(),
+            client: "stdio".to_string(),
+            source: None,
+        }
+    }
+
+    /// The stdio stream for exchanges the server starts, such as a DAP `runInTerminal` or an
+    /// LSP `workspace/configuration`. Their ids come from the server's own sequence and may
+    /// repeat the client's.
+    pub fn stdio_server() -> Self {
+        Self {
+            id: "stdio:server".to_string(),
+            ..Self::stdio()
+        }
+    }
+}
+
+impl JsonRpcMessage {
+    /// Whether a response on `self` may answer a request on `other`. Messages without a
+    /// connection, such as imported history, pair with any connection.
+    pub fn same_connection(&self, other: &JsonRpcMessage) -> bool {
+        match (&self.connection, &other.connection) {
+            (Some(connection), Some(other)) => connection.id == other.id,
+            _ => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +147,26 @@ impl JsonRpcExchange {
             .as_ref()
             .is_some_and(|request| request.id.is_none() && request.method.is_some())
     }
+
+    pub fn connection(&self) -> Option<&Connection> {
+        [&self.request, &self.response]
+            .into_iter()
+            .flatten()
+            .find_map(|message| message.connection.as_ref())
+    }
+
+    pub fn client(&self) -> Option<&str> {
+        self.connection()
+            .map(|connection| connection.client.as_str())
+    }
+
+    pub fn source(&self) -> Option<&str> {
+        self.connection()?.source.as_deref()
+    }
+
+    pub fn timing(&self) -> Option<&Timing> {
+        self.response.as_ref()?.timing.as_ref()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -43,6 +179,8 @@ pub enum MessageDirection {
 pub enum Framing {
     JsonLines,
     ContentLength,
+    /// `Content-Length` frames carrying Debug Adapter Protocol messages.
+    Dap,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -61,6 +199,7 @@ impl TransportType {
             Self::HttpBatch => "HTTP-BATCH",
             Self::Stdio(Framing::JsonLines) => "STDIO/JSONL",
             Self::Stdio(Framing::ContentLength) => "STDIO/LSP",
+            Self::Stdio(Framing::Dap) => "STDIO/DAP",
             Self::WebSocket => "WebSocket",
         }
     }
@@ -71,9 +210,14 @@ impl TransportType {
             Self::HttpBatch => "http-batch",
             Self::Stdio(Framing::JsonLines) => "stdio-json-lines",
             Self::Stdio(Framing::ContentLength) => "stdio-content-length",
+            Self::Stdio(Framing::Dap) => "stdio-dap",
             Self::WebSocket => "websocket",
         }
     }
+
+    pub fn is_dap(self) -> bool {
+        self == Self::Stdio(Framing::Dap)
+    }
 }
 
 pub fn json_rpc_messages(
@@ -82,12 +226,20 @@ pub fn json_rpc_messages(
     transport: TransportType,
     headers: Option<&HashMap<String, String>>,
 ) -> Vec<JsonRpcMessage> {
-    body.as_array()
+    let messages = body
+        .as_array()
         .map(Vec::as_slice)
         .unwrap_or_else(|| std::slice::from_ref(body))
         .iter()
-        .map(|body| json_rpc_message(body, direction, transport, headers))
-        .collect()
+        .map(|body| {
+            if transport.is_dap() {
+                dap_message(body, transport, headers)
+            } else {
+                json_rpc_message(body, direction, transport, headers)
+            }
+        })
+        .collect();
+    with_batch_positions(body, messages)
 }
 
 pub fn incoming_json_rpc_messages(
@@ -102,11 +254,15 @@ pub fn json_rpc_messages_by_shape(
     transport: TransportType,
     headers: Option<&HashMap<String, String>>,
 ) -> Vec<JsonRpcMessage> {
-    body.as_array()
+    let messages = body
+        .as_array()
         .map(Vec::as_slice)
         .unwrap_or_else(|| std::slice::from_ref(body))
         .iter()
         .map(|body| {
+            if transport.is_dap() {
+                return dap_message(body, transport, headers);
+            }
             let direction = if body.get("method").is_some() {
                 MessageDirection::Request
             } else {
@@ -114,7 +270,27 @@ pub fn json_rpc_messages_by_shape(
             };
             json_rpc_message(body, direction, transport, headers)
         })
-        .collect()
+        .collect();
+    with_batch_positions(body, messages)
+}
+
+fn with_batch_positions(
+    body: &serde_json::Value,
+    mut messages: Vec<JsonRpcMessage>,
+) -> Vec<JsonRpcMessage> {
+    if !body.is_array() {
+        return messages;
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let size = messages.len();
+    for (position, message) in messages.iter_mut().enumerate() {
+        message.batch = Some(BatchPosition {
+            id: id.clone(),
+            position,
+            size,
+        });
+    }
+    messages
 }
 
 fn json_rpc_message(
@@ -149,7 +325,169 @@ fn json_rpc_message(
         direction,
         transport,
         headers: headers.cloned(),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
+    }
+}
+
+// DAP requests pair with responses through `request_seq`, so the request `seq` and the
+// response `request_seq` both become the exchange id. Events carry no id and are shown
+// as notifications named after the event.
+fn dap_message(
+    body: &serde_json::Value,
+    transport: TransportType,
+    headers: Option<&HashMap<String, String>>,
+) -> JsonRpcMessage {
+    let string = |key: &str| {
+        body.get(key)
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+    };
+    let kind = body.get("type").and_then(serde_json::Value::as_str);
+    let (id, method, params, result, error, direction) = match kind {
+        Some("response") => {
+            let payload = body.get("body").cloned();
+            let (result, error) = if body.get("success") == Some(&serde_json::json!(false)) {
+                let mut error = serde_json::Map::new();
+                if let Some(message) = body.get("message") {
+                    error.insert("message".to_string(), message.clone());
+                }
+                if let Some(payload) = payload {
+                    error.insert("body".to_string(), payload);
+                }
+                (None, Some(serde_json::Value::Object(error)))
+            } else {
+                (Some(payload.unwrap_or(serde_json::Value::Null)), None)
+            };
+            (
+                body.get("request_seq").cloned(),
+                string("command"),
+                None,
+                result,
+                error,
+                MessageDirection::Response,
+            )
+        }
+        Some("event") => (
+            None,
+            string("event"),
+            body.get("body").cloned(),
+            None,
+            None,
+            MessageDirection::Request,
+        ),
+        _ => (
+            body.get("seq").cloned(),
+            string("command"),
+            body.get("arguments").cloned(),
+            None,
+            None,
+            MessageDirection::Request,
+        ),
+    };
+    JsonRpcMessage {
+        id,
+        method,
+        params,
+        result,
+        error,
+        timestamp: std::time::SystemTime::now(),
+        direction,
+        transport,
+        headers: headers.cloned(),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: matches!(kind, Some("response" | "event"))
+            .then(|| body.get("seq").cloned())
+            .flatten(),
+    }
+}
+
+/// Rebuilds the wire body of a captured message in its transport's protocol.
+pub fn message_body(message: &JsonRpcMessage) -> serde_json::Value {
+    let mut body = serde_json::Map::new();
+    if message.transport.is_dap() {
+        match (message.direction, &message.id) {
+            (MessageDirection::Response, request_seq) => {
+                if let Some(seq) = &message.seq {
+                    body.insert("seq".to_string(), seq.clone());
+                }
+                body.insert("type".to_string(), serde_json::json!("response"));
+                if let Some(request_seq) = request_seq {
+                    body.insert("request_seq".to_string(), request_seq.clone());
+                }
+                body.insert(
+                    "success".to_string(),
+                    serde_json::json!(message.error.is_none()),
+                );
+                if let Some(command) = &message.method {
+                    body.insert("command".to_string(), serde_json::json!(command));
+                }
+                if let Some(error) = &message.error {
+                    if let Some(text) = error.get("message") {
+                        body.insert("message".to_string(), text.clone());
+                    }
+                    if let Some(payload) = error.get("body") {
+                        body.insert("body".to_string(), payload.clone());
+                    }
+                } else if let Some(result) =
+                    message.result.as_ref().filter(|value| !value.is_null())
+                {
+                    body.insert("body".to_string(), result.clone());
+                }
+            }
+            (MessageDirection::Request, None) => {
+                if let Some(seq) = &message.seq {
+                    body.insert("seq".to_string(), seq.clone());
+                }
+                body.insert("type".to_string(), serde_json::json!("event"));
+                if let Some(event) = &message.method {
+                    body.insert("event".to_string(), serde_json::json!(event));
+                }
+                if let Some(params) = &message.params {
+                    body.insert("body".to_string(), params.clone());
+                }
+            }
+            (MessageDirection::Request, Some(seq)) => {
+                body.insert("seq".to_string(), seq.clone());
+                body.insert("type".to_string(), serde_json::json!("request"));
+                if let Some(command) = &message.method {
+                    body.insert("command".to_string(), serde_json::json!(command));
+                }
+                if let Some(params) = &message.params {
+                    body.insert("arguments".to_string(), params.clone());
+                }
+            }
+        }
+        return serde_json::Value::Object(body);
+    }
+
+    body.insert("jsonrpc".to_string(), serde_json::json!("2.0"));
+    if let Some(id) = &message.id {
+        body.insert("id".to_string(), id.clone());
     }
+    match message.direction {
+        MessageDirection::Request => {
+            if let Some(method) = &message.method {
+                body.insert("method".to_string(), serde_json::json!(method));
+            }
+            if let Some(params) = &message.params {
+                body.insert("params".to_string(), params.clone());
+            }
+        }
+        MessageDirection::Response => {
+            if let Some(result) = &message.result {
+                body.insert("result".to_string(), result.clone());
+            }
+            if let Some(error) = &message.error {
+                body.insert("error".to_string(), error.clone());
+            }
+        }
+    }
+    serde_json::Value::Object(body)
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -160,6 +498,9 @@ pub enum InputMode {
     AnnotatingSelection,
     NamingSession,
     RenamingSession,
+    Querying,
+    Searching,
+    Tagging,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -174,14 +515,51 @@ pub enum Focus {
 pub enum DetailTab {
     Headers,
     Body,
+    Decoded,
+}
+
+impl DetailTab {
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            0 => Self::Headers,
+            1 => Self::Body,
+            _ => Self::Decoded,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Self::Headers => 0,
+            Self::Body => 1,
+            Self::Decoded => 2,
+        }
+    }
 }
 
+const DETAIL_TAB_COUNT: usize = 3;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Overlay {
     None,
     Prefix,
     Help,
     Sessions,
+    Mcp,
+    Subscription,
+    Batch,
+    Diff,
+    Columns,
+    Snippets,
+    Profiles,
+}
+
+/// An exchange picked for comparison. It is copied so a mark survives switching sessions.
+#[derive(Debug, Clone)]
+pub struct DiffMark {
+    pub session_id: Option<String>,
+    pub index: usize,
+    pub label: String,
+    pub exchange: JsonRpcExchange,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
@@ -815,6 +1193,14 @@ pub enum ProxyDecision {
     Complete(serde_json::Value), // Complete with custom response
 }
 
+/// The proxy's report that the client took the responses logged for `connection`, sent once
+/// delivery finishes, after the responses themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    pub connection: String,
+    pub delivery: Duration,
+}
+
 #[allow(dead_code)]
 pub struct PendingRequest {
     pub id: String,
@@ -849,20 +1235,69 @@ pub struct App {
     pub selected_pending: usize,               // New field
     pub request_editor_buffer: String,         // New field
     pub focus: Focus,                          // New field for tracking which element is active
-    pub request_tab: usize,                    // 0 = Headers, 1 = Body
-    pub response_tab: usize,                   // 0 = Headers, 1 = Body
+    pub request_tab: usize,                    // 0 = Headers, 1 = Body, 2 = Decoded
+    pub response_tab: usize,                   // 0 = Headers, 1 = Body, 2 = Decoded
     pub line_selection: Option<LineSelection>,
     pub visual_selection_active: bool,
     pub annotations: Vec<LineAnnotation>,
     pub active_annotation_id: Option<String>,
+    /// Stars and tags of the current session's exchanges, by exchange index.
+    pub bookmarks: HashMap<usize, Bookmark>,
     pub editor: Option<TextEditor>,
     pub notice: Option<String>,
     pub control_port: u16,
     pub overlay: Overlay,
+    pub overlay_scroll: usize,
     pub panel_fullscreen: bool,
+    pub expanded_subscriptions: HashSet<usize>,
+    /// First exchanges of the batches whose calls are listed individually.
+    pub expanded_batches: HashSet<usize>,
+    /// Draws the request list as a waterfall of request-to-response bars.
+    pub timeline_visible: bool,
+    pub timeline_zoom: u32,
+    /// Set by panning; `None` keeps the selected exchange centred.
+    pub timeline_offset: Option<Duration>,
+    /// Draws JSON bodies as a tree whose objects and arrays fold.
+    pub json_tree: bool,
+    /// Folds the user opened or closed, by JSON path, for the selected exchange.
+    request_folds: HashMap<String, bool>,
+    response_folds: HashMap<String, bool>,
+    /// Queries whose matches replace the tab content of each detail panel.
+    pub request_query: Option<Query>,
+    pub response_query: Option<Query>,
+    /// Queries pinned as extra columns of the request list.
+    pub query_columns: Vec<Query>,
+    /// Shown columns of the request list, their widths, and its sort order.
+    pub columns: columns::Layout,
+    /// The highlighted row of the column picker, indexing [`Column::ALL`].
+    pub selected_column: usize,
+    /// Whether copied curl commands and replay scripts send through this debugger's proxy
+    /// instead of straight to the target.
+    pub snippet_via_proxy: bool,
+    /// The `/` search of one detail panel, or of the intercept panel outside normal mode.
+    pub search: Option<Search>,
+    /// Cursor line and scroll to return to when the search prompt is cancelled.
+    search_origin: (usize, usize),
+    /// Up to two exchanges marked for the diff view, oldest first.
+    pub diff_marks: Vec<DiffMark>,
+    pub comparison: Option<Comparison>,
+    /// How long a request may wait for its response before it times out, if ever.
+    pub request_timeout: Option<Duration>,
+    timed_out_count: usize,
+    /// Requests still waiting for a response, by exchange index, and how many exchanges have
+    /// been checked for them, so timeout checks skip settled exchanges.
+    awaiting_response: HashSet<usize>,
+    awaiting_checked: usize,
+    pub abi: Abi,
     pub session: Option<SessionSummary>,
     pub sessions: Vec<SessionSummary>,
     pub selected_session: usize,
+    /// Profiles from the configuration files, and the one applied last.
+    pub config: profile::Config,
+    pub profile: Option<String>,
+    pub selected_profile: usize,
+    /// The active profile's rules, shared with the proxy.
+    pub rules: Arc<Mutex<profile::Rules>>,
     revision: u64,
 }
 
@@ -880,22 +1315,213 @@ pub struct ProxyConfig {
     pub transport: TransportType,
     pub stdio: Option<StdioConfig>,
     pub transparent: bool,
+    /// Named listeners run beside this one, each with its own target, recording into the
+    /// same session.
+    pub listeners: Vec<Listener>,
+}
+
+/// The source name of the listener from `--port` and `--target` when others run beside it.
+pub const MAIN_LISTENER: &str = "main";
+
+impl ProxyConfig {
+    /// What the main listener records in the source column, or `None` when it runs alone.
+    pub fn source(&self) -> Option<&str> {
+        (!self.listeners.is_empty()).then_some(MAIN_LISTENER)
+    }
+
+    pub fn listener(&self, name: &str) -> Option<&Listener> {
+        self.listeners.iter().find(|listener| listener.name == name)
+    }
+
+    /// The proxy port of the named listener, or of the main one for `None` or `main`.
+    pub fn port(&self, listener: Option<&str>) -> Option<u16> {
+        match listener {
+            None | Some(MAIN_LISTENER) => Some(self.listen_port),
+            Some(name) => self.listener(name).map(|listener| listener.listen_port),
+        }
+    }
+}
+
+/// Another proxy port with its own HTTP target or stdio server.
+#[derive(Debug, Clone)]
+pub struct Listener {
+    pub name: String,
+    pub listen_port: u16,
+    pub target_url: String,
+    pub stdio: Option<StdioConfig>,
+}
+
+impl Listener {
+    pub fn transport(&self) -> TransportType {
+        self.stdio.as_ref().map_or(TransportType::Http, |stdio| {
+            TransportType::Stdio(stdio.framing)
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct StdioConfig {
     pub command: Vec<OsString>,
     pub framing: Framing,
+    /// Added to the debugger's own environment.
+    pub env: Vec<(OsString, OsString)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeStatus {
+    Notification,
+    Pending,
+    TimedOut,
+    Success,
+    Error,
+    Orphan,
 }
 
-fn exchange_status(exchange: &JsonRpcExchange) -> &'static str {
-    if exchange.is_notification() {
-        return "Notification";
+impl ExchangeStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Notification => "Notification",
+            Self::Pending => "Pending",
+            Self::TimedOut => "Timed out",
+            Self::Success => "Success",
+            Self::Error => "Error",
+            Self::Orphan => "Orphan",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Notification => "notification",
+            Self::Pending => "pending",
+            Self::TimedOut => "timedOut",
+            Self::Success => "success",
+            Self::Error => "error",
+            Self::Orphan => "orphan",
+        }
+    }
+}
+
+/// Why a response arrived after the client had stopped waiting for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lateness {
+    AfterTimeout,
+    AfterCancellation,
+}
+
+impl Lateness {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::AfterTimeout => "timeout",
+            Self::AfterCancellation => "cancellation",
+        }
+    }
+}
+
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout, clock, and cancellations shared by the status of every exchange in a list.
+pub struct StatusContext {
+    timeout: Option<Duration>,
+    now: SystemTime,
+    cancellations: HashMap<String, SystemTime>,
+}
+
+impl StatusContext {
+    pub fn new(exchanges: &[JsonRpcExchange], timeout: Option<Duration>, now: SystemTime) -> Self {
+        let mut cancellations = HashMap::new();
+        for exchange in exchanges {
+            let Some(request) = exchange.request.as_ref() else {
+                continue;
+            };
+            let Some(id) = cancelled_request_id(request) else {
+                continue;
+            };
+            cancellations
+                .entry(display_id(Some(id)))
+                .and_modify(|cancelled: &mut SystemTime| {
+                    *cancelled = (*cancelled).min(request.timestamp);
+                })
+                .or_insert(request.timestamp);
+        }
+        Self {
+            timeout,
+            now,
+            cancellations,
+        }
+    }
+
+    pub fn status(&self, exchange: &JsonRpcExchange) -> ExchangeStatus {
+        if exchange.is_notification() {
+            return ExchangeStatus::Notification;
+        }
+        match (&exchange.request, &exchange.response) {
+            (None, Some(_)) => ExchangeStatus::Orphan,
+            (_, Some(response)) if response.error.is_some() => ExchangeStatus::Error,
+            (_, Some(_)) => ExchangeStatus::Success,
+            (Some(request), None)
+                if self
+                    .now
+                    .duration_since(request.timestamp)
+                    .is_ok_and(|waited| self.timed_out(waited)) =>
+            {
+                ExchangeStatus::TimedOut
+            }
+            (_, None) => ExchangeStatus::Pending,
+        }
+    }
+
+    pub fn lateness(&self, exchange: &JsonRpcExchange) -> Option<Lateness> {
+        let (Some(request), Some(response)) = (&exchange.request, &exchange.response) else {
+            return None;
+        };
+        let cancelled = self
+            .cancellations
+            .get(&display_id(exchange.id.as_ref()))
+            .is_some_and(|cancelled| {
+                *cancelled >= request.timestamp && *cancelled <= response.timestamp
+            });
+        if cancelled {
+            return Some(Lateness::AfterCancellation);
+        }
+        response
+            .timestamp
+            .duration_since(request.timestamp)
+            .is_ok_and(|waited| self.timed_out(waited))
+            .then_some(Lateness::AfterTimeout)
+    }
+
+    fn timed_out(&self, waited: Duration) -> bool {
+        self.timeout.is_some_and(|timeout| waited > timeout)
+    }
+
+    pub fn matches_filter(
+        &self,
+        exchange: &JsonRpcExchange,
+        bookmark: Option<&Bookmark>,
+        filter: &str,
+    ) -> bool {
+        let status = self.status(exchange).name();
+        let late = self.lateness(exchange).map(|_| "late");
+        let statuses = [Some(status), late];
+        filter_matches(
+            exchange.method.as_deref(),
+            exchange.id.as_ref(),
+            exchange.client(),
+            exchange.source(),
+            statuses.iter().flatten().copied(),
+            bookmark,
+            filter,
+        )
     }
-    match &exchange.response {
-        None => "Pending",
-        Some(response) if response.error.is_some() => "Error",
-        Some(_) => "Success",
+}
+
+// LSP `$/cancelRequest`, MCP `notifications/cancelled`, and DAP `cancel`.
+fn cancelled_request_id(request: &JsonRpcMessage) -> Option<&serde_json::Value> {
+    let params = request.params.as_ref()?;
+    match request.method.as_deref()? {
+        "$/cancelRequest" => params.get("id"),
+        "notifications/cancelled" | "cancel" => params.get("requestId"),
+        _ => None,
     }
 }
 
@@ -903,7 +1529,7 @@ fn transport_name(transport: &TransportType) -> &'static str {
     transport.label()
 }
 
-fn display_id(id: Option<&serde_json::Value>) -> String {
+pub fn display_id(id: Option<&serde_json::Value>) -> String {
     match id {
         Some(serde_json::Value::String(value)) => value.clone(),
         Some(value) => value.to_string(),
@@ -911,15 +1537,53 @@ fn display_id(id: Option<&serde_json::Value>) -> String {
     }
 }
 
-pub fn request_matches_filter(
+pub fn request_matches_filter(request: &JsonRpcMessage, filter: &str) -> bool {
+    let connection = request.connection.as_ref();
+    filter_matches(
+        request.method.as_deref(),
+        request.id.as_ref(),
+        connection.map(|connection| connection.client.as_str()),
+        connection.and_then(|connection| connection.source.as_deref()),
+        ["pending"],
+        None,
+        filter,
+    )
+}
+
+// Whitespace-separated terms must all match. `status:<name>` matches a status name or
+// `late`, `client:<text>` matches part of the client, `source:<name>` matches the listener,
+// `tag:<name>` and `is:starred` match the bookmark, and any other term matches part of the
+// method or id.
+fn filter_matches<'a>(
     method: Option<&str>,
     id: Option<&serde_json::Value>,
+    client: Option<&str>,
+    source: Option<&str>,
+    statuses: impl IntoIterator<Item = &'a str> + Clone,
+    bookmark: Option<&Bookmark>,
     filter: &str,
 ) -> bool {
-    filter.is_empty() || method.unwrap_or("").contains(filter) || display_id(id).contains(filter)
+    filter.split_whitespace().all(|term| {
+        if let Some(matched) = bookmark::matches_term(bookmark, term) {
+            return matched;
+        }
+        if let Some(wanted) = term.strip_prefix("status:") {
+            return statuses
+                .clone()
+                .into_iter()
+                .any(|status| status.eq_ignore_ascii_case(wanted));
+        }
+        if let Some(wanted) = term.strip_prefix("client:") {
+            return client.is_some_and(|client| client.contains(wanted));
+        }
+        if let Some(wanted) = term.strip_prefix("source:") {
+            return source.is_some_and(|source| source.eq_ignore_ascii_case(wanted));
+        }
+        method.unwrap_or("").contains(term) || display_id(id).contains(term)
+    })
 }
 
-fn exchange_duration(exchange: &JsonRpcExchange) -> String {
+pub fn exchange_duration(exchange: &JsonRpcExchange) -> String {
     let (Some(request), Some(response)) = (&exchange.request, &exchange.response) else {
         return "-".to_string();
     };
@@ -975,36 +1639,15 @@ fn json_markdown(value: &serde_json::Value) -> String {
     format!("\n## Body\n\n```json\n{json}\n```")
 }
 
-fn request_json(request: &JsonRpcMessage) -> serde_json::Value {
-    let mut json = serde_json::Map::new();
-    json.insert("jsonrpc".to_string(), serde_json::json!("2.0"));
-    if let Some(id) = &request.id {
-        json.insert("id".to_string(), id.clone());
-    }
-    if let Some(method) = &request.method {
-        json.insert("method".to_string(), serde_json::json!(method));
-    }
-    if let Some(params) = &request.params {
-        json.insert("params".to_string(), params.clone());
-    }
-
-    serde_json::Value::Object(json)
-}
-
-fn response_json(response: &JsonRpcMessage) -> serde_json::Value {
-    let mut json = serde_json::Map::new();
-    json.insert("jsonrpc".to_string(), serde_json::json!("2.0"));
-    if let Some(id) = &response.id {
-        json.insert("id".to_string(), id.clone());
-    }
-    if let Some(result) = &response.result {
-        json.insert("result".to_string(), result.clone());
-    }
-    if let Some(error) = &response.error {
-        json.insert("error".to_string(), error.clone());
+fn query_markdown(query: &Query, exchange: &JsonRpcExchange) -> String {
+    let heading = format!("\n## Query `{}`\n\n", query.expression());
+    match query::combined(&query.evaluate_exchange(exchange)) {
+        Some(value) => {
+            let json = serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
+            format!("{heading}```json\n{json}\n```")
+        }
+        None => format!("{heading}_No match._"),
     }
-
-    serde_json::Value::Object(json)
 }
 
 impl Default for App {
@@ -1036,6 +1679,7 @@ impl App {
                 transport: TransportType::Http,
                 stdio: None,
                 transparent: false,
+                listeners: Vec::new(),
             },
             is_running: true,
             message_receiver: None,
@@ -1051,15 +1695,44 @@ impl App {
             line_selection: None,
             visual_selection_active: false,
             annotations: Vec::new(),
+            bookmarks: HashMap::new(),
             active_annotation_id: None,
             editor: None,
             notice: None,
             control_port: 8081,
             overlay: Overlay::None,
+            overlay_scroll: 0,
             panel_fullscreen: false,
+            expanded_subscriptions: HashSet::new(),
+            expanded_batches: HashSet::new(),
+            timeline_visible: false,
+            timeline_zoom: 1,
+            timeline_offset: None,
+            json_tree: true,
+            request_folds: HashMap::new(),
+            response_folds: HashMap::new(),
+            request_query: None,
+            response_query: None,
+            query_columns: Vec::new(),
+            columns: columns::Layout::default(),
+            selected_column: 0,
+            snippet_via_proxy: false,
+            search: None,
+            search_origin: (1, 0),
+            diff_marks: Vec::new(),
+            comparison: None,
+            request_timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            timed_out_count: 0,
+            awaiting_response: HashSet::new(),
+            awaiting_checked: 0,
+            abi: Abi::default(),
             session: None,
             sessions: Vec::new(),
             selected_session: 0,
+            config: profile::Config::default(),
+            profile: None,
+            selected_profile: 0,
+            rules: Arc::default(),
             revision: 0,
         }
     }
@@ -1098,17 +1771,24 @@ impl App {
         session: SessionSummary,
         exchanges: Vec<JsonRpcExchange>,
         annotations: Vec<LineAnnotation>,
+        bookmarks: HashMap<usize, Bookmark>,
     ) {
         self.exchanges = exchanges;
+        self.awaiting_response.clear();
+        self.awaiting_checked = 0;
         self.selected_exchange = self.exchanges.len().saturating_sub(1);
         self.history_scroll = None;
         self.filter_text.clear();
+        self.expanded_subscriptions.clear();
+        self.expanded_batches.clear();
+        self.timeline_offset = None;
         self.session = Some(session);
         self.overlay = Overlay::None;
         self.line_selection = None;
         self.visual_selection_active = false;
         self.annotations = annotations;
         self.active_annotation_id = None;
+        self.bookmarks = bookmarks;
         self.reset_details_scroll();
         self.request_details_scroll = 0;
         self.response_details_scroll = 0;
@@ -1116,6 +1796,15 @@ impl App {
         self.mark_changed();
     }
 
+    /// Replaces the exchanges from `from` on, keeping timeout tracking for the ones before.
+    pub fn splice_exchanges(&mut self, from: usize, exchanges: Vec<JsonRpcExchange>) {
+        self.exchanges.truncate(from);
+        self.exchanges.extend(exchanges);
+        self.awaiting_response.retain(|index| *index < from);
+        self.awaiting_checked = self.awaiting_checked.min(from);
+        self.mark_changed();
+    }
+
     pub fn show_prefix(&mut self) {
         self.overlay = Overlay::Prefix;
         self.mark_changed();
@@ -1126,6 +1815,134 @@ impl App {
         self.mark_changed();
     }
 
+    pub fn show_mcp_summary(&mut self) {
+        self.overlay = Overlay::Mcp;
+        self.overlay_scroll = 0;
+        self.mark_changed();
+    }
+
+    pub fn show_batch(&mut self) {
+        let batches = batch::batches(&self.exchanges);
+        if batch::containing(&batches, self.selected_exchange).is_none() {
+            self.overlay = Overlay::None;
+            self.notice = Some("No batch selected".to_string());
+        } else {
+            self.overlay = Overlay::Batch;
+            self.overlay_scroll = 0;
+        }
+        self.mark_changed();
+    }
+
+    /// The selected batch's request and response bodies as Markdown.
+    pub fn batch_markdown(&self) -> Option<String> {
+        let batches = batch::batches(&self.exchanges);
+        let batch = batch::containing(&batches, self.selected_exchange)?;
+        let pretty = |value: serde_json::Value| {
+            serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string())
+        };
+        Some(format!(
+            "# Batch of {}\n\n## Request\n\n```json\n{}\n```\n\n## Response\n\n```json\n{}\n```",
+            batch.members.len(),
+            pretty(batch.request_body(&self.exchanges)),
+            pretty(batch.response_body(&self.exchanges)),
+        ))
+    }
+
+    /// Pretty-printed body of the selected batch, for editing and sending again.
+    pub fn batch_request_json(&self) -> Option<String> {
+        let batches = batch::batches(&self.exchanges);
+        let batch = batch::containing(&batches, self.selected_exchange)?;
+        serde_json::to_string_pretty(&batch.request_body(&self.exchanges)).ok()
+    }
+
+    /// Marks the selected exchange for comparison, or unmarks it. Marking a third exchange
+    /// drops the oldest mark.
+    pub fn toggle_diff_mark(&mut self) {
+        let Some(exchange) = self.exchanges.get(self.selected_exchange) else {
+            return;
+        };
+        let session_id = self.session.as_ref().map(|session| session.id.clone());
+        let index = self.selected_exchange;
+        if let Some(position) = self
+            .diff_marks
+            .iter()
+            .position(|mark| mark.session_id == session_id && mark.index == index)
+        {
+            self.diff_marks.remove(position);
+            self.notice = Some(format!("Unmarked #{index}"));
+        } else {
+            let label = match &self.session {
+                Some(session) => format!("{} #{index}", session.name),
+                None => format!("#{index}"),
+            };
+            if self.diff_marks.len() == 2 {
+                self.diff_marks.remove(0);
+            }
+            self.diff_marks.push(DiffMark {
+                session_id,
+                index,
+                label,
+                exchange: exchange.clone(),
+            });
+            self.notice = Some(if self.diff_marks.len() == 2 {
+                "Marked two exchanges · Ctrl+b = to compare".to_string()
+            } else {
+                format!("Marked #{index} for diff")
+            });
+        }
+        self.mark_changed();
+    }
+
+    pub fn is_diff_marked(&self, index: usize) -> bool {
+        let session_id = self.session.as_ref().map(|session| &session.id);
+        self.diff_marks
+            .iter()
+            .any(|mark| mark.index == index && mark.session_id.as_ref() == session_id)
+    }
+
+    /// Opens the diff view on the two marked exchanges.
+    pub fn show_marked_diff(&mut self) {
+        let [left, right] = self.diff_marks.as_slice() else {
+            self.overlay = Overlay::None;
+            self.notice = Some("Mark two exchanges with m to compare them".to_string());
+            self.mark_changed();
+            return;
+        };
+        let comparison = Comparison::Exchanges {
+            left: left.label.clone(),
+            right: right.label.clone(),
+            differences: diff::exchanges(&left.exchange, &right.exchange),
+        };
+        self.show_comparison(comparison);
+    }
+
+    pub fn show_comparison(&mut self, comparison: Comparison) {
+        self.comparison = Some(comparison);
+        self.overlay = Overlay::Diff;
+        self.overlay_scroll = 0;
+        self.mark_changed();
+    }
+
+    pub fn show_subscription_events(&mut self) {
+        let subscriptions = subscription::subscriptions(&self.exchanges);
+        if subscription::containing(&subscriptions, self.selected_exchange).is_none() {
+            self.overlay = Overlay::None;
+            self.notice = Some("No subscription selected".to_string());
+        } else {
+            self.overlay = Overlay::Subscription;
+            self.overlay_scroll = 0;
+        }
+        self.mark_changed();
+    }
+
+    pub fn scroll_overlay(&mut self, lines: i64) {
+        let scroll = self.overlay_scroll.saturating_add_signed(lines as isize);
+        if scroll != self.overlay_scroll {
+            self.overlay_scroll = scroll;
+            self.mark_changed();
+        }
+    }
+
     pub fn show_sessions(&mut self, sessions: Vec<SessionSummary>) {
         self.selected_session = self
             .session
@@ -1137,6 +1954,92 @@ impl App {
         self.mark_changed();
     }
 
+    pub fn show_profiles(&mut self) {
+        self.selected_profile = self
+            .profile
+            .as_ref()
+            .and_then(|active| self.config.profiles.keys().position(|name| name == active))
+            .unwrap_or(0);
+        self.overlay = Overlay::Profiles;
+        self.mark_changed();
+    }
+
+    pub fn select_profile_row(&mut self, step: i64) {
+        let last = self.config.profiles.len().saturating_sub(1);
+        let selected = if step >= 0 {
+            self.selected_profile
+                .saturating_add(step as usize)
+                .min(last)
+        } else {
+            self.selected_profile
+                .saturating_sub(usize::try_from(step.unsigned_abs()).unwrap_or(usize::MAX))
+        };
+        if selected != self.selected_profile {
+            self.selected_profile = selected;
+            self.mark_changed();
+        }
+    }
+
+    pub fn show_columns(&mut self) {
+        self.overlay = Overlay::Columns;
+        self.mark_changed();
+    }
+
+    pub fn select_column(&mut self, step: i64) {
+        let last = Column::ALL.len() - 1;
+        let selected = if step >= 0 {
+            self.selected_column.saturating_add(step as usize).min(last)
+        } else {
+            self.selected_column
+                .saturating_sub(usize::try_from(step.unsigned_abs()).unwrap_or(usize::MAX))
+        };
+        if selected != self.selected_column {
+            self.selected_column = selected;
+            self.mark_changed();
+        }
+    }
+
+    fn picked_column(&self) -> Column {
+        Column::ALL[self.selected_column.min(Column::ALL.len() - 1)]
+    }
+
+    pub fn toggle_picked_column(&mut self) {
+        let column = self.picked_column();
+        self.columns
+            .set_shown(column, !self.columns.is_shown(column));
+        self.mark_changed();
+    }
+
+    /// Widens or narrows the picked column, showing it first if it is hidden.
+    pub fn resize_picked_column(&mut self, delta: i16) {
+        let column = self.picked_column();
+        self.columns.set_shown(column, true);
+        let width = self.columns.width(column).unwrap_or(column.default_width());
+        self.columns
+            .set_width(column, width.saturating_add_signed(delta));
+        self.mark_changed();
+    }
+
+    pub fn sort_by_picked_column(&mut self) {
+        self.columns.cycle_sort(self.picked_column());
+        self.mark_changed();
+    }
+
+    pub fn show_snippets(&mut self) {
+        self.overlay = Overlay::Snippets;
+        self.mark_changed();
+    }
+
+    pub fn toggle_snippet_target(&mut self) {
+        self.snippet_via_proxy = !self.snippet_via_proxy;
+        self.mark_changed();
+    }
+
+    pub fn set_columns(&mut self, layout: columns::Layout) {
+        self.columns = layout;
+        self.mark_changed();
+    }
+
     pub fn close_overlay(&mut self) {
         if self.overlay == Overlay::None {
             return;
@@ -1192,11 +2095,14 @@ impl App {
             }
             MessageDirection::Response => {
                 // Find matching request by ID and add response
-                if let Some(exchange) =
-                    self.exchanges.iter_mut().rev().find(|e| {
-                        !e.is_notification() && e.id == message.id && e.response.is_none()
-                    })
-                {
+                if let Some(exchange) = self.exchanges.iter_mut().rev().find(|e| {
+                    !e.is_notification()
+                        && e.id == message.id
+                        && e.response.is_none()
+                        && e.request
+                            .as_ref()
+                            .is_none_or(|request| message.same_connection(request))
+                }) {
                     exchange.response = Some(message);
                 } else {
                     // No matching request found, create exchange with just response
@@ -1218,25 +2124,239 @@ impl App {
         self.mark_changed();
     }
 
+    /// Fills in the delivery time of the responses logged for a connection.
+    pub fn record_delivery(&mut self, delivery: &Delivery) {
+        let responses = self
+            .exchanges
+            .iter_mut()
+            .rev()
+            .filter_map(|exchange| exchange.response.as_mut())
+            .filter(|response| {
+                response
+                    .connection
+                    .as_ref()
+                    .is_some_and(|response| response.id == delivery.connection)
+            });
+        for response in responses {
+            response.timing.get_or_insert_with(Timing::default).delivery = Some(delivery.delivery);
+        }
+        self.mark_changed();
+    }
+
     pub fn get_selected_exchange(&self) -> Option<&JsonRpcExchange> {
         self.exchanges.get(self.selected_exchange)
     }
 
-    pub fn filtered_exchange_indices(&self) -> Vec<usize> {
-        self.exchanges
+    pub fn status_context(&self) -> StatusContext {
+        StatusContext::new(&self.exchanges, self.request_timeout, SystemTime::now())
+    }
+
+    /// Changes the request timeout; `None` turns it off.
+    pub fn set_request_timeout(&mut self, timeout: Option<Duration>) {
+        self.request_timeout = timeout;
+        self.check_timeouts();
+        self.mark_changed();
+    }
+
+    /// Marks the app changed when a pending exchange crosses the request timeout, so views
+    /// and change waiters see it without new traffic.
+    pub fn check_timeouts(&mut self) -> bool {
+        if self.awaiting_checked > self.exchanges.len() {
+            self.awaiting_response.clear();
+            self.awaiting_checked = 0;
+        }
+        let exchanges = &self.exchanges;
+        let awaiting = |index: &usize| {
+            exchanges.get(*index).is_some_and(|exchange| {
+                !exchange.is_notification()
+                    && exchange.request.is_some()
+                    && exchange.response.is_none()
+            })
+        };
+        self.awaiting_response.retain(awaiting);
+        self.awaiting_response
+            .extend((self.awaiting_checked..exchanges.len()).filter(awaiting));
+        self.awaiting_checked = exchanges.len();
+
+        let now = SystemTime::now();
+        let timed_out = self
+            .awaiting_response
+            .iter()
+            .filter_map(|index| exchanges[*index].request.as_ref())
+            .filter(|request| {
+                now.duration_since(request.timestamp).is_ok_and(|waited| {
+                    self.request_timeout.is_some_and(|timeout| waited > timeout)
+                })
+            })
+            .count();
+        if timed_out == self.timed_out_count {
+            return false;
+        }
+        self.timed_out_count = timed_out;
+        self.mark_changed();
+        true
+    }
+
+    pub fn filtered_exchange_indices(&self) -> Vec<usize> {
+        let context = self.status_context();
+        let matches = |index: usize| {
+            context.matches_filter(
+                &self.exchanges[index],
+                self.bookmarks.get(&index),
+                &self.filter_text,
+            )
+        };
+        // Events of a collapsed subscription hide behind its subscribe row while that row
+        // is visible.
+        let mut hidden = subscription::subscriptions(&self.exchanges)
+            .into_iter()
+            .filter(|subscription| {
+                !self.expanded_subscriptions.contains(&subscription.request)
+                    && matches(subscription.request)
+            })
+            .flat_map(|subscription| subscription.events)
+            .collect::<HashSet<_>>();
+        // Likewise, the rest of a collapsed batch hides behind its first call.
+        hidden.extend(
+            batch::batches(&self.exchanges)
+                .into_iter()
+                .filter(|batch| {
+                    !self.expanded_batches.contains(&batch.head()) && matches(batch.head())
+                })
+                .flat_map(|batch| batch.members.into_iter().skip(1)),
+        );
+
+        let mut indices = (0..self.exchanges.len())
+            .filter(|index| !hidden.contains(index) && matches(*index))
+            .collect::<Vec<_>>();
+        self.columns.sort(&mut indices, &self.exchanges, &context);
+        indices
+    }
+
+    /// Every exchange a filter matches in arrival order, including those folded away
+    /// behind a collapsed batch or subscription.
+    pub fn exchanges_matching(&self, filter: &str) -> Vec<usize> {
+        let context = self.status_context();
+        (0..self.exchanges.len())
+            .filter(|index| {
+                context.matches_filter(&self.exchanges[*index], self.bookmarks.get(index), filter)
+            })
+            .collect()
+    }
+
+    pub fn toggle_timeline(&mut self) {
+        self.set_timeline(!self.timeline_visible, None);
+    }
+
+    pub fn set_timeline(&mut self, visible: bool, zoom: Option<u32>) {
+        self.timeline_visible = visible;
+        if let Some(zoom) = zoom {
+            self.timeline_zoom = zoom.clamp(1, timeline::MAX_ZOOM);
+            self.timeline_offset = None;
+        }
+        self.mark_changed();
+    }
+
+    /// Doubles or halves the zoom and centres the window on the selected exchange again.
+    pub fn zoom_timeline(&mut self, zoom_in: bool) {
+        let zoom = if zoom_in {
+            self.timeline_zoom.saturating_mul(2)
+        } else {
+            self.timeline_zoom / 2
+        };
+        self.set_timeline(self.timeline_visible, Some(zoom));
+    }
+
+    /// Moves the timeline window by quarter widths.
+    pub fn pan_timeline(&mut self, quarters: i64) {
+        let indices = self.filtered_exchange_indices();
+        if let Some(window) = self.timeline_window(&indices, SystemTime::now()) {
+            self.timeline_offset = Some(window.panned(quarters));
+            self.mark_changed();
+        }
+    }
+
+    /// Where each listed exchange sits on the timeline. A collapsed batch spans all its calls.
+    pub fn timeline_spans(&self, indices: &[usize]) -> Vec<Option<timeline::Span>> {
+        let batches = batch::batches(&self.exchanges);
+        indices
             .iter()
-            .enumerate()
-            .filter(|(_, exchange)| {
-                request_matches_filter(
-                    exchange.method.as_deref(),
-                    exchange.id.as_ref(),
-                    &self.filter_text,
-                )
+            .map(|index| {
+                let exchange = &self.exchanges[*index];
+                let request = exchange.request.as_ref().map(|request| request.timestamp);
+                let response = exchange
+                    .response
+                    .as_ref()
+                    .map(|response| response.timestamp);
+                let start = request.or(response)?;
+                let end = match batch::containing(&batches, *index) {
+                    Some(batch)
+                        if batch.head() == *index && !self.expanded_batches.contains(index) =>
+                    {
+                        batch
+                            .duration(&self.exchanges)
+                            .map(|duration| start + duration)
+                    }
+                    _ if exchange.is_notification() => Some(start),
+                    _ => response,
+                };
+                Some(timeline::Span { start, end })
             })
-            .map(|(index, _)| index)
             .collect()
     }
 
+    pub fn timeline_window(&self, indices: &[usize], now: SystemTime) -> Option<Window> {
+        let spans = self.timeline_spans(indices);
+        let focus = indices
+            .iter()
+            .position(|index| *index == self.selected_exchange)
+            .and_then(|position| spans[position])
+            .map(|span| span.start);
+        Window::fit(
+            &spans.into_iter().flatten().collect::<Vec<_>>(),
+            now,
+            self.timeline_zoom,
+            self.timeline_offset,
+            focus,
+        )
+    }
+
+    /// Expands or collapses the batch or subscription containing the selected exchange.
+    pub fn toggle_selected_group(&mut self) {
+        let batches = batch::batches(&self.exchanges);
+        let Some(head) = batch::containing(&batches, self.selected_exchange).map(Batch::head)
+        else {
+            self.toggle_selected_subscription();
+            return;
+        };
+
+        if self.expanded_batches.remove(&head) {
+            self.select_exchange(head);
+        } else {
+            self.expanded_batches.insert(head);
+            self.mark_changed();
+        }
+    }
+
+    /// Expands or collapses the subscription containing the selected exchange.
+    pub fn toggle_selected_subscription(&mut self) {
+        let subscriptions = subscription::subscriptions(&self.exchanges);
+        let Some(request) = subscription::containing(&subscriptions, self.selected_exchange)
+            .map(|subscription| subscription.request)
+        else {
+            self.notice = Some("No subscription selected".to_string());
+            self.mark_changed();
+            return;
+        };
+
+        if self.expanded_subscriptions.remove(&request) {
+            self.select_exchange(request);
+        } else {
+            self.expanded_subscriptions.insert(request);
+            self.mark_changed();
+        }
+    }
+
     pub fn history_scroll_offset(&self, visible_rows: usize) -> usize {
         let indices = self.filtered_exchange_indices();
         let selected = indices
@@ -1274,6 +2394,7 @@ impl App {
 
         self.selected_exchange = index;
         self.history_scroll = None;
+        self.timeline_offset = None;
         self.request_details_scroll = 0;
         self.response_details_scroll = 0;
         self.reset_detail_cursors();
@@ -1425,18 +2546,23 @@ impl App {
         true
     }
 
+    pub fn bookmark(&self, index: usize) -> Option<&Bookmark> {
+        self.bookmarks.get(&index)
+    }
+
+    pub fn set_bookmark(&mut self, index: usize, bookmark: Bookmark) {
+        if bookmark.is_empty() {
+            self.bookmarks.remove(&index);
+        } else {
+            self.bookmarks.insert(index, bookmark);
+        }
+        self.mark_changed();
+    }
+
     pub fn detail_tab(&self, panel: Focus) -> Option<DetailTab> {
         match panel {
-            Focus::RequestSection => Some(if self.request_tab == 0 {
-                DetailTab::Headers
-            } else {
-                DetailTab::Body
-            }),
-            Focus::ResponseSection => Some(if self.response_tab == 0 {
-                DetailTab::Headers
-            } else {
-                DetailTab::Body
-            }),
+            Focus::RequestSection => Some(DetailTab::from_index(self.request_tab)),
+            Focus::ResponseSection => Some(DetailTab::from_index(self.response_tab)),
             Focus::MessageList | Focus::StatusHeader => None,
         }
     }
@@ -1445,7 +2571,9 @@ impl App {
         &self,
         panel: Focus,
     ) -> impl DoubleEndedIterator<Item = &LineAnnotation> {
-        let tab = self.detail_tab(panel);
+        let tab = self
+            .detail_tab(panel)
+            .filter(|_| self.panel_query(panel).is_none());
         self.annotations.iter().filter(move |annotation| {
             annotation.exchange_index == self.selected_exchange
                 && annotation.panel == panel
@@ -1494,10 +2622,10 @@ impl App {
         lines: i64,
         total_lines: usize,
         visible_lines: usize,
+        folds: &Folds,
     ) {
         let total_lines = total_lines.max(1);
         let visible_lines = visible_lines.max(1);
-        let distance = usize::try_from(lines.unsigned_abs()).unwrap_or(usize::MAX);
         let previous_focus = self.focus;
         self.focus = panel;
 
@@ -1514,20 +2642,20 @@ impl App {
         };
         let previous_cursor = *cursor;
         let previous_scroll = *scroll;
-        *cursor = (*cursor).clamp(1, total_lines);
-        *cursor = if lines >= 0 {
-            cursor.saturating_add(distance).min(total_lines)
-        } else {
-            cursor.saturating_sub(distance).max(1)
-        };
+        *cursor = folds.step(*cursor, lines, total_lines);
 
-        let cursor_index = cursor.saturating_sub(1);
-        let max_scroll = total_lines.saturating_sub(visible_lines);
-        *scroll = (*scroll).min(max_scroll);
-        if cursor_index < *scroll {
-            *scroll = cursor_index;
-        } else if cursor_index >= scroll.saturating_add(visible_lines) {
-            *scroll = cursor_index.saturating_add(1).saturating_sub(visible_lines);
+        // Drawn lines, so a folded region counts once.
+        let cursor_index = folds.rank(*cursor);
+        let top = folds.rank(folds.shown(*scroll + 1));
+        let max_top = folds.rank(total_lines + 1).saturating_sub(visible_lines);
+        let mut wanted = top.min(max_top);
+        if cursor_index < wanted {
+            wanted = cursor_index;
+        } else if cursor_index >= wanted.saturating_add(visible_lines) {
+            wanted = cursor_index.saturating_add(1).saturating_sub(visible_lines);
+        }
+        if wanted != top {
+            *scroll = folds.step(1, wanted as i64, total_lines) - 1;
         }
 
         if previous_focus != self.focus || previous_cursor != *cursor || previous_scroll != *scroll
@@ -1539,6 +2667,54 @@ impl App {
     fn reset_detail_cursors(&mut self) {
         self.request_details_cursor_line = 1;
         self.response_details_cursor_line = 1;
+        self.request_folds.clear();
+        self.response_folds.clear();
+    }
+
+    pub fn toggle_json_tree(&mut self) {
+        self.json_tree = !self.json_tree;
+        self.mark_changed();
+    }
+
+    pub fn fold_overrides(&self, panel: Focus) -> &HashMap<String, bool> {
+        if panel == Focus::ResponseSection {
+            &self.response_folds
+        } else {
+            &self.request_folds
+        }
+    }
+
+    /// Folds or unfolds `fold`. A cursor inside a fold being closed moves to its first line.
+    pub fn set_fold(&mut self, panel: Focus, fold: &Fold, folded: bool) {
+        let (folds, cursor) = match panel {
+            Focus::RequestSection => (
+                &mut self.request_folds,
+                &mut self.request_details_cursor_line,
+            ),
+            Focus::ResponseSection => (
+                &mut self.response_folds,
+                &mut self.response_details_cursor_line,
+            ),
+            Focus::MessageList | Focus::StatusHeader => return,
+        };
+        folds.insert(fold.path.clone(), folded);
+        if folded && (fold.line..=fold.last).contains(cursor) {
+            *cursor = fold.line;
+        }
+        self.mark_changed();
+    }
+
+    /// Opens every fold that hides part of `start_line..=end_line`.
+    pub fn unfold_lines(
+        &mut self,
+        panel: Focus,
+        folds: &Folds,
+        start_line: usize,
+        end_line: usize,
+    ) {
+        for fold in folds.hiding(start_line, end_line) {
+            self.set_fold(panel, fold, false);
+        }
     }
 
     pub fn set_focus(&mut self, focus: Focus) {
@@ -1577,7 +2753,13 @@ impl App {
         self.select_exchange(self.exchanges.len() - 1);
     }
 
-    pub fn scroll_panel_lines(&mut self, panel: Focus, lines: i64, total_lines: usize) {
+    pub fn scroll_panel_lines(
+        &mut self,
+        panel: Focus,
+        lines: i64,
+        total_lines: usize,
+        folds: &Folds,
+    ) {
         let previous_focus = self.focus;
         self.focus = panel;
         let scroll_changed = {
@@ -1587,14 +2769,7 @@ impl App {
                 Focus::MessageList | Focus::StatusHeader => return,
             };
             let previous_scroll = *scroll;
-            let distance = usize::try_from(lines.unsigned_abs()).unwrap_or(usize::MAX);
-            let max_scroll = total_lines.saturating_sub(1);
-            *scroll = (*scroll).min(max_scroll);
-            *scroll = if lines >= 0 {
-                scroll.saturating_add(distance).min(max_scroll)
-            } else {
-                scroll.saturating_sub(distance)
-            };
+            *scroll = folds.step(*scroll + 1, lines, total_lines) - 1;
             previous_scroll != *scroll
         };
         if previous_focus != self.focus || scroll_changed {
@@ -1620,21 +2795,48 @@ impl App {
     }
 
     fn requests_markdown(&self) -> String {
+        let shown = self
+            .columns
+            .columns
+            .iter()
+            .map(|shown| shown.column)
+            .collect::<Vec<_>>();
+        let header = shown
+            .iter()
+            .map(|column| column.title().to_string())
+            .chain(
+                self.query_columns
+                    .iter()
+                    .map(|column| markdown_cell(column.expression())),
+            )
+            .collect::<Vec<_>>();
         let mut lines = vec![
-            "| Status | Transport | Method | ID | Duration |".to_string(),
-            "| --- | --- | --- | --- | --- |".to_string(),
+            format!("| {} |", header.join(" | ")),
+            format!("|{}", " --- |".repeat(header.len())),
         ];
 
+        let context = self.status_context();
+        let origin = columns::session_origin(&self.exchanges);
         for index in self.filtered_exchange_indices() {
             let exchange = &self.exchanges[index];
-            lines.push(format!(
-                "| {} | {} | {} | {} | {} |",
-                exchange_status(exchange),
-                transport_name(&exchange.transport),
-                markdown_cell(exchange.method.as_deref().unwrap_or("unknown")),
-                markdown_cell(&display_id(exchange.id.as_ref())),
-                exchange_duration(exchange),
-            ));
+            let document = query::document(exchange);
+            let cells = shown
+                .iter()
+                .map(|column| {
+                    let text = column.text(exchange, &context, origin);
+                    match (column, self.bookmark(index)) {
+                        (Column::Method, Some(bookmark)) => bookmark.decorate(&text),
+                        _ => text,
+                    }
+                })
+                .map(|text| markdown_cell(&text))
+                .chain(
+                    self.query_columns
+                        .iter()
+                        .map(|column| markdown_cell(&query::cell(&column.evaluate(&document)))),
+                )
+                .collect::<Vec<_>>();
+            lines.push(format!("| {} |", cells.join(" | ")));
         }
 
         lines.join("\n")
@@ -1644,11 +2846,17 @@ impl App {
         let exchange = self.get_selected_exchange()?;
         let request = exchange.request.as_ref()?;
         let mut markdown = exchange_heading("Request", exchange);
+        if let Some(query) = &self.request_query {
+            markdown.push_str(&query_markdown(query, exchange));
+            return Some(markdown);
+        }
 
-        if self.request_tab == 0 {
-            markdown.push_str(&headers_markdown(request.headers.as_ref()));
-        } else {
-            markdown.push_str(&json_markdown(&request_json(request)));
+        match self.request_tab {
+            0 => markdown.push_str(&headers_markdown(request.headers.as_ref())),
+            1 => markdown.push_str(&json_markdown(&message_body(request))),
+            _ => markdown.push_str(&ethereum::markdown(&ethereum::request_values(
+                exchange, &self.abi,
+            ))),
         }
 
         Some(markdown)
@@ -1658,16 +2866,37 @@ impl App {
         let exchange = self.get_selected_exchange()?;
         let response = exchange.response.as_ref()?;
         let mut markdown = "# Response\n".to_string();
+        if let Some(query) = &self.response_query {
+            markdown.push_str(&query_markdown(query, exchange));
+            return Some(markdown);
+        }
 
-        if self.response_tab == 0 {
-            markdown.push_str(&headers_markdown(response.headers.as_ref()));
-        } else {
-            markdown.push_str(&json_markdown(&response_json(response)));
+        match self.response_tab {
+            0 => markdown.push_str(&headers_markdown(response.headers.as_ref())),
+            1 => markdown.push_str(&json_markdown(&message_body(response))),
+            _ => markdown.push_str(&ethereum::markdown(&ethereum::response_values(
+                exchange, &self.abi,
+            ))),
         }
 
         Some(markdown)
     }
 
+    /// Each listener's name and proxy port, such as `main 8080 · l2 8082`.
+    pub fn listener_ports(&self) -> String {
+        let config = &self.proxy_config;
+        std::iter::once((MAIN_LISTENER, config.listen_port))
+            .chain(
+                config
+                    .listeners
+                    .iter()
+                    .map(|listener| (listener.name.as_str(), listener.listen_port)),
+            )
+            .map(|(name, port)| format!("{name} {port}"))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+
     fn status_markdown(&self) -> String {
         let state = if self.is_running {
             "Running"
@@ -1681,8 +2910,10 @@ impl App {
         };
         let data_plane = if self.proxy_config.transparent {
             "Stdio".to_string()
-        } else {
+        } else if self.proxy_config.listeners.is_empty() {
             format!("HTTP port {}", self.proxy_config.listen_port)
+        } else {
+            format!("HTTP ports {}", self.listener_ports())
         };
         format!(
             "# Status\n\n- State: {state}\n- Data plane: {data_plane}\n- Control port: {}\n- Mode: {mode}",
@@ -1691,43 +2922,45 @@ impl App {
     }
 
     pub fn select_next(&mut self) {
-        if !self.exchanges.is_empty() {
-            self.selected_exchange = (self.selected_exchange + 1) % self.exchanges.len();
-            self.history_scroll = None;
-            self.reset_details_scroll();
-            self.request_details_scroll = 0;
-            self.response_details_scroll = 0;
-            self.reset_detail_cursors();
-            self.details_tab = 0;
-            self.request_details_tab = 0;
-            self.response_details_tab = 0;
-            self.line_selection = None;
-            self.visual_selection_active = false;
-            self.active_annotation_id = None;
-            self.mark_changed();
-        }
+        self.step_selection(true);
     }
 
     pub fn select_previous(&mut self) {
-        if !self.exchanges.is_empty() {
-            self.selected_exchange = if self.selected_exchange == 0 {
-                self.exchanges.len() - 1
-            } else {
-                self.selected_exchange - 1
-            };
-            self.history_scroll = None;
-            self.reset_details_scroll();
-            self.request_details_scroll = 0;
-            self.response_details_scroll = 0;
-            self.reset_detail_cursors();
-            self.details_tab = 0;
-            self.request_details_tab = 0;
-            self.response_details_tab = 0;
-            self.line_selection = None;
-            self.visual_selection_active = false;
-            self.active_annotation_id = None;
-            self.mark_changed();
+        self.step_selection(false);
+    }
+
+    // Moves through visible rows, wrapping at either end.
+    fn step_selection(&mut self, forward: bool) {
+        let visible = self.filtered_exchange_indices();
+        if visible.is_empty() {
+            return;
         }
+        let position = visible
+            .iter()
+            .position(|index| *index == self.selected_exchange);
+        let position = match (position, forward) {
+            (Some(position), true) => (position + 1) % visible.len(),
+            (Some(0), false) => visible.len() - 1,
+            (Some(position), false) => position - 1,
+            (None, _) => visible
+                .iter()
+                .position(|index| *index > self.selected_exchange)
+                .unwrap_or(0),
+        };
+        self.selected_exchange = visible[position];
+        self.history_scroll = None;
+        self.timeline_offset = None;
+        self.reset_details_scroll();
+        self.request_details_scroll = 0;
+        self.response_details_scroll = 0;
+        self.reset_detail_cursors();
+        self.details_tab = 0;
+        self.request_details_tab = 0;
+        self.response_details_tab = 0;
+        self.line_selection = None;
+        self.visual_selection_active = false;
+        self.active_annotation_id = None;
+        self.mark_changed();
     }
 
     pub fn toggle_proxy(&mut self) {
@@ -1846,7 +3079,7 @@ impl App {
     }
 
     pub fn next_request_tab(&mut self) {
-        self.request_tab = 1 - self.request_tab; // Toggle between 0 and 1
+        self.request_tab = (self.request_tab + 1) % DETAIL_TAB_COUNT;
         self.request_details_scroll = 0;
         self.request_details_cursor_line = 1;
         self.line_selection = None;
@@ -1856,7 +3089,7 @@ impl App {
     }
 
     pub fn previous_request_tab(&mut self) {
-        self.request_tab = 1 - self.request_tab; // Toggle between 0 and 1
+        self.request_tab = (self.request_tab + DETAIL_TAB_COUNT - 1) % DETAIL_TAB_COUNT;
         self.request_details_scroll = 0;
         self.request_details_cursor_line = 1;
         self.line_selection = None;
@@ -1866,7 +3099,7 @@ impl App {
     }
 
     pub fn next_response_tab(&mut self) {
-        self.response_tab = 1 - self.response_tab; // Toggle between 0 and 1
+        self.response_tab = (self.response_tab + 1) % DETAIL_TAB_COUNT;
         self.response_details_scroll = 0;
         self.response_details_cursor_line = 1;
         self.line_selection = None;
@@ -1876,7 +3109,7 @@ impl App {
     }
 
     pub fn previous_response_tab(&mut self) {
-        self.response_tab = 1 - self.response_tab; // Toggle between 0 and 1
+        self.response_tab = (self.response_tab + DETAIL_TAB_COUNT - 1) % DETAIL_TAB_COUNT;
         self.response_details_scroll = 0;
         self.response_details_cursor_line = 1;
         self.line_selection = None;
@@ -1904,6 +3137,187 @@ impl App {
         self.mark_changed();
     }
 
+    /// Opens the query prompt for the focused detail panel with its current expression.
+    pub fn start_query(&mut self) {
+        if !matches!(self.focus, Focus::RequestSection | Focus::ResponseSection) {
+            return;
+        }
+        self.input_buffer = self
+            .panel_query(self.focus)
+            .map_or_else(|| ".".to_string(), |query| query.expression().to_string());
+        self.input_mode = InputMode::Querying;
+    }
+
+    /// Shows the prompt's query in the focused panel, or clears it when the prompt is empty.
+    /// An invalid expression keeps the prompt open.
+    pub fn apply_query(&mut self) {
+        let expression = self.input_buffer.trim();
+        let query = if expression.is_empty() {
+            None
+        } else {
+            match Query::parse(expression) {
+                Ok(query) => Some(query),
+                Err(error) => {
+                    self.notice = Some(format!("Error: {error}"));
+                    self.mark_changed();
+                    return;
+                }
+            }
+        };
+        self.cancel_editing();
+        self.set_query(self.focus, query);
+    }
+
+    pub fn panel_query(&self, panel: Focus) -> Option<&Query> {
+        match panel {
+            Focus::RequestSection => self.request_query.as_ref(),
+            Focus::ResponseSection => self.response_query.as_ref(),
+            Focus::MessageList | Focus::StatusHeader => None,
+        }
+    }
+
+    pub fn set_query(&mut self, panel: Focus, query: Option<Query>) {
+        match panel {
+            Focus::RequestSection => {
+                self.request_query = query;
+                self.request_details_scroll = 0;
+                self.request_details_cursor_line = 1;
+            }
+            Focus::ResponseSection => {
+                self.response_query = query;
+                self.response_details_scroll = 0;
+                self.response_details_cursor_line = 1;
+            }
+            Focus::MessageList | Focus::StatusHeader => return,
+        }
+        self.line_selection = None;
+        self.visual_selection_active = false;
+        self.mark_changed();
+    }
+
+    /// Pins the focused panel's query as a request list column, or unpins it.
+    pub fn toggle_query_column(&mut self) {
+        let Some(query) = self.panel_query(self.focus).cloned() else {
+            self.notice = Some("No query to pin; press . to write one".to_string());
+            self.mark_changed();
+            return;
+        };
+        match self
+            .query_columns
+            .iter()
+            .position(|column| column.expression() == query.expression())
+        {
+            Some(position) => {
+                self.query_columns.remove(position);
+                self.notice = Some(format!("Unpinned column {}", query.expression()));
+            }
+            None => {
+                self.notice = Some(format!("Pinned column {}", query.expression()));
+                self.query_columns.push(query);
+            }
+        }
+        self.mark_changed();
+    }
+
+    /// Opens the search prompt for the focused panel, keeping a search already made there.
+    pub fn start_search(&mut self) {
+        if !self.is_searchable() {
+            return;
+        }
+        self.search_origin = self.search_view(self.focus);
+        self.input_buffer = self
+            .panel_search(self.focus)
+            .map(|search| search.pattern.clone())
+            .unwrap_or_default();
+        self.input_mode = InputMode::Searching;
+        self.mark_changed();
+    }
+
+    /// Whether `/` searches the focused panel rather than filtering the request list.
+    pub fn is_searchable(&self) -> bool {
+        match self.app_mode {
+            AppMode::Normal => matches!(self.focus, Focus::RequestSection | Focus::ResponseSection),
+            AppMode::Paused | AppMode::Intercepting => self.focus == Focus::RequestSection,
+        }
+    }
+
+    /// Searches the focused panel for the prompt's pattern as it is typed, starting over
+    /// from where the prompt was opened.
+    pub fn update_search(&mut self) {
+        self.restore_search_origin();
+        self.search = Some(Search::new(self.focus, &self.input_buffer));
+        self.mark_changed();
+    }
+
+    pub fn finish_search(&mut self) {
+        if self.input_buffer.is_empty() {
+            self.search = None;
+        }
+        self.cancel_editing();
+        self.mark_changed();
+    }
+
+    /// Drops the search and returns to where the prompt was opened.
+    pub fn cancel_search(&mut self) {
+        self.restore_search_origin();
+        self.search = None;
+        self.cancel_editing();
+        self.mark_changed();
+    }
+
+    fn restore_search_origin(&mut self) {
+        let (cursor, scroll) = self.search_origin;
+        match (self.app_mode, self.focus) {
+            (AppMode::Normal, Focus::RequestSection) => {
+                self.request_details_cursor_line = cursor;
+                self.request_details_scroll = scroll;
+            }
+            (AppMode::Normal, Focus::ResponseSection) => {
+                self.response_details_cursor_line = cursor;
+                self.response_details_scroll = scroll;
+            }
+            (AppMode::Paused | AppMode::Intercepting, _) => self.intercept_details_scroll = scroll,
+            _ => {}
+        }
+        self.line_selection = None;
+        self.visual_selection_active = false;
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.clear_line_selection();
+        self.mark_changed();
+    }
+
+    pub fn panel_search(&self, panel: Focus) -> Option<&Search> {
+        self.search.as_ref().filter(|search| search.panel == panel)
+    }
+
+    fn search_view(&self, panel: Focus) -> (usize, usize) {
+        match (self.app_mode, panel) {
+            (AppMode::Normal, Focus::RequestSection) => (
+                self.request_details_cursor_line,
+                self.request_details_scroll,
+            ),
+            (AppMode::Normal, Focus::ResponseSection) => (
+                self.response_details_cursor_line,
+                self.response_details_scroll,
+            ),
+            _ => (1, self.intercept_details_scroll),
+        }
+    }
+
+    pub fn pin_query_column(&mut self, query: Query) {
+        if self
+            .query_columns
+            .iter()
+            .all(|column| column.expression() != query.expression())
+        {
+            self.query_columns.push(query);
+            self.mark_changed();
+        }
+    }
+
     // Get content lines for proper scrolling calculations
     // Target editing methods
     pub fn start_editing_target(&mut self) {
@@ -1934,6 +3348,18 @@ impl App {
         self.input_buffer.clear();
     }
 
+    /// Opens the tag prompt for the selected exchange with its current tags.
+    pub fn start_tagging(&mut self) {
+        if self.get_selected_exchange().is_none() {
+            return;
+        }
+        self.input_buffer = self
+            .bookmark(self.selected_exchange)
+            .map(|bookmark| bookmark.tags.join(" "))
+            .unwrap_or_default();
+        self.input_mode = InputMode::Tagging;
+    }
+
     pub fn start_renaming_session(&mut self) {
         let Some(session) = &self.session else {
             return;
@@ -2001,31 +3427,14 @@ impl App {
                             line_count += 1;
                         }
                     },
-                    _ => {
-                        let mut request_json = serde_json::Map::new();
-                        request_json.insert(
-                            "jsonrpc".to_string(),
-                            serde_json::Value::String("2.0".to_string()),
-                        );
-                        if let Some(id) = &request.id {
-                            request_json.insert("id".to_string(), id.clone());
-                        }
-                        if let Some(method) = &request.method {
-                            request_json.insert(
-                                "method".to_string(),
-                                serde_json::Value::String(method.clone()),
-                            );
-                        }
-                        if let Some(params) = &request.params {
-                            request_json.insert("params".to_string(), params.clone());
-                        }
-
-                        if let Ok(json_str) =
-                            serde_json::to_string_pretty(&serde_json::Value::Object(request_json))
-                        {
+                    1 => {
+                        if let Ok(json_str) = serde_json::to_string_pretty(&message_body(request)) {
                             line_count += json_str.lines().count();
                         }
                     }
+                    _ => {
+                        line_count += ethereum::request_values(exchange, &self.abi).len().max(1);
+                    }
                 }
             } else {
                 line_count += 1;
@@ -2046,28 +3455,15 @@ impl App {
                             line_count += 1;
                         }
                     },
-                    _ => {
-                        let mut response_json = serde_json::Map::new();
-                        response_json.insert(
-                            "jsonrpc".to_string(),
-                            serde_json::Value::String("2.0".to_string()),
-                        );
-                        if let Some(id) = &response.id {
-                            response_json.insert("id".to_string(), id.clone());
-                        }
-                        if let Some(result) = &response.result {
-                            response_json.insert("result".to_string(), result.clone());
-                        }
-                        if let Some(error) = &response.error {
-                            response_json.insert("error".to_string(), error.clone());
-                        }
-
-                        if let Ok(json_str) =
-                            serde_json::to_string_pretty(&serde_json::Value::Object(response_json))
+                    1 => {
+                        if let Ok(json_str) = serde_json::to_string_pretty(&message_body(response))
                         {
                             line_count += json_str.lines().count();
                         }
                     }
+                    _ => {
+                        line_count += ethereum::response_values(exchange, &self.abi).len().max(1);
+                    }
                 }
             } else {
                 line_count += 1;
@@ -2108,31 +3504,14 @@ impl App {
                             line_count += 1;
                         }
                     },
-                    _ => {
-                        let mut request_json = serde_json::Map::new();
-                        request_json.insert(
-                            "jsonrpc".to_string(),
-                            serde_json::Value::String("2.0".to_string()),
-                        );
-                        if let Some(id) = &request.id {
-                            request_json.insert("id".to_string(), id.clone());
-                        }
-                        if let Some(method) = &request.method {
-                            request_json.insert(
-                                "method".to_string(),
-                                serde_json::Value::String(method.clone()),
-                            );
-                        }
-                        if let Some(params) = &request.params {
-                            request_json.insert("params".to_string(), params.clone());
-                        }
-
-                        if let Ok(json_str) =
-                            serde_json::to_string_pretty(&serde_json::Value::Object(request_json))
-                        {
+                    1 => {
+                        if let Ok(json_str) = serde_json::to_string_pretty(&message_body(request)) {
                             line_count += json_str.lines().count();
                         }
                     }
+                    _ => {
+                        line_count += ethereum::request_values(exchange, &self.abi).len().max(1);
+                    }
                 }
             } else {
                 line_count += 1;
@@ -2162,28 +3541,15 @@ impl App {
                             line_count += 1;
                         }
                     },
-                    _ => {
-                        let mut response_json = serde_json::Map::new();
-                        response_json.insert(
-                            "jsonrpc".to_string(),
-                            serde_json::Value::String("2.0".to_string()),
-                        );
-                        if let Some(id) = &response.id {
-                            response_json.insert("id".to_string(), id.clone());
-                        }
-                        if let Some(result) = &response.result {
-                            response_json.insert("result".to_string(), result.clone());
-                        }
-                        if let Some(error) = &response.error {
-                            response_json.insert("error".to_string(), error.clone());
-                        }
-
-                        if let Ok(json_str) =
-                            serde_json::to_string_pretty(&serde_json::Value::Object(response_json))
+                    1 => {
+                        if let Ok(json_str) = serde_json::to_string_pretty(&message_body(response))
                         {
                             line_count += json_str.lines().count();
                         }
                     }
+                    _ => {
+                        line_count += ethereum::response_values(exchange, &self.abi).len().max(1);
+                    }
                 }
             } else {
                 line_count += 1;
@@ -2273,6 +3639,7 @@ impl App {
             };
 
             let _ = pending.decision_sender.send(decision);
+            self.settle_intercepting();
             self.mark_changed();
         }
     }
@@ -2286,10 +3653,63 @@ impl App {
 
             // Send block decision to proxy
             let _ = pending.decision_sender.send(ProxyDecision::Block);
+            self.settle_intercepting();
             self.mark_changed();
         }
     }
 
+    /// Queues a request the proxy is holding. One held at a breakpoint while not paused
+    /// opens the intercept view.
+    pub fn hold_pending(&mut self, pending: PendingRequest) {
+        if self.app_mode == AppMode::Normal {
+            self.app_mode = AppMode::Intercepting;
+            self.notice = Some(format!(
+                "Breakpoint: {}",
+                pending
+                    .original_request
+                    .method
+                    .as_deref()
+                    .unwrap_or("unknown")
+            ));
+        }
+        self.pending_requests.push(pending);
+        self.mark_changed();
+    }
+
+    /// A held request as the intercept view and the control plane show it: any edits applied,
+    /// then the profile's redactions. The held request itself stays intact, so editing and
+    /// allowing it forwards the real values.
+    pub fn shown_pending_request(&self, pending: &PendingRequest) -> JsonRpcMessage {
+        let mut request = pending.original_request.clone();
+        if let Some(headers) = &pending.modified_headers {
+            request.headers = Some(headers.clone());
+        }
+        if let Some(edited) = pending
+            .modified_request
+            .as_deref()
+            .and_then(|edited| serde_json::from_str::<serde_json::Value>(edited).ok())
+        {
+            request.id = edited.get("id").cloned();
+            request.method = edited
+                .get("method")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string);
+            request.params = edited.get("params").cloned();
+        }
+        if let Ok(rules) = self.rules.lock() {
+            rules.redact(&mut request);
+        }
+        request
+    }
+
+    // Leaves the intercept view once nothing is held, as resolving over the control plane
+    // does.
+    fn settle_intercepting(&mut self) {
+        if self.pending_requests.is_empty() && self.app_mode == AppMode::Intercepting {
+            self.app_mode = AppMode::Normal;
+        }
+    }
+
     pub fn resume_all_requests(&mut self) {
         let changed = !self.pending_requests.is_empty() || self.app_mode != AppMode::Normal;
         for pending in self.pending_requests.drain(..) {
@@ -2470,6 +3890,7 @@ impl App {
         let _ = pending
             .decision_sender
             .send(ProxyDecision::Complete(parsed));
+        self.settle_intercepting();
         self.mark_changed();
 
         Ok(())
@@ -2487,6 +3908,7 @@ impl App {
             serde_json::from_str(&request_json).map_err(|e| format!("Invalid JSON: {}", e))?;
 
         match &parsed {
+            _ if self.proxy_config.transport.is_dap() => validate_dap_message(&parsed)?,
             serde_json::Value::Array(requests) => {
                 if requests.is_empty() {
                     return Err("Batch request cannot be empty".to_string());
@@ -2538,6 +3960,29 @@ fn validate_json_rpc_message(
     Err("Missing 'method' field".to_string())
 }
 
+fn validate_dap_message(message: &serde_json::Value) -> Result<(), String> {
+    if message
+        .get("seq")
+        .and_then(serde_json::Value::as_u64)
+        .is_none()
+    {
+        return Err("Missing or invalid 'seq' field".to_string());
+    }
+    match message.get("type").and_then(serde_json::Value::as_str) {
+        Some("request")
+            if message
+                .get("command")
+                .is_some_and(serde_json::Value::is_string) =>
+        {
+            Ok(())
+        }
+        Some("request") => Err("Missing 'command' field".to_string()),
+        Some("response") if message.get("request_seq").is_some() => Ok(()),
+        Some("response") => Err("Missing 'request_seq' field".to_string()),
+        _ => Err("DAP 'type' must be \"request\" or \"response\"".to_string()),
+    }
+}
+
 pub async fn send_new_request(request: OutboundRequest) -> Result<serde_json::Value, String> {
     let response = reqwest::Client::new()
         .post(request.url)
@@ -2557,3 +4002,37 @@ pub async fn send_new_request(request: OutboundRequest) -> Result<serde_json::Va
         .await
         .map_err(|error| format!("Invalid JSON response: {error}"))
 }
+
+/// Apps built from JSON-RPC bodies, for tests across the crate.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    /// An app that logged each body as arriving over `transport` at its milliseconds after
+    /// the epoch. Requests never time out, so unanswered ones stay pending.
+    pub(crate) fn app(
+        transport: TransportType,
+        bodies: impl IntoIterator<Item = (serde_json::Value, u64)>,
+    ) -> App {
+        let mut app = App::new();
+        app.set_request_timeout(None);
+        for (body, at_ms) in bodies {
+            record(&mut app, &body, transport, at_ms);
+        }
+        app
+    }
+
+    /// Logs the messages in `body` as arriving over `transport` at `at_ms` after the epoch.
+    pub(crate) fn record(
+        app: &mut App,
+        body: &serde_json::Value,
+        transport: TransportType,
+        at_ms: u64,
+    ) {
+        for mut message in json_rpc_messages_by_shape(body, transport, None) {
+            message.timestamp = UNIX_EPOCH + Duration::from_millis(at_ms);
+            app.add_message(message);
+        }
+    }
+}
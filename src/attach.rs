@@ -1,6 +1,7 @@
 use crate::{
     app::SessionSummary,
-    app::{App, Framing, ProxyConfig, TransportType},
+    app::{App, ExchangeStatus, Framing, ProxyConfig, TransportType},
+    columns::Layout,
     control::{self, Session},
 };
 use serde::Deserialize;
@@ -23,11 +24,17 @@ pub struct RemoteState {
     pub target: String,
     pub transport: String,
     pub session: SessionSummary,
+    #[serde(default)]
+    pub request_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub columns: Option<Layout>,
 }
 
 pub struct Snapshot {
     pub state: RemoteState,
+    /// The wrapper's exchanges from index `from` on.
     pub session: Session,
+    pub from: usize,
 }
 
 impl ControlClient {
@@ -47,9 +54,43 @@ impl ControlClient {
         if state.data_plane != "stdio" {
             return Err("attach requires a transparent stdio wrapper".to_string());
         }
-        let session = serde_json::from_value(self.call("debugger.exportSession", json!({})).await?)
-            .map_err(|error| error.to_string())?;
-        Ok(Snapshot { state, session })
+        let session = self.session().await?;
+        Ok(Snapshot {
+            state,
+            session,
+            from: 0,
+        })
+    }
+
+    /// The next state once the wrapper's revision moves past `revision`, or the current one
+    /// after `timeout_ms`.
+    pub async fn wait_for_change(
+        &self,
+        revision: u64,
+        timeout_ms: u64,
+    ) -> Result<RemoteState, String> {
+        let change = self
+            .call(
+                "debugger.waitForChange",
+                json!({"afterRevision": revision, "timeoutMs": timeout_ms}),
+            )
+            .await?;
+        serde_json::from_value(change.get("state").cloned().unwrap_or_default())
+            .map_err(|error| error.to_string())
+    }
+
+    /// The active session of any debugger, including one driving an HTTP target.
+    pub async fn session(&self) -> Result<Session, String> {
+        self.session_from(0).await
+    }
+
+    /// The exchanges of the active session from index `from` on.
+    pub async fn session_from(&self, from: usize) -> Result<Session, String> {
+        serde_json::from_value(
+            self.call("debugger.exportSession", json!({"from": from}))
+                .await?,
+        )
+        .map_err(|error| error.to_string())
     }
 
     async fn call(&self, method: &str, params: Value) -> Result<Value, String> {
@@ -82,6 +123,10 @@ impl ControlClient {
 impl Snapshot {
     pub fn apply(self, app: &mut App) -> Result<(), String> {
         let transport = parse_transport(&self.state.transport)?;
+        let bookmarks = control::session_bookmarks(&self.session)
+            .into_iter()
+            .map(|(index, bookmark)| (index + self.from, bookmark))
+            .collect::<std::collections::HashMap<_, _>>();
         let exchanges = control::replay_session(self.session).map_err(|error| error.message)?;
         let first_snapshot = app.session.is_none();
 
@@ -91,14 +136,26 @@ impl Snapshot {
             transport,
             stdio: None,
             transparent: true,
+            listeners: Vec::new(),
         };
         app.control_port = self.state.control_port;
         app.is_running = self.state.running;
+        if let Some(timeout_ms) = self.state.request_timeout_ms {
+            app.request_timeout = Some(std::time::Duration::from_millis(timeout_ms));
+        }
         if first_snapshot {
-            app.activate_session(self.state.session, exchanges, Vec::new());
+            // The viewer starts from the wrapper's columns and then keeps its own.
+            if let Some(columns) = self.state.columns {
+                app.columns = columns;
+            }
+            app.activate_session(self.state.session, exchanges, Vec::new(), bookmarks);
         } else {
-            let selected = app.selected_exchange.min(exchanges.len().saturating_sub(1));
-            app.exchanges = exchanges;
+            app.splice_exchanges(self.from, exchanges);
+            app.bookmarks.retain(|index, _| *index < self.from);
+            app.bookmarks.extend(bookmarks);
+            let selected = app
+                .selected_exchange
+                .min(app.exchanges.len().saturating_sub(1));
             app.session = Some(self.state.session);
             app.selected_exchange = selected;
             app.mark_changed();
@@ -109,12 +166,26 @@ impl Snapshot {
     }
 }
 
+/// The first exchange a refresh has to fetch: the earliest one still pending, since only
+/// those change, or every one once the wrapper switched sessions.
+pub fn refresh_from(app: &App, state: &RemoteState) -> usize {
+    if app.session.as_ref().map(|session| &session.id) != Some(&state.session.id) {
+        return 0;
+    }
+    let context = app.status_context();
+    app.exchanges
+        .iter()
+        .position(|exchange| context.status(exchange) == ExchangeStatus::Pending)
+        .unwrap_or(app.exchanges.len())
+}
+
 fn parse_transport(name: &str) -> Result<TransportType, String> {
     match name {
         "http" => Ok(TransportType::Http),
         "http-batch" => Ok(TransportType::HttpBatch),
         "stdio-json-lines" => Ok(TransportType::Stdio(Framing::JsonLines)),
         "stdio-content-length" => Ok(TransportType::Stdio(Framing::ContentLength)),
+        "stdio-dap" => Ok(TransportType::Stdio(Framing::Dap)),
         "websocket" => Ok(TransportType::WebSocket),
         name => Err(format!("unsupported transport: {name}")),
     }
@@ -0,0 +1,204 @@
+use crate::app::{message_body, ExchangeStatus, JsonRpcExchange, StatusContext};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The exchanges sent together in one batch body, in request order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    pub id: String,
+    pub members: Vec<usize>,
+}
+
+/// How many members of a batch ended in each state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchCounts {
+    pub success: usize,
+    pub error: usize,
+    pub waiting: usize,
+}
+
+impl Batch {
+    /// The first exchange of the batch, which stands for the whole batch in the list.
+    pub fn head(&self) -> usize {
+        self.members[0]
+    }
+
+    pub fn counts(&self, exchanges: &[JsonRpcExchange], context: &StatusContext) -> BatchCounts {
+        let mut counts = BatchCounts::default();
+        for index in &self.members {
+            match context.status(&exchanges[*index]) {
+                ExchangeStatus::Success | ExchangeStatus::Notification => counts.success += 1,
+                ExchangeStatus::Error => counts.error += 1,
+                ExchangeStatus::Pending | ExchangeStatus::TimedOut | ExchangeStatus::Orphan => {
+                    counts.waiting += 1
+                }
+            }
+        }
+        counts
+    }
+
+    /// The status shown on the collapsed batch row.
+    pub fn status(&self, exchanges: &[JsonRpcExchange], context: &StatusContext) -> ExchangeStatus {
+        let statuses = self
+            .members
+            .iter()
+            .map(|index| context.status(&exchanges[*index]))
+            .collect::<Vec<_>>();
+        [
+            ExchangeStatus::TimedOut,
+            ExchangeStatus::Pending,
+            ExchangeStatus::Error,
+        ]
+        .into_iter()
+        .find(|status| statuses.contains(status))
+        .unwrap_or(ExchangeStatus::Success)
+    }
+
+    /// Time from the batch request until its last response, once every call is answered.
+    pub fn duration(&self, exchanges: &[JsonRpcExchange]) -> Option<Duration> {
+        let started = exchanges[self.head()].request.as_ref()?.timestamp;
+        let mut finished = started;
+        for index in &self.members {
+            let exchange = &exchanges[*index];
+            if exchange.is_notification() {
+                continue;
+            }
+            finished = finished.max(exchange.response.as_ref()?.timestamp);
+        }
+        finished.duration_since(started).ok()
+    }
+
+    /// The batch body as the client sent it.
+    pub fn request_body(&self, exchanges: &[JsonRpcExchange]) -> Value {
+        Value::Array(
+            self.members
+                .iter()
+                .filter_map(|index| exchanges[*index].request.as_ref())
+                .map(message_body)
+                .collect(),
+        )
+    }
+
+    /// The batch response in the order the server returned it.
+    pub fn response_body(&self, exchanges: &[JsonRpcExchange]) -> Value {
+        let mut responses = self
+            .members
+            .iter()
+            .filter_map(|index| exchanges[*index].response.as_ref())
+            .collect::<Vec<_>>();
+        responses.sort_by_key(|response| {
+            response
+                .batch
+                .as_ref()
+                .map_or(usize::MAX, |batch| batch.position)
+        });
+        Value::Array(responses.into_iter().map(message_body).collect())
+    }
+}
+
+/// Batches in the order they were sent. Single requests are not batches.
+pub fn batches(exchanges: &[JsonRpcExchange]) -> Vec<Batch> {
+    let mut batches: Vec<Batch> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    let mut order: Vec<Vec<(usize, usize)>> = Vec::new();
+
+    for (index, exchange) in exchanges.iter().enumerate() {
+        let Some(batch) = exchange
+            .request
+            .as_ref()
+            .and_then(|request| request.batch.as_ref())
+        else {
+            continue;
+        };
+        let slot = *positions.entry(batch.id.as_str()).or_insert_with(|| {
+            batches.push(Batch {
+                id: batch.id.clone(),
+                members: Vec::new(),
+            });
+            order.push(Vec::new());
+            batches.len() - 1
+        });
+        order[slot].push((batch.position, index));
+    }
+
+    for (batch, mut members) in batches.iter_mut().zip(order) {
+        members.sort_unstable();
+        batch.members = members.into_iter().map(|(_, index)| index).collect();
+    }
+    batches
+}
+
+/// The batch that `index` belongs to.
+pub fn containing(batches: &[Batch], index: usize) -> Option<&Batch> {
+    batches.iter().find(|batch| batch.members.contains(&index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{testing, TransportType};
+    use serde_json::json;
+    use std::time::SystemTime;
+
+    #[test]
+    fn batch_members_keep_request_and_response_order() {
+        let mut app = testing::app(
+            TransportType::HttpBatch,
+            [
+                (
+                    json!([
+                        {"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"},
+                        {"jsonrpc": "2.0", "id": 2, "method": "eth_blockNumber"},
+                        {"jsonrpc": "2.0", "method": "eth_ping"}
+                    ]),
+                    0,
+                ),
+                (
+                    json!([
+                        {"jsonrpc": "2.0", "id": 2, "error": {"code": -32000, "message": "busy"}},
+                        {"jsonrpc": "2.0", "id": 1, "result": "0x1"}
+                    ]),
+                    40,
+                ),
+            ],
+        );
+        testing::record(
+            &mut app,
+            &json!({"jsonrpc": "2.0", "id": 3, "method": "eth_gasPrice"}),
+            TransportType::Http,
+            50,
+        );
+
+        let batches = batches(&app.exchanges);
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.members, vec![0, 1, 2]);
+        assert_eq!(containing(&batches, 3), None);
+
+        let context = StatusContext::new(
+            &app.exchanges,
+            Some(Duration::from_secs(30)),
+            SystemTime::now(),
+        );
+        assert_eq!(
+            batch.counts(&app.exchanges, &context),
+            BatchCounts {
+                success: 2,
+                error: 1,
+                waiting: 0
+            }
+        );
+        assert_eq!(
+            batch.status(&app.exchanges, &context),
+            ExchangeStatus::Error
+        );
+        assert_eq!(
+            batch.duration(&app.exchanges),
+            Some(Duration::from_millis(40))
+        );
+        assert_eq!(batch.request_body(&app.exchanges)[2]["method"], "eth_ping");
+        assert_eq!(batch.response_body(&app.exchanges)[0]["id"], 2);
+        assert_eq!(batch.response_body(&app.exchanges)[1]["id"], 1);
+    }
+}
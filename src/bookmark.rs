@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+/// A star and tags on a whole exchange, such as `bug` or `baseline`. Tags are kept sorted
+/// and unique, and never contain whitespace or commas so they survive the filter syntax.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub starred: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Bookmark {
+    pub fn is_empty(&self) -> bool {
+        !self.starred && self.tags.is_empty()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own.eq_ignore_ascii_case(tag))
+    }
+
+    pub fn set_tags<I, S>(&mut self, tags: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.tags.clear();
+        self.add_tags(tags);
+    }
+
+    pub fn add_tags<I, S>(&mut self, tags: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for tag in tags {
+            for tag in split(tag.as_ref()) {
+                if !self.has_tag(tag) {
+                    self.tags.push(tag.to_string());
+                }
+            }
+        }
+        self.tags.sort();
+    }
+
+    pub fn remove_tags<I, S>(&mut self, tags: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for tag in tags {
+            for tag in split(tag.as_ref()) {
+                self.tags.retain(|own| !own.eq_ignore_ascii_case(tag));
+            }
+        }
+    }
+
+    /// Stars and tags a request list cell, as in `★ eth_call #baseline #bug`.
+    pub fn decorate(&self, text: &str) -> String {
+        let star = if self.starred { "★ " } else { "" };
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| format!(" #{tag}"))
+            .collect::<String>();
+        format!("{star}{text}{tags}")
+    }
+}
+
+/// Matches a `tag:<name>` filter term, or any tag for a bare `tag:`, and `is:starred`.
+/// Returns `None` for other terms.
+pub fn matches_term(bookmark: Option<&Bookmark>, term: &str) -> Option<bool> {
+    if let Some(wanted) = term.strip_prefix("tag:") {
+        return Some(bookmark.is_some_and(|bookmark| {
+            if wanted.is_empty() {
+                !bookmark.tags.is_empty()
+            } else {
+                bookmark.has_tag(wanted)
+            }
+        }));
+    }
+    term.eq_ignore_ascii_case("is:starred")
+        .then(|| bookmark.is_some_and(|bookmark| bookmark.starred))
+}
+
+/// Splits typed text such as `bug, slow baseline` into tags.
+fn split(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|character: char| character == ',' || character.is_whitespace())
+        .filter(|tag| !tag.is_empty())
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_split_deduplicated_and_sorted() {
+        let mut bookmark = Bookmark::default();
+        bookmark.set_tags(["slow, bug", "Bug baseline"]);
+        assert_eq!(bookmark.tags, ["baseline", "bug", "slow"]);
+
+        bookmark.remove_tags(["SLOW"]);
+        bookmark.starred = true;
+        assert_eq!(bookmark.decorate("eth_call"), "★ eth_call #baseline #bug");
+        assert_eq!(matches_term(Some(&bookmark), "tag:BUG"), Some(true));
+        assert_eq!(matches_term(None, "tag:"), Some(false));
+        assert_eq!(matches_term(None, "eth_call"), None);
+    }
+}
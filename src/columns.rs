@@ -0,0 +1,426 @@
+use crate::app::{display_id, exchange_duration, message_body, JsonRpcExchange, StatusContext};
+use crate::{query, timeline};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::time::SystemTime;
+
+pub const MIN_WIDTH: u16 = 4;
+pub const MAX_WIDTH: u16 = 80;
+
+/// A column the request list can show. The list keeps them in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Column {
+    Status,
+    Transport,
+    Client,
+    Source,
+    Method,
+    Id,
+    Duration,
+    RequestSize,
+    ResponseSize,
+    ErrorCode,
+    Time,
+    FirstParam,
+}
+
+impl Column {
+    pub const ALL: [Self; 12] = [
+        Self::Status,
+        Self::Transport,
+        Self::Client,
+        Self::Source,
+        Self::Method,
+        Self::Id,
+        Self::Duration,
+        Self::RequestSize,
+        Self::ResponseSize,
+        Self::ErrorCode,
+        Self::Time,
+        Self::FirstParam,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Transport => "transport",
+            Self::Client => "client",
+            Self::Source => "source",
+            Self::Method => "method",
+            Self::Id => "id",
+            Self::Duration => "duration",
+            Self::RequestSize => "requestSize",
+            Self::ResponseSize => "responseSize",
+            Self::ErrorCode => "errorCode",
+            Self::Time => "time",
+            Self::FirstParam => "firstParam",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|column| column.name() == name)
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Status => "Status",
+            Self::Transport => "Transport",
+            Self::Client => "Client",
+            Self::Source => "Source",
+            Self::Method => "Method",
+            Self::Id => "ID",
+            Self::Duration => "Duration",
+            Self::RequestSize => "Req size",
+            Self::ResponseSize => "Resp size",
+            Self::ErrorCode => "Error",
+            Self::Time => "Time",
+            Self::FirstParam => "First param",
+        }
+    }
+
+    pub fn default_width(self) -> u16 {
+        match self {
+            Self::Status | Self::Id => 12,
+            Self::Transport => 11,
+            Self::Client | Self::FirstParam => 16,
+            Self::Method => 15,
+            Self::Source => 10,
+            Self::Duration | Self::RequestSize | Self::ResponseSize | Self::Time => 10,
+            Self::ErrorCode => 8,
+        }
+    }
+
+    /// The cell for `exchange`, as copied to Markdown. `origin` is when the session started.
+    pub fn text(
+        self,
+        exchange: &JsonRpcExchange,
+        context: &StatusContext,
+        origin: Option<SystemTime>,
+    ) -> String {
+        match self {
+            Self::Status => match context.lateness(exchange) {
+                Some(lateness) => format!(
+                    "{} (late after {})",
+                    context.status(exchange).label(),
+                    lateness.name()
+                ),
+                None => context.status(exchange).label().to_string(),
+            },
+            Self::Transport => exchange.transport.label().to_string(),
+            Self::Client => exchange.client().unwrap_or("-").to_string(),
+            Self::Source => exchange.source().unwrap_or("-").to_string(),
+            Self::Method => exchange.method.as_deref().unwrap_or("unknown").to_string(),
+            Self::Id => display_id(exchange.id.as_ref()),
+            Self::Duration => exchange_duration(exchange),
+            Self::RequestSize => {
+                body_size(exchange.request.as_ref()).map_or("-".to_string(), format_size)
+            }
+            Self::ResponseSize => {
+                body_size(exchange.response.as_ref()).map_or("-".to_string(), format_size)
+            }
+            Self::ErrorCode => {
+                error_code(exchange).map_or("-".to_string(), |code| code.to_string())
+            }
+            Self::Time => since(exchange, origin).map_or("-".to_string(), |elapsed| {
+                format!("+{}", timeline::format_duration(elapsed))
+            }),
+            Self::FirstParam => first_param(exchange).map_or("-".to_string(), |param| {
+                query::cell(std::slice::from_ref(param))
+            }),
+        }
+    }
+
+    /// What the list sorts on: numbers for numeric columns, text otherwise.
+    pub fn key(
+        self,
+        exchange: &JsonRpcExchange,
+        context: &StatusContext,
+        origin: Option<SystemTime>,
+    ) -> Key {
+        let number = |value: Option<u128>| value.map_or(Key::Missing, Key::Number);
+        match self {
+            Self::Duration => number(
+                exchange
+                    .request
+                    .as_ref()
+                    .zip(exchange.response.as_ref())
+                    .and_then(|(request, response)| {
+                        response.timestamp.duration_since(request.timestamp).ok()
+                    })
+                    .map(|duration| duration.as_micros()),
+            ),
+            Self::RequestSize => number(body_size(exchange.request.as_ref()).map(u128::from)),
+            Self::ResponseSize => number(body_size(exchange.response.as_ref()).map(u128::from)),
+            Self::ErrorCode => error_code(exchange).map_or(Key::Missing, Key::Signed),
+            Self::Time => number(since(exchange, origin).map(|elapsed| elapsed.as_micros())),
+            Self::Id => value_key(exchange.id.as_ref()),
+            Self::FirstParam => value_key(first_param(exchange)),
+            Self::Client if exchange.client().is_none() => Key::Missing,
+            Self::Source if exchange.source().is_none() => Key::Missing,
+            _ => Key::Text(self.text(exchange, context, origin).to_lowercase()),
+        }
+    }
+}
+
+/// A sort key. Exchanges without a value sort first, numbers before text.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Key {
+    Missing,
+    Signed(i64),
+    Number(u128),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnWidth {
+    pub column: Column,
+    pub width: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sort {
+    pub column: Column,
+    pub descending: bool,
+}
+
+/// Which columns the request list shows, how wide, and what it is sorted by. Without a
+/// sort the list stays in arrival order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Layout {
+    pub columns: Vec<ColumnWidth>,
+    pub sort: Option<Sort>,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        let columns = [
+            Column::Status,
+            Column::Transport,
+            Column::Client,
+            Column::Method,
+            Column::Id,
+            Column::Duration,
+        ];
+        Self {
+            columns: columns
+                .into_iter()
+                .map(|column| ColumnWidth {
+                    column,
+                    width: column.default_width(),
+                })
+                .collect(),
+            sort: None,
+        }
+    }
+}
+
+impl Layout {
+    pub fn width(&self, column: Column) -> Option<u16> {
+        self.columns
+            .iter()
+            .find(|shown| shown.column == column)
+            .map(|shown| shown.width)
+    }
+
+    pub fn is_shown(&self, column: Column) -> bool {
+        self.width(column).is_some()
+    }
+
+    /// Shows or hides `column`. The last shown column stays.
+    pub fn set_shown(&mut self, column: Column, shown: bool) {
+        if shown == self.is_shown(column) || (!shown && self.columns.len() == 1) {
+            return;
+        }
+        if shown {
+            self.columns.push(ColumnWidth {
+                column,
+                width: column.default_width(),
+            });
+            self.columns.sort_by_key(|shown| {
+                Column::ALL
+                    .iter()
+                    .position(|column| *column == shown.column)
+            });
+        } else {
+            self.columns.retain(|shown| shown.column != column);
+        }
+    }
+
+    pub fn set_width(&mut self, column: Column, width: u16) {
+        if let Some(shown) = self.columns.iter_mut().find(|shown| shown.column == column) {
+            shown.width = width.clamp(MIN_WIDTH, MAX_WIDTH);
+        }
+    }
+
+    /// Cycles the sort on `column`: ascending, then descending, then arrival order.
+    pub fn cycle_sort(&mut self, column: Column) {
+        self.sort = match self.sort {
+            Some(sort) if sort.column == column && !sort.descending => Some(Sort {
+                column,
+                descending: true,
+            }),
+            Some(sort) if sort.column == column => None,
+            _ => Some(Sort {
+                column,
+                descending: false,
+            }),
+        };
+    }
+
+    /// Reorders `indices` into `exchanges` by the sort, keeping arrival order among ties.
+    pub fn sort(
+        &self,
+        indices: &mut [usize],
+        exchanges: &[JsonRpcExchange],
+        context: &StatusContext,
+    ) {
+        let Some(sort) = self.sort else {
+            return;
+        };
+        let origin = session_origin(exchanges);
+        let key = |index: &usize| sort.column.key(&exchanges[*index], context, origin);
+        if sort.descending {
+            indices.sort_by_cached_key(|index| Reverse(key(index)));
+        } else {
+            indices.sort_by_cached_key(key);
+        }
+    }
+}
+
+/// When the first exchange of a session was seen.
+pub fn session_origin(exchanges: &[JsonRpcExchange]) -> Option<SystemTime> {
+    exchanges.iter().map(|exchange| exchange.timestamp).min()
+}
+
+fn since(exchange: &JsonRpcExchange, origin: Option<SystemTime>) -> Option<std::time::Duration> {
+    exchange.timestamp.duration_since(origin?).ok()
+}
+
+fn body_size(message: Option<&crate::app::JsonRpcMessage>) -> Option<u64> {
+    message.map(|message| message_body(message).to_string().len() as u64)
+}
+
+fn error_code(exchange: &JsonRpcExchange) -> Option<i64> {
+    exchange
+        .response
+        .as_ref()?
+        .error
+        .as_ref()?
+        .get("code")?
+        .as_i64()
+}
+
+// The first positional parameter, or the first named one.
+fn first_param(exchange: &JsonRpcExchange) -> Option<&Value> {
+    match exchange.request.as_ref()?.params.as_ref()? {
+        Value::Array(items) => items.first(),
+        Value::Object(map) => map.values().next(),
+        value => Some(value),
+    }
+}
+
+fn value_key(value: Option<&Value>) -> Key {
+    match value {
+        None | Some(Value::Null) => Key::Missing,
+        Some(Value::Number(number)) => number
+            .as_i64()
+            .map_or_else(|| Key::Text(number.to_string()), Key::Signed),
+        Some(value) => Key::Text(query::cell(std::slice::from_ref(value)).to_lowercase()),
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{bytes} B"),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{testing, TransportType};
+    use serde_json::json;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn exchange(id: u64, params: Value, millis: u64, error: Option<Value>) -> JsonRpcExchange {
+        let response = match error {
+            Some(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+            None => json!({"jsonrpc": "2.0", "id": id, "result": "0x1"}),
+        };
+        testing::app(
+            TransportType::Http,
+            [
+                (
+                    json!({"jsonrpc": "2.0", "id": id, "method": "eth_call", "params": params}),
+                    id * 1000,
+                ),
+                (response, id * 1000 + millis),
+            ],
+        )
+        .exchanges
+        .remove(0)
+    }
+
+    #[test]
+    fn sorting_keeps_arrival_order_among_ties_in_both_directions() {
+        let exchanges = [
+            exchange(0, json!(["0xb"]), 20, None),
+            exchange(1, json!({"to": "0xa"}), 5, Some(json!({"code": -32000}))),
+            exchange(2, json!([]), 20, None),
+        ];
+        let context = StatusContext::new(&exchanges, Some(Duration::from_secs(30)), UNIX_EPOCH);
+        let sorted = |column, descending| {
+            let layout = Layout {
+                sort: Some(Sort { column, descending }),
+                ..Layout::default()
+            };
+            let mut indices = vec![0, 1, 2];
+            layout.sort(&mut indices, &exchanges, &context);
+            indices
+        };
+
+        assert_eq!(sorted(Column::Duration, false), [1, 0, 2]);
+        assert_eq!(sorted(Column::Duration, true), [0, 2, 1]);
+        assert_eq!(sorted(Column::FirstParam, false), [2, 1, 0]);
+        assert_eq!(sorted(Column::ErrorCode, true), [1, 0, 2]);
+
+        let origin = session_origin(&exchanges);
+        let text = |column: Column, index: usize| column.text(&exchanges[index], &context, origin);
+        assert_eq!(text(Column::FirstParam, 1), "0xa");
+        assert_eq!(text(Column::ErrorCode, 1), "-32000");
+        assert_eq!(text(Column::Time, 2), "+2.00s");
+        assert_eq!(text(Column::RequestSize, 2), "56 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+
+        let mut layout = Layout::default();
+        layout.cycle_sort(Column::Time);
+        layout.cycle_sort(Column::Time);
+        assert_eq!(
+            layout.sort,
+            Some(Sort {
+                column: Column::Time,
+                descending: true
+            })
+        );
+        layout.cycle_sort(Column::Time);
+        assert_eq!(layout.sort, None);
+
+        layout.set_shown(Column::ErrorCode, true);
+        layout.set_width(Column::ErrorCode, 200);
+        let shown = layout
+            .columns
+            .iter()
+            .map(|shown| shown.column)
+            .collect::<Vec<_>>();
+        assert_eq!(shown[6], Column::ErrorCode);
+        assert_eq!(layout.width(Column::ErrorCode), Some(MAX_WIDTH));
+        for column in Column::ALL {
+            layout.set_shown(column, false);
+        }
+        assert_eq!(layout.columns.len(), 1);
+    }
+}
@@ -1,7 +1,17 @@
 use crate::app::{
-    App, AppMode, DetailTab, Focus, JsonRpcExchange, JsonRpcMessage, LineAnnotation,
-    MessageDirection, Overlay, SessionSummary, TransportType,
+    json_rpc_messages_by_shape, message_body, App, AppMode, BatchPosition, Connection, DetailTab,
+    ExchangeStatus, Focus, JsonRpcExchange, JsonRpcMessage, Lateness, LineAnnotation,
+    MessageDirection, Overlay, SessionSummary, StatusContext, Timing, TransportType,
 };
+use crate::batch;
+use crate::bookmark::Bookmark;
+use crate::columns::{self, Column, ColumnWidth, Sort};
+use crate::diff::{self, Comparison, Difference};
+use crate::mcp;
+use crate::query::Query;
+use crate::report;
+use crate::snippet;
+use crate::subscription;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{
@@ -44,12 +54,39 @@ pub enum ControlAction {
         id: String,
         name: String,
     },
-    ExportSession,
+    ExportSession {
+        from: usize,
+    },
     ReplaySession {
         session: Session,
     },
+    ExportHar {
+        session_id: Option<String>,
+    },
+    ImportHar {
+        har: Value,
+    },
+    ExportReport {
+        format: report::Format,
+        filter: String,
+    },
+    ImportTrace {
+        text: String,
+        name: Option<String>,
+        target: String,
+    },
+    ExportFixtures {
+        dir: String,
+        filter: String,
+    },
+    ExportSnippet {
+        format: snippet::Format,
+        index: Option<usize>,
+        via_proxy: bool,
+    },
     SendRequest {
         request: Value,
+        listener: Option<String>,
     },
     SelectExchange {
         index: usize,
@@ -60,6 +97,10 @@ pub enum ControlAction {
     SetFullscreen {
         fullscreen: bool,
     },
+    SetTimeline {
+        visible: bool,
+        zoom: Option<u32>,
+    },
     RevealLines {
         focus: Focus,
         start_line: usize,
@@ -83,20 +124,62 @@ pub enum ControlAction {
     },
     SetTarget {
         url: String,
+        listener: Option<String>,
+    },
+    SelectProfile {
+        name: String,
     },
     SetFilter {
         text: String,
     },
+    SetRequestTimeout {
+        timeout_ms: u64,
+    },
     SetPaused {
         paused: bool,
     },
     GetPending,
+    GetMcpSummary,
+    GetSubscriptions,
+    GetBatches,
+    DiffExchanges {
+        left: ExchangeRef,
+        right: ExchangeRef,
+    },
+    DiffSessions {
+        left: String,
+        right: Option<String>,
+    },
+    QueryExchanges {
+        query: Query,
+        pin: bool,
+    },
+    SetColumns {
+        shown: Option<Vec<Column>>,
+        widths: Vec<ColumnWidth>,
+        /// `Some(None)` returns the list to arrival order.
+        sort: Option<Option<Sort>>,
+    },
+    TagExchange {
+        index: Option<usize>,
+        starred: Option<bool>,
+        tags: Option<Vec<String>>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
     ResolvePending {
         id: String,
         decision: PendingDecision,
     },
 }
 
+/// An exchange by index, in the active session unless `session_id` names another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExchangeRef {
+    pub session_id: Option<String>,
+    pub index: usize,
+}
+
 #[derive(Debug)]
 pub enum PendingDecision {
     Allow {
@@ -126,6 +209,8 @@ pub struct SessionExchange {
     pub request: Option<SessionMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response: Option<SessionMessage>,
+    #[serde(flatten)]
+    pub bookmark: Bookmark,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -138,6 +223,8 @@ pub enum SessionTransport {
     StdioJsonLines,
     #[serde(rename = "stdio-content-length")]
     StdioContentLength,
+    #[serde(rename = "stdio-dap")]
+    StdioDap,
     Websocket,
 }
 
@@ -148,6 +235,80 @@ pub struct SessionMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<HashMap<String, String>>,
     pub timestamp_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<SessionConnection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch: Option<SessionBatch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<SessionTiming>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionConnection {
+    pub id: String,
+    pub client: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionBatch {
+    pub id: String,
+    pub position: usize,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTiming {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queued_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_byte_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery_ms: Option<f64>,
+}
+
+impl From<&Timing> for SessionTiming {
+    fn from(timing: &Timing) -> Self {
+        let ms =
+            |duration: Option<Duration>| duration.map(|duration| duration.as_secs_f64() * 1000.0);
+        Self {
+            queued_ms: ms(timing.queued),
+            dns_ms: ms(timing.dns),
+            connect_ms: ms(timing.connect),
+            tls_ms: ms(timing.tls),
+            first_byte_ms: ms(timing.first_byte),
+            download_ms: ms(timing.download),
+            delivery_ms: ms(timing.delivery),
+        }
+    }
+}
+
+impl From<SessionTiming> for Timing {
+    fn from(timing: SessionTiming) -> Self {
+        let duration = |ms: Option<f64>| {
+            ms.filter(|ms| ms.is_finite() && *ms >= 0.0)
+                .map(|ms| Duration::from_secs_f64(ms / 1000.0))
+        };
+        Self {
+            queued: duration(timing.queued_ms),
+            dns: duration(timing.dns_ms),
+            connect: duration(timing.connect_ms),
+            tls: duration(timing.tls_ms),
+            first_byte: duration(timing.first_byte_ms),
+            download: duration(timing.download_ms),
+            delivery: duration(timing.delivery_ms),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -298,14 +459,52 @@ fn parse_request(request: &Value) -> Result<ControlAction, ControlError> {
             id: required_string(params, 0, "sessionId")?.to_string(),
             name: required_string(params, 1, "name")?.to_string(),
         }),
-        "debugger.exportSession" => Ok(ControlAction::ExportSession),
+        "debugger.exportSession" => Ok(ControlAction::ExportSession {
+            from: optional_usize(params, 0, "from")?.unwrap_or(0),
+        }),
         "debugger.replaySession" => Ok(ControlAction::ReplaySession {
             session: serde_json::from_value(required(params, 0, "session")?.clone()).map_err(
                 |error| ControlError::invalid_params(format!("invalid session: {error}")),
             )?,
         }),
+        "debugger.exportHar" => Ok(ControlAction::ExportHar {
+            session_id: optional_string(params, 0, "sessionId")?.map(str::to_string),
+        }),
+        "debugger.importHar" => Ok(ControlAction::ImportHar {
+            har: required(params, 0, "har")?.clone(),
+        }),
+        "debugger.exportReport" => Ok(ControlAction::ExportReport {
+            format: report::Format::parse(required_string(params, 0, "format")?)
+                .ok_or_else(|| ControlError::invalid_params("format must be markdown or html"))?,
+            filter: optional_string(params, 1, "filter")?
+                .unwrap_or_default()
+                .to_string(),
+        }),
+        "debugger.importTrace" => Ok(ControlAction::ImportTrace {
+            text: required_string(params, 0, "text")?.to_string(),
+            name: optional_string(params, 1, "name")?.map(str::to_string),
+            target: optional_string(params, 2, "target")?
+                .unwrap_or("trace")
+                .to_string(),
+        }),
+        "debugger.exportFixtures" => Ok(ControlAction::ExportFixtures {
+            dir: required_string(params, 0, "dir")?.to_string(),
+            filter: optional_string(params, 1, "filter")?
+                .unwrap_or_default()
+                .to_string(),
+        }),
+        "debugger.exportSnippet" => Ok(ControlAction::ExportSnippet {
+            format: parse_snippet_format(required_string(params, 0, "format")?)?,
+            index: optional_usize(params, 1, "index")?,
+            via_proxy: match optional_string(params, 2, "via")?.unwrap_or("target") {
+                "target" => false,
+                "proxy" => true,
+                _ => return Err(ControlError::invalid_params("via must be target or proxy")),
+            },
+        }),
         "debugger.sendRequest" => Ok(ControlAction::SendRequest {
             request: required(params, 0, "request")?.clone(),
+            listener: optional_string(params, 1, "listener")?.map(str::to_string),
         }),
         "debugger.selectExchange" => Ok(ControlAction::SelectExchange {
             index: required_usize(params, 0, "index")?,
@@ -316,6 +515,11 @@ fn parse_request(request: &Value) -> Result<ControlAction, ControlError> {
         "debugger.setFullscreen" => Ok(ControlAction::SetFullscreen {
             fullscreen: required_bool(params, 0, "fullscreen")?,
         }),
+        "debugger.setTimeline" => Ok(ControlAction::SetTimeline {
+            visible: required_bool(params, 0, "visible")?,
+            zoom: optional_u64(params, 1, "zoom")?
+                .map(|zoom| u32::try_from(zoom).unwrap_or(u32::MAX)),
+        }),
         "debugger.revealLines" => {
             let start_line = required_usize(params, 1, "startLine")?;
             Ok(ControlAction::RevealLines {
@@ -347,14 +551,74 @@ fn parse_request(request: &Value) -> Result<ControlAction, ControlError> {
         }),
         "debugger.setTarget" => Ok(ControlAction::SetTarget {
             url: required_string(params, 0, "url")?.to_string(),
+            listener: optional_string(params, 1, "listener")?.map(str::to_string),
+        }),
+        "debugger.selectProfile" => Ok(ControlAction::SelectProfile {
+            name: required_string(params, 0, "name")?.to_string(),
         }),
         "debugger.setFilter" => Ok(ControlAction::SetFilter {
             text: required_string(params, 0, "text")?.to_string(),
         }),
+        "debugger.setRequestTimeout" => {
+            let timeout_ms = required_u64(params, 0, "timeoutMs")?;
+            if timeout_ms == 0 {
+                return Err(ControlError::invalid_params("timeoutMs must be positive"));
+            }
+            Ok(ControlAction::SetRequestTimeout { timeout_ms })
+        }
         "debugger.setPaused" => Ok(ControlAction::SetPaused {
             paused: required_bool(params, 0, "paused")?,
         }),
         "debugger.getPending" => Ok(ControlAction::GetPending),
+        "debugger.getMcpSummary" => Ok(ControlAction::GetMcpSummary),
+        "debugger.getSubscriptions" => Ok(ControlAction::GetSubscriptions),
+        "debugger.getBatches" => Ok(ControlAction::GetBatches),
+        "debugger.diffExchanges" => Ok(ControlAction::DiffExchanges {
+            left: parse_exchange_ref(required(params, 0, "left")?)?,
+            right: parse_exchange_ref(required(params, 1, "right")?)?,
+        }),
+        "debugger.diffSessions" => Ok(ControlAction::DiffSessions {
+            left: required_string(params, 0, "left")?.to_string(),
+            right: optional_string(params, 1, "right")?.map(str::to_string),
+        }),
+        "debugger.queryExchanges" => Ok(ControlAction::QueryExchanges {
+            query: Query::parse(required_string(params, 0, "expression")?)
+                .map_err(ControlError::invalid_params)?,
+            pin: optional_bool(params, 1, "pin")?.unwrap_or(false),
+        }),
+        "debugger.setColumns" => {
+            let descending = optional_bool(params, 3, "descending")?.unwrap_or(false);
+            Ok(ControlAction::SetColumns {
+                shown: optional(params, 0, "columns")
+                    .map(parse_shown_columns)
+                    .transpose()?,
+                widths: optional(params, 1, "widths")
+                    .map(parse_column_widths)
+                    .transpose()?
+                    .unwrap_or_default(),
+                sort: match optional(params, 2, "sort") {
+                    None => None,
+                    Some(Value::Null) => Some(None),
+                    Some(column) => Some(Some(Sort {
+                        column: parse_column(column.as_str().unwrap_or_default())?,
+                        descending,
+                    })),
+                },
+            })
+        }
+        "debugger.tagExchange" => Ok(ControlAction::TagExchange {
+            index: optional_usize(params, 0, "index")?,
+            starred: optional_bool(params, 1, "starred")?,
+            tags: optional(params, 2, "tags").map(parse_tags).transpose()?,
+            add: optional(params, 3, "addTags")
+                .map(parse_tags)
+                .transpose()?
+                .unwrap_or_default(),
+            remove: optional(params, 4, "removeTags")
+                .map(parse_tags)
+                .transpose()?
+                .unwrap_or_default(),
+        }),
         "debugger.resolvePending" => parse_pending_decision(params),
         _ => Err(ControlError {
             code: -32601,
@@ -384,6 +648,20 @@ fn parse_pending_decision(params: &Value) -> Result<ControlAction, ControlError>
     Ok(ControlAction::ResolvePending { id, decision })
 }
 
+// An exchange is an index, or `{index, sessionId}` to reach into another session.
+fn parse_exchange_ref(value: &Value) -> Result<ExchangeRef, ControlError> {
+    if let Some(index) = value.as_u64().and_then(|index| usize::try_from(index).ok()) {
+        return Ok(ExchangeRef {
+            session_id: None,
+            index,
+        });
+    }
+    Ok(ExchangeRef {
+        session_id: optional_string(value, 1, "sessionId")?.map(str::to_string),
+        index: required_usize(value, 0, "index")?,
+    })
+}
+
 fn parameter<'a>(params: &'a Value, index: usize, name: &str) -> Option<&'a Value> {
     match params {
         Value::Object(params) => params.get(name),
@@ -432,6 +710,16 @@ fn required_bool(params: &Value, index: usize, name: &str) -> Result<bool, Contr
         .ok_or_else(|| ControlError::invalid_params(format!("{name} must be a boolean")))
 }
 
+fn optional_bool(params: &Value, index: usize, name: &str) -> Result<Option<bool>, ControlError> {
+    let Some(value) = optional(params, index, name) else {
+        return Ok(None);
+    };
+    value
+        .as_bool()
+        .map(Some)
+        .ok_or_else(|| ControlError::invalid_params(format!("{name} must be a boolean")))
+}
+
 fn required_usize(params: &Value, index: usize, name: &str) -> Result<usize, ControlError> {
     required(params, index, name)?
         .as_u64()
@@ -485,6 +773,55 @@ fn optional_headers(
         .map_err(|_| ControlError::invalid_params(format!("{name} must contain string values")))
 }
 
+fn parse_column(name: &str) -> Result<Column, ControlError> {
+    Column::parse(name).ok_or_else(|| {
+        let names = Column::ALL.map(Column::name).join(", ");
+        ControlError::invalid_params(format!("unknown column {name:?}; expected one of {names}"))
+    })
+}
+
+fn parse_shown_columns(columns: &Value) -> Result<Vec<Column>, ControlError> {
+    let shown = columns
+        .as_array()
+        .filter(|columns| !columns.is_empty())
+        .ok_or_else(|| ControlError::invalid_params("columns must name at least one column"))?;
+    shown
+        .iter()
+        .map(|name| parse_column(name.as_str().unwrap_or_default()))
+        .collect()
+}
+
+fn parse_column_widths(widths: &Value) -> Result<Vec<ColumnWidth>, ControlError> {
+    let widths = widths
+        .as_object()
+        .ok_or_else(|| ControlError::invalid_params("widths must map column names to widths"))?;
+    widths
+        .iter()
+        .map(|(name, width)| {
+            let width = width
+                .as_u64()
+                .and_then(|width| u16::try_from(width).ok())
+                .ok_or_else(|| {
+                    ControlError::invalid_params(format!("width of {name} must be an integer"))
+                })?;
+            Ok(ColumnWidth {
+                column: parse_column(name)?,
+                width,
+            })
+        })
+        .collect()
+}
+
+fn parse_tags(tags: &Value) -> Result<Vec<String>, ControlError> {
+    tags.as_array()
+        .and_then(|tags| {
+            tags.iter()
+                .map(|tag| tag.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or_else(|| ControlError::invalid_params("tags must be an array of strings"))
+}
+
 fn parse_focus(panel: &str) -> Result<Focus, ControlError> {
     match panel {
         "history" => Ok(Focus::MessageList),
@@ -497,6 +834,14 @@ fn parse_focus(panel: &str) -> Result<Focus, ControlError> {
     }
 }
 
+fn parse_snippet_format(format: &str) -> Result<snippet::Format, ControlError> {
+    snippet::Format::parse(format).ok_or_else(|| {
+        ControlError::invalid_params(
+            "format must be curl, httpie, shell, python, typescript, or rust",
+        )
+    })
+}
+
 fn parse_detail_focus(panel: &str) -> Result<Focus, ControlError> {
     match panel {
         "request" => Ok(Focus::RequestSection),
@@ -507,11 +852,22 @@ fn parse_detail_focus(panel: &str) -> Result<Focus, ControlError> {
     }
 }
 
+fn tab_name(tab: DetailTab) -> &'static str {
+    match tab {
+        DetailTab::Headers => "headers",
+        DetailTab::Body => "body",
+        DetailTab::Decoded => "decoded",
+    }
+}
+
 fn parse_detail_tab(tab: &str) -> Result<DetailTab, ControlError> {
     match tab {
         "headers" => Ok(DetailTab::Headers),
         "body" => Ok(DetailTab::Body),
-        _ => Err(ControlError::invalid_params("tab must be headers or body")),
+        "decoded" => Ok(DetailTab::Decoded),
+        _ => Err(ControlError::invalid_params(
+            "tab must be headers, body or decoded",
+        )),
     }
 }
 
@@ -549,6 +905,10 @@ pub fn state(app: &App) -> Value {
         .filter(|annotation| annotation.exchange_index == app.selected_exchange)
         .map(annotation_value)
         .collect::<Vec<_>>();
+    let bookmark = app
+        .bookmark(app.selected_exchange)
+        .cloned()
+        .unwrap_or_default();
     json!({
         "revision": app.revision(),
         "running": app.is_running,
@@ -558,13 +918,28 @@ pub fn state(app: &App) -> Value {
         "controlPort": app.control_port,
         "target": app.proxy_config.target_url,
         "transport": app.proxy_config.transport.name(),
+        "listeners": listeners(app),
+        "profile": app.profile,
+        "profiles": app.config.profiles.keys().collect::<Vec<_>>(),
         "filter": app.filter_text,
         "focus": focus_name(app.focus),
         "fullscreen": app.panel_fullscreen,
+        "timeline": {
+            "visible": app.timeline_visible,
+            "zoom": app.timeline_zoom,
+        },
+        "columns": app.columns,
+        "queryColumns": app
+            .query_columns
+            .iter()
+            .map(Query::expression)
+            .collect::<Vec<_>>(),
         "lineSelection": line_selection,
         "visualSelectionActive": app.visual_selection_active,
         "annotations": annotations,
         "activeAnnotationId": app.active_annotation_id,
+        "starred": bookmark.starred,
+        "tags": bookmark.tags,
         "cursor": {
             "requestLine": app.request_details_cursor_line,
             "responseLine": app.response_details_cursor_line,
@@ -574,17 +949,61 @@ pub fn state(app: &App) -> Value {
             "response": app.response_details_scroll,
         },
         "tabs": {
-            "request": if app.request_tab == 0 { "headers" } else { "body" },
-            "response": if app.response_tab == 0 { "headers" } else { "body" },
+            "request": tab_name(DetailTab::from_index(app.request_tab)),
+            "response": tab_name(DetailTab::from_index(app.response_tab)),
         },
         "selectedExchange": app.selected_exchange,
         "exchangeCount": app.exchanges.len(),
         "pendingCount": app.pending_requests.len(),
+        "requestTimeoutMs": app.request_timeout.map(|timeout| timeout.as_millis()),
+        "statusCounts": status_counts(app),
         "overlay": overlay_name(app.overlay),
         "session": app.session,
     })
 }
 
+/// Every proxy port, starting with the main one, when more than one runs.
+fn listeners(app: &App) -> Value {
+    let config = &app.proxy_config;
+    let Some(main) = config.source() else {
+        return json!([]);
+    };
+    let listeners = config.listeners.iter().map(|listener| {
+        json!({
+            "name": listener.name,
+            "port": listener.listen_port,
+            "target": listener.target_url,
+            "transport": listener.transport().name(),
+        })
+    });
+    json!(std::iter::once(json!({
+        "name": main,
+        "port": config.listen_port,
+        "target": config.target_url,
+        "transport": config.transport.name(),
+    }))
+    .chain(listeners)
+    .collect::<Vec<_>>())
+}
+
+fn status_counts(app: &App) -> Value {
+    let context = app.status_context();
+    let mut counts = json!({"pending": 0, "timedOut": 0, "orphan": 0, "late": 0});
+    for exchange in &app.exchanges {
+        let status = context.status(exchange);
+        if matches!(
+            status,
+            ExchangeStatus::Pending | ExchangeStatus::TimedOut | ExchangeStatus::Orphan
+        ) {
+            counts[status.name()] = json!(counts[status.name()].as_u64().unwrap_or(0) + 1);
+        }
+        if context.lateness(exchange).is_some() {
+            counts["late"] = json!(counts["late"].as_u64().unwrap_or(0) + 1);
+        }
+    }
+    counts
+}
+
 pub fn change(app: &App, changed: bool) -> Value {
     json!({
         "changed": changed,
@@ -604,11 +1023,24 @@ pub fn panel(focus: Focus, lines: Vec<String>) -> Value {
     })
 }
 
-pub fn stored_history(exchanges: Vec<(usize, JsonRpcExchange)>) -> Value {
+/// A page of stored history in the request list's sort order.
+pub fn stored_history(
+    exchanges: Vec<(usize, JsonRpcExchange)>,
+    bookmarks: &HashMap<usize, Bookmark>,
+    timeout: Option<Duration>,
+    layout: &columns::Layout,
+) -> Value {
+    let (indices, page): (Vec<usize>, Vec<JsonRpcExchange>) = exchanges.into_iter().unzip();
+    let context = StatusContext::new(&page, timeout, SystemTime::now());
+    let mut order = (0..page.len()).collect::<Vec<_>>();
+    layout.sort(&mut order, &page, &context);
     Value::Array(
-        exchanges
+        order
             .into_iter()
-            .map(|(index, exchange)| exchange_value(index, &exchange))
+            .map(|position| {
+                let index = indices[position];
+                exchange_value(index, &page[position], bookmarks.get(&index), &context)
+            })
             .collect(),
     )
 }
@@ -617,16 +1049,34 @@ pub fn sessions(sessions: Vec<SessionSummary>) -> Value {
     serde_json::to_value(sessions).expect("session summaries are serializable")
 }
 
-#[cfg(test)]
 pub fn export_session(app: &App) -> Session {
     Session {
         schema_version: 1,
         exported_at_ms: timestamp_ms(SystemTime::now()),
         target: app.proxy_config.target_url.clone(),
-        exchanges: app.exchanges.iter().map(SessionExchange::from).collect(),
+        exchanges: app
+            .exchanges
+            .iter()
+            .enumerate()
+            .map(|(index, exchange)| SessionExchange {
+                bookmark: app.bookmark(index).cloned().unwrap_or_default(),
+                ..SessionExchange::from(exchange)
+            })
+            .collect(),
     }
 }
 
+/// Stars and tags of a portable session, by position.
+pub fn session_bookmarks(session: &Session) -> HashMap<usize, Bookmark> {
+    session
+        .exchanges
+        .iter()
+        .enumerate()
+        .filter(|(_, exchange)| !exchange.bookmark.is_empty())
+        .map(|(index, exchange)| (index, exchange.bookmark.clone()))
+        .collect()
+}
+
 pub fn replay_session(session: Session) -> Result<Vec<JsonRpcExchange>, ControlError> {
     if session.schema_version != 1 {
         return Err(ControlError::invalid_params(format!(
@@ -659,10 +1109,12 @@ impl From<&JsonRpcExchange> for SessionExchange {
                 TransportType::Stdio(crate::app::Framing::ContentLength) => {
                     SessionTransport::StdioContentLength
                 }
+                TransportType::Stdio(crate::app::Framing::Dap) => SessionTransport::StdioDap,
                 TransportType::WebSocket => SessionTransport::Websocket,
             },
             request: exchange.request.as_ref().map(SessionMessage::from),
             response: exchange.response.as_ref().map(SessionMessage::from),
+            bookmark: Bookmark::default(),
         }
     }
 }
@@ -673,6 +1125,20 @@ impl From<&JsonRpcMessage> for SessionMessage {
             body: message_body(message),
             headers: message.headers.clone(),
             timestamp_ms: timestamp_ms(message.timestamp),
+            connection: message
+                .connection
+                .as_ref()
+                .map(|connection| SessionConnection {
+                    id: connection.id.clone(),
+                    client: connection.client.clone(),
+                    source: connection.source.clone(),
+                }),
+            batch: message.batch.as_ref().map(|batch| SessionBatch {
+                id: batch.id.clone(),
+                position: batch.position,
+                size: batch.size,
+            }),
+            timing: message.timing.as_ref().map(SessionTiming::from),
         }
     }
 }
@@ -685,17 +1151,7 @@ impl TryFrom<SessionExchange> for JsonRpcExchange {
             return Err("request or response is required".to_string());
         }
 
-        let transport = match exchange.transport {
-            SessionTransport::Http => TransportType::Http,
-            SessionTransport::HttpBatch => TransportType::HttpBatch,
-            SessionTransport::StdioJsonLines => {
-                TransportType::Stdio(crate::app::Framing::JsonLines)
-            }
-            SessionTransport::StdioContentLength => {
-                TransportType::Stdio(crate::app::Framing::ContentLength)
-            }
-            SessionTransport::Websocket => TransportType::WebSocket,
-        };
+        let transport = TransportType::from(exchange.transport);
         let request = exchange
             .request
             .map(|message| session_message(message, MessageDirection::Request, transport))
@@ -726,6 +1182,39 @@ impl TryFrom<SessionExchange> for JsonRpcExchange {
     }
 }
 
+impl From<SessionTransport> for TransportType {
+    fn from(transport: SessionTransport) -> Self {
+        match transport {
+            SessionTransport::Http => Self::Http,
+            SessionTransport::HttpBatch => Self::HttpBatch,
+            SessionTransport::StdioJsonLines => Self::Stdio(crate::app::Framing::JsonLines),
+            SessionTransport::StdioContentLength => Self::Stdio(crate::app::Framing::ContentLength),
+            SessionTransport::StdioDap => Self::Stdio(crate::app::Framing::Dap),
+            SessionTransport::Websocket => Self::WebSocket,
+        }
+    }
+}
+
+impl From<SessionConnection> for Connection {
+    fn from(connection: SessionConnection) -> Self {
+        Self {
+            id: connection.id,
+            client: connection.client,
+            source: connection.source,
+        }
+    }
+}
+
+impl From<SessionBatch> for BatchPosition {
+    fn from(batch: SessionBatch) -> Self {
+        Self {
+            id: batch.id,
+            position: batch.position,
+            size: batch.size,
+        }
+    }
+}
+
 fn session_message(
     message: SessionMessage,
     direction: MessageDirection,
@@ -735,6 +1224,9 @@ fn session_message(
         .body
         .as_object()
         .ok_or_else(|| "message body must be an object".to_string())?;
+    if transport.is_dap() {
+        return dap_session_message(message, direction, transport);
+    }
     if body.get("jsonrpc") != Some(&json!("2.0")) {
         return Err("message jsonrpc must be \"2.0\"".to_string());
     }
@@ -757,10 +1249,14 @@ fn session_message(
         direction,
         transport,
         headers: message.headers,
+        connection: message.connection.map(Connection::from),
+        batch: message.batch.map(BatchPosition::from),
+        timing: message.timing.map(Timing::from),
+        seq: None,
     })
 }
 
-fn timestamp_ms(timestamp: SystemTime) -> u64 {
+pub(crate) fn timestamp_ms(timestamp: SystemTime) -> u64 {
     timestamp
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -769,18 +1265,177 @@ fn timestamp_ms(timestamp: SystemTime) -> u64 {
         .unwrap_or(u64::MAX)
 }
 
+pub fn mcp_summary(app: &App) -> Value {
+    let Some(summary) = mcp::summary(&app.exchanges) else {
+        return Value::Null;
+    };
+    let tool_calls = app
+        .exchanges
+        .iter()
+        .enumerate()
+        .filter_map(|(index, exchange)| {
+            let issues = mcp::argument_issues(&summary, exchange)?;
+            Some(json!({
+                "index": index,
+                "tool": mcp::target(exchange),
+                "issues": issues,
+            }))
+        })
+        .collect::<Vec<_>>();
+    let related = app
+        .exchanges
+        .iter()
+        .enumerate()
+        .filter_map(|(index, _)| {
+            let (relation, request) = mcp::related_request(&app.exchanges, index)?;
+            Some(json!({
+                "index": index,
+                "relation": match relation {
+                    mcp::Relation::Progress => "progress",
+                    mcp::Relation::Cancellation => "cancellation",
+                },
+                "requestIndex": request,
+            }))
+        })
+        .collect::<Vec<_>>();
+    let mut value = serde_json::to_value(summary).expect("MCP summary is serializable");
+    value["toolCalls"] = Value::Array(tool_calls);
+    value["notifications"] = Value::Array(related);
+    value
+}
+
+pub fn subscriptions(app: &App) -> Value {
+    Value::Array(
+        subscription::subscriptions(&app.exchanges)
+            .into_iter()
+            .map(|subscription| {
+                json!({
+                    "id": subscription.id,
+                    "method": subscription.method,
+                    "kind": subscription.kind,
+                    "requestIndex": subscription.request,
+                    "eventCount": subscription.events.len(),
+                    "eventsPerSecond": subscription.rate(&app.exchanges),
+                    "eventIndices": subscription.events,
+                    "active": subscription.is_active(),
+                    "unsubscribeIndex": subscription.unsubscribe,
+                })
+            })
+            .collect(),
+    )
+}
+
+pub fn batches(app: &App) -> Value {
+    let context = app.status_context();
+    Value::Array(
+        batch::batches(&app.exchanges)
+            .into_iter()
+            .map(|batch| {
+                let counts = batch.counts(&app.exchanges, &context);
+                json!({
+                    "exchangeIndices": batch.members,
+                    "status": batch.status(&app.exchanges, &context).name(),
+                    "succeeded": counts.success,
+                    "failed": counts.error,
+                    "waiting": counts.waiting,
+                    "durationMs": batch.duration(&app.exchanges).map(|duration| duration.as_millis()),
+                    "request": batch.request_body(&app.exchanges),
+                    "response": batch.response_body(&app.exchanges),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Matches of `query` in every exchange that passes the request list filter, including
+/// calls folded under a batch or subscription row.
+pub fn query_results(app: &App, query: &Query) -> Value {
+    let context = app.status_context();
+    let results = app
+        .exchanges
+        .iter()
+        .enumerate()
+        .filter(|(index, exchange)| {
+            context.matches_filter(exchange, app.bookmark(*index), &app.filter_text)
+        })
+        .map(|(index, exchange)| {
+            json!({
+                "index": index,
+                "method": exchange.method,
+                "id": exchange.id,
+                "matches": query.evaluate_exchange(exchange),
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "expression": query.expression(),
+        "results": results,
+    })
+}
+
+/// A diff result. Session diffs list only the pairs that differ; the summary counts the rest.
+pub fn comparison(comparison: &Comparison) -> Value {
+    match comparison {
+        Comparison::Exchanges {
+            left,
+            right,
+            differences,
+        } => json!({
+            "left": left,
+            "right": right,
+            "differences": differences.iter().map(difference_value).collect::<Vec<_>>(),
+        }),
+        Comparison::Sessions { left, right, pairs } => {
+            let tally = diff::tally(pairs);
+            json!({
+                "left": left,
+                "right": right,
+                "summary": {
+                    "identical": tally.identical,
+                    "changed": tally.changed,
+                    "onlyLeft": tally.only_left,
+                    "onlyRight": tally.only_right,
+                },
+                "pairs": pairs
+                    .iter()
+                    .filter(|pair| pair.left.is_none() || pair.right.is_none() || !pair.differences.is_empty())
+                    .map(|pair| json!({
+                        "left": pair.left,
+                        "right": pair.right,
+                        "method": pair.method,
+                        "id": pair.id,
+                        "differences": pair.differences.iter().map(difference_value).collect::<Vec<_>>(),
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        }
+    }
+}
+
+// `before` and `after` are left out rather than null when a key was added or removed.
+fn difference_value(difference: &Difference) -> Value {
+    let mut value = json!({
+        "path": difference.path,
+        "kind": difference.kind.name(),
+    });
+    for (name, side) in [("before", &difference.before), ("after", &difference.after)] {
+        if let Some(side) = side {
+            value[name] = side.clone();
+        }
+    }
+    value
+}
+
 pub fn pending(app: &App) -> Value {
     Value::Array(
         app.pending_requests
             .iter()
             .map(|pending| {
+                let shown = app.shown_pending_request(pending);
                 json!({
                     "id": pending.id,
-                    "request": pending.modified_request.as_ref()
-                        .and_then(|request| serde_json::from_str::<Value>(request).ok())
-                        .unwrap_or_else(|| message_body(&pending.original_request)),
-                    "headers": pending.modified_headers.as_ref()
-                        .or(pending.original_request.headers.as_ref()),
+                    "request": message_body(&shown),
+                    "headers": shown.headers,
                     "modified": pending.modified_request.is_some()
                         || pending.modified_headers.is_some(),
                 })
@@ -789,7 +1444,12 @@ pub fn pending(app: &App) -> Value {
     )
 }
 
-fn exchange_value(index: usize, exchange: &JsonRpcExchange) -> Value {
+fn exchange_value(
+    index: usize,
+    exchange: &JsonRpcExchange,
+    bookmark: Option<&Bookmark>,
+    context: &StatusContext,
+) -> Value {
     let duration = exchange
         .request
         .as_ref()
@@ -801,16 +1461,14 @@ fn exchange_value(index: usize, exchange: &JsonRpcExchange) -> Value {
         "id": exchange.id,
         "method": exchange.method,
         "transport": transport_name(&exchange.transport),
-        "status": if exchange.is_notification() {
-            "notification"
-        } else if exchange.response.as_ref().is_some_and(|response| response.error.is_some()) {
-            "error"
-        } else if exchange.response.is_some() {
-            "success"
-        } else {
-            "pending"
-        },
+        "client": exchange.client(),
+        "source": exchange.source(),
+        "status": context.status(exchange).name(),
+        "late": context.lateness(exchange).map(Lateness::name),
         "durationMs": duration,
+        "timing": exchange.timing().map(SessionTiming::from),
+        "starred": bookmark.is_some_and(|bookmark| bookmark.starred),
+        "tags": bookmark.map(|bookmark| bookmark.tags.as_slice()).unwrap_or_default(),
         "request": exchange.request.as_ref().map(message_value),
         "response": exchange.response.as_ref().map(message_value),
     })
@@ -822,6 +1480,13 @@ fn overlay_name(overlay: Overlay) -> &'static str {
         Overlay::Prefix => "commands",
         Overlay::Help => "help",
         Overlay::Sessions => "sessions",
+        Overlay::Mcp => "mcp",
+        Overlay::Subscription => "subscription",
+        Overlay::Batch => "batch",
+        Overlay::Diff => "diff",
+        Overlay::Columns => "columns",
+        Overlay::Snippets => "snippets",
+        Overlay::Profiles => "profiles",
     }
 }
 
@@ -834,31 +1499,29 @@ fn message_value(message: &JsonRpcMessage) -> Value {
     })
 }
 
-fn message_body(message: &JsonRpcMessage) -> Value {
-    let mut body = serde_json::Map::new();
-    body.insert("jsonrpc".to_string(), json!("2.0"));
-    if let Some(id) = &message.id {
-        body.insert("id".to_string(), id.clone());
-    }
-    match message.direction {
-        MessageDirection::Request => {
-            if let Some(method) = &message.method {
-                body.insert("method".to_string(), json!(method));
-            }
-            if let Some(params) = &message.params {
-                body.insert("params".to_string(), params.clone());
-            }
-        }
-        MessageDirection::Response => {
-            if let Some(result) = &message.result {
-                body.insert("result".to_string(), result.clone());
-            }
-            if let Some(error) = &message.error {
-                body.insert("error".to_string(), error.clone());
-            }
-        }
+fn dap_session_message(
+    message: SessionMessage,
+    direction: MessageDirection,
+    transport: TransportType,
+) -> Result<JsonRpcMessage, String> {
+    let expected = match direction {
+        MessageDirection::Request => &["request", "event"][..],
+        MessageDirection::Response => &["response"][..],
+    };
+    let kind = message.body.get("type").and_then(Value::as_str);
+    if !kind.is_some_and(|kind| expected.contains(&kind)) {
+        return Err(format!("DAP message type must be one of {expected:?}"));
     }
-    Value::Object(body)
+
+    let mut parsed = json_rpc_messages_by_shape(&message.body, transport, None)
+        .pop()
+        .ok_or_else(|| "DAP message body is empty".to_string())?;
+    parsed.timestamp = UNIX_EPOCH + Duration::from_millis(message.timestamp_ms);
+    parsed.headers = message.headers;
+    parsed.connection = message.connection.map(Connection::from);
+    parsed.batch = message.batch.map(BatchPosition::from);
+    parsed.timing = message.timing.map(Timing::from);
+    Ok(parsed)
 }
 
 fn mode_name(mode: &AppMode) -> &'static str {
@@ -883,10 +1546,7 @@ fn annotation_value(annotation: &LineAnnotation) -> Value {
         "id": annotation.id,
         "exchangeIndex": annotation.exchange_index,
         "panel": focus_name(annotation.panel),
-        "tab": match annotation.tab {
-            DetailTab::Headers => "headers",
-            DetailTab::Body => "body",
-        },
+        "tab": tab_name(annotation.tab),
         "startLine": annotation.start_line,
         "endLine": annotation.end_line,
         "message": annotation.message,
@@ -945,6 +1605,238 @@ mod tests {
             parse_request(&fullscreen),
             Ok(ControlAction::SetFullscreen { fullscreen: true })
         ));
+
+        let timeline = json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "debugger.setTimeline",
+            "params": {"visible": true, "zoom": 8},
+        });
+        assert!(matches!(
+            parse_request(&timeline),
+            Ok(ControlAction::SetTimeline {
+                visible: true,
+                zoom: Some(8)
+            })
+        ));
+
+        let target = json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "debugger.setTarget",
+            "params": ["http://localhost:9545", "l2"],
+        });
+        let Ok(ControlAction::SetTarget { url, listener }) = parse_request(&target) else {
+            panic!("setTarget should parse");
+        };
+        assert_eq!(
+            (url.as_str(), listener.as_deref()),
+            ("http://localhost:9545", Some("l2"))
+        );
+
+        let diff = json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "debugger.diffExchanges",
+            "params": {"left": 3, "right": {"index": 7, "sessionId": "earlier"}},
+        });
+        let Ok(ControlAction::DiffExchanges { left, right }) = parse_request(&diff) else {
+            panic!("diffExchanges should parse");
+        };
+        assert_eq!(
+            (left, right),
+            (
+                ExchangeRef {
+                    session_id: None,
+                    index: 3
+                },
+                ExchangeRef {
+                    session_id: Some("earlier".to_string()),
+                    index: 7
+                }
+            )
+        );
+
+        let query = json!({
+            "jsonrpc": "2.0",
+            "id": 6,
+            "method": "debugger.queryExchanges",
+            "params": ["result.number", true],
+        });
+        let Ok(ControlAction::QueryExchanges { query, pin: true }) = parse_request(&query) else {
+            panic!("queryExchanges should parse");
+        };
+        assert_eq!(query.expression(), "result.number");
+
+        let invalid = json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "debugger.queryExchanges",
+            "params": {"expression": "result["},
+        });
+        assert_eq!(
+            parse_request(&invalid).unwrap_err().message,
+            "expected an index at column 8"
+        );
+
+        let columns = json!({
+            "jsonrpc": "2.0",
+            "id": 8,
+            "method": "debugger.setColumns",
+            "params": {
+                "columns": ["method", "duration"],
+                "widths": {"method": 30},
+                "sort": "duration",
+                "descending": true,
+            },
+        });
+        let Ok(ControlAction::SetColumns {
+            shown: Some(shown),
+            widths,
+            sort: Some(Some(sort)),
+        }) = parse_request(&columns)
+        else {
+            panic!("setColumns should parse");
+        };
+        assert_eq!(shown, [Column::Method, Column::Duration]);
+        assert_eq!(
+            widths,
+            [ColumnWidth {
+                column: Column::Method,
+                width: 30
+            }]
+        );
+        assert!(sort.descending);
+        let unsorted = json!({
+            "jsonrpc": "2.0",
+            "id": 9,
+            "method": "debugger.setColumns",
+            "params": {"sort": null},
+        });
+        assert!(matches!(
+            parse_request(&unsorted),
+            Ok(ControlAction::SetColumns {
+                shown: None,
+                sort: Some(None),
+                ..
+            })
+        ));
+        let unknown = json!({
+            "jsonrpc": "2.0",
+            "id": 10,
+            "method": "debugger.setColumns",
+            "params": {"columns": ["size"]},
+        });
+        assert!(parse_request(&unknown)
+            .unwrap_err()
+            .message
+            .starts_with("unknown column \"size\""));
+        let tag = json!({
+            "jsonrpc": "2.0",
+            "id": 11,
+            "method": "debugger.tagExchange",
+            "params": {"index": 3, "starred": true, "addTags": ["bug"]},
+        });
+        let Ok(ControlAction::TagExchange {
+            index: Some(3),
+            starred: Some(true),
+            tags: None,
+            add,
+            remove,
+        }) = parse_request(&tag)
+        else {
+            panic!("tagExchange should parse");
+        };
+        assert_eq!(add, ["bug"]);
+        assert!(remove.is_empty());
+        let invalid_tags = json!({
+            "jsonrpc": "2.0",
+            "id": 12,
+            "method": "debugger.tagExchange",
+            "params": {"tags": "bug"},
+        });
+        assert!(parse_request(&invalid_tags).is_err());
+        let export_har = json!({
+            "jsonrpc": "2.0",
+            "id": 13,
+            "method": "debugger.exportHar",
+            "params": {"sessionId": "saved"},
+        });
+        assert!(matches!(
+            parse_request(&export_har),
+            Ok(ControlAction::ExportHar { session_id: Some(id) }) if id == "saved"
+        ));
+        let import_har = json!({
+            "jsonrpc": "2.0",
+            "id": 14,
+            "method": "debugger.importHar",
+            "params": {},
+        });
+        assert!(parse_request(&import_har).is_err());
+        let export_snippet = json!({
+            "jsonrpc": "2.0",
+            "id": 15,
+            "method": "debugger.exportSnippet",
+            "params": {"format": "httpie", "index": 2, "via": "proxy"},
+        });
+        assert!(matches!(
+            parse_request(&export_snippet),
+            Ok(ControlAction::ExportSnippet {
+                format: snippet::Format::Httpie,
+                index: Some(2),
+                via_proxy: true,
+            })
+        ));
+        let unknown_format = json!({
+            "jsonrpc": "2.0",
+            "id": 16,
+            "method": "debugger.exportSnippet",
+            "params": {"format": "wget"},
+        });
+        assert!(parse_request(&unknown_format).is_err());
+        let import_trace = json!({
+            "jsonrpc": "2.0",
+            "id": 17,
+            "method": "debugger.importTrace",
+            "params": {"text": "{}", "name": "bug 42"},
+        });
+        assert!(matches!(
+            parse_request(&import_trace),
+            Ok(ControlAction::ImportTrace { name: Some(name), target, .. })
+                if name == "bug 42" && target == "trace"
+        ));
+        let export_report = json!({
+            "jsonrpc": "2.0",
+            "id": 18,
+            "method": "debugger.exportReport",
+            "params": ["html", "tag:incident"],
+        });
+        assert!(matches!(
+            parse_request(&export_report),
+            Ok(ControlAction::ExportReport { format: report::Format::Html, filter })
+                if filter == "tag:incident"
+        ));
+        let export_fixtures = json!({
+            "jsonrpc": "2.0",
+            "id": 19,
+            "method": "debugger.exportFixtures",
+            "params": {"dir": "fixtures"},
+        });
+        assert!(matches!(
+            parse_request(&export_fixtures),
+            Ok(ControlAction::ExportFixtures { dir, filter })
+                if dir == "fixtures" && filter.is_empty()
+        ));
+        let select_profile = json!({
+            "jsonrpc": "2.0",
+            "id": 20,
+            "method": "debugger.selectProfile",
+            "params": ["mainnet"],
+        });
+        assert!(matches!(
+            parse_request(&select_profile),
+            Ok(ControlAction::SelectProfile { name }) if name == "mainnet"
+        ));
     }
 
     #[test]
@@ -954,7 +1846,7 @@ mod tests {
         assert_eq!(document["openrpc"], "1.3.2");
         assert_eq!(document["servers"][0]["url"], "http://127.0.0.1:8081");
         assert_eq!(document["info"]["version"], env!("CARGO_PKG_VERSION"));
-        assert_eq!(document["methods"].as_array().unwrap().len(), 25);
+        assert_eq!(document["methods"].as_array().unwrap().len(), 42);
         assert!(document["methods"]
             .as_array()
             .unwrap()
@@ -1205,6 +2097,10 @@ mod tests {
             direction: MessageDirection::Request,
             transport: TransportType::Http,
             headers: Some(HashMap::from([("x-test".to_string(), "yes".to_string())])),
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         app.add_message(JsonRpcMessage {
             id: Some(json!(7)),
@@ -1216,18 +2112,45 @@ mod tests {
             direction: MessageDirection::Response,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: Some(Timing {
+                queued: Some(Duration::from_millis(1500)),
+                first_byte: Some(Duration::from_micros(250_500)),
+                ..Timing::default()
+            }),
+            seq: None,
         });
+        let mut bookmark = Bookmark {
+            starred: true,
+            ..Bookmark::default()
+        };
+        bookmark.add_tags(["baseline"]);
+        app.set_bookmark(0, bookmark.clone());
 
         let session = export_session(&app);
         let exchanges = replay_session(session.clone()).unwrap();
 
         assert_eq!(session.schema_version, 1);
         assert_eq!(exchanges.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&session.exchanges[0]).unwrap()["tags"],
+            json!(["baseline"])
+        );
+        assert_eq!(session_bookmarks(&session), HashMap::from([(0, bookmark)]));
         assert_eq!(exchanges[0].method.as_deref(), Some("eth_chainId"));
         assert_eq!(
             exchanges[0].response.as_ref().unwrap().result,
             Some(json!("0x1"))
         );
+        assert_eq!(
+            serde_json::to_value(&session.exchanges[0].response).unwrap()["timing"],
+            json!({"queuedMs": 1500.0, "firstByteMs": 250.5})
+        );
+        assert_eq!(
+            exchanges[0].timing().unwrap().first_byte,
+            Some(Duration::from_micros(250_500))
+        );
         assert_eq!(
             exchanges[0]
                 .request
@@ -1258,6 +2181,10 @@ mod tests {
             direction: MessageDirection::Request,
             transport: TransportType::Stdio(crate::app::Framing::ContentLength),
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
 
         let exchanges = replay_session(export_session(&app)).unwrap();
@@ -1269,6 +2196,41 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn dap_transport_round_trips_through_sessions() {
+        let mut app = App::new();
+        let transport = TransportType::Stdio(crate::app::Framing::Dap);
+        for body in [
+            json!({"seq": 4, "type": "request", "command": "threads"}),
+            json!({
+                "seq": 5,
+                "type": "response",
+                "request_seq": 4,
+                "success": false,
+                "command": "threads",
+                "message": "not stopped"
+            }),
+        ] {
+            for message in json_rpc_messages_by_shape(&body, transport, None) {
+                app.add_message(message);
+            }
+        }
+
+        let session = export_session(&app);
+        assert_eq!(
+            session.exchanges[0].request.as_ref().unwrap().body,
+            json!({"seq": 4, "type": "request", "command": "threads"})
+        );
+        let exchanges = replay_session(session).unwrap();
+
+        assert_eq!(exchanges[0].method.as_deref(), Some("threads"));
+        assert_eq!(
+            exchanges[0].response.as_ref().unwrap().error,
+            Some(json!({"message": "not stopped"}))
+        );
+        assert!(exchanges[0].transport.is_dap());
+    }
+
     #[tokio::test]
     async fn bind_rejects_an_occupied_control_port() {
         let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
@@ -0,0 +1,349 @@
+use crate::app::{JsonRpcExchange, JsonRpcMessage};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Changed => "changed",
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Removed => '-',
+            Self::Changed => '~',
+        }
+    }
+}
+
+/// One key or element that differs, addressed by a path such as `params[0].to`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Two exchanges of a session diff matched by method and id. Either side is missing when
+/// the other session has no counterpart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pair {
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+    pub method: Option<String>,
+    pub id: Option<Value>,
+    pub differences: Vec<Difference>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub identical: usize,
+    pub changed: usize,
+    pub only_left: usize,
+    pub only_right: usize,
+}
+
+/// What the diff view shows: two exchanges, or two whole sessions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comparison {
+    Exchanges {
+        left: String,
+        right: String,
+        differences: Vec<Difference>,
+    },
+    Sessions {
+        left: String,
+        right: String,
+        pairs: Vec<Pair>,
+    },
+}
+
+/// Differences between the method, params, result, error, and headers of two exchanges.
+/// Ids are left out: two calls being compared rarely share one.
+pub fn exchanges(left: &JsonRpcExchange, right: &JsonRpcExchange) -> Vec<Difference> {
+    let method = |exchange: &JsonRpcExchange| exchange.method.clone().map(Value::String);
+    let field = |message: &Option<JsonRpcMessage>, pick: fn(&JsonRpcMessage) -> Option<Value>| {
+        message.as_ref().and_then(pick)
+    };
+    let headers = |message: &JsonRpcMessage| {
+        message
+            .headers
+            .as_ref()
+            .and_then(|headers| serde_json::to_value(headers).ok())
+    };
+
+    let mut differences = Vec::new();
+    let sections = [
+        ("method", method(left), method(right)),
+        (
+            "params",
+            field(&left.request, |message| message.params.clone()),
+            field(&right.request, |message| message.params.clone()),
+        ),
+        (
+            "result",
+            field(&left.response, |message| message.result.clone()),
+            field(&right.response, |message| message.result.clone()),
+        ),
+        (
+            "error",
+            field(&left.response, |message| message.error.clone()),
+            field(&right.response, |message| message.error.clone()),
+        ),
+        (
+            "request.headers",
+            field(&left.request, headers),
+            field(&right.request, headers),
+        ),
+        (
+            "response.headers",
+            field(&left.response, headers),
+            field(&right.response, headers),
+        ),
+    ];
+    for (path, before, after) in sections {
+        values(path, before.as_ref(), after.as_ref(), &mut differences);
+    }
+    differences
+}
+
+/// Appends the differences between `before` and `after` below `path`. Objects are compared
+/// key by key and arrays element by element; anything else differs as a whole.
+pub fn values(
+    path: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    differences: &mut Vec<Difference>,
+) {
+    match (before, after) {
+        (None, None) => {}
+        (None, Some(after)) => differences.push(Difference {
+            path: path.to_string(),
+            kind: ChangeKind::Added,
+            before: None,
+            after: Some(after.clone()),
+        }),
+        (Some(before), None) => differences.push(Difference {
+            path: path.to_string(),
+            kind: ChangeKind::Removed,
+            before: Some(before.clone()),
+            after: None,
+        }),
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            for (key, value) in before {
+                values(
+                    &key_path(path, key),
+                    Some(value),
+                    after.get(key),
+                    differences,
+                );
+            }
+            for (key, value) in after.iter().filter(|(key, _)| !before.contains_key(*key)) {
+                values(&key_path(path, key), None, Some(value), differences);
+            }
+        }
+        (Some(Value::Array(before)), Some(Value::Array(after))) => {
+            for index in 0..before.len().max(after.len()) {
+                values(
+                    &format!("{path}[{index}]"),
+                    before.get(index),
+                    after.get(index),
+                    differences,
+                );
+            }
+        }
+        (Some(before), Some(after)) if before != after => differences.push(Difference {
+            path: path.to_string(),
+            kind: ChangeKind::Changed,
+            before: Some(before.clone()),
+            after: Some(after.clone()),
+        }),
+        _ => {}
+    }
+}
+
+pub fn key_path(path: &str, key: &str) -> String {
+    let plain = !key.is_empty()
+        && !key.starts_with(|character: char| character.is_ascii_digit())
+        && key
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_');
+    if plain {
+        format!("{path}.{key}")
+    } else {
+        format!("{path}[{}]", Value::String(key.to_string()))
+    }
+}
+
+/// Matches the exchanges of two sessions by method and id, in order of appearance, so a
+/// repeated call pairs with its nth counterpart. Unmatched exchanges from the right session
+/// come last.
+pub fn sessions(left: &[JsonRpcExchange], right: &[JsonRpcExchange]) -> Vec<Pair> {
+    let key = |exchange: &JsonRpcExchange| {
+        (
+            exchange.method.clone(),
+            exchange.id.as_ref().map(Value::to_string),
+        )
+    };
+    let mut unmatched: HashMap<_, VecDeque<usize>> = HashMap::new();
+    for (index, exchange) in right.iter().enumerate() {
+        unmatched.entry(key(exchange)).or_default().push_back(index);
+    }
+
+    let mut pairs = left
+        .iter()
+        .enumerate()
+        .map(|(index, exchange)| {
+            let counterpart = unmatched
+                .get_mut(&key(exchange))
+                .and_then(VecDeque::pop_front);
+            Pair {
+                left: Some(index),
+                right: counterpart,
+                method: exchange.method.clone(),
+                id: exchange.id.clone(),
+                differences: counterpart
+                    .map(|counterpart| exchanges(exchange, &right[counterpart]))
+                    .unwrap_or_default(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut only_right = unmatched.into_values().flatten().collect::<Vec<_>>();
+    only_right.sort_unstable();
+    pairs.extend(only_right.into_iter().map(|index| Pair {
+        left: None,
+        right: Some(index),
+        method: right[index].method.clone(),
+        id: right[index].id.clone(),
+        differences: Vec::new(),
+    }));
+    pairs
+}
+
+pub fn tally(pairs: &[Pair]) -> Tally {
+    let mut tally = Tally::default();
+    for pair in pairs {
+        match (pair.left, pair.right) {
+            (Some(_), None) => tally.only_left += 1,
+            (None, _) => tally.only_right += 1,
+            _ if pair.differences.is_empty() => tally.identical += 1,
+            _ => tally.changed += 1,
+        }
+    }
+    tally
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{testing, TransportType};
+    use serde_json::json;
+
+    fn exchange(id: u64, method: &str, params: Value, result: Value) -> JsonRpcExchange {
+        testing::app(
+            TransportType::Http,
+            [
+                (
+                    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}),
+                    0,
+                ),
+                (json!({"jsonrpc": "2.0", "id": id, "result": result}), 0),
+            ],
+        )
+        .exchanges
+        .remove(0)
+    }
+
+    #[test]
+    fn exchange_differences_carry_paths_to_what_changed() {
+        let mut left = exchange(
+            1,
+            "eth_call",
+            json!([{"to": "0xa", "data": "0x1"}, "latest"]),
+            json!({"gas": 1, "logs": []}),
+        );
+        let mut right = exchange(
+            2,
+            "eth_call",
+            json!([{"to": "0xb", "value": "0x0"}]),
+            json!({"gas": 1, "logs": [7]}),
+        );
+        let trace = |id: &str| Some(HashMap::from([("x-trace-id".to_string(), id.to_string())]));
+        left.request.as_mut().unwrap().headers = trace("abc");
+        right.request.as_mut().unwrap().headers = trace("def");
+
+        let differences = exchanges(&left, &right)
+            .into_iter()
+            .map(|difference| (difference.kind, difference.path))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            differences,
+            [
+                (ChangeKind::Removed, "params[0].data".to_string()),
+                (ChangeKind::Changed, "params[0].to".to_string()),
+                (ChangeKind::Added, "params[0].value".to_string()),
+                (ChangeKind::Removed, "params[1]".to_string()),
+                (ChangeKind::Added, "result.logs[0]".to_string()),
+                (
+                    ChangeKind::Changed,
+                    "request.headers[\"x-trace-id\"]".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessions_pair_repeated_calls_in_order_and_tally_the_rest() {
+        let left = [
+            exchange(1, "eth_chainId", json!([]), json!("0x1")),
+            exchange(2, "eth_call", json!([]), json!("0x0")),
+            exchange(2, "eth_call", json!([]), json!("0x1")),
+            exchange(3, "eth_blockNumber", json!([]), json!("0x10")),
+        ];
+        let right = [
+            exchange(2, "eth_call", json!([]), json!("0x0")),
+            exchange(1, "eth_chainId", json!([]), json!("0x1")),
+            exchange(2, "eth_call", json!([]), json!("0x2")),
+            exchange(4, "eth_gasPrice", json!([]), json!("0x3")),
+        ];
+
+        let pairs = sessions(&left, &right);
+        let matched = pairs
+            .iter()
+            .map(|pair| (pair.left, pair.right))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            matched,
+            [
+                (Some(0), Some(1)),
+                (Some(1), Some(0)),
+                (Some(2), Some(2)),
+                (Some(3), None),
+                (None, Some(3)),
+            ]
+        );
+        assert_eq!(pairs[2].differences[0].path, "result");
+        assert_eq!(
+            tally(&pairs),
+            Tally {
+                identical: 2,
+                changed: 1,
+                only_left: 1,
+                only_right: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,933 @@
+use crate::app::JsonRpcExchange;
+use crate::time::civil_from_days;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use tiny_keccak::{Hasher, Keccak};
+
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+const WEI_KEYS: &[&str] = &[
+    "value",
+    "gasPrice",
+    "maxFeePerGas",
+    "maxPriorityFeePerGas",
+    "maxFeePerBlobGas",
+    "baseFeePerGas",
+    "effectiveGasPrice",
+    "balance",
+];
+
+const WEI_RESULTS: &[&str] = &[
+    "eth_getBalance",
+    "eth_gasPrice",
+    "eth_maxPriorityFeePerGas",
+    "eth_blobBaseFee",
+];
+
+const CALL_METHODS: &[&str] = &[
+    "eth_call",
+    "eth_estimateGas",
+    "eth_sendTransaction",
+    "eth_createAccessList",
+];
+
+/// One decoded value, shown as `path: detail` in the Decoded tab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub path: String,
+    pub detail: String,
+}
+
+impl Decoded {
+    fn new(path: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Functions and custom errors from a directory of contract ABIs, keyed by selector.
+#[derive(Debug, Clone, Default)]
+pub struct Abi {
+    functions: HashMap<[u8; 4], Function>,
+    errors: HashMap<[u8; 4], Function>,
+}
+
+#[derive(Debug, Clone)]
+struct Function {
+    name: String,
+    inputs: Vec<Param>,
+    outputs: Vec<Param>,
+}
+
+#[derive(Debug, Clone)]
+struct Param {
+    name: String,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    Uint(usize),
+    Int(usize),
+    Address,
+    Bool,
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<Kind>),
+    FixedArray(Box<Kind>, usize),
+    Tuple(Vec<Kind>),
+}
+
+impl Abi {
+    /// Loads every `*.json` file in `path`. Files may hold a bare ABI array or a build
+    /// artifact with an `abi` field.
+    pub fn load_dir(path: &Path) -> Result<Self, String> {
+        let entries = std::fs::read_dir(path)
+            .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut abi = Self::default();
+        for path in paths {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+            let value = serde_json::from_str::<Value>(&contents)
+                .map_err(|error| format!("invalid ABI in {}: {error}", path.display()))?;
+            abi.extend(&value);
+        }
+        Ok(abi)
+    }
+
+    pub fn extend(&mut self, value: &Value) {
+        let Some(entries) = value
+            .as_array()
+            .or_else(|| value.get("abi").and_then(Value::as_array))
+        else {
+            return;
+        };
+
+        for entry in entries {
+            let entry_type = entry.get("type").and_then(Value::as_str);
+            let Some(function) = parse_function(entry) else {
+                continue;
+            };
+            let selector = selector(&function);
+            match entry_type {
+                Some("error") => {
+                    self.errors.insert(selector, function);
+                }
+                Some("function") | None => {
+                    self.functions.insert(selector, function);
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Decoded values in the request of `exchange`.
+pub fn request_values(exchange: &JsonRpcExchange, abi: &Abi) -> Vec<Decoded> {
+    let Some(request) = &exchange.request else {
+        return Vec::new();
+    };
+    let method = exchange.method.as_deref().unwrap_or_default();
+    let mut decoded = Vec::new();
+    if let Some(params) = &request.params {
+        walk(params, "params", None, method, abi, &mut decoded);
+    }
+    decoded
+}
+
+/// Decoded values in the response of `exchange`, including `eth_call` return data and
+/// revert reasons.
+pub fn response_values(exchange: &JsonRpcExchange, abi: &Abi) -> Vec<Decoded> {
+    let Some(response) = &exchange.response else {
+        return Vec::new();
+    };
+    let method = exchange.method.as_deref().unwrap_or_default();
+    let mut decoded = Vec::new();
+    if let Some(result) = &response.result {
+        match (call_function(exchange, abi), result.as_str()) {
+            (Some(function), Some(data)) if method == "eth_call" => {
+                decode_outputs(function, data, &mut decoded);
+            }
+            _ => walk(result, "result", None, method, abi, &mut decoded),
+        }
+    }
+    if let Some(error) = &response.error {
+        decode_error(error, abi, &mut decoded);
+    }
+    decoded
+}
+
+fn walk(
+    value: &Value,
+    path: &str,
+    key: Option<&str>,
+    method: &str,
+    abi: &Abi,
+    decoded: &mut Vec<Decoded>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (child_key, child) in map {
+                walk(
+                    child,
+                    &format!("{path}.{child_key}"),
+                    Some(child_key),
+                    method,
+                    abi,
+                    decoded,
+                );
+            }
+        }
+        Value::Array(values) => {
+            for (index, child) in values.iter().enumerate() {
+                walk(
+                    child,
+                    &format!("{path}[{index}]"),
+                    key,
+                    method,
+                    abi,
+                    decoded,
+                );
+            }
+        }
+        Value::String(text) => {
+            let is_call_data = matches!(key, Some("data" | "input"))
+                && CALL_METHODS.contains(&method)
+                && path.starts_with("params[0].");
+            if is_call_data {
+                decode_call_data(path, text, abi, decoded);
+            } else if let Some(detail) = describe(text, key, path == "result", method) {
+                decoded.push(Decoded::new(path, detail));
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+fn describe(text: &str, key: Option<&str>, is_result: bool, method: &str) -> Option<String> {
+    let digits = text.strip_prefix("0x")?;
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let key = key.unwrap_or_default();
+    let is_wei = WEI_KEYS.contains(&key) || (is_result && WEI_RESULTS.contains(&method));
+    match digits.len() {
+        40 => return Some("address".to_string()),
+        64 if !is_wei => return Some("32-byte hash".to_string()),
+        _ => {}
+    }
+    // Quantities never carry leading zeros, which separates them from fixed-size data.
+    if digits.len() > 64 || (digits != "0" && digits.starts_with('0')) {
+        return None;
+    }
+
+    let decimal = decimal(&hex_bytes(digits)?);
+    if is_wei {
+        return Some(format!(
+            "{decimal} wei · {} gwei · {} ETH",
+            with_decimals(&decimal, 9),
+            with_decimals(&decimal, 18)
+        ));
+    }
+    if key == "timestamp" {
+        if let Ok(seconds) = u64::from_str_radix(digits, 16) {
+            return Some(format!("{decimal} ({})", format_timestamp(seconds)));
+        }
+    }
+    Some(decimal)
+}
+
+fn decode_call_data(path: &str, text: &str, abi: &Abi, decoded: &mut Vec<Decoded>) {
+    let Some(data) = text.strip_prefix("0x").and_then(hex_bytes) else {
+        return;
+    };
+    let Some(selector) = data
+        .get(..4)
+        .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+    else {
+        return;
+    };
+    let Some(function) = abi.functions.get(&selector) else {
+        decoded.push(Decoded::new(
+            path,
+            format!(
+                "selector 0x{} (not in ABI directory)",
+                hex_string(&selector)
+            ),
+        ));
+        return;
+    };
+
+    decoded.push(Decoded::new(path, signature(function)));
+    decode_params(&function.inputs, &data[4..], decoded);
+}
+
+fn decode_outputs(function: &Function, text: &str, decoded: &mut Vec<Decoded>) {
+    let Some(data) = text.strip_prefix("0x").and_then(hex_bytes) else {
+        return;
+    };
+    let outputs = function
+        .outputs
+        .iter()
+        .map(|param| canonical(&param.kind))
+        .collect::<Vec<_>>();
+    decoded.push(Decoded::new(
+        "result",
+        format!("{} returns ({})", function.name, outputs.join(",")),
+    ));
+    decode_params(&function.outputs, &data, decoded);
+}
+
+fn decode_params(params: &[Param], data: &[u8], decoded: &mut Vec<Decoded>) {
+    let kinds = params
+        .iter()
+        .map(|param| param.kind.clone())
+        .collect::<Vec<_>>();
+    let Some(values) = decode_sequence(&kinds, data) else {
+        decoded.push(Decoded::new("  ", "data does not match the ABI"));
+        return;
+    };
+    for (index, (param, value)) in params.iter().zip(values).enumerate() {
+        let name = if param.name.is_empty() {
+            format!("[{index}]")
+        } else {
+            param.name.clone()
+        };
+        decoded.push(Decoded::new(
+            format!("  {name}"),
+            format!("{} = {value}", canonical(&param.kind)),
+        ));
+    }
+}
+
+fn decode_error(error: &Value, abi: &Abi, decoded: &mut Vec<Decoded>) {
+    // Nodes put revert data in `error.data`, some wrap it once more as `error.data.data`.
+    let (path, data) = match error.get("data") {
+        Some(Value::String(data)) => ("error.data", data.as_str()),
+        Some(data) => match data.get("data").and_then(Value::as_str) {
+            Some(data) => ("error.data.data", data),
+            None => return,
+        },
+        None => return,
+    };
+    let Some(bytes) = data.strip_prefix("0x").and_then(hex_bytes) else {
+        return;
+    };
+    let Some(selector) = bytes
+        .get(..4)
+        .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+    else {
+        return;
+    };
+    let arguments = &bytes[4..];
+
+    if selector == ERROR_SELECTOR {
+        if let Some(values) = decode_sequence(&[Kind::String], arguments) {
+            decoded.push(Decoded::new(path, format!("revert {}", values[0])));
+            return;
+        }
+    }
+    if selector == PANIC_SELECTOR {
+        if let Some(code) = arguments.get(..32) {
+            let code = code[31];
+            decoded.push(Decoded::new(
+                path,
+                format!("panic 0x{code:02x} ({})", panic_reason(code)),
+            ));
+            return;
+        }
+    }
+    if let Some(function) = abi.errors.get(&selector) {
+        decoded.push(Decoded::new(
+            path,
+            format!("revert {}", signature(function)),
+        ));
+        decode_params(&function.inputs, arguments, decoded);
+        return;
+    }
+    decoded.push(Decoded::new(
+        path,
+        format!("unknown revert selector 0x{}", hex_string(&selector)),
+    ));
+}
+
+fn panic_reason(code: u8) -> &'static str {
+    match code {
+        0x00 => "generic panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "corrupt storage byte array",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized function",
+        _ => "unknown panic code",
+    }
+}
+
+fn call_function<'a>(exchange: &JsonRpcExchange, abi: &'a Abi) -> Option<&'a Function> {
+    let params = exchange.request.as_ref()?.params.as_ref()?;
+    let call = params.get(0)?;
+    let data = call
+        .get("data")
+        .or_else(|| call.get("input"))?
+        .as_str()?
+        .strip_prefix("0x")?;
+    let selector = hex_bytes(data.get(..8)?)?;
+    abi.functions.get(&<[u8; 4]>::try_from(selector).ok()?)
+}
+
+fn parse_function(entry: &Value) -> Option<Function> {
+    let name = entry.get("name")?.as_str()?.to_string();
+    let params = |field: &str| -> Option<Vec<Param>> {
+        entry
+            .get(field)
+            .and_then(Value::as_array)
+            .map(|params| params.iter().map(parse_param).collect())
+            .unwrap_or(Some(Vec::new()))
+    };
+    Some(Function {
+        name,
+        inputs: params("inputs")?,
+        outputs: params("outputs")?,
+    })
+}
+
+fn parse_param(param: &Value) -> Option<Param> {
+    let name = param
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let kind = parse_kind(param.get("type")?.as_str()?, param.get("components"))?;
+    Some(Param { name, kind })
+}
+
+fn parse_kind(kind: &str, components: Option<&Value>) -> Option<Kind> {
+    if let Some(prefix) = kind.strip_suffix(']') {
+        let open = prefix.rfind('[')?;
+        let inner = Box::new(parse_kind(&prefix[..open], components)?);
+        let size = &prefix[open + 1..];
+        return Some(if size.is_empty() {
+            Kind::Array(inner)
+        } else {
+            Kind::FixedArray(inner, size.parse().ok()?)
+        });
+    }
+
+    Some(match kind {
+        "address" => Kind::Address,
+        "bool" => Kind::Bool,
+        "string" => Kind::String,
+        "bytes" => Kind::Bytes,
+        "function" => Kind::FixedBytes(24),
+        "uint" => Kind::Uint(256),
+        "int" => Kind::Int(256),
+        "tuple" => Kind::Tuple(
+            components?
+                .as_array()?
+                .iter()
+                .map(|component| parse_param(component).map(|param| param.kind))
+                .collect::<Option<_>>()?,
+        ),
+        _ => {
+            if let Some(bits) = kind.strip_prefix("uint") {
+                Kind::Uint(bits.parse().ok()?)
+            } else if let Some(bits) = kind.strip_prefix("int") {
+                Kind::Int(bits.parse().ok()?)
+            } else if let Some(size) = kind.strip_prefix("bytes") {
+                Kind::FixedBytes(size.parse().ok().filter(|size| (1..=32).contains(size))?)
+            } else {
+                return None;
+            }
+        }
+    })
+}
+
+fn canonical(kind: &Kind) -> String {
+    match kind {
+        Kind::Uint(bits) => format!("uint{bits}"),
+        Kind::Int(bits) => format!("int{bits}"),
+        Kind::Address => "address".to_string(),
+        Kind::Bool => "bool".to_string(),
+        Kind::FixedBytes(size) => format!("bytes{size}"),
+        Kind::Bytes => "bytes".to_string(),
+        Kind::String => "string".to_string(),
+        Kind::Array(inner) => format!("{}[]", canonical(inner)),
+        Kind::FixedArray(inner, size) => format!("{}[{size}]", canonical(inner)),
+        Kind::Tuple(kinds) => format!(
+            "({})",
+            kinds.iter().map(canonical).collect::<Vec<_>>().join(",")
+        ),
+    }
+}
+
+fn signature(function: &Function) -> String {
+    let inputs = function
+        .inputs
+        .iter()
+        .map(|param| canonical(&param.kind))
+        .collect::<Vec<_>>();
+    format!("{}({})", function.name, inputs.join(","))
+}
+
+fn selector(function: &Function) -> [u8; 4] {
+    let hash = keccak256(signature(function).as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn is_dynamic(kind: &Kind) -> bool {
+    match kind {
+        Kind::Bytes | Kind::String | Kind::Array(_) => true,
+        Kind::FixedArray(inner, _) => is_dynamic(inner),
+        Kind::Tuple(kinds) => kinds.iter().any(is_dynamic),
+        Kind::Uint(_) | Kind::Int(_) | Kind::Address | Kind::Bool | Kind::FixedBytes(_) => false,
+    }
+}
+
+/// The bytes `kind` takes in the head of its sequence, or `None` when an ABI's array sizes
+/// overflow it.
+fn head_size(kind: &Kind) -> Option<usize> {
+    if is_dynamic(kind) {
+        return Some(32);
+    }
+    match kind {
+        Kind::FixedArray(inner, size) => head_size(inner)?.checked_mul(*size),
+        Kind::Tuple(kinds) => kinds
+            .iter()
+            .try_fold(0usize, |total, kind| total.checked_add(head_size(kind)?)),
+        _ => Some(32),
+    }
+}
+
+fn decode_sequence(kinds: &[Kind], data: &[u8]) -> Option<Vec<String>> {
+    let mut position = 0;
+    let mut values = Vec::with_capacity(kinds.len());
+    for kind in kinds {
+        let value = if is_dynamic(kind) {
+            let offset = word_usize(data.get(position..)?)?;
+            decode_value(kind, data.get(offset..)?)?
+        } else {
+            decode_value(kind, data.get(position..)?)?
+        };
+        values.push(value);
+        position = position.checked_add(head_size(kind)?)?;
+    }
+    Some(values)
+}
+
+fn decode_value(kind: &Kind, data: &[u8]) -> Option<String> {
+    let word = data.get(..32);
+    match kind {
+        Kind::Uint(_) => Some(decimal(word?)),
+        Kind::Int(_) => {
+            let word = word?;
+            if word[0] & 0x80 == 0 {
+                return Some(decimal(word));
+            }
+            // Two's complement: the magnitude is !word + 1.
+            let mut magnitude = word.iter().map(|byte| !byte).collect::<Vec<_>>();
+            for byte in magnitude.iter_mut().rev() {
+                let (sum, carry) = byte.overflowing_add(1);
+                *byte = sum;
+                if !carry {
+                    break;
+                }
+            }
+            Some(format!("-{}", decimal(&magnitude)))
+        }
+        Kind::Address => Some(format!("0x{}", hex_string(&word?[12..]))),
+        Kind::Bool => Some((word?[31] != 0).to_string()),
+        Kind::FixedBytes(size) => Some(format!("0x{}", hex_string(&word?[..*size]))),
+        Kind::Bytes => {
+            let length = word_usize(data)?;
+            Some(format!(
+                "0x{}",
+                hex_string(data.get(32..length.checked_add(32)?)?)
+            ))
+        }
+        Kind::String => {
+            let length = word_usize(data)?;
+            let text = String::from_utf8_lossy(data.get(32..length.checked_add(32)?)?);
+            Some(format!("{text:?}"))
+        }
+        Kind::Array(inner) => {
+            let length = word_usize(data)?;
+            // The length is untrusted; each element needs at least one word after it.
+            if length.checked_mul(32)? > data.len().saturating_sub(32) {
+                return None;
+            }
+            let kinds = vec![(**inner).clone(); length];
+            Some(format!(
+                "[{}]",
+                decode_sequence(&kinds, data.get(32..)?)?.join(", ")
+            ))
+        }
+        Kind::FixedArray(inner, size) => {
+            // The size comes from a user's ABI; the data must hold every element's head.
+            if head_size(inner)?.checked_mul(*size)? > data.len() {
+                return None;
+            }
+            let kinds = vec![(**inner).clone(); *size];
+            Some(format!("[{}]", decode_sequence(&kinds, data)?.join(", ")))
+        }
+        Kind::Tuple(kinds) => Some(format!("({})", decode_sequence(kinds, data)?.join(", "))),
+    }
+}
+
+fn word_usize(data: &[u8]) -> Option<usize> {
+    let word = data.get(..32)?;
+    if word[..24].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    usize::try_from(u64::from_be_bytes(word[24..].try_into().ok()?)).ok()
+}
+
+fn hex_bytes(digits: &str) -> Option<Vec<u8>> {
+    let padded;
+    let digits = if digits.len() % 2 == 1 {
+        padded = format!("0{digits}");
+        padded.as_str()
+    } else {
+        digits
+    };
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(digits.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Big-endian unsigned bytes as a decimal string.
+fn decimal(bytes: &[u8]) -> String {
+    const BASE: u64 = 1_000_000_000;
+    // Little-endian limbs in base 10^9.
+    let mut limbs: Vec<u64> = Vec::new();
+    for byte in bytes {
+        let mut carry = u64::from(*byte);
+        for limb in &mut limbs {
+            let value = *limb * 256 + carry;
+            *limb = value % BASE;
+            carry = value / BASE;
+        }
+        while carry > 0 {
+            limbs.push(carry % BASE);
+            carry /= BASE;
+        }
+    }
+
+    let Some((last, rest)) = limbs.split_last() else {
+        return "0".to_string();
+    };
+    let mut text = last.to_string();
+    for limb in rest.iter().rev() {
+        text.push_str(&format!("{limb:09}"));
+    }
+    text
+}
+
+fn with_decimals(decimal: &str, places: usize) -> String {
+    let padded = format!("{decimal:0>width$}", width = places + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - places);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+fn format_timestamp(seconds: u64) -> String {
+    let (year, month, day) =
+        civil_from_days(i64::try_from(seconds / 86_400).unwrap_or(i64::MAX / 2));
+    let time = seconds % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+/// Keccak-256 as used by Ethereum (original padding, not SHA3-256).
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// Markdown list of decoded values for the focused panel.
+pub fn markdown(values: &[Decoded]) -> String {
+    if values.is_empty() {
+        return "Nothing to decode\n".to_string();
+    }
+    values
+        .iter()
+        .map(|value| {
+            let indent = value.path.len() - value.path.trim_start().len();
+            format!(
+                "{}- `{}`: {}\n",
+                " ".repeat(indent),
+                value.path.trim_start(),
+                value.detail
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{JsonRpcExchange, JsonRpcMessage, TransportType};
+    use serde_json::json;
+    use std::time::SystemTime;
+
+    fn message(
+        params: Option<Value>,
+        result: Option<Value>,
+        error: Option<Value>,
+    ) -> JsonRpcMessage {
+        JsonRpcMessage {
+            id: Some(json!(1)),
+            method: None,
+            params,
+            result,
+            error,
+            timestamp: SystemTime::now(),
+            direction: crate::app::MessageDirection::Request,
+            transport: TransportType::Http,
+            headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
+        }
+    }
+
+    fn exchange(method: &str, params: Value, response: JsonRpcMessage) -> JsonRpcExchange {
+        JsonRpcExchange {
+            id: Some(json!(1)),
+            method: Some(method.to_string()),
+            request: Some(message(Some(params), None, None)),
+            response: Some(response),
+            timestamp: SystemTime::now(),
+            transport: TransportType::Http,
+        }
+    }
+
+    fn erc20() -> Abi {
+        let mut abi = Abi::default();
+        abi.extend(&json!({ "abi": [
+            {
+                "type": "function",
+                "name": "transfer",
+                "inputs": [
+                    { "name": "to", "type": "address" },
+                    { "name": "amount", "type": "uint256" }
+                ],
+                "outputs": [{ "name": "", "type": "bool" }]
+            },
+            {
+                "type": "error",
+                "name": "InsufficientBalance",
+                "inputs": [{ "name": "available", "type": "uint256" }]
+            }
+        ]}));
+        abi
+    }
+
+    #[test]
+    fn keccak_matches_known_selectors() {
+        assert_eq!(
+            hex_string(&keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex_string(&keccak256(b"transfer(address,uint256)")[..4]),
+            "a9059cbb"
+        );
+    }
+
+    #[test]
+    fn block_values_show_units_dates_and_hashes() {
+        let block = exchange(
+            "eth_getBlockByNumber",
+            json!(["latest", false]),
+            message(
+                None,
+                Some(json!({
+                    "number": "0x10",
+                    "timestamp": "0x6553f100",
+                    "baseFeePerGas": "0x3b9aca00",
+                    "hash": format!("0x{}", "ab".repeat(32)),
+                    "miner": format!("0x{}", "12".repeat(20)),
+                    "logsBloom": "0x0000",
+                })),
+                None,
+            ),
+        );
+
+        let values = response_values(&block, &Abi::default());
+
+        assert_eq!(
+            values,
+            vec![
+                Decoded::new(
+                    "result.baseFeePerGas",
+                    "1000000000 wei · 1 gwei · 0.000000001 ETH"
+                ),
+                Decoded::new("result.hash", "32-byte hash"),
+                Decoded::new("result.miner", "address"),
+                Decoded::new("result.number", "16"),
+                Decoded::new("result.timestamp", "1700000000 (2023-11-14 22:13:20 UTC)"),
+            ]
+        );
+        assert_eq!(
+            decimal(&[0xff; 32]),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+
+    #[test]
+    fn eth_call_input_output_and_reverts_decode_against_the_abi() {
+        let abi = erc20();
+        let recipient = "11".repeat(20);
+        let data = format!("0xa9059cbb{:0>64}{:0>64}", recipient, "de0b6b3a7640000");
+        let call = exchange(
+            "eth_call",
+            json!([{ "to": format!("0x{}", "22".repeat(20)), "data": data }, "latest"]),
+            message(None, Some(json!(format!("0x{:0>64}", "1"))), None),
+        );
+
+        assert_eq!(
+            request_values(&call, &abi),
+            vec![
+                Decoded::new("params[0].data", "transfer(address,uint256)"),
+                Decoded::new("  to", format!("address = 0x{recipient}")),
+                Decoded::new("  amount", "uint256 = 1000000000000000000"),
+                Decoded::new("params[0].to", "address"),
+            ]
+        );
+        assert_eq!(
+            response_values(&call, &abi),
+            vec![
+                Decoded::new("result", "transfer returns (bool)"),
+                Decoded::new("  [0]", "bool = true"),
+            ]
+        );
+
+        let reason = hex_string(b"not owner");
+        let revert = exchange(
+            "eth_call",
+            json!([{ "data": "0xdeadbeef" }]),
+            message(
+                None,
+                None,
+                Some(json!({
+                    "code": 3,
+                    "message": "execution reverted",
+                    "data": format!(
+                        "0x08c379a0{:0>64}{:0>64}{:0<64}",
+                        "20", "9", reason
+                    ),
+                })),
+            ),
+        );
+        let panic = exchange(
+            "eth_call",
+            json!([{ "data": "0xdeadbeef" }]),
+            message(
+                None,
+                None,
+                Some(json!({ "code": 3, "data": { "data": format!("0x4e487b71{:0>64}", "11") } })),
+            ),
+        );
+        let custom = exchange(
+            "eth_call",
+            json!([{ "data": "0xdeadbeef" }]),
+            message(
+                None,
+                None,
+                Some(json!({
+                    "code": 3,
+                    "data": format!("0x{}{:0>64}", hex_string(&keccak256(b"InsufficientBalance(uint256)")[..4]), "ff"),
+                })),
+            ),
+        );
+
+        assert_eq!(
+            request_values(&revert, &abi),
+            vec![Decoded::new(
+                "params[0].data",
+                "selector 0xdeadbeef (not in ABI directory)"
+            )]
+        );
+        assert_eq!(
+            response_values(&revert, &abi),
+            vec![Decoded::new("error.data", "revert \"not owner\"")]
+        );
+        assert_eq!(
+            response_values(&panic, &abi),
+            vec![Decoded::new(
+                "error.data.data",
+                "panic 0x11 (arithmetic overflow or underflow)"
+            )]
+        );
+        assert_eq!(
+            decode_value(
+                &Kind::Array(Box::new(Kind::Uint(256))),
+                &hex_bytes(&format!("{:0>64}", "ffffffffffff")).unwrap()
+            ),
+            None
+        );
+        assert_eq!(
+            decode_value(
+                &Kind::Bytes,
+                &hex_bytes(&format!("{:0>64}", "ffffffffffffffff")).unwrap()
+            ),
+            None
+        );
+        let two_words = hex_bytes(&format!("{:0>64}{:0>64}", "1", "2")).unwrap();
+        assert_eq!(
+            decode_value(&parse_kind("uint256[2]", None).unwrap(), &two_words),
+            Some("[1, 2]".to_string())
+        );
+        for kind in [
+            "uint256[99999999999]",
+            "uint256[99999999999][99999999999999]",
+        ] {
+            assert_eq!(
+                decode_value(&parse_kind(kind, None).unwrap(), &two_words),
+                None
+            );
+        }
+        assert_eq!(
+            response_values(&custom, &abi),
+            vec![
+                Decoded::new("error.data", "revert InsufficientBalance(uint256)"),
+                Decoded::new("  available", "uint256 = 255"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,617 @@
+use crate::app::Connection;
+use crate::control::{Session, SessionMessage, SessionTransport};
+use crate::diff::{self, ChangeKind, Difference};
+use crate::snippet::should_replay_header;
+use crate::stdio::StdioTransport;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The file in a fixture directory that holds the target and rules for every fixture.
+pub const MANIFEST: &str = "fixtures.json";
+
+/// Where fixtures were recorded, and comparison rules shared by all of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub target: String,
+    pub transport: SessionTransport,
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default)]
+    pub tolerance: BTreeMap<String, Value>,
+}
+
+/// One recorded call: the request to send and the response it should get. Notifications
+/// have no response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fixture {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    pub request: Value,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+    /// Paths such as `$.result.timestamp` or `$.result[*].hash` whose values, and everything
+    /// below them, may differ.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+    /// How far numbers, including `0x` quantities, may drift: `{"$.result.gasUsed": 500}` or
+    /// `{"$.result.gasUsed": "5%"}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tolerance: BTreeMap<String, Value>,
+}
+
+/// A fixture directory read in file name order.
+#[derive(Debug)]
+pub struct Suite {
+    pub manifest: Manifest,
+    pub fixtures: Vec<(String, Fixture)>,
+}
+
+/// Turns each captured request of a session into a fixture, named in capture order. Requests
+/// the server sent, such as `workspace/configuration`, are left out: `verify` can only replay
+/// what the client sends.
+pub fn generate(session: &Session) -> (Manifest, Vec<(String, Fixture)>) {
+    let fixtures = session
+        .exchanges
+        .iter()
+        .filter_map(|exchange| {
+            let request = exchange
+                .request
+                .as_ref()
+                .filter(|request| !sent_by_server(request))?;
+            let method = request
+                .body
+                .get("method")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let headers = request
+                .headers
+                .iter()
+                .flatten()
+                .filter(|(name, _)| should_replay_header(name))
+                .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+                .collect();
+            Some(Fixture {
+                method,
+                request: request.body.clone(),
+                headers,
+                response: exchange
+                    .response
+                    .as_ref()
+                    .map(|response| response.body.clone()),
+                ignore: Vec::new(),
+                tolerance: BTreeMap::new(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let width = fixtures.len().to_string().len().max(3);
+    let fixtures = fixtures
+        .into_iter()
+        .enumerate()
+        .map(|(index, fixture)| {
+            let method = fixture
+                .method
+                .as_deref()
+                .unwrap_or("response")
+                .split(|character: char| !character.is_ascii_alphanumeric() && character != '_')
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join("-");
+            (format!("{:0width$}-{method}.json", index + 1), fixture)
+        })
+        .collect();
+    let manifest = Manifest {
+        target: session.target.clone(),
+        transport: session
+            .exchanges
+            .first()
+            .map_or(SessionTransport::Http, |exchange| exchange.transport),
+        ignore: Vec::new(),
+        tolerance: BTreeMap::new(),
+    };
+    (manifest, fixtures)
+}
+
+/// Writes the fixtures of a session into `dir`. An existing manifest is kept so hand-written
+/// rules survive regenerating, while fixtures from an earlier run are removed so none outlive
+/// the session.
+pub fn write(dir: &Path, session: &Session) -> Result<Vec<PathBuf>, String> {
+    let (manifest, fixtures) = generate(session);
+    if fixtures.is_empty() {
+        return Err("The session has no requests".to_string());
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|error| format!("failed to create {}: {error}", dir.display()))?;
+    let stale = std::fs::read_dir(dir)
+        .map_err(|error| format!("failed to read {}: {error}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.file_name().is_some_and(is_generated_name));
+    for path in stale {
+        std::fs::remove_file(&path)
+            .map_err(|error| format!("failed to remove {}: {error}", path.display()))?;
+    }
+    let manifest_path = dir.join(MANIFEST);
+    let manifest = (!manifest_path.exists()).then(|| (manifest_path, to_json(&manifest)));
+    let mut written = Vec::new();
+    for (path, json) in manifest.into_iter().chain(
+        fixtures
+            .iter()
+            .map(|(name, fixture)| (dir.join(name), to_json(fixture))),
+    ) {
+        std::fs::write(&path, json + "\n")
+            .map_err(|error| format!("failed to write {}: {error}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+pub fn load(dir: &Path) -> Result<Suite, String> {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map_err(|error| format!("failed to read {}: {error}", path.display()))
+    };
+    let manifest_path = dir.join(MANIFEST);
+    let manifest = serde_json::from_str(&read(&manifest_path)?)
+        .map_err(|error| format!("invalid {}: {error}", manifest_path.display()))?;
+
+    let mut paths = std::fs::read_dir(dir)
+        .map_err(|error| format!("failed to read {}: {error}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+                && path.file_name().is_some_and(|name| name != MANIFEST)
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    let fixtures = paths
+        .iter()
+        .map(|path| {
+            let fixture = serde_json::from_str(&read(path)?)
+                .map_err(|error| format!("invalid {}: {error}", path.display()))?;
+            let name = path
+                .file_name()
+                .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+            Ok((name, fixture))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if fixtures.is_empty() {
+        return Err(format!("No fixtures in {}", dir.display()));
+    }
+    Ok(Suite { manifest, fixtures })
+}
+
+/// Where `verify` sends fixture requests: an HTTP endpoint or a spawned stdio server.
+pub enum Target {
+    Http {
+        url: String,
+        client: reqwest::Client,
+    },
+    Stdio(StdioTransport),
+}
+
+impl Target {
+    pub fn http(url: &str) -> Result<Self, String> {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(format!(
+                "{url} is not an HTTP URL; pass --target or a stdio command"
+            ));
+        }
+        Ok(Self::Http {
+            url: url.to_string(),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Sends a request and returns its response, or `None` for a notification.
+    async fn send(&self, fixture: &Fixture) -> Result<Option<Value>, String> {
+        match self {
+            Self::Http { url, client } => {
+                let mut request = client.post(url).json(&fixture.request);
+                for (name, value) in &fixture.headers {
+                    request = request.header(name, value);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .map_err(|error| format!("failed to send request: {error}"))?;
+                let status = response.status();
+                let text = response
+                    .text()
+                    .await
+                    .map_err(|error| format!("failed to read response: {error}"))?;
+                if text.trim().is_empty() {
+                    return Ok(None);
+                }
+                serde_json::from_str(&text)
+                    .map(Some)
+                    .map_err(|error| format!("HTTP {status}: invalid JSON response: {error}"))
+            }
+            Self::Stdio(transport) => transport
+                .send(fixture.request.clone(), None)
+                .await
+                .map(|response| Some(response).filter(|response| !response.is_null())),
+        }
+    }
+}
+
+/// How one fixture fared.
+#[derive(Debug)]
+pub struct Outcome {
+    pub name: String,
+    pub result: Result<(), Failure>,
+}
+
+#[derive(Debug)]
+pub enum Failure {
+    Send(String),
+    Mismatch(Vec<Difference>),
+}
+
+/// Sends every fixture in order, so calls such as `initialize` set up the ones after them,
+/// and compares each response with the recorded one.
+pub async fn verify(suite: &Suite, target: &Target, timeout: Duration) -> Vec<Outcome> {
+    let mut outcomes = Vec::with_capacity(suite.fixtures.len());
+    for (name, fixture) in &suite.fixtures {
+        let sent = tokio::time::timeout(timeout, target.send(fixture))
+            .await
+            .unwrap_or_else(|_| Err(format!("no response within {}s", timeout.as_secs())));
+        let result = match (sent, &fixture.response) {
+            (Err(error), _) => Err(Failure::Send(error)),
+            (Ok(_), None) => Ok(()),
+            (Ok(actual), Some(expected)) => {
+                let differences = compare(expected, actual.as_ref(), &suite.manifest, fixture);
+                if differences.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Failure::Mismatch(differences))
+                }
+            }
+        };
+        outcomes.push(Outcome {
+            name: name.clone(),
+            result,
+        });
+    }
+    outcomes
+}
+
+/// Differences between the recorded and actual responses that the ignore and tolerance
+/// rules of the manifest and the fixture do not allow.
+pub fn compare(
+    expected: &Value,
+    actual: Option<&Value>,
+    manifest: &Manifest,
+    fixture: &Fixture,
+) -> Vec<Difference> {
+    let ignore = manifest
+        .ignore
+        .iter()
+        .chain(&fixture.ignore)
+        .map(|pattern| parse_path(pattern))
+        .collect::<Vec<_>>();
+    let tolerance = manifest
+        .tolerance
+        .iter()
+        .chain(&fixture.tolerance)
+        .map(|(pattern, allowed)| (parse_path(pattern), allowed))
+        .collect::<Vec<_>>();
+
+    let mut differences = Vec::new();
+    diff::values("$", Some(expected), actual, &mut differences);
+    differences.retain(|difference| {
+        let path = parse_path(&difference.path);
+        if ignore.iter().any(|pattern| matches(pattern, &path, true)) {
+            return false;
+        }
+        !tolerance
+            .iter()
+            .any(|(pattern, allowed)| matches(pattern, &path, false) && within(difference, allowed))
+    });
+    differences
+}
+
+/// A readable account of every failure, then a count.
+pub fn describe(outcomes: &[Outcome]) -> String {
+    let mut out = String::new();
+    for outcome in outcomes {
+        match &outcome.result {
+            Ok(()) => out.push_str(&format!("ok      {}\n", outcome.name)),
+            Err(Failure::Send(error)) => {
+                out.push_str(&format!("FAILED  {}\n    {error}\n", outcome.name));
+            }
+            Err(Failure::Mismatch(differences)) => {
+                out.push_str(&format!("FAILED  {}\n", outcome.name));
+                for difference in differences {
+                    let value = |value: &Option<Value>| {
+                        let text = value.as_ref().map_or_else(String::new, Value::to_string);
+                        if text.chars().count() > 80 {
+                            format!("{}…", text.chars().take(79).collect::<String>())
+                        } else {
+                            text
+                        }
+                    };
+                    let change = match difference.kind {
+                        ChangeKind::Changed => format!(
+                            "expected {}, got {}",
+                            value(&difference.before),
+                            value(&difference.after)
+                        ),
+                        ChangeKind::Removed => {
+                            format!("missing, expected {}", value(&difference.before))
+                        }
+                        ChangeKind::Added => format!("unexpected {}", value(&difference.after)),
+                    };
+                    out.push_str(&format!("    {}: {change}\n", difference.path));
+                }
+            }
+        }
+    }
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| outcome.result.is_err())
+        .count();
+    out.push_str(&format!(
+        "\n{} passed, {failed} failed\n",
+        outcomes.len() - failed
+    ));
+    out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Any,
+}
+
+/// Splits `$.result.logs[0]["a b"]` into segments; `*` and `[*]` match any key or index.
+fn parse_path(path: &str) -> Vec<Segment> {
+    let path = path.trim().strip_prefix('$').unwrap_or(path.trim());
+    // Allow `result.value` for `$.result.value`.
+    let path = if path.starts_with(['.', '[']) || path.is_empty() {
+        path.to_string()
+    } else {
+        format!(".{path}")
+    };
+    let mut segments = Vec::new();
+    let mut rest = path.as_str();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            segments.push(match &after[..end] {
+                "*" => Segment::Any,
+                key => Segment::Key(key.to_string()),
+            });
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix("[\"") {
+            let end = after.find("\"]").unwrap_or(after.len());
+            let key = serde_json::from_str(&format!("\"{}\"", &after[..end]))
+                .unwrap_or_else(|_| after[..end].to_string());
+            segments.push(Segment::Key(key));
+            rest = after.get(end + 2..).unwrap_or("");
+        } else {
+            let after = &rest[1..];
+            let end = after.find(']').unwrap_or(after.len());
+            segments.push(match after[..end].parse() {
+                Ok(index) => Segment::Index(index),
+                Err(_) => Segment::Any,
+            });
+            rest = after.get(end + 1..).unwrap_or("");
+        }
+    }
+    segments
+}
+
+/// Whether `pattern` names `path`, or with `below` any of its ancestors.
+fn matches(pattern: &[Segment], path: &[Segment], below: bool) -> bool {
+    let length_fits = if below {
+        pattern.len() <= path.len()
+    } else {
+        pattern.len() == path.len()
+    };
+    length_fits
+        && pattern
+            .iter()
+            .zip(path)
+            .all(|(pattern, segment)| *pattern == Segment::Any || pattern == segment)
+}
+
+fn within(difference: &Difference, allowed: &Value) -> bool {
+    let (Some(expected), Some(actual)) = (
+        difference.before.as_ref().and_then(number),
+        difference.after.as_ref().and_then(number),
+    ) else {
+        return false;
+    };
+    let limit = match allowed {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text
+            .trim()
+            .strip_suffix('%')
+            .and_then(|percent| percent.trim().parse::<f64>().ok())
+            .map(|percent| expected.abs() * percent / 100.0),
+        _ => None,
+    };
+    limit.is_some_and(|limit| (expected - actual).abs() <= limit)
+}
+
+/// A JSON number or a `0x` quantity.
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text
+            .strip_prefix("0x")
+            .and_then(|digits| u128::from_str_radix(digits, 16).ok())
+            .map(|number| number as f64),
+        _ => None,
+    }
+}
+
+/// Whether the server started the exchange: trace imports name the sender `server`, and the
+/// stdio relay logs server requests on their own connection.
+fn sent_by_server(request: &SessionMessage) -> bool {
+    request.connection.as_ref().is_some_and(|connection| {
+        connection.client == "server" || connection.id == Connection::stdio_server().id
+    })
+}
+
+/// Whether a file name has the `NNN-method.json` shape that `generate` gives fixtures.
+fn is_generated_name(name: &std::ffi::OsStr) -> bool {
+    name.to_str()
+        .and_then(|name| name.strip_suffix(".json"))
+        .and_then(|stem| stem.split_once('-'))
+        .is_some_and(|(number, _)| {
+            !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit())
+        })
+}
+
+fn to_json(value: &impl Serialize) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{json_rpc_messages_by_shape, App, TransportType};
+    use crate::control::export_session;
+    use serde_json::json;
+
+    #[test]
+    fn sessions_become_numbered_fixtures() {
+        let mut app = App::new();
+        app.proxy_config.target_url = "https://rpc.example/".to_string();
+        let headers = std::collections::HashMap::from([
+            ("Authorization".to_string(), "Bearer token".to_string()),
+            ("Content-Length".to_string(), "42".to_string()),
+        ]);
+        for (body, headers) in [
+            (
+                json!({"jsonrpc": "2.0", "id": 1, "method": "eth_getBalance"}),
+                Some(&headers),
+            ),
+            (json!({"jsonrpc": "2.0", "id": 1, "result": "0x10"}), None),
+            (json!({"jsonrpc": "2.0", "method": "net/ping"}), None),
+        ] {
+            for message in json_rpc_messages_by_shape(&body, TransportType::Http, headers) {
+                app.add_message(message);
+            }
+        }
+
+        let (manifest, fixtures) = generate(&export_session(&app));
+        assert_eq!(manifest.target, "https://rpc.example/");
+        assert_eq!(manifest.transport, SessionTransport::Http);
+        let names = fixtures
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["001-eth_getBalance.json", "002-net-ping.json"]);
+        let balance = &fixtures[0].1;
+        assert_eq!(
+            balance.response,
+            Some(json!({"jsonrpc": "2.0", "id": 1, "result": "0x10"}))
+        );
+        assert_eq!(
+            balance.headers,
+            BTreeMap::from([("authorization".to_string(), "Bearer token".to_string())])
+        );
+        assert_eq!(fixtures[1].1.response, None);
+    }
+
+    #[test]
+    fn regenerating_replaces_fixtures_and_skips_server_requests() {
+        let log = [
+            json!({"jsonrpc": "2.0", "id": 0, "method": "initialize"}),
+            json!({"jsonrpc": "2.0", "id": 0, "result": {}}),
+            json!({"jsonrpc": "2.0", "id": 1, "method": "workspace/configuration"}),
+            json!({"jsonrpc": "2.0", "id": 1, "result": [null]}),
+        ]
+        .iter()
+        .zip(["client", "server", "server", "client"])
+        .map(|(message, sender)| format!("Message from {sender}: {message}\n"))
+        .collect::<String>();
+        let session = crate::trace::import(&log, "rust-analyzer").unwrap().session;
+        assert_eq!(session.exchanges.len(), 2);
+
+        let (_, fixtures) = generate(&session);
+        let names = fixtures
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["001-initialize.json"]);
+
+        let dir = std::env::temp_dir().join(format!("fixture-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["001-shutdown.json", "002-exit.json", "notes.json"] {
+            std::fs::write(dir.join(name), "{}").unwrap();
+        }
+        write(&dir, &session).unwrap();
+        let mut files = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(
+            files,
+            ["001-initialize.json", "fixtures.json", "notes.json"]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn comparisons_honor_ignore_paths_and_tolerances() {
+        let manifest = Manifest {
+            target: String::new(),
+            transport: SessionTransport::Http,
+            ignore: vec!["$.result.blocks[*].hash".to_string()],
+            tolerance: BTreeMap::from([("$.result.gas".to_string(), json!("10%"))]),
+        };
+        let fixture = Fixture {
+            method: None,
+            request: json!({}),
+            headers: BTreeMap::new(),
+            response: None,
+            ignore: vec!["$[\"extra key\"]".to_string()],
+            tolerance: BTreeMap::from([("$.result.fee".to_string(), json!(5))]),
+        };
+        let expected = json!({"result": {
+            "blocks": [{"hash": "0xa", "number": 1}],
+            "gas": "0x64",
+            "fee": 10,
+            "name": "a",
+        }});
+        let actual = json!({"extra key": true, "result": {
+            "blocks": [{"hash": "0xb", "number": 1}],
+            "gas": "0x6e",
+            "fee": 20,
+        }});
+
+        let differences = compare(&expected, Some(&actual), &manifest, &fixture);
+        let paths = differences
+            .iter()
+            .map(|difference| difference.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["$.result.fee", "$.result.name"]);
+
+        let report = describe(&[
+            Outcome {
+                name: "001-a.json".to_string(),
+                result: Ok(()),
+            },
+            Outcome {
+                name: "002-b.json".to_string(),
+                result: Err(Failure::Mismatch(differences)),
+            },
+        ]);
+        assert!(report.contains("FAILED  002-b.json\n    $.result.fee: expected 10, got 20\n"));
+        assert!(report.contains("    $.result.name: missing, expected \"a\"\n"));
+        assert!(report.ends_with("\n1 passed, 1 failed\n"));
+    }
+}
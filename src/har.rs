@@ -0,0 +1,612 @@
+use crate::app::{
+    json_rpc_messages_by_shape, Connection, JsonRpcExchange, JsonRpcMessage, Timing, TransportType,
+};
+use crate::bookmark::Bookmark;
+use crate::control::{
+    timestamp_ms, Session, SessionExchange, SessionMessage, SessionTiming, SessionTransport,
+};
+use crate::time::{iso8601, parse_iso8601};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const HAR_VERSION: &str = "1.2";
+const STDIO_SCHEME: &str = "stdio:";
+
+/// A HAR log read as a portable session. `skipped` counts entries whose bodies were not
+/// JSON-RPC, such as the page and asset requests of a browser capture.
+#[derive(Debug)]
+pub struct Import {
+    pub session: Session,
+    pub skipped: usize,
+}
+
+/// Writes a session as a HAR 1.2 log. Every HTTP request is one entry, so the calls of a batch
+/// share one. Stdio exchanges have no HTTP request: each becomes an entry whose URL names the
+/// command, and `_transport` keeps the framing so an import restores it.
+pub fn export(session: &Session) -> Value {
+    let mut entries: Vec<Vec<&SessionExchange>> = Vec::new();
+    let mut batches: HashMap<&str, usize> = HashMap::new();
+    for exchange in &session.exchanges {
+        let batch = exchange
+            .request
+            .as_ref()
+            .or(exchange.response.as_ref())
+            .and_then(|message| message.batch.as_ref());
+        match batch.map(|batch| batch.id.as_str()) {
+            Some(id) if batches.contains_key(id) => entries[batches[id]].push(exchange),
+            Some(id) => {
+                batches.insert(id, entries.len());
+                entries.push(vec![exchange]);
+            }
+            None => entries.push(vec![exchange]),
+        }
+    }
+
+    json!({
+        "log": {
+            "version": HAR_VERSION,
+            "creator": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "entries": entries
+                .iter()
+                .map(|exchanges| entry(&session.target, exchanges))
+                .collect::<Vec<_>>(),
+        }
+    })
+}
+
+/// Reads a HAR log, keeping the entries whose request body is JSON-RPC. Each body splits into
+/// exchanges the way the proxy splits live traffic, and responses pair with requests by id.
+pub fn import(har: &Value) -> Result<Import, String> {
+    let entries = har
+        .pointer("/log/entries")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "HAR log.entries must be an array".to_string())?;
+    let mut target = None;
+    let mut exchanges = Vec::new();
+    let mut skipped = 0;
+    for entry in entries {
+        let Some(found) = entry_exchanges(entry) else {
+            skipped += 1;
+            continue;
+        };
+        target.get_or_insert_with(|| entry_target(entry));
+        exchanges.extend(found);
+    }
+
+    Ok(Import {
+        session: Session {
+            schema_version: 1,
+            exported_at_ms: timestamp_ms(SystemTime::now()),
+            target: target.unwrap_or_default(),
+            exchanges,
+        },
+        skipped,
+    })
+}
+
+fn entry(target: &str, exchanges: &[&SessionExchange]) -> Value {
+    let first = exchanges[0];
+    let batched = exchanges
+        .iter()
+        .flat_map(|exchange| [&exchange.request, &exchange.response])
+        .flatten()
+        .any(|message| message.batch.is_some());
+    let requests = messages(exchanges, |exchange| exchange.request.as_ref());
+    let responses = messages(exchanges, |exchange| exchange.response.as_ref());
+    let started = requests
+        .first()
+        .or(responses.first())
+        .map_or(0, |message| message.timestamp_ms);
+    let finished = responses
+        .iter()
+        .map(|message| message.timestamp_ms)
+        .max()
+        .unwrap_or(started);
+    let elapsed = finished.saturating_sub(started) as f64;
+    let timing = responses
+        .iter()
+        .find_map(|message| message.timing.clone())
+        .unwrap_or_default();
+    let stdio = matches!(
+        first.transport,
+        SessionTransport::StdioJsonLines
+            | SessionTransport::StdioContentLength
+            | SessionTransport::StdioDap
+    );
+
+    let mut request = json!({
+        "method": "POST",
+        "url": if stdio { format!("{STDIO_SCHEME}{target}") } else { target.to_string() },
+        "httpVersion": "HTTP/1.1",
+        "cookies": [],
+        "headers": har_headers(requests.first().and_then(|message| message.headers.as_ref())),
+        "queryString": [],
+        "headersSize": -1,
+        "bodySize": -1,
+    });
+    if let Some(text) = body_text(&requests, batched) {
+        request["bodySize"] = json!(text.len());
+        request["postData"] = json!({"mimeType": "application/json", "text": text});
+    }
+
+    let mut response = json!({
+        "status": 0,
+        "statusText": "",
+        "httpVersion": "HTTP/1.1",
+        "cookies": [],
+        "headers": har_headers(responses.first().and_then(|message| message.headers.as_ref())),
+        "content": {"size": 0, "mimeType": "application/json"},
+        "redirectURL": "",
+        "headersSize": -1,
+        "bodySize": -1,
+    });
+    if let Some(text) = body_text(&responses, batched) {
+        response["status"] = json!(200);
+        response["statusText"] = json!("OK");
+        response["bodySize"] = json!(text.len());
+        response["content"] = json!({
+            "size": text.len(),
+            "mimeType": "application/json",
+            "text": text,
+        });
+    }
+
+    // Unknown phases are -1, and the entry's time is the sum of the known ones.
+    let blocked = timing.queued_ms.unwrap_or(-1.0);
+    let dns = timing.dns_ms.unwrap_or(-1.0);
+    // HAR counts the TLS handshake in `connect` as well as in `ssl`.
+    let ssl = timing.tls_ms.unwrap_or(-1.0);
+    let connect = match (timing.connect_ms, timing.tls_ms) {
+        (None, None) => -1.0,
+        (connect, tls) => connect.unwrap_or(0.0) + tls.unwrap_or(0.0),
+    };
+    let receive = timing.download_ms.unwrap_or(0.0);
+    let setup = blocked.max(0.0) + dns.max(0.0) + connect.max(0.0);
+    let wait = timing
+        .first_byte_ms
+        .unwrap_or_else(|| (elapsed - setup - receive).max(0.0));
+    let time = setup + wait + receive;
+
+    let mut entry = json!({
+        "startedDateTime": iso8601(started),
+        "time": time,
+        "request": request,
+        "response": response,
+        "cache": {},
+        "timings": {
+            "blocked": blocked,
+            "dns": dns,
+            "connect": connect,
+            "send": 0,
+            "wait": wait,
+            "receive": receive,
+            "ssl": ssl,
+        },
+        "_transport": first.transport,
+    });
+    if stdio {
+        entry["comment"] = json!("stdio messages; no HTTP request was made");
+    }
+    if let Some(connection) = requests
+        .iter()
+        .chain(&responses)
+        .find_map(|message| message.connection.as_ref())
+    {
+        entry["_connection"] = json!(connection);
+    }
+    if !first.bookmark.is_empty() {
+        entry["_bookmark"] = json!(first.bookmark);
+    }
+    entry
+}
+
+/// The messages on one side of an entry, in their order within the batch.
+fn messages<'a>(
+    exchanges: &[&'a SessionExchange],
+    side: impl Fn(&'a SessionExchange) -> Option<&'a SessionMessage>,
+) -> Vec<&'a SessionMessage> {
+    let mut messages = exchanges
+        .iter()
+        .filter_map(|exchange| side(exchange))
+        .collect::<Vec<_>>();
+    messages.sort_by_key(|message| message.batch.as_ref().map(|batch| batch.position));
+    messages
+}
+
+fn body_text(messages: &[&SessionMessage], batched: bool) -> Option<String> {
+    match messages {
+        [] => None,
+        [message] if !batched => Some(message.body.to_string()),
+        messages => Some(
+            Value::Array(
+                messages
+                    .iter()
+                    .map(|message| message.body.clone())
+                    .collect(),
+            )
+            .to_string(),
+        ),
+    }
+}
+
+fn har_headers(headers: Option<&HashMap<String, String>>) -> Value {
+    let mut headers = headers
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| json!({"name": name, "value": value}))
+        .collect::<Vec<_>>();
+    headers.sort_by(|left, right| left["name"].as_str().cmp(&right["name"].as_str()));
+    Value::Array(headers)
+}
+
+fn entry_exchanges(entry: &Value) -> Option<Vec<SessionExchange>> {
+    let request_body = entry_body(entry.pointer("/request/postData"))?;
+    let transport = entry
+        .get("_transport")
+        .and_then(|transport| serde_json::from_value::<SessionTransport>(transport.clone()).ok())
+        .map_or_else(
+            || {
+                if request_body.is_array() {
+                    TransportType::HttpBatch
+                } else {
+                    TransportType::Http
+                }
+            },
+            TransportType::from,
+        );
+    if !is_json_rpc(&request_body, transport, true) {
+        return None;
+    }
+    let response_body = entry_body(entry.pointer("/response/content"))
+        .filter(|body| is_json_rpc(body, transport, false));
+
+    let started = entry
+        .get("startedDateTime")
+        .and_then(Value::as_str)
+        .and_then(parse_iso8601)
+        .map_or(UNIX_EPOCH, |ms| UNIX_EPOCH + Duration::from_millis(ms));
+    let elapsed = entry
+        .get("time")
+        .and_then(Value::as_f64)
+        .filter(|time| time.is_finite() && *time >= 0.0)
+        .unwrap_or(0.0);
+    let connection = entry.get("_connection").and_then(|connection| {
+        Some(Connection {
+            id: connection.get("id")?.as_str()?.to_string(),
+            client: connection.get("client")?.as_str()?.to_string(),
+            source: connection
+                .get("source")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        })
+    });
+    // Stdio entries carry no network phases of their own.
+    let timing = (!matches!(transport, TransportType::Stdio(_)))
+        .then(|| entry.get("timings").and_then(entry_timing))
+        .flatten();
+
+    let request_headers = entry_headers(entry.pointer("/request/headers"));
+    let mut requests =
+        json_rpc_messages_by_shape(&request_body, transport, request_headers.as_ref());
+    for request in &mut requests {
+        request.timestamp = started;
+        request.connection.clone_from(&connection);
+    }
+    let response_headers = entry_headers(entry.pointer("/response/headers"));
+    let mut responses = response_body
+        .map(|body| json_rpc_messages_by_shape(&body, transport, response_headers.as_ref()))
+        .unwrap_or_default();
+    // A `time` too large for a timestamp leaves the response at the request's time.
+    let answered = Duration::try_from_secs_f64(elapsed / 1000.0)
+        .ok()
+        .and_then(|elapsed| started.checked_add(elapsed))
+        .unwrap_or(started);
+    for response in &mut responses {
+        response.timestamp = answered;
+        response.connection.clone_from(&connection);
+        response.timing = timing;
+    }
+
+    let mut exchanges = Vec::new();
+    for request in requests {
+        let response = request
+            .id
+            .as_ref()
+            .and_then(|id| {
+                responses
+                    .iter()
+                    .position(|response| response.id.as_ref() == Some(id))
+            })
+            .map(|position| responses.remove(position));
+        exchanges.push(exchange(Some(request), response, transport));
+    }
+    exchanges.extend(
+        responses
+            .into_iter()
+            .map(|response| exchange(None, Some(response), transport)),
+    );
+
+    let mut exchanges = exchanges
+        .iter()
+        .map(SessionExchange::from)
+        .collect::<Vec<_>>();
+    if let Some(bookmark) = entry
+        .get("_bookmark")
+        .and_then(|bookmark| serde_json::from_value::<Bookmark>(bookmark.clone()).ok())
+    {
+        exchanges[0].bookmark = bookmark;
+    }
+    Some(exchanges)
+}
+
+fn exchange(
+    request: Option<JsonRpcMessage>,
+    response: Option<JsonRpcMessage>,
+    transport: TransportType,
+) -> JsonRpcExchange {
+    let first = request.as_ref().or(response.as_ref());
+    JsonRpcExchange {
+        id: first.and_then(|message| message.id.clone()),
+        method: request.as_ref().and_then(|request| request.method.clone()),
+        timestamp: first.map_or(UNIX_EPOCH, |message| message.timestamp),
+        transport,
+        request,
+        response,
+    }
+}
+
+fn entry_target(entry: &Value) -> String {
+    let url = entry
+        .pointer("/request/url")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    url.strip_prefix(STDIO_SCHEME).unwrap_or(url).to_string()
+}
+
+/// The JSON of a `postData` or `content` object, which browsers may store as base64.
+fn entry_body(body: Option<&Value>) -> Option<Value> {
+    let body = body?;
+    let text = body.get("text")?.as_str()?;
+    if body.get("encoding").and_then(Value::as_str) == Some("base64") {
+        let decoded = BASE64.decode(text).ok()?;
+        return serde_json::from_slice(&decoded).ok();
+    }
+    serde_json::from_str(text).ok()
+}
+
+fn is_json_rpc(body: &Value, transport: TransportType, request: bool) -> bool {
+    let messages = body
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_else(|| std::slice::from_ref(body));
+    !messages.is_empty()
+        && messages.iter().all(|message| {
+            if transport.is_dap() {
+                return message.get("type").is_some_and(Value::is_string);
+            }
+            let shaped = if request {
+                message.get("method").is_some_and(Value::is_string)
+            } else {
+                message.get("result").is_some() || message.get("error").is_some()
+            };
+            message.get("jsonrpc") == Some(&json!("2.0")) && shaped
+        })
+}
+
+fn entry_headers(headers: Option<&Value>) -> Option<HashMap<String, String>> {
+    let headers = headers?
+        .as_array()?
+        .iter()
+        .filter_map(|header| {
+            Some((
+                header.get("name")?.as_str()?.to_string(),
+                header.get("value")?.as_str()?.to_string(),
+            ))
+        })
+        .collect::<HashMap<_, _>>();
+    (!headers.is_empty()).then_some(headers)
+}
+
+fn entry_timing(timings: &Value) -> Option<Timing> {
+    let phase = |name: &str| {
+        timings
+            .get(name)
+            .and_then(Value::as_f64)
+            .filter(|ms| ms.is_finite() && *ms >= 0.0)
+    };
+    let tls_ms = phase("ssl");
+    let timing = Timing::from(SessionTiming {
+        queued_ms: phase("blocked"),
+        dns_ms: phase("dns"),
+        connect_ms: phase("connect").map(|connect| (connect - tls_ms.unwrap_or(0.0)).max(0.0)),
+        tls_ms,
+        first_byte_ms: phase("wait"),
+        download_ms: phase("receive"),
+        delivery_ms: None,
+    });
+    (timing != Timing::default()).then_some(timing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+    use crate::control::{export_session, replay_session, session_bookmarks};
+
+    fn message(body: Value, timestamp_ms: u64) -> SessionMessage {
+        SessionMessage {
+            body,
+            headers: None,
+            timestamp_ms,
+            connection: None,
+            batch: None,
+            timing: None,
+        }
+    }
+
+    #[test]
+    fn batches_share_an_entry_and_round_trip() {
+        let mut app = App::new();
+        let body = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"},
+            {"jsonrpc": "2.0", "method": "eth_subscribe"},
+        ]);
+        for message in json_rpc_messages_by_shape(&body, TransportType::HttpBatch, None) {
+            app.add_message(message);
+        }
+        let response = json!([{"jsonrpc": "2.0", "id": 1, "result": "0x1"}]);
+        for message in json_rpc_messages_by_shape(&response, TransportType::HttpBatch, None) {
+            app.add_message(message);
+        }
+        app.set_bookmark(
+            0,
+            Bookmark {
+                starred: true,
+                ..Bookmark::default()
+            },
+        );
+
+        let har = export(&export_session(&app));
+        let entries = har["log"]["entries"].as_array().unwrap();
+        assert_eq!(har["log"]["version"], "1.2");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["response"]["status"], 200);
+        let sent: Value =
+            serde_json::from_str(entries[0]["request"]["postData"]["text"].as_str().unwrap())
+                .unwrap();
+        assert_eq!(sent, body);
+
+        let imported = import(&har).unwrap();
+        assert_eq!(imported.skipped, 0);
+        assert_eq!(session_bookmarks(&imported.session).len(), 1);
+        let exchanges = replay_session(imported.session).unwrap();
+        assert_eq!(exchanges.len(), 2);
+        assert_eq!(
+            exchanges[0].response.as_ref().unwrap().result,
+            Some(json!("0x1"))
+        );
+        assert!(exchanges[1].is_notification());
+        assert!(exchanges[0].request.as_ref().unwrap().batch.is_some());
+    }
+
+    #[test]
+    fn browser_captures_keep_json_rpc_entries_with_their_timings() {
+        let har = json!({"log": {"version": "1.2", "entries": [
+            {
+                "startedDateTime": "2024-05-01T12:30:00.000Z",
+                "time": 120.5,
+                "request": {
+                    "method": "GET",
+                    "url": "https://app.example/index.html",
+                    "headers": [],
+                },
+                "response": {"status": 200, "content": {"text": "<html>"}},
+            },
+            {
+                "startedDateTime": "2024-05-01T12:30:01.000Z",
+                "time": 80.0,
+                "request": {
+                    "method": "POST",
+                    "url": "https://rpc.example/",
+                    "headers": [{"name": "content-type", "value": "application/json"}],
+                    "postData": {
+                        "mimeType": "application/json",
+                        "text": "{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"eth_blockNumber\"}",
+                    },
+                },
+                "response": {
+                    "status": 200,
+                    "headers": [],
+                    "content": {
+                        "encoding": "base64",
+                        "text": BASE64.encode(r#"{"jsonrpc":"2.0","id":7,"result":"0x10"}"#),
+                    },
+                },
+                "timings": {
+                    "blocked": 2.0,
+                    "dns": -1,
+                    "connect": 12.0,
+                    "ssl": 9.0,
+                    "wait": 70.0,
+                    "receive": 8.0,
+                },
+            },
+        ]}});
+
+        let imported = import(&har).unwrap();
+
+        assert_eq!(imported.skipped, 1);
+        assert_eq!(imported.session.target, "https://rpc.example/");
+        let exchange = &imported.session.exchanges[0];
+        assert_eq!(exchange.transport, SessionTransport::Http);
+        assert_eq!(
+            exchange.request.as_ref().unwrap().timestamp_ms,
+            1_714_566_601_000
+        );
+        let response = exchange.response.as_ref().unwrap();
+        assert_eq!(response.timestamp_ms, 1_714_566_601_080);
+        let timing = response.timing.as_ref().unwrap();
+        assert_eq!(timing.first_byte_ms, Some(70.0));
+        assert_eq!((timing.connect_ms, timing.tls_ms), (Some(3.0), Some(9.0)));
+        assert_eq!(
+            exchange.request.as_ref().unwrap().headers.as_ref().unwrap()["content-type"],
+            "application/json"
+        );
+        assert!(import(&json!({"entries": []})).is_err());
+
+        let mut endless = har.clone();
+        endless["log"]["entries"][1]["time"] = json!(1e300);
+        let imported = import(&endless).unwrap();
+        let response = imported.session.exchanges[0].response.as_ref().unwrap();
+        assert_eq!(response.timestamp_ms, 1_714_566_601_000);
+    }
+
+    #[test]
+    fn stdio_exchanges_name_their_command_and_framing() {
+        let session = Session {
+            schema_version: 1,
+            exported_at_ms: 0,
+            target: "node server.js".to_string(),
+            exchanges: vec![SessionExchange {
+                transport: SessionTransport::StdioContentLength,
+                request: Some(message(
+                    json!({"jsonrpc": "2.0", "id": 0, "method": "initialize"}),
+                    1_000,
+                )),
+                response: Some(message(
+                    json!({"jsonrpc": "2.0", "id": 0, "result": {}}),
+                    1_250,
+                )),
+                bookmark: Bookmark::default(),
+            }],
+        };
+
+        let har = export(&session);
+        let entry = &har["log"]["entries"][0];
+        assert_eq!(entry["request"]["url"], "stdio:node server.js");
+        assert_eq!(entry["_transport"], "stdio-content-length");
+        assert_eq!(entry["time"], 250.0);
+
+        let imported = import(&har).unwrap();
+        assert_eq!(imported.session.target, "node server.js");
+        assert_eq!(
+            imported.session.exchanges[0].transport,
+            SessionTransport::StdioContentLength
+        );
+        assert_eq!(
+            imported.session.exchanges[0]
+                .response
+                .as_ref()
+                .unwrap()
+                .timing,
+            None
+        );
+    }
+}
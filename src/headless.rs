@@ -0,0 +1,165 @@
+use crate::app::{App, ExchangeStatus};
+
+/// Which exchanges of the active session have been logged, so each one is written once,
+/// when it settles.
+#[derive(Debug, Default)]
+pub struct ExchangeLog {
+    session: Option<String>,
+    /// The app revision of the last call. Nothing settles without a new revision.
+    revision: Option<u64>,
+    /// How many exchanges have been looked at, and which of those were still pending.
+    seen: usize,
+    pending: Vec<usize>,
+}
+
+impl ExchangeLog {
+    /// Every exchange that got a response, timed out, or needs none since the last call.
+    /// Exchanges already in a session the control plane switches to are not logged again.
+    pub fn settled(&mut self, app: &App) -> Vec<usize> {
+        if self.revision == Some(app.revision()) {
+            return Vec::new();
+        }
+        self.revision = Some(app.revision());
+        let context = app.status_context();
+        let session = app.session.as_ref().map(|session| session.id.clone());
+        if session != self.session || app.exchanges.len() < self.seen {
+            let first = self.session.is_none() && self.seen == 0;
+            self.session = session;
+            self.pending.clear();
+            self.seen = 0;
+            if !first {
+                self.pending = (0..app.exchanges.len())
+                    .filter(|index| {
+                        context.status(&app.exchanges[*index]) == ExchangeStatus::Pending
+                    })
+                    .collect();
+                self.seen = app.exchanges.len();
+            }
+        }
+
+        let mut settled = Vec::new();
+        for index in std::mem::take(&mut self.pending)
+            .into_iter()
+            .chain(self.seen..app.exchanges.len())
+        {
+            if context.status(&app.exchanges[index]) == ExchangeStatus::Pending {
+                self.pending.push(index);
+            } else {
+                settled.push(index);
+            }
+        }
+        self.seen = app.exchanges.len();
+        settled.sort_unstable();
+        settled
+    }
+}
+
+/// The session and its exchange counts by status, for when the run ends.
+pub fn summary(app: &App) -> String {
+    let context = app.status_context();
+    let mut counts = Vec::<(ExchangeStatus, usize)>::new();
+    for exchange in &app.exchanges {
+        let status = context.status(exchange);
+        match counts.iter_mut().find(|(counted, _)| *counted == status) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((status, 1)),
+        }
+    }
+    let mut summary = match &app.session {
+        Some(session) => format!("session {} ({})", session.id, session.name),
+        None => "no session".to_string(),
+    };
+    summary.push_str(&format!(
+        ": {}",
+        counted(app.exchanges.len(), "exchange", "exchanges")
+    ));
+    for (status, count) in counts {
+        let (one, many) = match status {
+            ExchangeStatus::Notification => ("notification", "notifications"),
+            ExchangeStatus::Pending => ("pending", "pending"),
+            ExchangeStatus::TimedOut => ("timed out", "timed out"),
+            ExchangeStatus::Success => ("success", "successes"),
+            ExchangeStatus::Error => ("error", "errors"),
+            ExchangeStatus::Orphan => ("orphan", "orphans"),
+        };
+        summary.push_str(&format!(", {}", counted(count, one, many)));
+    }
+    summary
+}
+
+/// `count` followed by `one` or `many` to agree with it.
+fn counted(count: usize, one: &str, many: &str) -> String {
+    format!("{count} {}", if count == 1 { one } else { many })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{testing, TransportType};
+    use serde_json::json;
+
+    #[test]
+    fn exchanges_are_logged_once_when_they_settle() {
+        let mut app = testing::app(
+            TransportType::Http,
+            [(
+                json!([
+                    {"jsonrpc": "2.0", "id": 1, "method": "eth_call", "params": []},
+                    {"jsonrpc": "2.0", "method": "ping"},
+                ]),
+                1_700_000_000_000,
+            )],
+        );
+        let mut log = ExchangeLog::default();
+        assert_eq!(log.settled(&app), [1]);
+
+        testing::record(
+            &mut app,
+            &json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "reverted"}}),
+            TransportType::Http,
+            1_700_000_000_042,
+        );
+        assert_eq!(log.settled(&app), [0]);
+        assert!(log.settled(&app).is_empty());
+        assert_eq!(
+            summary(&app),
+            "no session: 2 exchanges, 1 error, 1 notification"
+        );
+
+        let app = testing::app(
+            TransportType::Http,
+            [(json!({"jsonrpc": "2.0", "method": "ping"}), 0)],
+        );
+        assert_eq!(summary(&app), "no session: 1 exchange, 1 notification");
+    }
+
+    #[test]
+    fn summaries_count_each_status_in_singular_or_plural() {
+        let call = |id: u64| json!({"jsonrpc": "2.0", "id": id, "method": "eth_call"});
+        let app = testing::app(
+            TransportType::Http,
+            [
+                (call(1), 0),
+                (json!({"jsonrpc": "2.0", "id": 1, "result": "0x"}), 1),
+                (call(2), 2),
+                (call(3), 3),
+                (call(4), 4),
+                (
+                    json!([
+                        {"jsonrpc": "2.0", "id": 3, "error": {"code": -1, "message": "no"}},
+                        {"jsonrpc": "2.0", "id": 4, "error": {"code": -1, "message": "no"}},
+                    ]),
+                    5,
+                ),
+                (json!({"jsonrpc": "2.0", "method": "ping"}), 6),
+                (json!({"jsonrpc": "2.0", "method": "ping"}), 7),
+                (json!({"jsonrpc": "2.0", "id": 9, "result": "0x"}), 8),
+            ],
+        );
+
+        assert_eq!(
+            summary(&app),
+            "no session: 7 exchanges, 1 success, 1 pending, 2 errors, 2 notifications, 1 orphan"
+        );
+    }
+}
@@ -1,20 +1,30 @@
 use crate::{
     app::{
-        DetailTab, Focus, JsonRpcExchange, JsonRpcMessage, LineAnnotation, MessageDirection,
-        SessionSummary,
+        Delivery, DetailTab, Focus, JsonRpcExchange, JsonRpcMessage, LineAnnotation,
+        MessageDirection, SessionSummary,
     },
+    bookmark::Bookmark,
     control::{Session, SessionExchange, SessionMessage},
 };
 use anyhow::{anyhow, bail, Context, Result};
 use rusqlite::{params, Connection, OptionalExtension, Transaction};
 use std::{
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use uuid::Uuid;
 
-const SCHEMA_VERSION: i64 = 2;
+const SCHEMA_VERSION: i64 = 5;
+
+/// A stored session with its exchanges, annotations, and bookmarks by exchange index.
+pub type LoadedSession = (
+    SessionSummary,
+    Vec<JsonRpcExchange>,
+    Vec<LineAnnotation>,
+    HashMap<usize, Bookmark>,
+);
 
 pub struct HistoryStore {
     connection: Connection,
@@ -68,6 +78,7 @@ impl HistoryStore {
                 method TEXT,
                 complete INTEGER NOT NULL,
                 exchange_json TEXT NOT NULL,
+                connection TEXT,
                 UNIQUE(session_id, sequence)
             );
             CREATE INDEX IF NOT EXISTS exchanges_session_sequence
@@ -88,9 +99,31 @@ impl HistoryStore {
             );
             CREATE INDEX IF NOT EXISTS annotations_session_exchange
                 ON annotations(session_id, exchange_index, created_at_ms);
-            PRAGMA user_version = 2;
+            CREATE TABLE IF NOT EXISTS bookmarks (
+                session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                exchange_index INTEGER NOT NULL,
+                starred INTEGER NOT NULL,
+                tags_json TEXT NOT NULL,
+                PRIMARY KEY(session_id, exchange_index)
+            );
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
             ",
         )?;
+        let has_connection: bool = connection.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('exchanges') WHERE name = 'connection'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_connection {
+            connection.execute_batch("ALTER TABLE exchanges ADD COLUMN connection TEXT;")?;
+        }
+        connection.execute_batch(
+            "CREATE INDEX IF NOT EXISTS exchanges_connection ON exchanges(connection);",
+        )?;
+        connection.execute_batch("PRAGMA user_version = 5;")?;
 
         Ok(Self { connection })
     }
@@ -152,10 +185,7 @@ impl HistoryStore {
             .map_err(Into::into)
     }
 
-    pub fn load_session(
-        &self,
-        id: &str,
-    ) -> Result<(SessionSummary, Vec<JsonRpcExchange>, Vec<LineAnnotation>)> {
+    pub fn load_session(&self, id: &str) -> Result<LoadedSession> {
         let session = self
             .session(id)?
             .ok_or_else(|| anyhow!("session not found: {id}"))?;
@@ -164,7 +194,12 @@ impl HistoryStore {
             .into_iter()
             .map(|(_, exchange)| exchange)
             .collect();
-        Ok((session, exchanges, self.annotations(id)?))
+        Ok((
+            session,
+            exchanges,
+            self.annotations(id)?,
+            self.bookmarks(id)?,
+        ))
     }
 
     pub fn annotations(&self, session_id: &str) -> Result<Vec<LineAnnotation>> {
@@ -183,6 +218,7 @@ impl HistoryStore {
             let tab = match row.get::<_, String>(3)?.as_str() {
                 "headers" => DetailTab::Headers,
                 "body" => DetailTab::Body,
+                "decoded" => DetailTab::Decoded,
                 value => return Err(invalid_annotation_column(3, value)),
             };
             let text_json = row.get::<_, String>(7)?;
@@ -243,6 +279,62 @@ impl HistoryStore {
         Ok(removed > 0)
     }
 
+    pub fn bookmarks(&self, session_id: &str) -> Result<HashMap<usize, Bookmark>> {
+        let mut statement = self.connection.prepare(
+            "SELECT exchange_index, starred, tags_json
+             FROM bookmarks
+             WHERE session_id = ?1",
+        )?;
+        let rows = statement.query_map([session_id], |row| {
+            let tags_json = row.get::<_, String>(2)?;
+            let tags = serde_json::from_str(&tags_json).map_err(|error| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    2,
+                    rusqlite::types::Type::Text,
+                    Box::new(error),
+                )
+            })?;
+            Ok((
+                row.get::<_, i64>(0)?.max(0) as usize,
+                Bookmark {
+                    starred: row.get(1)?,
+                    tags,
+                },
+            ))
+        })?;
+        rows.collect::<rusqlite::Result<HashMap<_, _>>>()
+            .map_err(Into::into)
+    }
+
+    /// Stores the star and tags of one exchange, forgetting an empty bookmark.
+    pub fn set_bookmark(
+        &self,
+        session_id: &str,
+        exchange_index: usize,
+        bookmark: &Bookmark,
+    ) -> Result<()> {
+        if bookmark.is_empty() {
+            self.connection.execute(
+                "DELETE FROM bookmarks WHERE session_id = ?1 AND exchange_index = ?2",
+                params![session_id, sqlite_index(exchange_index)],
+            )?;
+        } else {
+            self.connection.execute(
+                "INSERT INTO bookmarks (session_id, exchange_index, starred, tags_json)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(session_id, exchange_index)
+                 DO UPDATE SET starred = excluded.starred, tags_json = excluded.tags_json",
+                params![
+                    session_id,
+                    sqlite_index(exchange_index),
+                    bookmark.starred,
+                    serde_json::to_string(&bookmark.tags)?,
+                ],
+            )?;
+        }
+        self.touch_session(session_id)
+    }
+
     fn touch_session(&self, session_id: &str) -> Result<()> {
         self.connection.execute(
             "UPDATE sessions SET updated_at_ms = ?2 WHERE id = ?1",
@@ -283,6 +375,14 @@ impl HistoryStore {
         Ok(rows)
     }
 
+    /// Fills in the delivery time of stored responses once the proxy reports it.
+    pub fn record_delivery(&mut self, delivery: &Delivery) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        record_delivery(&transaction, &delivery.connection, delivery.delivery)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
     pub fn record_messages(
         &mut self,
         active_session_id: &str,
@@ -315,19 +415,19 @@ impl HistoryStore {
         Ok(session_ids)
     }
 
+    /// Appends exchanges to a session and returns the index of the first.
     pub fn append_exchanges(
         &mut self,
         session_id: &str,
         exchanges: &[JsonRpcExchange],
-    ) -> Result<()> {
+    ) -> Result<usize> {
+        let transaction = self.connection.transaction()?;
+        let first = next_sequence(&transaction, session_id)?;
         if exchanges.is_empty() {
-            return Ok(());
+            return Ok(first.saturating_sub(1) as usize);
         }
 
-        let transaction = self.connection.transaction()?;
-        for (sequence, exchange) in
-            (next_sequence(&transaction, session_id)?..).zip(exchanges.iter())
-        {
+        for (sequence, exchange) in (first..).zip(exchanges.iter()) {
             insert_exchange(&transaction, session_id, sequence, exchange)?;
         }
         transaction.execute(
@@ -335,7 +435,7 @@ impl HistoryStore {
             params![session_id, database_timestamp_ms(SystemTime::now())],
         )?;
         transaction.commit()?;
-        Ok(())
+        Ok(first.saturating_sub(1) as usize)
     }
 
     pub fn update_target(&self, session_id: &str, target: &str) -> Result<()> {
@@ -356,14 +456,57 @@ impl HistoryStore {
         Ok(renamed > 0)
     }
 
+    /// Deletes a session with its exchanges, annotations, and bookmarks.
+    pub fn delete_session(&self, session_id: &str) -> Result<bool> {
+        let deleted = self
+            .connection
+            .execute("DELETE FROM sessions WHERE id = ?1", [session_id])?;
+        Ok(deleted > 0)
+    }
+
+    /// A preference saved across restarts, such as the request list columns.
+    pub fn setting(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
     pub fn export_session(&self, session_id: &str) -> Result<Session> {
+        self.export_session_from(session_id, 0)
+    }
+
+    /// The session's exchanges from index `from` on, for a viewer that holds the earlier ones.
+    pub fn export_session_from(&self, session_id: &str, from: usize) -> Result<Session> {
         let session = self
             .session(session_id)?
             .ok_or_else(|| anyhow!("session not found: {session_id}"))?;
-        let exchanges = self
-            .history(session_id, usize::MAX, None)?
+        let mut bookmarks = self.bookmarks(session_id)?;
+        let mut statement = self.connection.prepare(
+            "SELECT sequence, exchange_json
+             FROM exchanges
+             WHERE session_id = ?1 AND sequence > ?2
+             ORDER BY sequence",
+        )?;
+        let exchanges = collect_exchanges(&mut statement, params![session_id, sqlite_index(from)])?
             .into_iter()
-            .map(|(_, exchange)| SessionExchange::from(&exchange))
+            .map(|(index, exchange)| SessionExchange {
+                bookmark: bookmarks.remove(&index).unwrap_or_default(),
+                ..SessionExchange::from(&exchange)
+            })
             .collect();
 
         Ok(Session {
@@ -376,16 +519,19 @@ impl HistoryStore {
 }
 
 fn history_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("sqlite.db"))
+}
+
+/// Directory holding the history database and other per-user files.
+pub fn config_dir() -> Result<PathBuf> {
     if let Some(path) = std::env::var_os("JSONRPC_DEBUGGER_CONFIG_DIR") {
-        return Ok(PathBuf::from(path).join("sqlite.db"));
+        return Ok(PathBuf::from(path));
     }
     if let Some(path) = std::env::var_os("XDG_CONFIG_HOME") {
-        return Ok(PathBuf::from(path)
-            .join("jsonrpc-debugger")
-            .join("sqlite.db"));
+        return Ok(PathBuf::from(path).join("jsonrpc-debugger"));
     }
     if let Some(path) = std::env::var_os("HOME") {
-        return Ok(PathBuf::from(path).join(".config/jsonrpc-debugger/sqlite.db"));
+        return Ok(PathBuf::from(path).join(".config/jsonrpc-debugger"));
     }
 
     bail!("HOME is not set; set JSONRPC_DEBUGGER_CONFIG_DIR for session history")
@@ -446,9 +592,10 @@ fn pending_session(
             "SELECT session_id
              FROM exchanges
              WHERE rpc_id = ?1 AND complete = 0
+               AND (?2 IS NULL OR connection IS NULL OR connection = ?2)
              ORDER BY id DESC
              LIMIT 1",
-            [rpc_id(message)],
+            params![rpc_id(message), connection_id(message)],
             |row| row.get(0),
         )
         .optional()
@@ -482,9 +629,10 @@ fn update_response(
             "SELECT id, exchange_json
              FROM exchanges
              WHERE session_id = ?1 AND rpc_id = ?2 AND complete = 0
+               AND (?3 IS NULL OR connection IS NULL OR connection = ?3)
              ORDER BY id DESC
              LIMIT 1",
-            params![session_id, rpc_id(message)],
+            params![session_id, rpc_id(message), connection_id(message)],
             |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
         )
         .optional()?;
@@ -511,6 +659,40 @@ fn update_response(
     Ok(())
 }
 
+/// Fills in the delivery time of the responses stored for `connection`.
+fn record_delivery(
+    transaction: &Transaction<'_>,
+    connection: &str,
+    delivery: Duration,
+) -> Result<()> {
+    let mut statement = transaction.prepare(
+        "SELECT id, exchange_json
+         FROM exchanges
+         WHERE connection = ?1 AND complete = 1",
+    )?;
+    let stored = statement
+        .query_map(params![connection], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for (id, json) in stored {
+        let mut exchange: SessionExchange = serde_json::from_str(&json)?;
+        let Some(response) = &mut exchange.response else {
+            continue;
+        };
+        response
+            .timing
+            .get_or_insert_with(Default::default)
+            .delivery_ms = Some(delivery.as_secs_f64() * 1000.0);
+        transaction.execute(
+            "UPDATE exchanges SET exchange_json = ?2 WHERE id = ?1",
+            params![id, serde_json::to_string(&exchange)?],
+        )?;
+    }
+    Ok(())
+}
+
 fn next_sequence(transaction: &Transaction<'_>, session_id: &str) -> Result<i64> {
     let sequence = transaction.query_row(
         "SELECT COALESCE(MAX(sequence), 0) + 1 FROM exchanges WHERE session_id = ?1",
@@ -529,8 +711,8 @@ fn insert_exchange(
     let value = SessionExchange::from(exchange);
     transaction.execute(
         "INSERT INTO exchanges
-         (session_id, sequence, rpc_id, method, complete, exchange_json)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+         (session_id, sequence, rpc_id, method, complete, exchange_json, connection)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         params![
             session_id,
             sequence,
@@ -543,6 +725,9 @@ fn insert_exchange(
             exchange.method,
             i64::from(exchange.response.is_some() || exchange.is_notification()),
             serde_json::to_string(&value)?,
+            exchange
+                .connection()
+                .map(|connection| connection.id.as_str()),
         ],
     )?;
     Ok(())
@@ -556,6 +741,13 @@ fn rpc_id(message: &JsonRpcMessage) -> String {
         .unwrap_or_else(|| "null".to_string())
 }
 
+fn connection_id(message: &JsonRpcMessage) -> Option<&str> {
+    message
+        .connection
+        .as_ref()
+        .map(|connection| connection.id.as_str())
+}
+
 fn annotation_panel(panel: Focus) -> Result<&'static str> {
     match panel {
         Focus::RequestSection => Ok("request"),
@@ -575,6 +767,7 @@ fn annotation_tab(tab: DetailTab) -> &'static str {
     match tab {
         DetailTab::Headers => "headers",
         DetailTab::Body => "body",
+        DetailTab::Decoded => "decoded",
     }
 }
 
@@ -632,6 +825,10 @@ mod tests {
             direction: MessageDirection::Request,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         }
     }
 
@@ -646,6 +843,10 @@ mod tests {
             direction: MessageDirection::Response,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         }
     }
 
@@ -687,6 +888,69 @@ mod tests {
         assert_eq!(commits.load(Ordering::Relaxed), 2);
     }
 
+    #[test]
+    fn responses_pair_with_requests_on_their_connection() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        let session = store
+            .create_session(Some("workers"), "http://node")
+            .unwrap();
+        let on = |mut message: JsonRpcMessage, id: &str| {
+            message.connection = Some(crate::app::Connection {
+                id: id.to_string(),
+                client: format!("worker {id}"),
+                source: None,
+            });
+            message
+        };
+        let mut answer = on(response(1), "a");
+        answer.result = Some(json!("0xa"));
+
+        store
+            .record_messages(
+                &session.id,
+                &[on(request(1), "a"), on(request(1), "b"), answer],
+            )
+            .unwrap();
+
+        let exchanges = store.load_session(&session.id).unwrap().1;
+        assert_eq!(exchanges.len(), 2);
+        assert_eq!(
+            exchanges[0].response.as_ref().unwrap().result,
+            Some(json!("0xa"))
+        );
+        assert_eq!(exchanges[0].client(), Some("worker a"));
+        assert!(exchanges[1].response.is_none());
+    }
+
+    #[test]
+    fn deliveries_fill_in_the_stored_response() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        let session = store.create_session(Some("chain"), "http://node").unwrap();
+        let connection = crate::app::Connection {
+            id: "a".to_string(),
+            client: "worker a".to_string(),
+            source: None,
+        };
+        let (mut asked, mut answered) = (request(1), response(1));
+        asked.connection = Some(connection.clone());
+        answered.connection = Some(connection.clone());
+
+        store
+            .record_messages(&session.id, &[asked, answered])
+            .unwrap();
+        store
+            .record_delivery(&Delivery {
+                connection: connection.id,
+                delivery: Duration::from_millis(4),
+            })
+            .unwrap();
+
+        let exchanges = store.load_session(&session.id).unwrap().1;
+        assert_eq!(exchanges.len(), 1);
+        let timing = exchanges[0].response.as_ref().unwrap().timing.unwrap();
+        assert_eq!(timing.delivery, Some(Duration::from_millis(4)));
+    }
+
     #[test]
     fn recording_request_and_response_together_commits_once() {
         let mut store = HistoryStore::in_memory().unwrap();
@@ -776,6 +1040,14 @@ mod tests {
             store
                 .add_annotation(&session.id, &annotation("saved-note", 0))
                 .unwrap();
+            store.set_setting("columns", "first").unwrap();
+            store.set_setting("columns", "second").unwrap();
+            let mut bookmark = Bookmark::default();
+            bookmark.add_tags(["bug"]);
+            store.set_bookmark(&session.id, 0, &bookmark).unwrap();
+            store
+                .set_bookmark(&session.id, 1, &Bookmark::default())
+                .unwrap();
             session.id
         };
 
@@ -787,6 +1059,17 @@ mod tests {
             store.load_session(&session_id).unwrap().2[0].id,
             "saved-note"
         );
+        assert_eq!(store.setting("columns").unwrap().as_deref(), Some("second"));
+        assert_eq!(store.setting("missing").unwrap(), None);
+        let bookmarks = store.load_session(&session_id).unwrap().3;
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[&0].tags, ["bug"]);
+        assert_eq!(
+            store.export_session(&session_id).unwrap().exchanges[0]
+                .bookmark
+                .tags,
+            ["bug"]
+        );
         drop(store);
         fs::remove_file(path).unwrap();
     }
@@ -823,6 +1106,24 @@ mod tests {
         assert!(store.rename_session(&session.id, "").is_err());
     }
 
+    #[test]
+    fn deletes_a_session_with_its_exchanges() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        let session = store.create_session(None, "").unwrap();
+        store
+            .record_messages(&session.id, &[request(1), response(1)])
+            .unwrap();
+        store
+            .add_annotation(&session.id, &annotation("note", 0))
+            .unwrap();
+
+        assert!(store.delete_session(&session.id).unwrap());
+        assert!(store.session(&session.id).unwrap().is_none());
+        assert!(store.history(&session.id, 10, None).unwrap().is_empty());
+        assert!(store.annotations(&session.id).unwrap().is_empty());
+        assert!(!store.delete_session(&session.id).unwrap());
+    }
+
     #[test]
     fn expands_an_existing_history_database_without_losing_sessions() {
         let path =
@@ -1,7 +1,28 @@
 pub mod app;
 pub mod attach;
+pub mod batch;
+pub mod bookmark;
+pub mod columns;
 pub mod control;
+pub mod diff;
+pub mod ethereum;
+pub mod fixture;
+pub mod har;
+pub mod headless;
 pub mod history;
+pub mod mcp;
+pub mod offline;
+pub mod profile;
 pub mod proxy;
+pub mod query;
+pub mod report;
+pub mod search;
+pub mod snippet;
 pub mod stdio;
+pub mod subscription;
+pub mod tail;
+pub mod time;
+pub mod timeline;
+pub mod trace;
+pub mod tree;
 pub mod ui;
@@ -10,9 +10,11 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
+use std::collections::HashMap;
 use std::ffi::OsString;
 use std::io;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tokio::sync::{mpsc, oneshot};
 use tokio::task::JoinHandle;
@@ -20,39 +22,91 @@ use tokio::time::Instant;
 
 mod app;
 mod attach;
+mod batch;
+mod bookmark;
+mod columns;
 mod control;
+mod diff;
+mod ethereum;
+mod fixture;
+mod har;
+mod headless;
 mod history;
+mod mcp;
+mod offline;
+mod profile;
 mod proxy;
+mod query;
+mod report;
+mod search;
+mod snippet;
 mod stdio;
+mod subscription;
+mod tail;
+mod time;
+mod timeline;
+mod trace;
+mod tree;
 mod ui;
 
 use app::{
-    App, AppMode, EditorMode, EditorMotion, EditorOperator, EditorTarget, LineAnnotation, Overlay,
-    TextEditor,
+    App, AppMode, EditorMode, EditorMotion, EditorOperator, EditorTarget, JsonRpcExchange,
+    LineAnnotation, Overlay, TextEditor,
 };
-use control::{ControlAction, ControlCommand, ControlError, PendingDecision};
+use bookmark::Bookmark;
+use control::{ControlAction, ControlCommand, ControlError, ExchangeRef, PendingDecision};
+use diff::Comparison;
 use history::HistoryStore;
 use proxy::{ProxyServer, ProxyState};
 use uuid::Uuid;
 
 const AGENT_SKILL: &str = include_str!("../skills/jsonrpc-debugger/SKILL.md");
+const DEFAULT_PORT: u16 = 8080;
 
 #[derive(Parser)]
 #[command(name = "jsonrpc-debugger", version)]
 #[command(about = "A JSON-RPC debugger for intercepting and inspecting requests")]
 struct Cli {
-    /// HTTP proxy port in driver mode
-    #[arg(short, long, default_value = "8080")]
-    port: u16,
+    /// HTTP proxy port in driver mode [default: 8080]
+    #[arg(short, long)]
+    port: Option<u16>,
 
     /// Target URL to proxy requests to
     #[arg(short, long)]
     target: Option<String>,
 
+    /// Another proxy port with its own target, as NAME=PORT=URL; repeat for more. Replaces
+    /// the profile's listeners
+    #[arg(long = "listen", value_name = "NAME=PORT=URL", value_parser = parse_listener)]
+    listeners: Vec<app::Listener>,
+
     /// Local JSON-RPC control port (defaults to the proxy port plus one)
     #[arg(long)]
     control_port: Option<u16>,
 
+    /// Directory of contract ABI JSON files for Ethereum decoding (defaults to abi/ in the
+    /// config directory)
+    #[arg(long, global = true)]
+    abi_dir: Option<PathBuf>,
+
+    /// Seconds to wait for a response before marking a request as timed out
+    #[arg(long, global = true, default_value = "30", value_parser = clap::value_parser!(u64).range(1..))]
+    request_timeout: u64,
+
+    /// Run the proxy, history, and control plane without the TUI, printing one line per
+    /// exchange
+    #[arg(long)]
+    headless: bool,
+
+    /// Append headless exchange lines to this file instead of stdout
+    #[arg(long, requires = "headless")]
+    log: Option<PathBuf>,
+
+    /// Profile from config.toml in the config directory or ./jsonrpc-debugger.toml (defaults
+    /// to the configured default profile)
+    #[arg(long)]
+    profile: Option<String>,
+
     /// Print agent instructions and exit
     #[arg(long)]
     skill: bool,
@@ -91,12 +145,193 @@ enum TargetMode {
         #[arg(default_value = "http://127.0.0.1:8081")]
         control_url: String,
     },
+
+    /// Save an LSP or MCP trace log as a new session in history, then exit
+    Import {
+        /// Session name (defaults to the file name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// VS Code LSP trace or JSON-lines log
+        file: PathBuf,
+    },
+
+    /// Browse, export, import, and edit saved sessions without a running debugger
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommand,
+    },
+
+    /// Search saved history without a running debugger
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+    },
+
+    /// Print exchanges as they complete, from a new proxy or a running debugger
+    Tail {
+        /// Control-plane URL of a running debugger to follow instead of starting a proxy
+        #[arg(long)]
+        attach: Option<String>,
+
+        #[arg(long, value_enum, default_value = "compact")]
+        format: CliTailFormat,
+
+        /// Only print exchanges this request list filter matches, such as status:error
+        #[arg(long, default_value = "")]
+        filter: String,
+
+        #[arg(long, value_enum, default_value = "auto")]
+        color: CliColor,
+
+        /// Message framing used on stdin and stdout of a stdio server
+        #[arg(long, value_enum, default_value = "json-lines")]
+        framing: CliFraming,
+
+        /// Stdio server command and arguments (defaults to proxying --target over HTTP)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<OsString>,
+    },
+
+    /// Write a saved session as regression fixtures, then exit
+    Fixtures {
+        /// Session ID from history
+        session: String,
+
+        /// Directory to write fixture files into
+        dir: PathBuf,
+    },
+
+    /// Send every fixture in a directory to a target and check the responses
+    Verify {
+        /// Directory of fixture files
+        dir: PathBuf,
+
+        /// Message framing used on stdin and stdout of a stdio server
+        #[arg(long, value_enum, default_value = "json-lines")]
+        framing: CliFraming,
+
+        /// Stdio server command and arguments (defaults to --target or the recorded target)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<OsString>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SessionsCommand {
+    /// List sessions, most recently updated first
+    List {
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Print a session with its exchanges and annotations
+    Show {
+        id: String,
+
+        /// Most recent exchanges to include
+        #[arg(long, default_value = "1000")]
+        limit: usize,
+    },
+
+    /// Print a session as portable JSON, a HAR log, or one exchange per line
+    Export {
+        id: String,
+
+        #[arg(long, value_enum, default_value = "json")]
+        format: CliSessionFormat,
+    },
+
+    /// Save a portable session, HAR log, or NDJSON export as a new session
+    Import {
+        /// Session name (defaults to the file name)
+        #[arg(long)]
+        name: Option<String>,
+
+        file: PathBuf,
+    },
+
+    /// Rename a session
+    Rename { id: String, name: String },
+
+    /// Delete a session with its exchanges, annotations, and bookmarks
+    Delete { id: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum HistoryCommand {
+    /// Print exchanges whose request or response has a line matching a regular expression
+    Grep {
+        pattern: String,
+
+        /// Only search this session
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Most exchanges to print
+        #[arg(long, default_value = "100")]
+        limit: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliSessionFormat {
+    Json,
+    Har,
+    Ndjson,
+}
+
+impl From<CliSessionFormat> for offline::Format {
+    fn from(format: CliSessionFormat) -> Self {
+        match format {
+            CliSessionFormat::Json => Self::Json,
+            CliSessionFormat::Har => Self::Har,
+            CliSessionFormat::Ndjson => Self::Ndjson,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliTailFormat {
+    Pretty,
+    Compact,
+    Ndjson,
+}
+
+impl From<CliTailFormat> for tail::Format {
+    fn from(format: CliTailFormat) -> Self {
+        match format {
+            CliTailFormat::Pretty => Self::Pretty,
+            CliTailFormat::Compact => Self::Compact,
+            CliTailFormat::Ndjson => Self::Ndjson,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliColor {
+    /// Colour when stdout is a terminal and NO_COLOR is unset
+    Auto,
+    Always,
+    Never,
+}
+
+impl CliColor {
+    fn enabled(self) -> bool {
+        match self {
+            Self::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum CliFraming {
     JsonLines,
     ContentLength,
+    /// Content-Length framing with Debug Adapter Protocol messages
+    Dap,
 }
 
 impl From<CliFraming> for app::Framing {
@@ -104,10 +339,52 @@ impl From<CliFraming> for app::Framing {
         match framing {
             CliFraming::JsonLines => Self::JsonLines,
             CliFraming::ContentLength => Self::ContentLength,
+            CliFraming::Dap => Self::Dap,
         }
     }
 }
 
+fn parse_listener(text: &str) -> Result<app::Listener, String> {
+    let mut parts = text.splitn(3, '=');
+    let (Some(name), Some(port), Some(target)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("expected NAME=PORT=URL".to_string());
+    };
+    let listen_port = port.parse().map_err(|_| format!("invalid port: {port}"))?;
+    if target.trim().is_empty() {
+        return Err("the target URL cannot be empty".to_string());
+    }
+    Ok(app::Listener {
+        name: name.to_string(),
+        listen_port,
+        target_url: target.to_string(),
+        stdio: None,
+    })
+}
+
+/// Writes the output of an offline command for scripts. A reader that closes the pipe early,
+/// such as `head`, ends the command quietly.
+fn print_output(text: &str) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    match writeln!(stdout, "{text}").and_then(|()| stdout.flush()) {
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn load_abi(abi_dir: Option<&Path>) -> Result<ethereum::Abi> {
+    if let Some(abi_dir) = abi_dir {
+        return ethereum::Abi::load_dir(abi_dir).map_err(anyhow::Error::msg);
+    }
+    // The default directory is optional; only a directory that exists must be valid.
+    let Ok(abi_dir) = history::config_dir().map(|directory| directory.join("abi")) else {
+        return Ok(ethereum::Abi::default());
+    };
+    if !abi_dir.is_dir() {
+        return Ok(ethereum::Abi::default());
+    }
+    ethereum::Abi::load_dir(&abi_dir).map_err(anyhow::Error::msg)
+}
+
 fn copy_to_clipboard(
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
     content: &str,
@@ -138,6 +415,109 @@ fn copy_focused_panel(
     copy_to_clipboard(terminal, &markdown)
 }
 
+fn copy_snippet(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    app: &mut App,
+    format: snippet::Format,
+) -> Result<()> {
+    app.close_overlay();
+    match snippet::export(app, format, app.selected_exchange, app.snippet_via_proxy) {
+        Ok(snippet) => {
+            copy_to_clipboard(terminal, &snippet.text)?;
+            app.notice = Some(format!("Copied as {}", format.title()));
+        }
+        Err(error) => app.notice = Some(format!("Error: {error}")),
+    }
+    app.mark_changed();
+    Ok(())
+}
+
+fn handle_snippets_key(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    app: &mut App,
+    key: KeyEvent,
+) -> Result<()> {
+    match key.code {
+        KeyCode::Char('v') => app.toggle_snippet_target(),
+        KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
+        KeyCode::Char(character) => {
+            let format = snippet::Format::ALL
+                .into_iter()
+                .find(|format| ui::snippet_key(*format) == character);
+            if let Some(format) = format {
+                copy_snippet(terminal, app, format)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Saves Markdown and HTML reports of the exchanges the request list filter matches to the
+/// working directory.
+fn save_reports(app: &mut App) {
+    let indices = app.exchanges_matching(&app.filter_text);
+    let scope = Some(app.filter_text.trim()).filter(|filter| !filter.is_empty());
+    let stem = report_file_stem(app);
+    let saved = report::Format::ALL
+        .into_iter()
+        .map(|format| {
+            let path = format!("{stem}.{}", format.extension());
+            std::fs::write(&path, report::render(app, &indices, scope, format)).map(|_| path)
+        })
+        .collect::<std::io::Result<Vec<_>>>();
+    app.notice = Some(match saved {
+        Ok(paths) => format!("Report saved to {}", paths.join(" and ")),
+        Err(error) => format!("Error: save report: {error}"),
+    });
+    app.mark_changed();
+}
+
+/// `payments-bug-report` for a session named `Payments bug`.
+fn report_file_stem(app: &App) -> String {
+    let name = app
+        .session
+        .as_ref()
+        .map_or("jsonrpc", |session| session.name.as_str())
+        .to_lowercase()
+        .split(|character: char| !character.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if name.is_empty() {
+        "jsonrpc-report".to_string()
+    } else {
+        format!("{name}-report")
+    }
+}
+
+fn timeline_keys_active(app: &App) -> bool {
+    app.timeline_visible && app.is_message_list_focused()
+}
+
+fn handle_timeline_key(app: &mut App, character: char) {
+    match character {
+        '+' | '=' => app.zoom_timeline(true),
+        '-' => app.zoom_timeline(false),
+        '[' => app.pan_timeline(-1),
+        ']' => app.pan_timeline(1),
+        _ => {}
+    }
+}
+
+fn copy_batch(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    app: &mut App,
+) -> Result<()> {
+    let Some(markdown) = app.batch_markdown() else {
+        return Ok(());
+    };
+    copy_to_clipboard(terminal, &markdown)?;
+    app.notice = Some("Batch copied as Markdown".to_string());
+    app.mark_changed();
+    Ok(())
+}
+
 fn enter_request_list(app: &mut App) -> bool {
     if app.app_mode != AppMode::Normal
         || !app.is_message_list_focused()
@@ -173,14 +553,22 @@ struct ChangeWaiter {
     reply: oneshot::Sender<control::ControlResult>,
 }
 
+/// The running proxy tasks: the main listener and the named ones beside it.
+#[derive(Default)]
+struct ProxyServers {
+    main: Option<JoinHandle<()>>,
+    listeners: HashMap<String, JoinHandle<()>>,
+}
+
 struct Runtime {
     history: HistoryStore,
     message_sender: mpsc::UnboundedSender<app::JsonRpcMessage>,
     shared_app_mode: Arc<Mutex<AppMode>>,
     pending_receiver: mpsc::UnboundedReceiver<app::PendingRequest>,
+    delivery_receiver: mpsc::UnboundedReceiver<app::Delivery>,
     control_receiver: mpsc::UnboundedReceiver<ControlCommand>,
     proxy_state: ProxyState,
-    proxy_server: Option<JoinHandle<()>>,
+    proxy_servers: ProxyServers,
     control_server: Option<JoinHandle<Result<(), String>>>,
     request_result_sender: mpsc::UnboundedSender<Result<(), String>>,
     request_result_receiver: mpsc::UnboundedReceiver<Result<(), String>>,
@@ -221,10 +609,9 @@ fn handle_editor_key(editor: &mut TextEditor, key: KeyEvent) -> EditorAction {
                 editor.mode = EditorMode::Normal;
                 editor.command.clear();
             }
+            KeyCode::Backspace if editor.command.is_empty() => editor.mode = EditorMode::Normal,
             KeyCode::Backspace => {
-                if editor.command.pop().is_none() {
-                    editor.mode = EditorMode::Normal;
-                }
+                editor.command.pop();
             }
             KeyCode::Enter => match editor.command.as_str() {
                 "w" | "wq" | "x" => return EditorAction::Save,
@@ -383,7 +770,7 @@ fn save_editor(app: &mut App, request_result_sender: &mpsc::UnboundedSender<Resu
 
 struct ControlContext<'a> {
     terminal_area: ratatui::layout::Rect,
-    proxy_server: &'a mut Option<JoinHandle<()>>,
+    proxy_servers: &'a mut ProxyServers,
     message_sender: &'a mpsc::UnboundedSender<app::JsonRpcMessage>,
     proxy_state: &'a ProxyState,
     request_result_sender: &'a mpsc::UnboundedSender<Result<(), String>>,
@@ -397,7 +784,7 @@ async fn handle_control_command(
 ) {
     let ControlContext {
         terminal_area,
-        proxy_server,
+        proxy_servers,
         message_sender,
         proxy_state,
         request_result_sender,
@@ -434,7 +821,15 @@ async fn handle_control_command(
             .and_then(|session_id| {
                 history
                     .history(session_id, limit, before)
-                    .map(control::stored_history)
+                    .and_then(|exchanges| Ok((exchanges, history.bookmarks(session_id)?)))
+                    .map(|(exchanges, bookmarks)| {
+                        control::stored_history(
+                            exchanges,
+                            &bookmarks,
+                            app.request_timeout,
+                            &app.columns,
+                        )
+                    })
                     .map_err(|error| ControlError::runtime(error.to_string()))
             }),
         ControlAction::ListSessions { limit } => history
@@ -447,7 +842,7 @@ async fn handle_control_command(
         ControlAction::SelectSession { id } => match select_session(app, history, &id) {
             Ok(target_changed) => {
                 if target_changed && app.is_running {
-                    restart_proxy(app, proxy_server, message_sender, proxy_state).await;
+                    restart_proxy(app, proxy_servers, message_sender, proxy_state).await;
                 }
                 Ok(control::state(app))
             }
@@ -456,40 +851,123 @@ async fn handle_control_command(
         ControlAction::RenameSession { id, name } => {
             rename_session(app, history, &id, &name).map(|_| control::state(app))
         }
-        ControlAction::ExportSession => active_session_id(app)
+        ControlAction::ExportSession { from } => active_session_id(app)
             .ok_or_else(|| ControlError::runtime("No active session"))
             .and_then(|session_id| {
                 history
-                    .export_session(session_id)
+                    .export_session_from(session_id, from)
                     .and_then(|session| serde_json::to_value(session).map_err(Into::into))
                     .map_err(|error| ControlError::runtime(error.to_string()))
             }),
-        ControlAction::ReplaySession { session } => match control::replay_session(session) {
-            Ok(exchanges) => {
-                let imported = exchanges.len();
-                active_session_id(app)
-                    .ok_or_else(|| ControlError::runtime("No active session"))
-                    .and_then(|session_id| {
-                        history
-                            .append_exchanges(session_id, &exchanges)
-                            .map_err(|error| ControlError::runtime(error.to_string()))
-                    })
-                    .map(|_| {
-                        app.append_exchanges(exchanges);
-                        serde_json::json!({
-                            "imported": imported,
-                            "state": control::state(app),
-                        })
-                    })
-            }
-            Err(error) => Err(error),
-        },
+        ControlAction::ReplaySession { session } => replay_session(app, history, session),
+        ControlAction::ExportHar { session_id } => active_session_id(app)
+            .map(|active| session_id.as_deref().unwrap_or(active))
+            .ok_or_else(|| ControlError::runtime("No active session"))
+            .and_then(|session_id| {
+                history
+                    .export_session(session_id)
+                    .map(|session| har::export(&session))
+                    .map_err(|error| ControlError::runtime(error.to_string()))
+            }),
+        ControlAction::ImportHar { har } => har::import(&har)
+            .map_err(ControlError::invalid_params)
+            .and_then(|import| {
+                replay_session(app, history, import.session).map(|mut result| {
+                    result["skipped"] = serde_json::json!(import.skipped);
+                    result
+                })
+            }),
+        ControlAction::ExportReport { format, filter } => {
+            let indices = app.exchanges_matching(&filter);
+            let scope = Some(filter.trim()).filter(|filter| !filter.is_empty());
+            Ok(serde_json::json!({
+                "format": format.name(),
+                "exchanges": indices.len(),
+                "text": report::render(app, &indices, scope, format),
+            }))
+        }
+        ControlAction::ImportTrace { text, name, target } => {
+            import_trace(history, &text, name.as_deref(), &target)
+        }
+        ControlAction::ExportFixtures { dir, filter } => {
+            let mut session = control::export_session(app);
+            let indices = app.exchanges_matching(&filter);
+            session.exchanges = indices
+                .into_iter()
+                .filter_map(|index| session.exchanges.get(index).cloned())
+                .collect();
+            fixture::write(Path::new(&dir), &session)
+                .map(|files| serde_json::json!({ "dir": dir, "files": files.len() }))
+                .map_err(ControlError::runtime)
+        }
+        ControlAction::ExportSnippet {
+            format,
+            index,
+            via_proxy,
+        } => snippet::export(
+            app,
+            format,
+            index.unwrap_or(app.selected_exchange),
+            via_proxy,
+        )
+        .map(|snippet| {
+            serde_json::json!({
+                "format": format.name(),
+                "url": snippet.url,
+                "text": snippet.text,
+            })
+        })
+        .map_err(ControlError::runtime),
         ControlAction::GetPending => Ok(control::pending(app)),
-        ControlAction::SendRequest { request } => {
+        ControlAction::GetMcpSummary => Ok(control::mcp_summary(app)),
+        ControlAction::GetSubscriptions => Ok(control::subscriptions(app)),
+        ControlAction::GetBatches => Ok(control::batches(app)),
+        ControlAction::QueryExchanges { query, pin } => {
+            let results = control::query_results(app, &query);
+            if pin {
+                app.pin_query_column(query);
+            }
+            Ok(results)
+        }
+        ControlAction::SetColumns {
+            shown,
+            widths,
+            sort,
+        } => {
+            let mut layout = app.columns.clone();
+            if let Some(shown) = shown {
+                for column in columns::Column::ALL {
+                    layout.set_shown(column, true);
+                }
+                for column in columns::Column::ALL {
+                    layout.set_shown(column, shown.contains(&column));
+                }
+            }
+            for width in widths {
+                layout.set_shown(width.column, true);
+                layout.set_width(width.column, width.width);
+            }
+            if let Some(sort) = sort {
+                layout.sort = sort;
+            }
+            app.set_columns(layout);
+            save_columns(app, history);
+            Ok(control::state(app))
+        }
+        ControlAction::DiffExchanges { left, right } => diff_exchanges(app, history, &left, &right)
+            .map(|comparison| control::comparison(&comparison)),
+        ControlAction::DiffSessions { left, right } => {
+            diff_sessions(app, history, &left, right.as_deref())
+                .map(|comparison| control::comparison(&comparison))
+        }
+        ControlAction::SendRequest { request, listener } => {
+            let Some(port) = app.proxy_config.port(listener.as_deref()) else {
+                return send_control_reply(reply, Err(unknown_listener(listener.as_deref())));
+            };
             let request = app
                 .prepare_new_request(request.to_string())
                 .map(|mut request| {
-                    request.url = format!("http://127.0.0.1:{}", app.proxy_config.listen_port);
+                    request.url = format!("http://127.0.0.1:{port}");
                     request
                 });
             match request {
@@ -523,6 +1001,10 @@ async fn handle_control_command(
             app.set_panel_fullscreen(fullscreen);
             Ok(control::state(app))
         }
+        ControlAction::SetTimeline { visible, zoom } => {
+            app.set_timeline(visible, zoom);
+            Ok(control::state(app))
+        }
         ControlAction::RevealLines {
             focus,
             start_line,
@@ -536,6 +1018,8 @@ async fn handle_control_command(
                 let total_lines = ui::detail_line_count(app, focus).unwrap_or(0);
                 match ui::detail_line_text(app, focus, start_line, end_line) {
                     Some(text) => {
+                        let folds = ui::detail_folds(app, focus);
+                        app.unfold_lines(focus, &folds, start_line, end_line);
                         app.reveal_lines(focus, start_line, end_line, text);
                         center_detail_range(
                             app,
@@ -600,11 +1084,26 @@ async fn handle_control_command(
                 ))
             } else {
                 let total_lines = ui::detail_line_count(app, focus).unwrap_or(0);
-                app.scroll_panel_lines(focus, lines, total_lines);
+                let folds = ui::detail_folds(app, focus);
+                app.scroll_panel_lines(focus, lines, total_lines, &folds);
                 Ok(control::state(app))
             }
         }
-        ControlAction::SetTarget { url } => {
+        ControlAction::SetTarget {
+            url,
+            listener: Some(name),
+        } if name != app::MAIN_LISTENER => {
+            set_listener_target(
+                app,
+                &name,
+                url.trim(),
+                proxy_servers,
+                message_sender,
+                proxy_state,
+            )
+            .await
+        }
+        ControlAction::SetTarget { url, .. } => {
             let url = url.trim();
             if app.proxy_config.stdio.is_some() {
                 Err(ControlError::invalid_params(
@@ -630,11 +1129,25 @@ async fn handle_control_command(
                     app.mark_changed();
                 }
                 if changed && app.is_running {
-                    restart_proxy(app, proxy_server, message_sender, proxy_state).await;
+                    restart_proxy(app, proxy_servers, message_sender, proxy_state).await;
                 }
                 Ok(control::state(app))
             }
         }
+        ControlAction::SelectProfile { name } => apply_profile(
+            app,
+            history,
+            proxy_servers,
+            message_sender,
+            proxy_state,
+            &name,
+        )
+        .await
+        .map(|()| control::state(app)),
+        ControlAction::SetRequestTimeout { timeout_ms } => {
+            app.set_request_timeout(Some(std::time::Duration::from_millis(timeout_ms)));
+            Ok(control::state(app))
+        }
         ControlAction::SetFilter { text } => {
             if app.filter_text != text {
                 app.filter_text = text;
@@ -665,12 +1178,83 @@ async fn handle_control_command(
             }
             Ok(control::state(app))
         }
+        ControlAction::TagExchange {
+            index,
+            starred,
+            tags,
+            add,
+            remove,
+        } => update_bookmark(
+            app,
+            history,
+            index.unwrap_or(app.selected_exchange),
+            |bookmark| {
+                if let Some(starred) = starred {
+                    bookmark.starred = starred;
+                }
+                if let Some(tags) = tags {
+                    bookmark.set_tags(tags);
+                }
+                bookmark.add_tags(add);
+                bookmark.remove_tags(remove);
+            },
+        )
+        .map(|bookmark| {
+            serde_json::json!({
+                "starred": bookmark.starred,
+                "tags": bookmark.tags,
+                "state": control::state(app),
+            })
+        }),
         ControlAction::ResolvePending { id, decision } => resolve_pending(app, id, decision),
     };
 
     let _ = reply.send(result);
 }
 
+fn unknown_listener(name: Option<&str>) -> ControlError {
+    ControlError::invalid_params(format!(
+        "listener not found: {}",
+        name.unwrap_or(app::MAIN_LISTENER)
+    ))
+}
+
+/// Points a named listener at another HTTP target and restarts it. The session keeps the
+/// main listener's target.
+async fn set_listener_target(
+    app: &mut App,
+    name: &str,
+    url: &str,
+    proxy_servers: &mut ProxyServers,
+    message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
+    proxy_state: &ProxyState,
+) -> control::ControlResult {
+    let Some(listener) = app
+        .proxy_config
+        .listeners
+        .iter_mut()
+        .find(|listener| listener.name == name)
+    else {
+        return Err(unknown_listener(Some(name)));
+    };
+    if listener.stdio.is_some() {
+        return Err(ControlError::invalid_params(
+            "stdio commands are configured at startup",
+        ));
+    }
+    if url.is_empty() {
+        return Err(ControlError::invalid_params("url cannot be empty"));
+    }
+    if listener.target_url != url {
+        listener.target_url = url.to_string();
+        app.mark_changed();
+        if app.is_running {
+            restart_listener(app, proxy_servers, name, message_sender, proxy_state).await;
+        }
+    }
+    Ok(control::state(app))
+}
+
 fn center_detail_range(
     app: &mut App,
     terminal_area: ratatui::layout::Rect,
@@ -681,6 +1265,8 @@ fn center_detail_range(
     annotation_id: Option<&str>,
 ) {
     let visible_lines = ui::panel_visible_lines(terminal_area, app, panel).max(1);
+    // Folded lines are not drawn, so positions count drawn lines only.
+    let folds = ui::detail_folds(app, panel);
     let annotations = app
         .visible_annotations(panel)
         .filter(|annotation| {
@@ -690,32 +1276,33 @@ fn center_detail_range(
     let annotations_before = |line: usize| {
         annotations
             .iter()
-            .filter(|annotation| annotation.end_line < line)
+            .filter(|annotation| folds.shown(annotation.end_line) < line)
             .count()
     };
-    let range_start = start_line.saturating_sub(1) + annotations_before(start_line);
-    let source_range_end = end_line.saturating_sub(1) + annotations_before(end_line);
+    let range_start = folds.rank(start_line) + annotations_before(start_line);
+    let source_range_end = folds.rank(end_line) + annotations_before(end_line);
     let range_end = annotation_id
         .and_then(|id| {
             annotations
                 .iter()
                 .filter(|annotation| annotation.end_line == end_line)
                 .position(|annotation| annotation.id == id)
-                .map(|position| end_line + annotations_before(end_line) + position)
+                .map(|position| source_range_end + 1 + position)
         })
         .unwrap_or(source_range_end);
-    let display_total = total_lines + annotations.len();
+    let display_total = folds.rank(total_lines + 1) + annotations.len();
     let range_center = range_start + range_end.saturating_sub(range_start) / 2;
     let viewport_center = visible_lines.saturating_sub(1) / 2;
     let display_scroll = range_center
         .saturating_sub(viewport_center)
         .min(display_total.saturating_sub(visible_lines));
     let source_scroll = (0..total_lines)
+        .filter(|source| !folds.is_hidden(source + 1))
         .take_while(|source| {
-            *source
+            folds.rank(source + 1)
                 + annotations
                     .iter()
-                    .filter(|annotation| annotation.end_line <= *source)
+                    .filter(|annotation| folds.shown(annotation.end_line) <= *source)
                     .count()
                 <= display_scroll
         })
@@ -821,6 +1408,11 @@ fn annotate_visual_selection(
         .filter(|_| app.visual_selection_active)
         .cloned()
         .ok_or_else(|| ControlError::invalid_params("No visual selection"))?;
+    if app.panel_query(selection.panel).is_some() {
+        return Err(ControlError::invalid_params(
+            "Query results cannot be annotated; press Esc to clear the query",
+        ));
+    }
     let annotation = build_annotation(
         app,
         selection.panel,
@@ -851,6 +1443,87 @@ fn persist_annotation(
     Ok(())
 }
 
+/// Appends a portable session, with its stars and tags, to the active session.
+fn replay_session(
+    app: &mut App,
+    history: &mut HistoryStore,
+    session: control::Session,
+) -> Result<serde_json::Value, ControlError> {
+    let bookmarks = control::session_bookmarks(&session);
+    let exchanges = control::replay_session(session)?;
+    let imported = exchanges.len();
+    let session_id = active_session_id(app)
+        .ok_or_else(|| ControlError::runtime("No active session"))?
+        .to_string();
+    let first = history
+        .append_exchanges(&session_id, &exchanges)
+        .and_then(|first| {
+            for (position, bookmark) in &bookmarks {
+                history.set_bookmark(&session_id, first + position, bookmark)?;
+            }
+            Ok(first)
+        })
+        .map_err(|error| ControlError::runtime(error.to_string()))?;
+    app.append_exchanges(exchanges);
+    for (position, bookmark) in bookmarks {
+        app.set_bookmark(first + position, bookmark);
+    }
+    Ok(serde_json::json!({
+        "imported": imported,
+        "state": control::state(app),
+    }))
+}
+
+/// Saves a trace log as a new session without selecting it, so the live target stays put.
+fn import_trace(
+    history: &mut HistoryStore,
+    text: &str,
+    name: Option<&str>,
+    target: &str,
+) -> Result<serde_json::Value, ControlError> {
+    let import = trace::import(text, target).map_err(ControlError::invalid_params)?;
+    let exchanges = control::replay_session(import.session)?;
+    let session = history
+        .create_session(name, target)
+        .and_then(|session| {
+            history.append_exchanges(&session.id, &exchanges)?;
+            history
+                .session(&session.id)?
+                .ok_or_else(|| anyhow::anyhow!("session not found"))
+        })
+        .map_err(|error| ControlError::runtime(error.to_string()))?;
+    Ok(serde_json::json!({
+        "session": session,
+        "format": import.format.name(),
+        "imported": exchanges.len(),
+        "skipped": import.skipped,
+    }))
+}
+
+/// Edits the star and tags of an exchange, saving them before the view shows them.
+fn update_bookmark(
+    app: &mut App,
+    history: &HistoryStore,
+    index: usize,
+    edit: impl FnOnce(&mut Bookmark),
+) -> Result<Bookmark, ControlError> {
+    if index >= app.exchanges.len() {
+        return Err(ControlError::invalid_params(format!(
+            "exchange index out of range: {index}"
+        )));
+    }
+    let session_id = active_session_id(app)
+        .ok_or_else(|| ControlError::runtime("No active session"))?
+        .to_string();
+    let mut bookmark = app.bookmark(index).cloned().unwrap_or_default();
+    edit(&mut bookmark);
+    history
+        .set_bookmark(&session_id, index, &bookmark)
+        .map_err(|error| ControlError::runtime(error.to_string()))?;
+    app.set_bookmark(index, bookmark.clone());
+    Ok(bookmark)
+}
+
 fn remove_annotation(
     app: &mut App,
     history: &HistoryStore,
@@ -903,7 +1576,7 @@ fn create_session(
     let session = history
         .create_session(name, &app.proxy_config.target_url)
         .map_err(|error| ControlError::runtime(error.to_string()))?;
-    app.activate_session(session, Vec::new(), Vec::new());
+    app.activate_session(session, Vec::new(), Vec::new(), HashMap::new());
     Ok(())
 }
 
@@ -913,7 +1586,7 @@ fn select_session(app: &mut App, history: &HistoryStore, id: &str) -> Result<boo
             "Resolve pending requests before changing sessions",
         ));
     }
-    let (session, exchanges, annotations) = history
+    let (session, exchanges, annotations, bookmarks) = history
         .load_session(id)
         .map_err(|error| ControlError::runtime(error.to_string()))?;
     if app.proxy_config.stdio.is_some() && session.target != app.proxy_config.target_url {
@@ -923,10 +1596,182 @@ fn select_session(app: &mut App, history: &HistoryStore, id: &str) -> Result<boo
     }
     let target_changed = app.proxy_config.target_url != session.target;
     app.proxy_config.target_url = session.target.clone();
-    app.activate_session(session, exchanges, annotations);
+    app.activate_session(session, exchanges, annotations, bookmarks);
     Ok(target_changed)
 }
 
+/// Applies a configured profile: its target or stdio server, port, listeners, filter,
+/// breakpoints, rewrites, and redactions. Fields the profile leaves out keep their current
+/// values, and the control port stays as it was at startup.
+async fn apply_profile(
+    app: &mut App,
+    history: &HistoryStore,
+    proxy_servers: &mut ProxyServers,
+    message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
+    proxy_state: &ProxyState,
+    name: &str,
+) -> Result<(), ControlError> {
+    if app.proxy_config.transparent {
+        return Err(ControlError::invalid_params(
+            "transparent wrappers cannot switch profiles",
+        ));
+    }
+    if !app.pending_requests.is_empty() {
+        return Err(ControlError::runtime(
+            "Resolve pending requests before switching profiles",
+        ));
+    }
+    let profile = app
+        .config
+        .profiles
+        .get(name)
+        .cloned()
+        .ok_or_else(|| ControlError::invalid_params(format!("profile not found: {name}")))?;
+    let rules = profile.rules().map_err(ControlError::invalid_params)?;
+    let listeners = profile.listeners().map_err(ControlError::invalid_params)?;
+    let mut config = app.proxy_config.clone();
+    if let Some(port) = profile.port {
+        config.listen_port = port;
+    }
+    let replace_listeners = !listeners.is_empty();
+    if replace_listeners {
+        config.listeners = listeners;
+    }
+    profile::check_listener_ports(config.listen_port, &config.listeners)
+        .map_err(ControlError::invalid_params)?;
+    match profile.stdio().map_err(ControlError::invalid_params)? {
+        Some(stdio) => {
+            config.target_url = stdio::display_command(&stdio.command);
+            config.transport = app::TransportType::Stdio(stdio.framing);
+            config.stdio = Some(stdio);
+        }
+        None => {
+            if let Some(target) = profile.target {
+                config.target_url = target;
+                config.transport = app::TransportType::Http;
+                config.stdio = None;
+            }
+        }
+    }
+
+    let target_changed = config.target_url != app.proxy_config.target_url;
+    if target_changed {
+        if let Some(session_id) = active_session_id(app) {
+            history
+                .update_target(session_id, &config.target_url)
+                .map_err(|error| ControlError::runtime(error.to_string()))?;
+        }
+        if let Some(session) = &mut app.session {
+            session.target = config.target_url.clone();
+        }
+    }
+    // A stdio server restarts even for the same command, so its new environment applies.
+    let restart = target_changed
+        || config.listen_port != app.proxy_config.listen_port
+        || config.stdio.is_some();
+    app.proxy_config = config;
+    if let Ok(mut active) = app.rules.lock() {
+        *active = rules;
+    }
+    if let Some(filter) = profile.filter {
+        app.filter_text = filter;
+    }
+    app.profile = Some(name.to_string());
+    app.notice = Some(format!("Profile {name} applied"));
+    if replace_listeners {
+        app.columns.set_shown(columns::Column::Source, true);
+    }
+    app.mark_changed();
+    if replace_listeners && app.is_running {
+        restart_all_proxies(app, proxy_servers, message_sender, proxy_state).await;
+    } else if restart && app.is_running {
+        restart_proxy(app, proxy_servers, message_sender, proxy_state).await;
+    }
+    Ok(())
+}
+
+/// Compares two exchanges, each from the active session or one in history.
+fn diff_exchanges(
+    app: &App,
+    history: &HistoryStore,
+    left: &ExchangeRef,
+    right: &ExchangeRef,
+) -> Result<Comparison, ControlError> {
+    let (left_label, left) = exchange_at(app, history, left)?;
+    let (right_label, right) = exchange_at(app, history, right)?;
+    Ok(Comparison::Exchanges {
+        left: left_label,
+        right: right_label,
+        differences: diff::exchanges(&left, &right),
+    })
+}
+
+fn exchange_at(
+    app: &App,
+    history: &HistoryStore,
+    reference: &ExchangeRef,
+) -> Result<(String, JsonRpcExchange), ControlError> {
+    let index = reference.index;
+    let missing = || ControlError::invalid_params(format!("exchange {index} does not exist"));
+    match reference.session_id.as_deref() {
+        Some(session_id) if Some(session_id) != active_session_id(app) => {
+            let session = history
+                .session(session_id)
+                .map_err(|error| ControlError::runtime(error.to_string()))?
+                .ok_or_else(|| ControlError::invalid_params("session not found"))?;
+            let exchange = history
+                .history(session_id, 1, Some(index + 1))
+                .map_err(|error| ControlError::runtime(error.to_string()))?
+                .into_iter()
+                .find(|(sequence, _)| *sequence == index)
+                .map(|(_, exchange)| exchange)
+                .ok_or_else(missing)?;
+            Ok((format!("{} #{index}", session.name), exchange))
+        }
+        _ => {
+            let exchange = app.exchanges.get(index).cloned().ok_or_else(missing)?;
+            let label = match &app.session {
+                Some(session) => format!("{} #{index}", session.name),
+                None => format!("#{index}"),
+            };
+            Ok((label, exchange))
+        }
+    }
+}
+
+/// Compares session `left` with `right`, or with the active session when `right` is omitted.
+fn diff_sessions(
+    app: &App,
+    history: &HistoryStore,
+    left: &str,
+    right: Option<&str>,
+) -> Result<Comparison, ControlError> {
+    let right = right
+        .or_else(|| active_session_id(app))
+        .ok_or_else(|| ControlError::runtime("No active session"))?;
+    let (left_name, left) = session_exchanges(app, history, left)?;
+    let (right_name, right) = session_exchanges(app, history, right)?;
+    Ok(Comparison::Sessions {
+        left: left_name,
+        right: right_name,
+        pairs: diff::sessions(&left, &right),
+    })
+}
+
+fn session_exchanges(
+    app: &App,
+    history: &HistoryStore,
+    id: &str,
+) -> Result<(String, Vec<JsonRpcExchange>), ControlError> {
+    match &app.session {
+        Some(session) if session.id == id => Ok((session.name.clone(), app.exchanges.clone())),
+        _ => history
+            .load_session(id)
+            .map(|(session, exchanges, _, _)| (session.name, exchanges))
+            .map_err(|error| ControlError::runtime(error.to_string())),
+    }
+}
+
 fn scroll_history(app: &mut App, lines: i64, visible_rows: usize) {
     app.set_focus(app::Focus::MessageList);
     match app.app_mode {
@@ -1010,6 +1855,44 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    if cli.headless {
+        match &cli.mode {
+            Some(TargetMode::Wrap { .. }) => {
+                anyhow::bail!("--headless cannot be used with the wrap subcommand")
+            }
+            Some(TargetMode::Attach { .. }) => {
+                anyhow::bail!("--headless cannot be used with the attach subcommand")
+            }
+            Some(TargetMode::Tail { .. }) => {
+                anyhow::bail!("--headless cannot be used with the tail subcommand")
+            }
+            _ => {}
+        }
+    }
+
+    if cli.profile.is_some()
+        && !matches!(
+            cli.mode,
+            None | Some(TargetMode::Stdio { .. } | TargetMode::Tail { attach: None, .. })
+        )
+    {
+        anyhow::bail!("--profile only applies when the debugger drives a target");
+    }
+
+    let printer = match &cli.mode {
+        Some(TargetMode::Tail {
+            format,
+            filter,
+            color,
+            ..
+        }) => Some(tail::Printer {
+            format: (*format).into(),
+            filter: filter.clone(),
+            color: color.enabled(),
+        }),
+        _ => None,
+    };
+
     match &cli.mode {
         Some(TargetMode::Wrap { framing, command }) => {
             if cli.target.is_some() {
@@ -1017,50 +1900,201 @@ async fn main() -> Result<()> {
             }
             let control_port = cli
                 .control_port
-                .or_else(|| cli.port.checked_add(1))
+                .or_else(|| cli.port.unwrap_or(DEFAULT_PORT).checked_add(1))
                 .ok_or_else(|| {
                     anyhow::anyhow!("--control-port is required when --port is 65535")
                 })?;
-            return run_transparent_wrap(control_port, (*framing).into(), command).await;
+            let request_timeout = std::time::Duration::from_secs(cli.request_timeout);
+            return run_transparent_wrap(control_port, (*framing).into(), command, request_timeout)
+                .await;
         }
         Some(TargetMode::Attach { control_url }) => {
             if cli.target.is_some() {
                 anyhow::bail!("--target cannot be used with the attach subcommand");
             }
-            return run_attached_tui(control_url).await;
+            let abi = load_abi(cli.abi_dir.as_deref())?;
+            return run_attached_tui(control_url, abi).await;
+        }
+        Some(TargetMode::Import { name, file }) => {
+            let text = std::fs::read_to_string(file)
+                .map_err(|error| anyhow::anyhow!("failed to read {}: {error}", file.display()))?;
+            let file_name = file.file_name().map_or_else(
+                || file.display().to_string(),
+                |name| name.to_string_lossy().into(),
+            );
+            let name = name.as_deref().unwrap_or(&file_name);
+            let target = cli.target.as_deref().unwrap_or(&file_name);
+            let mut history = HistoryStore::open_default()?;
+            let result = import_trace(&mut history, &text, Some(name), target)
+                .map_err(|error| anyhow::anyhow!(error.message))?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+            return Ok(());
+        }
+        Some(TargetMode::Sessions { command }) => {
+            let mut history = HistoryStore::open_default()?;
+            let result = match command {
+                SessionsCommand::List { limit } => offline::list(&history, *limit)?,
+                SessionsCommand::Show { id, limit } => offline::show(&history, id, *limit)?,
+                SessionsCommand::Export { id, format } => {
+                    return print_output(&offline::export(&history, id, (*format).into())?);
+                }
+                SessionsCommand::Import { name, file } => {
+                    let text = std::fs::read_to_string(file).map_err(|error| {
+                        anyhow::anyhow!("failed to read {}: {error}", file.display())
+                    })?;
+                    let file_name = file.file_name().map_or_else(
+                        || file.display().to_string(),
+                        |name| name.to_string_lossy().into(),
+                    );
+                    let name = name.as_deref().unwrap_or(&file_name);
+                    let target = cli.target.as_deref().unwrap_or(&file_name);
+                    offline::import(&mut history, &text, name, target)?
+                }
+                SessionsCommand::Rename { id, name } => offline::rename(&history, id, name)?,
+                SessionsCommand::Delete { id } => offline::delete(&history, id)?,
+            };
+            return print_output(&serde_json::to_string_pretty(&result)?);
+        }
+        Some(TargetMode::History {
+            command:
+                HistoryCommand::Grep {
+                    pattern,
+                    session,
+                    limit,
+                },
+        }) => {
+            let pattern = regex::Regex::new(pattern)
+                .map_err(|error| anyhow::anyhow!("invalid pattern: {error}"))?;
+            let history = HistoryStore::open_default()?;
+            let result = offline::grep(&history, &pattern, session.as_deref(), *limit)?;
+            return print_output(&serde_json::to_string_pretty(&result)?);
+        }
+        Some(TargetMode::Fixtures { session, dir }) => {
+            let session = HistoryStore::open_default()?.export_session(session)?;
+            let files = fixture::write(dir, &session).map_err(|error| anyhow::anyhow!(error))?;
+            println!("wrote {} files to {}", files.len(), dir.display());
+            return Ok(());
+        }
+        Some(TargetMode::Verify {
+            dir,
+            framing,
+            command,
+        }) => {
+            let suite = fixture::load(dir).map_err(|error| anyhow::anyhow!(error))?;
+            // Server notifications are not part of any fixture.
+            let (sender, _receiver) = mpsc::unbounded_channel();
+            let target = if command.is_empty() {
+                let url = cli.target.as_deref().unwrap_or(&suite.manifest.target);
+                fixture::Target::http(url)
+            } else {
+                stdio::StdioTransport::spawn(command, &[], (*framing).into(), sender)
+                    .map(fixture::Target::Stdio)
+            }
+            .map_err(|error| anyhow::anyhow!(error))?;
+            let timeout = std::time::Duration::from_secs(cli.request_timeout);
+            let outcomes = fixture::verify(&suite, &target, timeout).await;
+            print!("{}", fixture::describe(&outcomes));
+            let failed = outcomes
+                .iter()
+                .filter(|outcome| outcome.result.is_err())
+                .count();
+            if failed > 0 {
+                anyhow::bail!("{failed} of {} fixtures failed", outcomes.len());
+            }
+            return Ok(());
+        }
+        Some(TargetMode::Tail {
+            attach: Some(control_url),
+            command,
+            ..
+        }) => {
+            if cli.target.is_some() || !command.is_empty() {
+                anyhow::bail!(
+                    "tail --attach follows an existing target; drop --target and the command"
+                );
+            }
+            let printer = printer.expect("tail builds a printer");
+            return run_attached_tail(control_url, printer).await;
         }
-        Some(TargetMode::Stdio { .. }) | None => {}
+        Some(TargetMode::Stdio { .. } | TargetMode::Tail { .. }) | None => {}
     }
 
+    // Flags override the profile.
+    let config = profile::Config::load().map_err(anyhow::Error::msg)?;
+    let (profile_name, profile) = match config
+        .select(cli.profile.as_deref())
+        .map_err(anyhow::Error::msg)?
+    {
+        Some((name, profile)) => (Some(name.to_string()), profile.clone()),
+        None => (None, profile::Profile::default()),
+    };
+    let port = cli.port.or(profile.port).unwrap_or(DEFAULT_PORT);
     let control_port = cli
         .control_port
-        .or_else(|| cli.port.checked_add(1))
+        .or(profile.control_port)
+        .or_else(|| port.checked_add(1))
         .ok_or_else(|| anyhow::anyhow!("--control-port is required when --port is 65535"))?;
-    let (target, transport, stdio) = match cli.mode {
-        Some(TargetMode::Stdio { framing, command }) => {
+    let stdio_command = match cli.mode {
+        Some(TargetMode::Stdio { framing, command }) => Some((framing, command, "stdio")),
+        Some(TargetMode::Tail {
+            framing, command, ..
+        }) if !command.is_empty() => Some((framing, command, "tail")),
+        Some(TargetMode::Tail { .. }) | None => None,
+        Some(
+            TargetMode::Wrap { .. }
+            | TargetMode::Attach { .. }
+            | TargetMode::Import { .. }
+            | TargetMode::Sessions { .. }
+            | TargetMode::History { .. }
+            | TargetMode::Fixtures { .. }
+            | TargetMode::Verify { .. },
+        ) => unreachable!(),
+    };
+    let (target, transport, stdio) = match stdio_command {
+        Some((framing, command, subcommand)) => {
             if cli.target.is_some() {
-                anyhow::bail!("--target cannot be used with the stdio subcommand");
+                anyhow::bail!("--target cannot be used with the {subcommand} subcommand");
             }
             let framing = app::Framing::from(framing);
             (
                 stdio::display_command(&command),
                 app::TransportType::Stdio(framing),
-                Some(app::StdioConfig { command, framing }),
+                Some(app::StdioConfig {
+                    command,
+                    framing,
+                    env: Vec::new(),
+                }),
             )
         }
-        None => (
-            cli.target.unwrap_or_default(),
-            app::TransportType::Http,
-            None,
-        ),
-        Some(TargetMode::Wrap { .. } | TargetMode::Attach { .. }) => unreachable!(),
+        None => match profile.stdio().map_err(anyhow::Error::msg)? {
+            Some(stdio) if cli.target.is_none() => (
+                stdio::display_command(&stdio.command),
+                app::TransportType::Stdio(stdio.framing),
+                Some(stdio),
+            ),
+            _ => (
+                cli.target.or(profile.target.clone()).unwrap_or_default(),
+                app::TransportType::Http,
+                None,
+            ),
+        },
     };
+    let listeners = if cli.listeners.is_empty() {
+        profile.listeners().map_err(anyhow::Error::msg)?
+    } else {
+        profile::check_listener_names(&cli.listeners).map_err(anyhow::Error::msg)?;
+        cli.listeners
+    };
+    profile::check_listener_ports(port, &listeners).map_err(anyhow::Error::msg)?;
+    let rules = Arc::new(Mutex::new(profile.rules().map_err(anyhow::Error::msg)?));
+    let abi = load_abi(cli.abi_dir.as_deref())?;
     let proxy_config = app::ProxyConfig {
-        listen_port: cli.port,
+        listen_port: port,
         target_url: target.clone(),
         transport,
         stdio,
         transparent: false,
+        listeners,
     };
 
     // Create message channel for proxy communication
@@ -1069,6 +2103,9 @@ async fn main() -> Result<()> {
     // Create pending request channel for pause/intercept functionality
     let (pending_sender, pending_receiver) = mpsc::unbounded_channel();
 
+    // Create the channel the proxy reports response delivery times on.
+    let (deliveries, delivery_receiver) = mpsc::unbounded_channel();
+
     // Create the local agent control plane.
     let (control_sender, control_receiver) = mpsc::unbounded_channel();
 
@@ -1077,26 +2114,33 @@ async fn main() -> Result<()> {
     let proxy_state = ProxyState {
         app_mode: shared_app_mode.clone(),
         pending_sender,
+        rules: rules.clone(),
+        deliveries,
     };
 
-    // Bind both ports before entering the TUI. A second debugger must not send through
+    // Bind every port before entering the TUI. A second debugger must not send through
     // or expose the control plane of an older process that owns the same ports.
     let initial_server = ProxyServer::from_config(&proxy_config, message_sender.clone())?
         .with_state(proxy_state.clone());
     let initial_proxy_server = initial_server.bind()?;
+    let mut listener_servers = Vec::new();
+    for listener in &proxy_config.listeners {
+        let server = ProxyServer::from_listener(listener, message_sender.clone())?
+            .with_state(proxy_state.clone());
+        listener_servers.push((listener.name.clone(), server.bind()?));
+    }
     let control_server = control::bind(control_port, control_sender).map_err(anyhow::Error::msg)?;
 
     let mut history = HistoryStore::open_default()?;
     let session = history.create_session(None, &target)?;
 
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    let initial_proxy_handle = tokio::spawn(initial_proxy_server);
+    let proxy_servers = ProxyServers {
+        main: Some(tokio::spawn(initial_proxy_server)),
+        listeners: listener_servers
+            .into_iter()
+            .map(|(name, server)| (name, tokio::spawn(server)))
+            .collect(),
+    };
     let control_handle = tokio::spawn(async move {
         control_server.await;
         Ok(())
@@ -1108,7 +2152,17 @@ async fn main() -> Result<()> {
     // Override default config with CLI arguments
     app.proxy_config = proxy_config;
     app.control_port = control_port;
-    app.activate_session(session, Vec::new(), Vec::new());
+    app.abi = abi;
+    app.set_request_timeout(Some(std::time::Duration::from_secs(cli.request_timeout)));
+    app.activate_session(session, Vec::new(), Vec::new(), HashMap::new());
+    load_columns(&mut app, &history);
+    if !app.proxy_config.listeners.is_empty() {
+        app.columns.set_shown(columns::Column::Source, true);
+    }
+    app.config = config;
+    app.profile = profile_name;
+    app.rules = rules;
+    app.filter_text = profile.filter.unwrap_or_default();
 
     let (request_result_sender, request_result_receiver) = mpsc::unbounded_channel();
     let runtime = Runtime {
@@ -1116,14 +2170,50 @@ async fn main() -> Result<()> {
         message_sender,
         shared_app_mode,
         pending_receiver,
+        delivery_receiver,
         control_receiver,
         proxy_state,
-        proxy_server: Some(initial_proxy_handle),
+        proxy_servers,
         control_server: Some(control_handle),
         request_result_sender,
         request_result_receiver,
         change_waiters: Vec::new(),
     };
+    if let Some(printer) = printer {
+        // Keep stdout to exchanges so it can feed other tools.
+        return run_headless(
+            app,
+            runtime,
+            printer,
+            Box::new(io::stdout()),
+            Box::new(io::stderr()),
+        )
+        .await;
+    }
+    if cli.headless {
+        let (log, notes): (Box<dyn Write>, Box<dyn Write>) = match &cli.log {
+            Some(path) => {
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|error| {
+                        anyhow::anyhow!("failed to open {}: {error}", path.display())
+                    })?;
+                (Box::new(file.try_clone()?), Box::new(file))
+            }
+            None => (Box::new(io::stdout()), Box::new(io::stdout())),
+        };
+        return run_headless(app, runtime, tail::Printer::plain(), log, notes).await;
+    }
+
+    // Setup terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
     let res = run_app(&mut terminal, app, runtime).await;
 
     // Restore terminal
@@ -1142,10 +2232,188 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Drives the proxy, history, and control plane like `run_app`, but prints each settled
+/// exchange to `log` instead of drawing, until interrupted or terminated. The ports, failed
+/// sends, and the final summary go to `notes`.
+async fn run_headless(
+    mut app: App,
+    mut runtime: Runtime,
+    printer: tail::Printer,
+    mut log: Box<dyn Write>,
+    mut notes: Box<dyn Write>,
+) -> Result<()> {
+    let session = app
+        .session
+        .as_ref()
+        .map_or_else(String::new, |session| session.id.clone());
+    writeln!(
+        notes,
+        "proxy on port {} for {}, control on port {}, session {session}",
+        app.proxy_config.listen_port, app.proxy_config.target_url, app.control_port
+    )?;
+    for listener in &app.proxy_config.listeners {
+        writeln!(
+            notes,
+            "listener {} on port {} for {}",
+            listener.name, listener.listen_port, listener.target_url
+        )?;
+    }
+    notes.flush()?;
+    let mut exchanges = headless::ExchangeLog::default();
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        record_proxy_messages(&mut app, &mut runtime);
+        if let Ok(mut shared_mode) = runtime.shared_app_mode.try_lock() {
+            *shared_mode = app.app_mode;
+        }
+        while let Ok(pending_request) = runtime.pending_receiver.try_recv() {
+            // Nothing on screen shows a held request, so name it for the control plane.
+            writeln!(
+                notes,
+                "holding {} request {}; resolve it with debugger.resolvePending",
+                pending_request
+                    .original_request
+                    .method
+                    .as_deref()
+                    .unwrap_or("unknown"),
+                pending_request.id
+            )?;
+            notes.flush()?;
+            app.hold_pending(pending_request);
+        }
+        while let Ok(result) = runtime.request_result_receiver.try_recv() {
+            if let Err(error) = result {
+                writeln!(notes, "request failed: {error}")?;
+            }
+        }
+        while let Ok(command) = runtime.control_receiver.try_recv() {
+            let Some(command) = register_change_waiter(&app, command, &mut runtime.change_waiters)
+            else {
+                continue;
+            };
+            handle_control_command(
+                &mut app,
+                command,
+                ControlContext {
+                    terminal_area: ratatui::layout::Rect::new(0, 0, 120, 40),
+                    proxy_servers: &mut runtime.proxy_servers,
+                    message_sender: &runtime.message_sender,
+                    proxy_state: &runtime.proxy_state,
+                    request_result_sender: &runtime.request_result_sender,
+                    history: &mut runtime.history,
+                },
+            )
+            .await;
+        }
+        app.check_timeouts();
+        resolve_change_waiters(&app, &mut runtime.change_waiters);
+
+        let lines = printer.render(&app, &exchanges.settled(&app));
+        for line in &lines {
+            writeln!(log, "{line}")?;
+        }
+        if !lines.is_empty() {
+            log.flush()?;
+        }
+
+        if runtime
+            .control_server
+            .as_ref()
+            .is_some_and(JoinHandle::is_finished)
+        {
+            stop_proxy(&mut runtime.proxy_servers).await;
+            anyhow::bail!("control plane stopped");
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_millis(10)) => {}
+            result = &mut shutdown => {
+                result?;
+                stop_proxy(&mut runtime.proxy_servers).await;
+                if let Some(control_server) = runtime.control_server.take() {
+                    control_server.abort();
+                }
+                record_new_messages(&mut app, &mut runtime.history);
+                for line in printer.render(&app, &exchanges.settled(&app)) {
+                    writeln!(log, "{line}")?;
+                }
+                log.flush()?;
+                writeln!(notes, "{}", headless::summary(&app))?;
+                notes.flush()?;
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Prints the exchanges of a running debugger as they settle, starting from the next one.
+async fn run_attached_tail(control_url: &str, printer: tail::Printer) -> Result<()> {
+    let client = attach::ControlClient::new(control_url.to_string());
+    let mut app = App::new();
+    let mut revision = None;
+    let mut exchanges = headless::ExchangeLog::default();
+    let mut stdout = io::stdout();
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        let first = revision.is_none();
+        let state = tokio::select! {
+            state = async {
+                match revision {
+                    Some(revision) => client.wait_for_change(revision, 30_000).await,
+                    None => client.state().await,
+                }
+            } => state.map_err(anyhow::Error::msg)?,
+            result = &mut shutdown => return result.map_err(Into::into),
+        };
+        if revision != Some(state.revision) {
+            revision = Some(state.revision);
+            // Settled exchanges were printed already, so only the rest are fetched again.
+            let from = attach::refresh_from(&app, &state);
+            let session = client
+                .session_from(from)
+                .await
+                .map_err(anyhow::Error::msg)?;
+            attach::Snapshot {
+                state,
+                session,
+                from,
+            }
+            .apply(&mut app)
+            .map_err(anyhow::Error::msg)?;
+        }
+        let settled = exchanges.settled(&app);
+        if !first {
+            for line in printer.render(&app, &settled) {
+                writeln!(stdout, "{line}")?;
+            }
+            stdout.flush()?;
+        }
+    }
+}
+
+/// Resolves on Ctrl-C, or on SIGTERM where there is one, so CI can stop a headless run.
+async fn shutdown_signal() -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await
+}
+
 async fn run_transparent_wrap(
     control_port: u16,
     framing: app::Framing,
     command: &[OsString],
+    request_timeout: std::time::Duration,
 ) -> Result<()> {
     let target = stdio::display_command(command);
     let (message_sender, message_receiver) = mpsc::unbounded_channel();
@@ -1153,9 +2421,12 @@ async fn run_transparent_wrap(
     let (control_sender, control_receiver) = mpsc::unbounded_channel();
     let shared_app_mode = Arc::new(Mutex::new(AppMode::Normal));
     let (pending_sender, _pending_messages) = mpsc::unbounded_channel();
+    let (deliveries, delivery_receiver) = mpsc::unbounded_channel();
     let proxy_state = ProxyState {
         app_mode: shared_app_mode.clone(),
         pending_sender,
+        rules: Arc::default(),
+        deliveries,
     };
     let control_server = control::bind(control_port, control_sender).map_err(anyhow::Error::msg)?;
     let control_server = tokio::spawn(async move {
@@ -1166,6 +2437,7 @@ async fn run_transparent_wrap(
     let mut history = HistoryStore::open_default()?;
     let session = history.create_session(None, &target)?;
     let mut app = App::new_with_receiver(message_receiver);
+    app.set_request_timeout(Some(request_timeout));
     app.proxy_config = app::ProxyConfig {
         listen_port: 0,
         target_url: target,
@@ -1173,11 +2445,14 @@ async fn run_transparent_wrap(
         stdio: Some(app::StdioConfig {
             command: command.to_vec(),
             framing,
+            env: Vec::new(),
         }),
         transparent: true,
+        listeners: Vec::new(),
     };
     app.control_port = control_port;
-    app.activate_session(session, Vec::new(), Vec::new());
+    app.activate_session(session, Vec::new(), Vec::new(), HashMap::new());
+    load_columns(&mut app, &history);
 
     let (request_result_sender, request_result_receiver) = mpsc::unbounded_channel();
     let mut runtime = Runtime {
@@ -1185,9 +2460,10 @@ async fn run_transparent_wrap(
         message_sender: message_sender.clone(),
         shared_app_mode,
         pending_receiver,
+        delivery_receiver,
         control_receiver,
         proxy_state,
-        proxy_server: None,
+        proxy_servers: ProxyServers::default(),
         control_server: Some(control_server),
         request_result_sender,
         request_result_receiver,
@@ -1211,7 +2487,7 @@ async fn run_transparent_wrap(
                 command,
                 ControlContext {
                     terminal_area: ratatui::layout::Rect::new(0, 0, 120, 40),
-                    proxy_server: &mut runtime.proxy_server,
+                    proxy_servers: &mut runtime.proxy_servers,
                     message_sender: &runtime.message_sender,
                     proxy_state: &runtime.proxy_state,
                     request_result_sender: &runtime.request_result_sender,
@@ -1220,6 +2496,7 @@ async fn run_transparent_wrap(
             )
             .await;
         }
+        app.check_timeouts();
         resolve_change_waiters(&app, &mut runtime.change_waiters);
 
         if relay.is_finished() {
@@ -1255,11 +2532,12 @@ async fn run_transparent_wrap(
     }
 }
 
-async fn run_attached_tui(control_url: &str) -> Result<()> {
+async fn run_attached_tui(control_url: &str, abi: ethereum::Abi) -> Result<()> {
     let client = attach::ControlClient::new(control_url.to_string());
     let state = client.state().await.map_err(anyhow::Error::msg)?;
     let mut revision = state.revision;
     let mut app = App::new();
+    app.abi = abi;
     client
         .snapshot(state)
         .await
@@ -1312,6 +2590,7 @@ async fn run_attached_app(
             }
             last_refresh = Instant::now();
         }
+        app.check_timeouts();
 
         terminal.draw(|frame| ui::draw(frame, app))?;
         if !event::poll(std::time::Duration::from_millis(50))? {
@@ -1336,22 +2615,83 @@ async fn run_attached_app(
                 KeyCode::Char('?') => app.show_help(),
                 KeyCode::Char('z') => app.set_panel_fullscreen(!app.panel_fullscreen),
                 KeyCode::Char('y') => copy_focused_panel(terminal, app)?,
+                KeyCode::Char('m') => app.show_mcp_summary(),
+                KeyCode::Char('e') => app.show_subscription_events(),
+                KeyCode::Char('b') => app.show_batch(),
+                KeyCode::Char('w') => app.toggle_timeline(),
+                KeyCode::Char('f') => app.toggle_json_tree(),
+                KeyCode::Char('=') => app.show_marked_diff(),
+                KeyCode::Char('o') => app.show_columns(),
+                KeyCode::Char('Y') => app.show_snippets(),
+                KeyCode::Char('r') => {
+                    app.close_overlay();
+                    save_reports(app);
+                }
                 KeyCode::Char('q') => return Ok(()),
                 KeyCode::Esc => app.close_overlay(),
                 _ => {}
             }
             continue;
         }
-        if app.overlay == Overlay::Help {
-            if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
-                app.close_overlay();
+        if app.overlay == Overlay::Help {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+                app.close_overlay();
+            }
+            continue;
+        }
+        if matches!(
+            app.overlay,
+            Overlay::Mcp | Overlay::Subscription | Overlay::Diff
+        ) {
+            handle_scrolling_overlay_key(app, key);
+            continue;
+        }
+        if app.overlay == Overlay::Columns {
+            handle_columns_key(app, key);
+            continue;
+        }
+        if app.overlay == Overlay::Snippets {
+            handle_snippets_key(terminal, app, key)?;
+            continue;
+        }
+        if app.overlay == Overlay::Batch {
+            if key.code == KeyCode::Char('y') {
+                copy_batch(terminal, app)?;
+            } else {
+                handle_scrolling_overlay_key(app, key);
+            }
+            continue;
+        }
+        if app.input_mode == app::InputMode::FilteringRequests {
+            match key.code {
+                KeyCode::Enter => app.apply_filter(),
+                KeyCode::Esc => app.cancel_filtering(),
+                KeyCode::Backspace => app.handle_backspace(),
+                KeyCode::Char(character) => app.handle_input_char(character),
+                _ => {}
+            }
+            continue;
+        }
+        if app.input_mode == app::InputMode::Searching {
+            match key.code {
+                KeyCode::Enter => app.finish_search(),
+                KeyCode::Esc => app.cancel_search(),
+                KeyCode::Backspace => {
+                    app.handle_backspace();
+                    search_incrementally(app, terminal.size()?);
+                }
+                KeyCode::Char(character) => {
+                    app.handle_input_char(character);
+                    search_incrementally(app, terminal.size()?);
+                }
+                _ => {}
             }
             continue;
         }
-        if app.input_mode == app::InputMode::FilteringRequests {
+        if app.input_mode == app::InputMode::Querying {
             match key.code {
-                KeyCode::Enter => app.apply_filter(),
-                KeyCode::Esc => app.cancel_filtering(),
+                KeyCode::Enter => app.apply_query(),
+                KeyCode::Esc => app.cancel_editing(),
                 KeyCode::Backspace => app.handle_backspace(),
                 KeyCode::Char(character) => app.handle_input_char(character),
                 _ => {}
@@ -1360,9 +2700,15 @@ async fn run_attached_app(
         }
 
         match key.code {
+            KeyCode::Esc if app.panel_search(app.focus).is_some() => app.clear_search(),
+            KeyCode::Esc if app.panel_query(app.focus).is_some() => {
+                app.set_query(app.focus, None);
+            }
             KeyCode::Esc => app.clear_line_selection(),
             KeyCode::Enter => {
-                if !enter_request_list(app) {
+                // Not a guard, or an Enter that entered the list would fall through to later arms.
+                let entered = enter_request_list(app);
+                if !entered {
                     copy_focused_panel(terminal, app)?;
                 }
             }
@@ -1398,6 +2744,18 @@ async fn run_attached_app(
                     app.select_next();
                 }
             }
+            KeyCode::Char(' ') if app.is_message_list_focused() => {
+                app.toggle_selected_group();
+            }
+            KeyCode::Char(' ') => toggle_fold_at_cursor(app),
+            KeyCode::Char('m') if app.is_message_list_focused() => app.toggle_diff_mark(),
+            KeyCode::Char('.') => app.start_query(),
+            KeyCode::Char('P') => app.toggle_query_column(),
+            KeyCode::Char(character @ ('+' | '=' | '-' | '[' | ']'))
+                if timeline_keys_active(app) =>
+            {
+                handle_timeline_key(app, character);
+            }
             KeyCode::Tab => app.switch_focus(),
             KeyCode::BackTab => app.switch_focus_reverse(),
             KeyCode::Char('u') => {
@@ -1412,7 +2770,11 @@ async fn run_attached_app(
             KeyCode::Char('G') => {
                 move_focused_detail_cursor(app, i64::MAX, terminal.size()?);
             }
+            KeyCode::Char('/') if app.is_searchable() => app.start_search(),
             KeyCode::Char('/') => app.start_filtering_requests(),
+            KeyCode::Char(character @ ('n' | 'N')) if app.panel_search(app.focus).is_some() => {
+                jump_to_search_match(app, terminal.size()?, character == 'n');
+            }
             KeyCode::Char('v') => toggle_visual_selection(app),
             _ => {}
         }
@@ -1428,7 +2790,7 @@ async fn run_app(
 
     loop {
         // Check for new messages from proxy
-        let received_messages = record_new_messages(&mut app, &mut runtime.history);
+        let received_messages = record_proxy_messages(&mut app, &mut runtime);
 
         // Sync app mode with shared state
         if let Ok(mut shared_mode) = runtime.shared_app_mode.try_lock() {
@@ -1438,8 +2800,7 @@ async fn run_app(
         // Check for new pending requests
         let mut received_pending_request = false;
         while let Ok(pending_request) = runtime.pending_receiver.try_recv() {
-            app.pending_requests.push(pending_request);
-            app.mark_changed();
+            app.hold_pending(pending_request);
             received_pending_request = true;
         }
 
@@ -1464,7 +2825,7 @@ async fn run_app(
                 command,
                 ControlContext {
                     terminal_area: terminal.size()?,
-                    proxy_server: &mut runtime.proxy_server,
+                    proxy_servers: &mut runtime.proxy_servers,
                     message_sender: &runtime.message_sender,
                     proxy_state: &runtime.proxy_state,
                     request_result_sender: &runtime.request_result_sender,
@@ -1474,9 +2835,11 @@ async fn run_app(
             .await;
             received_control_command = true;
         }
+        let timed_out = app.check_timeouts();
         resolve_change_waiters(&app, &mut runtime.change_waiters);
 
         if should_draw
+            || timed_out
             || received_messages
             || received_pending_request
             || received_request_result
@@ -1496,7 +2859,7 @@ async fn run_app(
                     if key.code == KeyCode::Char('c')
                         && key.modifiers.contains(KeyModifiers::CONTROL)
             ) {
-                stop_proxy(&mut runtime.proxy_server).await;
+                stop_proxy(&mut runtime.proxy_servers).await;
                 return Ok(());
             }
 
@@ -1520,7 +2883,9 @@ async fn run_app(
             if app.overlay != Overlay::None {
                 match input_event {
                     Event::Key(key) => {
-                        if handle_overlay_key(terminal, &mut app, &mut runtime, key).await? {
+                        let quit =
+                            handle_overlay_key(terminal, &mut app, &mut runtime, key).await?;
+                        if quit {
                             return Ok(());
                         }
                     }
@@ -1529,7 +2894,7 @@ async fn run_app(
                             terminal,
                             &mut app,
                             mouse,
-                            &mut runtime.proxy_server,
+                            &mut runtime.proxy_servers,
                             &runtime.message_sender,
                             &runtime.proxy_state,
                             &mut runtime.history,
@@ -1582,7 +2947,7 @@ async fn run_app(
                     terminal,
                     &mut app,
                     *mouse,
-                    &mut runtime.proxy_server,
+                    &mut runtime.proxy_servers,
                     &runtime.message_sender,
                     &runtime.proxy_state,
                     &mut runtime.history,
@@ -1662,7 +3027,7 @@ async fn run_app(
                                 if app.is_running {
                                     restart_proxy(
                                         &app,
-                                        &mut runtime.proxy_server,
+                                        &mut runtime.proxy_servers,
                                         &runtime.message_sender,
                                         &runtime.proxy_state,
                                     )
@@ -1742,6 +3107,59 @@ async fn run_app(
                         continue;
                     }
 
+                    app::InputMode::Searching => {
+                        match key.code {
+                            KeyCode::Enter => app.finish_search(),
+                            KeyCode::Esc => app.cancel_search(),
+                            KeyCode::Backspace => {
+                                app.handle_backspace();
+                                search_incrementally(&mut app, terminal.size()?);
+                            }
+                            KeyCode::Char(c) => {
+                                app.handle_input_char(c);
+                                search_incrementally(&mut app, terminal.size()?);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    app::InputMode::Tagging => {
+                        match key.code {
+                            KeyCode::Enter => {
+                                let tags = app.input_buffer.clone();
+                                let index = app.selected_exchange;
+                                match update_bookmark(
+                                    &mut app,
+                                    &runtime.history,
+                                    index,
+                                    |bookmark| bookmark.set_tags([tags]),
+                                ) {
+                                    Ok(_) => app.cancel_editing(),
+                                    Err(error) => {
+                                        app.notice = Some(format!("Error: {}", error.message));
+                                    }
+                                }
+                            }
+                            KeyCode::Esc => app.cancel_editing(),
+                            KeyCode::Backspace => app.handle_backspace(),
+                            KeyCode::Char(c) => app.handle_input_char(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    app::InputMode::Querying => {
+                        match key.code {
+                            KeyCode::Enter => app.apply_query(),
+                            KeyCode::Esc => app.cancel_editing(),
+                            KeyCode::Backspace => app.handle_backspace(),
+                            KeyCode::Char(c) => app.handle_input_char(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
                     app::InputMode::Normal => {
                         // Continue to normal key handling below
                     }
@@ -1749,11 +3167,17 @@ async fn run_app(
 
                 // Normal mode key handling
                 match key.code {
+                    KeyCode::Esc if app.panel_search(app.focus).is_some() => app.clear_search(),
+                    KeyCode::Esc if app.panel_query(app.focus).is_some() => {
+                        app.set_query(app.focus, None);
+                    }
                     KeyCode::Esc => {
                         app.clear_line_selection();
                     }
-                    KeyCode::Enter => {
-                        if app.app_mode == AppMode::Normal && !enter_request_list(&mut app) {
+                    KeyCode::Enter if app.app_mode == AppMode::Normal => {
+                        // Not a guard, or an Enter that entered the list would fall through to later arms.
+                        let entered = enter_request_list(&mut app);
+                        if !entered {
                             copy_focused_panel(terminal, &app)?;
                         }
                     }
@@ -1787,7 +3211,7 @@ async fn run_app(
                             if set_proxy_running(
                                 &mut app,
                                 desired_running,
-                                &mut runtime.proxy_server,
+                                &mut runtime.proxy_servers,
                                 &runtime.message_sender,
                                 &runtime.proxy_state,
                             )
@@ -1812,7 +3236,7 @@ async fn run_app(
                             if set_proxy_running(
                                 &mut app,
                                 desired_running,
-                                &mut runtime.proxy_server,
+                                &mut runtime.proxy_servers,
                                 &runtime.message_sender,
                                 &runtime.proxy_state,
                             )
@@ -1906,9 +3330,49 @@ async fn run_app(
                             app.goto_top_intercept_details()
                         }
                     },
+                    KeyCode::Char('/') if app.is_searchable() => app.start_search(),
                     KeyCode::Char('/') => {
                         app.start_filtering_requests();
                     }
+                    KeyCode::Char(' ')
+                        if app.app_mode == AppMode::Normal && app.is_message_list_focused() =>
+                    {
+                        app.toggle_selected_group();
+                    }
+                    KeyCode::Char(' ') if app.app_mode == AppMode::Normal => {
+                        toggle_fold_at_cursor(&mut app);
+                    }
+                    KeyCode::Char('m')
+                        if app.app_mode == AppMode::Normal && app.is_message_list_focused() =>
+                    {
+                        app.toggle_diff_mark();
+                    }
+                    KeyCode::Char('*')
+                        if app.app_mode == AppMode::Normal && app.is_message_list_focused() =>
+                    {
+                        let index = app.selected_exchange;
+                        if let Err(error) =
+                            update_bookmark(&mut app, &runtime.history, index, |bookmark| {
+                                bookmark.starred = !bookmark.starred;
+                            })
+                        {
+                            app.notice = Some(format!("Error: {}", error.message));
+                        }
+                    }
+                    KeyCode::Char('#')
+                        if app.app_mode == AppMode::Normal && app.is_message_list_focused() =>
+                    {
+                        app.start_tagging();
+                    }
+                    KeyCode::Char(character @ ('+' | '=' | '-' | '[' | ']'))
+                        if app.app_mode == AppMode::Normal && timeline_keys_active(&app) =>
+                    {
+                        handle_timeline_key(&mut app, character);
+                    }
+                    KeyCode::Char('.') if app.app_mode == AppMode::Normal => app.start_query(),
+                    KeyCode::Char('P') if app.app_mode == AppMode::Normal => {
+                        app.toggle_query_column();
+                    }
                     KeyCode::Char('v') if app.app_mode == AppMode::Normal => {
                         toggle_visual_selection(&mut app);
                     }
@@ -1940,6 +3404,11 @@ async fn run_app(
                             }
                         }
                     }
+                    KeyCode::Char(character @ ('n' | 'N'))
+                        if app.panel_search(app.focus).is_some() =>
+                    {
+                        jump_to_search_match(&mut app, terminal.size()?, character == 'n');
+                    }
                     KeyCode::Char('a')
                         if app.app_mode != AppMode::Normal && !app.pending_requests.is_empty() =>
                     {
@@ -1959,7 +3428,7 @@ async fn run_app(
                             if set_proxy_running(
                                 &mut app,
                                 desired_running,
-                                &mut runtime.proxy_server,
+                                &mut runtime.proxy_servers,
                                 &runtime.message_sender,
                                 &runtime.proxy_state,
                             )
@@ -2016,7 +3485,7 @@ async fn run_app(
                             if set_proxy_running(
                                 &mut app,
                                 desired_running,
-                                &mut runtime.proxy_server,
+                                &mut runtime.proxy_servers,
                                 &runtime.message_sender,
                                 &runtime.proxy_state,
                             )
@@ -2043,15 +3512,27 @@ async fn run_app(
         }
 
         // Check if proxy server has died unexpectedly
-        if let Some(handle) = &runtime.proxy_server {
+        if let Some(handle) = &runtime.proxy_servers.main {
             if handle.is_finished() {
-                runtime.proxy_server = None;
+                runtime.proxy_servers.main = None;
                 if app.is_running {
                     app.toggle_proxy(); // Mark as stopped
                     should_draw = true;
                 }
             }
         }
+        let stopped = runtime
+            .proxy_servers
+            .listeners
+            .iter()
+            .filter(|(_, handle)| handle.is_finished())
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        for name in stopped {
+            runtime.proxy_servers.listeners.remove(&name);
+            app.notice = Some(format!("Listener {name} stopped"));
+            should_draw = true;
+        }
 
         if runtime
             .control_server
@@ -2070,11 +3551,35 @@ async fn run_app(
     }
 }
 
+/// Records new messages and then the delivery times the proxy reported for them.
+fn record_proxy_messages(app: &mut App, runtime: &mut Runtime) -> bool {
+    // A delivery is reported after its responses, so taking deliveries first means their
+    // responses are recorded by the time they are applied.
+    let mut deliveries = Vec::new();
+    while let Ok(delivery) = runtime.delivery_receiver.try_recv() {
+        deliveries.push(delivery);
+    }
+    let received_messages = record_new_messages(app, &mut runtime.history);
+    for delivery in &deliveries {
+        app.record_delivery(delivery);
+        if let Err(error) = runtime.history.record_delivery(delivery) {
+            app.notice = Some(format!("Error: save history: {error}"));
+        }
+    }
+    received_messages || !deliveries.is_empty()
+}
+
 fn record_new_messages(app: &mut App, history: &mut HistoryStore) -> bool {
-    let messages = app.take_new_messages();
+    let mut messages = app.take_new_messages();
     if messages.is_empty() {
         return false;
     }
+    // Redacted values never reach the screen or the history database.
+    if let Ok(rules) = app.rules.lock() {
+        for message in &mut messages {
+            rules.redact(message);
+        }
+    }
 
     let active_session_id = app
         .session
@@ -2133,6 +3638,18 @@ async fn handle_overlay_key(
                 app.close_overlay();
                 copy_focused_panel(terminal, app)?;
             }
+            KeyCode::Char('m') => app.show_mcp_summary(),
+            KeyCode::Char('e') => app.show_subscription_events(),
+            KeyCode::Char('b') => app.show_batch(),
+            KeyCode::Char('w') => app.toggle_timeline(),
+            KeyCode::Char('f') => app.toggle_json_tree(),
+            KeyCode::Char('=') => app.show_marked_diff(),
+            KeyCode::Char('o') => app.show_columns(),
+            KeyCode::Char('Y') => app.show_snippets(),
+            KeyCode::Char('r') => {
+                app.close_overlay();
+                save_reports(app);
+            }
             KeyCode::Char('s') => match runtime.history.list_sessions(1000) {
                 Ok(sessions) => app.show_sessions(sessions),
                 Err(error) => {
@@ -2161,13 +3678,14 @@ async fn handle_overlay_key(
                 app.close_overlay();
                 app.start_editing_target();
             }
+            KeyCode::Char('P') => app.show_profiles(),
             KeyCode::Char('x') => {
                 app.close_overlay();
                 let desired_running = !app.is_running;
                 if set_proxy_running(
                     app,
                     desired_running,
-                    &mut runtime.proxy_server,
+                    &mut runtime.proxy_servers,
                     &runtime.message_sender,
                     &runtime.proxy_state,
                 )
@@ -2177,13 +3695,34 @@ async fn handle_overlay_key(
                 }
             }
             KeyCode::Char('q') => {
-                stop_proxy(&mut runtime.proxy_server).await;
+                stop_proxy(&mut runtime.proxy_servers).await;
                 return Ok(true);
             }
             KeyCode::Esc => app.close_overlay(),
             _ => app.close_overlay(),
         },
         Overlay::Help => app.close_overlay(),
+        Overlay::Mcp | Overlay::Subscription | Overlay::Diff => {
+            handle_scrolling_overlay_key(app, key)
+        }
+        Overlay::Columns => {
+            let before = app.columns.clone();
+            handle_columns_key(app, key);
+            if app.columns != before {
+                save_columns(app, &runtime.history);
+            }
+        }
+        Overlay::Snippets => handle_snippets_key(terminal, app, key)?,
+        Overlay::Batch => match key.code {
+            KeyCode::Char('r') => {
+                if let Some(content) = app.batch_request_json() {
+                    app.close_overlay();
+                    app.open_editor(EditorTarget::NewRequest, content);
+                }
+            }
+            KeyCode::Char('y') => copy_batch(terminal, app)?,
+            _ => handle_scrolling_overlay_key(app, key),
+        },
         Overlay::Sessions => match key.code {
             KeyCode::Up | KeyCode::Char('k') => app.select_previous_session(),
             KeyCode::Down | KeyCode::Char('j') => app.select_next_session(),
@@ -2197,7 +3736,7 @@ async fn handle_overlay_key(
                         Ok(target_changed) if target_changed && app.is_running => {
                             restart_proxy(
                                 app,
-                                &mut runtime.proxy_server,
+                                &mut runtime.proxy_servers,
                                 &runtime.message_sender,
                                 &runtime.proxy_state,
                             )
@@ -2210,6 +3749,47 @@ async fn handle_overlay_key(
                     }
                 }
             }
+            KeyCode::Char('=') => {
+                let session_id = app
+                    .sessions
+                    .get(app.selected_session)
+                    .map(|session| session.id.clone());
+                if let Some(session_id) = session_id {
+                    match diff_sessions(app, &runtime.history, &session_id, None) {
+                        Ok(comparison) => app.show_comparison(comparison),
+                        Err(error) => app.notice = Some(format!("Error: {}", error.message)),
+                    }
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
+            _ => {}
+        },
+        Overlay::Profiles => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => app.select_profile_row(-1),
+            KeyCode::Down | KeyCode::Char('j') => app.select_profile_row(1),
+            KeyCode::Enter => {
+                let name = app
+                    .config
+                    .profiles
+                    .keys()
+                    .nth(app.selected_profile)
+                    .cloned();
+                if let Some(name) = name {
+                    app.close_overlay();
+                    if let Err(error) = apply_profile(
+                        app,
+                        &runtime.history,
+                        &mut runtime.proxy_servers,
+                        &runtime.message_sender,
+                        &runtime.proxy_state,
+                        &name,
+                    )
+                    .await
+                    {
+                        app.notice = Some(format!("Error: {}", error.message));
+                    }
+                }
+            }
             KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
             _ => {}
         },
@@ -2219,6 +3799,54 @@ async fn handle_overlay_key(
     Ok(false)
 }
 
+fn handle_columns_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => app.select_column(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.select_column(1),
+        KeyCode::Char(' ') | KeyCode::Enter => app.toggle_picked_column(),
+        KeyCode::Char('<' | 'h') | KeyCode::Left => app.resize_picked_column(-2),
+        KeyCode::Char('>' | 'l') | KeyCode::Right => app.resize_picked_column(2),
+        KeyCode::Char('s') => app.sort_by_picked_column(),
+        KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
+        _ => {}
+    }
+}
+
+const COLUMNS_SETTING: &str = "columns";
+
+fn load_columns(app: &mut App, history: &HistoryStore) {
+    let layout = history.setting(COLUMNS_SETTING).and_then(|value| {
+        value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(Into::into)
+    });
+    match layout {
+        Ok(Some(layout)) => app.columns = layout,
+        Ok(None) => {}
+        Err(error) => app.notice = Some(format!("Error: load columns: {error}")),
+    }
+}
+
+fn save_columns(app: &mut App, history: &HistoryStore) {
+    let saved = serde_json::to_string(&app.columns)
+        .map_err(anyhow::Error::from)
+        .and_then(|value| history.set_setting(COLUMNS_SETTING, &value));
+    if let Err(error) = saved {
+        app.notice = Some(format!("Error: save columns: {error}"));
+    }
+}
+
+fn handle_scrolling_overlay_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_overlay(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_overlay(1),
+        KeyCode::Char('u') => app.scroll_overlay(-10),
+        KeyCode::Char('d') => app.scroll_overlay(10),
+        _ => app.close_overlay(),
+    }
+}
+
 fn is_fullscreen_key(key: &KeyEvent) -> bool {
     key.code == KeyCode::Char('z')
 }
@@ -2234,11 +3862,20 @@ fn open_new_request(app: &mut App) {
     app.open_editor(EditorTarget::NewRequest, content);
 }
 
-async fn stop_proxy(proxy_server: &mut Option<JoinHandle<()>>) {
-    if let Some(handle) = proxy_server.take() {
+async fn stop_proxy(proxy_servers: &mut ProxyServers) {
+    let handles = proxy_servers
+        .main
+        .take()
+        .into_iter()
+        .chain(proxy_servers.listeners.drain().map(|(_, handle)| handle))
+        .collect::<Vec<_>>();
+    if handles.is_empty() {
+        return;
+    }
+    for handle in handles {
         handle.abort();
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
     }
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 }
 
 fn register_change_waiter(
@@ -2292,7 +3929,7 @@ async fn handle_mouse_event(
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
     app: &mut App,
     mouse: MouseEvent,
-    proxy_server: &mut Option<JoinHandle<()>>,
+    proxy_servers: &mut ProxyServers,
     message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
     proxy_state: &ProxyState,
     history: &mut HistoryStore,
@@ -2314,7 +3951,7 @@ async fn handle_mouse_event(
                         if let Some(session_id) = session_id {
                             match select_session(app, history, &session_id) {
                                 Ok(target_changed) if target_changed && app.is_running => {
-                                    restart_proxy(app, proxy_server, message_sender, proxy_state)
+                                    restart_proxy(app, proxy_servers, message_sender, proxy_state)
                                         .await;
                                 }
                                 Ok(_) => {}
@@ -2367,7 +4004,8 @@ async fn handle_mouse_event(
         ui::MouseAction::EditFilter => app.start_filtering_requests(),
         ui::MouseAction::SetProxyRunning(should_run) => {
             app.set_focus(app::Focus::StatusHeader);
-            if set_proxy_running(app, should_run, proxy_server, message_sender, proxy_state).await {
+            if set_proxy_running(app, should_run, proxy_servers, message_sender, proxy_state).await
+            {
                 terminal.clear()?;
             }
         }
@@ -2413,6 +4051,12 @@ async fn handle_mouse_event(
             app.select_lines_from_anchor(panel, anchor, start_line, end_line, text);
         }
         ui::MouseAction::SelectAnnotation { id } => app.focus_annotation(&id),
+        ui::MouseAction::ToggleFold { panel, line } => {
+            app.set_focus(panel);
+            if let Some(fold) = ui::detail_folds(app, panel).at(line).cloned() {
+                app.set_fold(panel, &fold, !fold.folded);
+            }
+        }
         ui::MouseAction::SelectSession(_) | ui::MouseAction::CloseOverlay => {}
         ui::MouseAction::Focus(focus) => app.set_focus(focus),
     }
@@ -2465,6 +4109,15 @@ fn scroll_panel(app: &mut App, focus: app::Focus, down: bool, visible_lines: usi
         _ => return,
     };
 
+    let folds = match app.app_mode {
+        AppMode::Normal => ui::detail_folds(app, focus),
+        AppMode::Paused | AppMode::Intercepting => tree::Folds::default(),
+    };
+    let ticks = if down {
+        LINES_PER_TICK as i64
+    } else {
+        -(LINES_PER_TICK as i64)
+    };
     let (previous_scroll, current_scroll) = {
         let scroll = match (app.app_mode, focus) {
             (AppMode::Normal, app::Focus::RequestSection) => &mut app.request_details_scroll,
@@ -2475,37 +4128,29 @@ fn scroll_panel(app: &mut App, focus: app::Focus, down: bool, visible_lines: usi
             _ => return,
         };
         let previous_scroll = *scroll;
-        *scroll = if down {
-            scroll.saturating_add(LINES_PER_TICK).min(max_scroll)
-        } else {
-            scroll.saturating_sub(LINES_PER_TICK)
-        };
+        *scroll = folds.step(*scroll + 1, ticks, max_scroll + 1) - 1;
         (previous_scroll, *scroll)
     };
     if previous_scroll != current_scroll {
-        let moved = current_scroll.abs_diff(previous_scroll);
-        match (app.app_mode, focus, down) {
-            (AppMode::Normal, app::Focus::RequestSection, true) => {
-                app.request_details_cursor_line = app
-                    .request_details_cursor_line
-                    .saturating_add(moved)
-                    .min(app.get_request_details_content_lines().max(1));
-            }
-            (AppMode::Normal, app::Focus::RequestSection, false) => {
-                app.request_details_cursor_line =
-                    app.request_details_cursor_line.saturating_sub(moved).max(1);
-            }
-            (AppMode::Normal, app::Focus::ResponseSection, true) => {
-                app.response_details_cursor_line = app
-                    .response_details_cursor_line
-                    .saturating_add(moved)
-                    .min(app.get_response_details_content_lines().max(1));
-            }
-            (AppMode::Normal, app::Focus::ResponseSection, false) => {
-                app.response_details_cursor_line = app
-                    .response_details_cursor_line
-                    .saturating_sub(moved)
-                    .max(1);
+        // Folded regions count as one line, so the cursor follows by drawn lines.
+        let moved = folds
+            .rank(current_scroll + 1)
+            .abs_diff(folds.rank(previous_scroll + 1)) as i64;
+        let moved = if down { moved } else { -moved };
+        match (app.app_mode, focus) {
+            (AppMode::Normal, app::Focus::RequestSection) => {
+                app.request_details_cursor_line = folds.step(
+                    app.request_details_cursor_line,
+                    moved,
+                    app.get_request_details_content_lines(),
+                );
+            }
+            (AppMode::Normal, app::Focus::ResponseSection) => {
+                app.response_details_cursor_line = folds.step(
+                    app.response_details_cursor_line,
+                    moved,
+                    app.get_response_details_content_lines(),
+                );
             }
             _ => {}
         }
@@ -2520,10 +4165,73 @@ fn move_focused_detail_cursor(app: &mut App, lines: i64, area: ratatui::layout::
         return;
     };
     let visible_lines = ui::panel_visible_lines(area, app, panel);
-    app.move_detail_cursor(panel, lines, total_lines, visible_lines);
+    let folds = ui::detail_folds(app, panel);
+    app.move_detail_cursor(panel, lines, total_lines, visible_lines, &folds);
     extend_visual_selection(app, panel);
 }
 
+fn search_incrementally(app: &mut App, area: ratatui::layout::Rect) {
+    app.update_search();
+    jump_to_search_match(app, area, true);
+}
+
+/// Moves to the next or previous match of the focused panel's search. In normal mode the
+/// match's line is selected, so it can be annotated straight away.
+fn jump_to_search_match(app: &mut App, area: ratatui::layout::Rect, forward: bool) {
+    let panel = app.focus;
+    let Some((search, lines)) = app.panel_search(panel).zip(ui::search_lines(app, panel)) else {
+        return;
+    };
+    let matches = search.matches(&lines);
+    let normal = app.app_mode == AppMode::Normal;
+    let cursor_line = if normal {
+        app.detail_cursor_line(panel).unwrap_or(1)
+    } else {
+        app.intercept_details_scroll + 1
+    };
+    // Step on from the last match while the cursor is still there, else from the cursor.
+    let current = search
+        .current
+        .filter(|(line, _)| !normal || *line == cursor_line);
+    let next = match current {
+        Some(current) => search::step(&matches, current, forward, false),
+        None => search::step(&matches, (cursor_line, 0), forward, true),
+    };
+    let found = next.map(|index| matches[index]);
+    if let Some(search) = &mut app.search {
+        search.current = found.map(|found| (found.line, found.start));
+    }
+    app.mark_changed();
+    let Some(found) = found else {
+        return;
+    };
+
+    if !normal {
+        let visible_lines = ui::panel_visible_lines(area, app, panel);
+        app.intercept_details_scroll = (found.line - 1).saturating_sub(visible_lines / 2);
+        return;
+    }
+    let folds = ui::detail_folds(app, panel);
+    app.unfold_lines(panel, &folds, found.line, found.line);
+    if let Some(text) = ui::detail_line_text(app, panel, found.line, found.line) {
+        app.select_lines(panel, found.line, found.line, text);
+        app.start_visual_selection();
+    }
+    let total_lines = lines.len();
+    center_detail_range(app, area, panel, found.line, found.line, total_lines, None);
+}
+
+/// Folds or unfolds the innermost object or array around the detail cursor.
+fn toggle_fold_at_cursor(app: &mut App) {
+    let panel = app.focus;
+    let Some(cursor_line) = app.detail_cursor_line(panel) else {
+        return;
+    };
+    if let Some(fold) = ui::detail_folds(app, panel).enclosing(cursor_line).cloned() {
+        app.set_fold(panel, &fold, !fold.folded);
+    }
+}
+
 fn extend_visual_selection(app: &mut App, panel: app::Focus) {
     if !app.visual_selection_active {
         return;
@@ -2571,34 +4279,98 @@ fn toggle_visual_selection(app: &mut App) {
     app.start_visual_selection();
 }
 
+/// Restarts the main listener after its target or port changed.
 async fn restart_proxy(
     app: &App,
-    proxy_server: &mut Option<JoinHandle<()>>,
+    proxy_servers: &mut ProxyServers,
+    message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
+    proxy_state: &ProxyState,
+) {
+    if let Some(handle) = proxy_servers.main.take() {
+        handle.abort();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    proxy_servers.main = Some(spawn_proxy(app, None, message_sender, proxy_state));
+}
+
+/// Restarts one named listener, or stops it when the config no longer has it.
+async fn restart_listener(
+    app: &App,
+    proxy_servers: &mut ProxyServers,
+    name: &str,
     message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
     proxy_state: &ProxyState,
 ) {
-    if let Some(handle) = proxy_server.take() {
+    if let Some(handle) = proxy_servers.listeners.remove(name) {
         handle.abort();
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
     }
+    if app.proxy_config.listener(name).is_some() {
+        let handle = spawn_proxy(app, Some(name), message_sender, proxy_state);
+        proxy_servers.listeners.insert(name.to_string(), handle);
+    }
+}
+
+/// Replaces every running listener with the ones in the config.
+async fn restart_all_proxies(
+    app: &App,
+    proxy_servers: &mut ProxyServers,
+    message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
+    proxy_state: &ProxyState,
+) {
+    stop_proxy(proxy_servers).await;
+    start_proxies(app, proxy_servers, message_sender, proxy_state);
+}
+
+fn start_proxies(
+    app: &App,
+    proxy_servers: &mut ProxyServers,
+    message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
+    proxy_state: &ProxyState,
+) {
+    proxy_servers.main = Some(spawn_proxy(app, None, message_sender, proxy_state));
+    for listener in &app.proxy_config.listeners {
+        let handle = spawn_proxy(app, Some(&listener.name), message_sender, proxy_state);
+        proxy_servers
+            .listeners
+            .insert(listener.name.clone(), handle);
+    }
+}
 
+/// Runs the main listener, or the named one, until aborted.
+fn spawn_proxy(
+    app: &App,
+    listener: Option<&str>,
+    message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
+    proxy_state: &ProxyState,
+) -> JoinHandle<()> {
     let config = app.proxy_config.clone();
+    let listener = listener.map(str::to_string);
     let sender = message_sender.clone();
     let state = proxy_state.clone();
-    *proxy_server = Some(tokio::spawn(async move {
-        match ProxyServer::from_config(&config, sender) {
+    tokio::spawn(async move {
+        let server = match &listener {
+            Some(name) => match config.listener(name) {
+                Some(listener) => ProxyServer::from_listener(listener, sender),
+                None => return,
+            },
+            None => ProxyServer::from_config(&config, sender),
+        };
+        match server {
             Ok(server) => {
-                let _ = server.with_state(state).start().await;
+                if let Err(error) = server.with_state(state).start().await {
+                    eprintln!("Proxy server error: {error}");
+                }
             }
             Err(error) => eprintln!("Proxy server error: {error}"),
         }
-    }));
+    })
 }
 
 async fn set_proxy_running(
     app: &mut App,
     should_run: bool,
-    proxy_server: &mut Option<JoinHandle<()>>,
+    proxy_servers: &mut ProxyServers,
     message_sender: &mpsc::UnboundedSender<app::JsonRpcMessage>,
     proxy_state: &ProxyState,
 ) -> bool {
@@ -2608,28 +4380,9 @@ async fn set_proxy_running(
 
     if should_run {
         app.toggle_proxy();
-
-        let config = app.proxy_config.clone();
-        let sender_clone = message_sender.clone();
-        let state_clone = proxy_state.clone();
-
-        *proxy_server = Some(tokio::spawn(async move {
-            match ProxyServer::from_config(&config, sender_clone) {
-                Ok(server) => {
-                    if let Err(error) = server.with_state(state_clone).start().await {
-                        eprintln!("Proxy server error: {error}");
-                    }
-                }
-                Err(error) => {
-                    eprintln!("Proxy server error: {error}");
-                }
-            }
-        }));
+        start_proxies(app, proxy_servers, message_sender, proxy_state);
     } else {
-        if let Some(handle) = proxy_server.take() {
-            handle.abort();
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-        }
+        stop_proxy(proxy_servers).await;
         app.toggle_proxy();
     }
 
@@ -2662,6 +4415,16 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn port_is_left_unset_for_the_profile_to_fill() {
+        let cli = Cli::try_parse_from(["jsonrpc-debugger", "--profile", "mainnet"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("mainnet"));
+        assert_eq!(cli.port, None);
+
+        let cli = Cli::try_parse_from(["jsonrpc-debugger", "-p", "9000"]).unwrap();
+        assert_eq!(cli.port, Some(9000));
+    }
+
     #[test]
     fn parses_transparent_wrap_command() {
         let cli = Cli::try_parse_from([
@@ -2711,6 +4474,10 @@ mod tests {
             direction,
             transport: app::TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         }
     }
 
@@ -2722,7 +4489,7 @@ mod tests {
         let session = history
             .create_session(Some("batch"), "http://node")
             .unwrap();
-        app.activate_session(session.clone(), Vec::new(), Vec::new());
+        app.activate_session(session.clone(), Vec::new(), Vec::new(), HashMap::new());
 
         for message in [
             rpc_message(1, app::MessageDirection::Request),
@@ -2750,6 +4517,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn diffs_reach_exchanges_and_sessions_outside_the_active_one() {
+        let mut history = HistoryStore::in_memory().unwrap();
+        let earlier = history.create_session(Some("earlier"), "").unwrap();
+        let mut changed = rpc_message(2, app::MessageDirection::Response);
+        changed.result = Some(serde_json::json!({"balance": "0x1"}));
+        history
+            .record_messages(
+                &earlier.id,
+                &[
+                    rpc_message(1, app::MessageDirection::Request),
+                    rpc_message(1, app::MessageDirection::Response),
+                    rpc_message(2, app::MessageDirection::Request),
+                    changed,
+                ],
+            )
+            .unwrap();
+
+        let mut app = App::new();
+        let current = history.create_session(Some("current"), "").unwrap();
+        app.activate_session(current, Vec::new(), Vec::new(), HashMap::new());
+        let mut changed = rpc_message(2, app::MessageDirection::Response);
+        changed.result = Some(serde_json::json!({"balance": "0x2"}));
+        for message in [
+            rpc_message(2, app::MessageDirection::Request),
+            changed,
+            rpc_message(3, app::MessageDirection::Request),
+        ] {
+            app.add_message(message);
+        }
+
+        let comparison = diff_exchanges(
+            &app,
+            &history,
+            &ExchangeRef {
+                session_id: Some(earlier.id.clone()),
+                index: 1,
+            },
+            &ExchangeRef {
+                session_id: None,
+                index: 0,
+            },
+        )
+        .unwrap();
+        let value = control::comparison(&comparison);
+        assert_eq!(value["left"], "earlier #1");
+        assert_eq!(value["right"], "current #0");
+        assert_eq!(
+            value["differences"],
+            serde_json::json!([
+                {"path": "result.balance", "kind": "changed", "before": "0x1", "after": "0x2"}
+            ])
+        );
+
+        let value = control::comparison(&diff_sessions(&app, &history, &earlier.id, None).unwrap());
+        assert_eq!(
+            value["summary"],
+            serde_json::json!({"identical": 0, "changed": 1, "onlyLeft": 1, "onlyRight": 1})
+        );
+        assert_eq!(value["pairs"][0]["method"], "method_1");
+        assert_eq!(value["pairs"][0]["right"], serde_json::Value::Null);
+    }
+
     #[test]
     fn fullscreen_prefix_key_is_z() {
         assert!(is_fullscreen_key(&key(KeyCode::Char('z'))));
@@ -2859,6 +4689,10 @@ mod tests {
             direction: app::MessageDirection::Request,
             transport: app::TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         scroll_panel(&mut app, app::Focus::RequestSection, true, 2);
         assert_eq!(app.request_details_scroll, 3);
@@ -2881,6 +4715,10 @@ mod tests {
                 direction: app::MessageDirection::Request,
                 transport: app::TransportType::Http,
                 headers: None,
+                connection: None,
+                batch: None,
+                timing: None,
+                seq: None,
             });
         }
         app.select_exchange(1);
@@ -2905,6 +4743,10 @@ mod tests {
                 direction: app::MessageDirection::Request,
                 transport: app::TransportType::Http,
                 headers: None,
+                connection: None,
+                batch: None,
+                timing: None,
+                seq: None,
             });
         }
         app.selected_exchange = 0;
@@ -2937,6 +4779,10 @@ mod tests {
             direction: app::MessageDirection::Request,
             transport: app::TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         app.focus = app::Focus::RequestSection;
         let area = ratatui::layout::Rect::new(0, 0, 120, 24);
@@ -2968,6 +4814,10 @@ mod tests {
             direction: app::MessageDirection::Request,
             transport: app::TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         app.focus = app::Focus::RequestSection;
         let area = ratatui::layout::Rect::new(0, 0, 120, 24);
@@ -2985,6 +4835,60 @@ mod tests {
         assert!(app.line_selection.is_none());
     }
 
+    #[test]
+    fn search_jumps_between_matches_and_selects_each_for_annotation() {
+        let mut app = App::new();
+        app.add_message(app::JsonRpcMessage {
+            id: Some(serde_json::json!(1)),
+            method: Some("eth_call".to_string()),
+            params: Some(serde_json::json!(["0xab", {"to": "0xAB"}, "latest"])),
+            result: None,
+            error: None,
+            timestamp: std::time::SystemTime::now(),
+            direction: app::MessageDirection::Request,
+            transport: app::TransportType::Http,
+            headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
+        });
+        app.focus = app::Focus::RequestSection;
+        let area = ratatui::layout::Rect::new(0, 0, 120, 24);
+        let lines = ui::search_lines(&app, app.focus).unwrap();
+        let matching = (1..=lines.len())
+            .filter(|line| lines[line - 1].to_lowercase().contains("0xab"))
+            .collect::<Vec<_>>();
+        assert_eq!(matching.len(), 2);
+
+        app.start_search();
+        for character in "0xa".chars() {
+            app.handle_input_char(character);
+            search_incrementally(&mut app, area);
+        }
+        app.finish_search();
+        assert_eq!(app.input_mode, app::InputMode::Normal);
+        assert_eq!(app.request_details_cursor_line, matching[0]);
+        let selection = app.line_selection.as_ref().unwrap();
+        assert_eq!(selection.start_line, matching[0]);
+        assert!(app.visual_selection_active);
+
+        jump_to_search_match(&mut app, area, true);
+        assert_eq!(app.request_details_cursor_line, matching[1]);
+        jump_to_search_match(&mut app, area, true);
+        assert_eq!(app.request_details_cursor_line, matching[0]);
+        jump_to_search_match(&mut app, area, false);
+        assert_eq!(app.request_details_cursor_line, matching[1]);
+
+        app.start_search();
+        app.handle_input_char('X');
+        search_incrementally(&mut app, area);
+        assert!(app.search.as_ref().unwrap().current.is_none());
+        app.cancel_search();
+        assert!(app.search.is_none());
+        assert_eq!(app.request_details_cursor_line, matching[1]);
+    }
+
     #[test]
     fn visual_selection_annotation_uses_the_persistent_annotation_path() {
         let mut history = HistoryStore::in_memory().unwrap();
@@ -3004,6 +4908,10 @@ mod tests {
             direction: app::MessageDirection::Request,
             transport: app::TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         app.focus = app::Focus::RequestSection;
         let text = ui::detail_line_text(&app, app.focus, 2, 2).unwrap();
@@ -3037,6 +4945,10 @@ mod tests {
             direction: app::MessageDirection::Request,
             transport: app::TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         let text = ui::detail_line_text(&app, app::Focus::RequestSection, 2, 3).unwrap();
         app.reveal_lines(app::Focus::RequestSection, 2, 3, text.clone());
@@ -3151,6 +5063,10 @@ mod tests {
             direction: app::MessageDirection::Request,
             transport: app::TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         app.add_message(app::JsonRpcMessage {
             id: Some(serde_json::json!(2)),
@@ -3162,16 +5078,22 @@ mod tests {
             direction: app::MessageDirection::Request,
             transport: app::TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         let (message_sender, _) = mpsc::unbounded_channel();
         let (pending_sender, _) = mpsc::unbounded_channel();
         let proxy_state = ProxyState {
             app_mode: Arc::new(Mutex::new(AppMode::Normal)),
             pending_sender,
+            rules: Arc::default(),
+            deliveries: mpsc::unbounded_channel().0,
         };
         let (notice_sender, _) = mpsc::unbounded_channel();
         let (reply, result) = tokio::sync::oneshot::channel();
-        let mut proxy_server = None;
+        let mut proxy_servers = ProxyServers::default();
         let mut history = HistoryStore::in_memory().unwrap();
         app.session = Some(
             history
@@ -3190,7 +5112,7 @@ mod tests {
             },
             ControlContext {
                 terminal_area,
-                proxy_server: &mut proxy_server,
+                proxy_servers: &mut proxy_servers,
                 message_sender: &message_sender,
                 proxy_state: &proxy_state,
                 request_result_sender: &notice_sender,
@@ -3215,7 +5137,7 @@ mod tests {
             },
             ControlContext {
                 terminal_area,
-                proxy_server: &mut proxy_server,
+                proxy_servers: &mut proxy_servers,
                 message_sender: &message_sender,
                 proxy_state: &proxy_state,
                 request_result_sender: &notice_sender,
@@ -3245,7 +5167,7 @@ mod tests {
             },
             ControlContext {
                 terminal_area,
-                proxy_server: &mut proxy_server,
+                proxy_servers: &mut proxy_servers,
                 message_sender: &message_sender,
                 proxy_state: &proxy_state,
                 request_result_sender: &notice_sender,
@@ -3280,7 +5202,7 @@ mod tests {
             },
             ControlContext {
                 terminal_area,
-                proxy_server: &mut proxy_server,
+                proxy_servers: &mut proxy_servers,
                 message_sender: &message_sender,
                 proxy_state: &proxy_state,
                 request_result_sender: &notice_sender,
@@ -3302,7 +5224,7 @@ mod tests {
             },
             ControlContext {
                 terminal_area,
-                proxy_server: &mut proxy_server,
+                proxy_servers: &mut proxy_servers,
                 message_sender: &message_sender,
                 proxy_state: &proxy_state,
                 request_result_sender: &notice_sender,
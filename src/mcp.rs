@@ -0,0 +1,426 @@
+use crate::app::JsonRpcExchange;
+use serde::Serialize;
+use serde_json::Value;
+
+/// What an MCP session has advertised so far: the `initialize` handshake and the
+/// catalogs returned by the `*/list` methods.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub protocol_version: Option<String>,
+    pub client_info: Option<Value>,
+    pub server_info: Option<Value>,
+    pub client_capabilities: Option<Value>,
+    pub server_capabilities: Option<Value>,
+    pub instructions: Option<String>,
+    pub tools: Vec<Value>,
+    pub resources: Vec<Value>,
+    pub resource_templates: Vec<Value>,
+    pub prompts: Vec<Value>,
+}
+
+impl Summary {
+    pub fn tool(&self, name: &str) -> Option<&Value> {
+        self.tools
+            .iter()
+            .find(|tool| tool.get("name").and_then(Value::as_str) == Some(name))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Progress,
+    Cancellation,
+}
+
+impl Relation {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Progress => "Progress for",
+            Self::Cancellation => "Cancels",
+        }
+    }
+}
+
+/// Builds the MCP summary, or `None` when the exchanges contain no MCP handshake or catalog.
+pub fn summary(exchanges: &[JsonRpcExchange]) -> Option<Summary> {
+    let mut summary = Summary::default();
+    let mut found = false;
+    for exchange in exchanges {
+        let result = exchange
+            .response
+            .as_ref()
+            .and_then(|response| response.result.as_ref());
+        match exchange.method.as_deref() {
+            Some("initialize") => {
+                let params = exchange
+                    .request
+                    .as_ref()
+                    .and_then(|request| request.params.as_ref());
+                let Some(version) = params
+                    .and_then(|params| params.get("protocolVersion"))
+                    .or_else(|| result.and_then(|result| result.get("protocolVersion")))
+                else {
+                    continue;
+                };
+                found = true;
+                summary.protocol_version = result
+                    .and_then(|result| result.get("protocolVersion"))
+                    .unwrap_or(version)
+                    .as_str()
+                    .map(str::to_string);
+                summary.client_info = params.and_then(|params| params.get("clientInfo")).cloned();
+                summary.client_capabilities = params
+                    .and_then(|params| params.get("capabilities"))
+                    .cloned();
+                summary.server_info = result.and_then(|result| result.get("serverInfo")).cloned();
+                summary.server_capabilities = result
+                    .and_then(|result| result.get("capabilities"))
+                    .cloned();
+                summary.instructions = result
+                    .and_then(|result| result.get("instructions"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+            }
+            Some(method) => {
+                let (catalog, key, name) = match method {
+                    "tools/list" => (&mut summary.tools, "tools", "name"),
+                    "resources/list" => (&mut summary.resources, "resources", "uri"),
+                    "resources/templates/list" => (
+                        &mut summary.resource_templates,
+                        "resourceTemplates",
+                        "uriTemplate",
+                    ),
+                    "prompts/list" => (&mut summary.prompts, "prompts", "name"),
+                    _ => continue,
+                };
+                let Some(entries) = result
+                    .and_then(|result| result.get(key))
+                    .and_then(Value::as_array)
+                else {
+                    continue;
+                };
+                found = true;
+                // A request without a cursor starts a fresh listing; paged
+                // requests extend it.
+                let paged = exchange
+                    .request
+                    .as_ref()
+                    .and_then(|request| request.params.as_ref())
+                    .and_then(|params| params.get("cursor"))
+                    .is_some();
+                if !paged {
+                    catalog.clear();
+                }
+                for entry in entries {
+                    let entry_name = entry.get(name);
+                    catalog.retain(|existing| existing.get(name) != entry_name);
+                    catalog.push(entry.clone());
+                }
+            }
+            None => {}
+        }
+    }
+
+    found.then_some(summary)
+}
+
+/// A short description of what an MCP request targets, e.g. the tool name of `tools/call`.
+pub fn target(exchange: &JsonRpcExchange) -> Option<&str> {
+    let params = exchange.request.as_ref()?.params.as_ref()?;
+    let key = match exchange.method.as_deref()? {
+        "tools/call" | "prompts/get" => "name",
+        "resources/read" | "resources/subscribe" | "resources/unsubscribe" => "uri",
+        _ => return None,
+    };
+    params.get(key)?.as_str()
+}
+
+/// The method label shown in request lists.
+pub fn label(exchange: &JsonRpcExchange) -> Option<String> {
+    let method = exchange.method.as_deref()?;
+    Some(match target(exchange) {
+        Some(target) => format!("{method} · {target}"),
+        None => method.to_string(),
+    })
+}
+
+/// Finds the request a progress or cancellation notification refers to.
+pub fn related_request(exchanges: &[JsonRpcExchange], index: usize) -> Option<(Relation, usize)> {
+    let exchange = exchanges.get(index)?;
+    let params = exchange.request.as_ref()?.params.as_ref()?;
+    let (relation, key) = match exchange.method.as_deref()? {
+        "notifications/progress" => (Relation::Progress, params.get("progressToken")?),
+        "notifications/cancelled" => (Relation::Cancellation, params.get("requestId")?),
+        _ => return None,
+    };
+
+    exchanges[..index]
+        .iter()
+        .rposition(|candidate| {
+            candidate.request.is_some()
+                && match relation {
+                    Relation::Progress => progress_token(candidate) == Some(key),
+                    Relation::Cancellation => {
+                        !candidate.is_notification() && candidate.id.as_ref() == Some(key)
+                    }
+                }
+        })
+        .map(|position| (relation, position))
+}
+
+/// Counts progress notifications that refer to the request at `index`.
+pub fn progress_updates(exchanges: &[JsonRpcExchange], index: usize) -> usize {
+    let Some(token) = exchanges.get(index).and_then(progress_token) else {
+        return 0;
+    };
+    exchanges[index + 1..]
+        .iter()
+        .filter(|exchange| {
+            exchange.method.as_deref() == Some("notifications/progress")
+                && exchange
+                    .request
+                    .as_ref()
+                    .and_then(|request| request.params.as_ref())
+                    .and_then(|params| params.get("progressToken"))
+                    == Some(token)
+        })
+        .count()
+}
+
+fn progress_token(exchange: &JsonRpcExchange) -> Option<&Value> {
+    exchange
+        .request
+        .as_ref()?
+        .params
+        .as_ref()?
+        .get("_meta")?
+        .get("progressToken")
+}
+
+/// Checks `tools/call` arguments against the tool's advertised `inputSchema`.
+///
+/// Returns `None` when the exchange is not a tool call or the tool was never listed.
+pub fn argument_issues(summary: &Summary, exchange: &JsonRpcExchange) -> Option<Vec<String>> {
+    if exchange.method.as_deref() != Some("tools/call") {
+        return None;
+    }
+    let schema = summary.tool(target(exchange)?)?.get("inputSchema")?;
+    let arguments = exchange
+        .request
+        .as_ref()
+        .and_then(|request| request.params.as_ref())
+        .and_then(|params| params.get("arguments"))
+        .cloned()
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    let mut issues = Vec::new();
+    check_schema(schema, &arguments, "arguments", &mut issues);
+    Some(issues)
+}
+
+// Covers the JSON Schema keywords MCP servers use for tool inputs. Unknown keywords are
+// ignored rather than reported.
+fn check_schema(schema: &Value, value: &Value, path: &str, issues: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        let types = expected
+            .as_array()
+            .map(|types| types.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .or_else(|| expected.as_str().map(|expected| vec![expected]))
+            .unwrap_or_default();
+        if !types.is_empty() && !types.iter().any(|expected| has_type(value, expected)) {
+            issues.push(format!(
+                "{path}: expected {}, got {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            issues.push(format!("{path}: {value} is not one of the allowed values"));
+        }
+    }
+
+    if let Value::Object(object) = value {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    issues.push(format!("{path}.{name}: required property is missing"));
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (name, property) in object {
+            let property_path = format!("{path}.{name}");
+            match properties.and_then(|properties| properties.get(name)) {
+                Some(property_schema) => {
+                    check_schema(property_schema, property, &property_path, issues)
+                }
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        issues.push(format!("{property_path}: property is not allowed"))
+                    }
+                    Some(additional @ Value::Object(_)) => {
+                        check_schema(additional, property, &property_path, issues)
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            check_schema(item_schema, item, &format!("{path}[{index}]"), issues);
+        }
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "integer" => value.as_f64().is_some_and(|number| number.fract() == 0.0),
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{testing, App, Framing, TransportType};
+    use serde_json::json;
+
+    fn record(app: &mut App, request: Value, response: Option<Value>) {
+        let transport = TransportType::Stdio(Framing::JsonLines);
+        for body in std::iter::once(request).chain(response) {
+            testing::record(app, &body, transport, 0);
+        }
+    }
+
+    fn session() -> App {
+        let mut app = App::new();
+        record(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {"sampling": {}},
+                "clientInfo": {"name": "inspector", "version": "1.0"}
+            }}),
+            Some(json!({"jsonrpc": "2.0", "id": 0, "result": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {"tools": {"listChanged": true}},
+                "serverInfo": {"name": "files", "version": "0.1"}
+            }})),
+        );
+        record(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+            Some(json!({"jsonrpc": "2.0", "id": 1, "result": {"tools": [{
+                "name": "read_file",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string"},
+                        "limit": {"type": "integer"}
+                    },
+                    "required": ["path"],
+                    "additionalProperties": false
+                }
+            }]}})),
+        );
+        app
+    }
+
+    #[test]
+    fn summarizes_the_handshake_and_catalogs() {
+        let app = session();
+        let summary = summary(&app.exchanges).unwrap();
+
+        assert_eq!(summary.protocol_version.as_deref(), Some("2025-06-18"));
+        assert_eq!(summary.server_info.unwrap()["name"], "files");
+        assert_eq!(
+            summary.client_capabilities.unwrap(),
+            json!({"sampling": {}})
+        );
+        assert_eq!(summary.tools.len(), 1);
+        assert!(summary.prompts.is_empty());
+        assert!(super::summary(&App::new().exchanges).is_none());
+    }
+
+    #[test]
+    fn tool_calls_are_labelled_and_checked_against_input_schema() {
+        let mut app = session();
+        record(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {
+                "name": "read_file",
+                "arguments": {"limit": 1.5, "mode": "fast"}
+            }}),
+            None,
+        );
+        let summary = summary(&app.exchanges).unwrap();
+
+        assert_eq!(
+            label(&app.exchanges[2]).as_deref(),
+            Some("tools/call · read_file")
+        );
+        assert_eq!(
+            argument_issues(&summary, &app.exchanges[2]).unwrap(),
+            vec![
+                "arguments.path: required property is missing",
+                "arguments.limit: expected integer, got number",
+                "arguments.mode: property is not allowed",
+            ]
+        );
+        assert!(argument_issues(&summary, &app.exchanges[1]).is_none());
+    }
+
+    #[test]
+    fn progress_and_cancellation_link_to_their_requests() {
+        let mut app = session();
+        record(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {
+                "name": "read_file",
+                "arguments": {"path": "/tmp/a"},
+                "_meta": {"progressToken": "t1"}
+            }}),
+            None,
+        );
+        record(
+            &mut app,
+            json!({"jsonrpc": "2.0", "method": "notifications/progress", "params": {
+                "progressToken": "t1", "progress": 1
+            }}),
+            None,
+        );
+        record(
+            &mut app,
+            json!({"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {
+                "requestId": 2
+            }}),
+            None,
+        );
+
+        assert_eq!(
+            related_request(&app.exchanges, 3),
+            Some((Relation::Progress, 2))
+        );
+        assert_eq!(
+            related_request(&app.exchanges, 4),
+            Some((Relation::Cancellation, 2))
+        );
+        assert_eq!(progress_updates(&app.exchanges, 2), 1);
+    }
+}
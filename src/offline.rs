@@ -0,0 +1,244 @@
+use crate::app::DEFAULT_REQUEST_TIMEOUT;
+use crate::columns::Layout;
+use crate::control::{self, Session, SessionExchange};
+use crate::har;
+use crate::history::HistoryStore;
+use anyhow::{anyhow, bail, Result};
+use regex::Regex;
+use serde_json::{json, Value};
+
+/// How `sessions export` writes a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The portable session of `debugger.exportSession`.
+    Json,
+    Har,
+    /// One portable exchange per line.
+    Ndjson,
+}
+
+pub fn list(history: &HistoryStore, limit: usize) -> Result<Value> {
+    Ok(control::sessions(history.list_sessions(limit)?))
+}
+
+/// A session with its exchanges and annotations, shaped like `debugger.getHistory`.
+pub fn show(history: &HistoryStore, id: &str, limit: usize) -> Result<Value> {
+    let session = history
+        .session(id)?
+        .ok_or_else(|| anyhow!("session not found: {id}"))?;
+    let exchanges = history.history(id, limit, None)?;
+    let bookmarks = history.bookmarks(id)?;
+    Ok(json!({
+        "session": session,
+        "exchanges": control::stored_history(
+            exchanges,
+            &bookmarks,
+            Some(DEFAULT_REQUEST_TIMEOUT),
+            &Layout::default(),
+        ),
+        "annotations": history
+            .annotations(id)?
+            .iter()
+            .map(control::annotation)
+            .collect::<Vec<_>>(),
+    }))
+}
+
+pub fn export(history: &HistoryStore, id: &str, format: Format) -> Result<String> {
+    let session = history.export_session(id)?;
+    Ok(match format {
+        Format::Json => serde_json::to_string_pretty(&session)?,
+        Format::Har => serde_json::to_string_pretty(&har::export(&session))?,
+        Format::Ndjson => session
+            .exchanges
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<serde_json::Result<Vec<_>>>()?
+            .join("\n"),
+    })
+}
+
+/// Saves a portable session, HAR log, or NDJSON export as a new session. `target` is used
+/// when the file does not name one.
+pub fn import(history: &mut HistoryStore, text: &str, name: &str, target: &str) -> Result<Value> {
+    let (format, session, skipped) = match serde_json::from_str::<Value>(text) {
+        Ok(har) if har.get("log").is_some() => {
+            let import = har::import(&har).map_err(|error| anyhow!(error))?;
+            ("har", import.session, import.skipped)
+        }
+        Ok(session) if session.get("exchanges").is_some() => (
+            "json",
+            serde_json::from_value::<Session>(session)
+                .map_err(|error| anyhow!("invalid session: {error}"))?,
+            0,
+        ),
+        // A file of one exchange is both JSON and NDJSON.
+        _ => {
+            let exchanges = text
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(number, line)| {
+                    serde_json::from_str::<SessionExchange>(line)
+                        .map_err(|error| anyhow!("line {}: invalid exchange: {error}", number + 1))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let session = Session {
+                schema_version: 1,
+                exported_at_ms: 0,
+                target: target.to_string(),
+                exchanges,
+            };
+            ("ndjson", session, 0)
+        }
+    };
+    let target = if session.target.is_empty() {
+        target.to_string()
+    } else {
+        session.target.clone()
+    };
+    let bookmarks = control::session_bookmarks(&session);
+    let exchanges = control::replay_session(session).map_err(|error| anyhow!(error.message))?;
+
+    let summary = history.create_session(Some(name), &target)?;
+    history.append_exchanges(&summary.id, &exchanges)?;
+    for (index, bookmark) in &bookmarks {
+        history.set_bookmark(&summary.id, *index, bookmark)?;
+    }
+    Ok(json!({
+        "session": history.session(&summary.id)?,
+        "format": format,
+        "imported": exchanges.len(),
+        "skipped": skipped,
+    }))
+}
+
+pub fn rename(history: &HistoryStore, id: &str, name: &str) -> Result<Value> {
+    if !history.rename_session(id, name)? {
+        bail!("session not found: {id}");
+    }
+    Ok(serde_json::to_value(history.session(id)?)?)
+}
+
+pub fn delete(history: &HistoryStore, id: &str) -> Result<Value> {
+    let session = history
+        .session(id)?
+        .ok_or_else(|| anyhow!("session not found: {id}"))?;
+    history.delete_session(id)?;
+    Ok(json!({ "deleted": session }))
+}
+
+/// Exchanges whose pretty-printed request or response has a line matching `pattern`, newest
+/// session first, with the matching lines numbered from the top of each body.
+pub fn grep(
+    history: &HistoryStore,
+    pattern: &Regex,
+    session_id: Option<&str>,
+    limit: usize,
+) -> Result<Value> {
+    let sessions = match session_id {
+        Some(id) => vec![history
+            .session(id)?
+            .ok_or_else(|| anyhow!("session not found: {id}"))?],
+        None => history.list_sessions(usize::MAX)?,
+    };
+    let mut results = Vec::new();
+    for session in sessions {
+        let exported = history.export_session(&session.id)?;
+        for (index, exchange) in exported.exchanges.iter().enumerate() {
+            if results.len() >= limit {
+                return Ok(Value::Array(results));
+            }
+            let matches = [
+                ("request", &exchange.request),
+                ("response", &exchange.response),
+            ]
+            .into_iter()
+            .filter_map(|(panel, message)| Some((panel, message.as_ref()?)))
+            .flat_map(|(panel, message)| {
+                serde_json::to_string_pretty(&message.body)
+                        .unwrap_or_default()
+                        .lines()
+                        .enumerate()
+                        .filter(|(_, line)| pattern.is_match(line))
+                        .map(|(number, line)| {
+                            json!({ "panel": panel, "line": number + 1, "text": line.trim() })
+                        })
+                        .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+            if matches.is_empty() {
+                continue;
+            }
+            let body = exchange
+                .request
+                .as_ref()
+                .or(exchange.response.as_ref())
+                .map(|message| &message.body);
+            results.push(json!({
+                "sessionId": session.id,
+                "sessionName": session.name,
+                "index": index,
+                "method": body.and_then(|body| body.get("method")),
+                "id": body.and_then(|body| body.get("id")),
+                "matches": matches,
+            }));
+        }
+    }
+    Ok(Value::Array(results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> (HistoryStore, String) {
+        let mut history = HistoryStore::in_memory().unwrap();
+        let text = [
+            r#"{"transport":"http","request":{"body":{"jsonrpc":"2.0","id":1,"method":"eth_call","params":[{"to":"0xabc"}]},"timestampMs":1000},"response":{"body":{"jsonrpc":"2.0","id":1,"result":"0x"},"timestampMs":1040},"starred":true}"#,
+            r#"{"transport":"http","request":{"body":{"jsonrpc":"2.0","id":2,"method":"eth_chainId"},"timestampMs":2000}}"#,
+        ]
+        .join("\n");
+        let imported = import(&mut history, &text, "capture", "https://rpc.example/").unwrap();
+        assert_eq!(imported["format"], "ndjson");
+        assert_eq!(imported["imported"], 2);
+        let id = imported["session"]["id"].as_str().unwrap().to_string();
+        (history, id)
+    }
+
+    #[test]
+    fn exports_round_trip_through_import() {
+        let (mut history, id) = store();
+        for (format, target) in [
+            (Format::Json, "https://rpc.example/"),
+            (Format::Har, "https://rpc.example/"),
+            (Format::Ndjson, "fallback"),
+        ] {
+            let text = export(&history, &id, format).unwrap();
+            let imported = import(&mut history, &text, "copy", "fallback").unwrap();
+            assert_eq!(imported["imported"], 2, "{format:?}");
+            assert_eq!(imported["session"]["target"], target, "{format:?}");
+        }
+        let shown = show(&history, &id, 10).unwrap();
+        assert_eq!(shown["session"]["name"], "capture");
+        assert_eq!(shown["exchanges"].as_array().unwrap().len(), 2);
+        assert_eq!(shown["exchanges"][0]["starred"], true);
+    }
+
+    #[test]
+    fn grep_finds_lines_across_sessions() {
+        let (history, id) = store();
+        let results = grep(&history, &Regex::new("0xabc").unwrap(), None, 10).unwrap();
+
+        assert_eq!(results.as_array().unwrap().len(), 1);
+        assert_eq!(results[0]["sessionId"], id.as_str());
+        assert_eq!(results[0]["method"], "eth_call");
+        assert_eq!(results[0]["matches"][0]["panel"], "request");
+        assert_eq!(results[0]["matches"][0]["text"], "\"to\": \"0xabc\"");
+
+        rename(&history, &id, "renamed").unwrap();
+        assert_eq!(list(&history, 10).unwrap()[0]["name"], "renamed");
+        delete(&history, &id).unwrap();
+        assert!(show(&history, &id, 10).is_err());
+    }
+}
@@ -0,0 +1,530 @@
+use crate::app::{
+    json_rpc_messages_by_shape, request_matches_filter, Connection, Framing, JsonRpcMessage,
+    Listener, StdioConfig, TransportType, MAIN_LISTENER,
+};
+use crate::query::Query;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// The per-user configuration file in the config directory.
+pub const USER_FILE: &str = "config.toml";
+/// The per-project configuration file in the working directory.
+pub const PROJECT_FILE: &str = "jsonrpc-debugger.toml";
+
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The profile used when `--profile` is not given.
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings applied together. Command-line flags override the matching fields.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Profile {
+    pub target: Option<String>,
+    pub port: Option<u16>,
+    pub control_port: Option<u16>,
+    /// `http`, or the framing of `command`: `stdio-json-lines` (the default),
+    /// `stdio-content-length`, or `stdio-dap`.
+    pub transport: Option<String>,
+    /// A stdio server to drive instead of `target`.
+    pub command: Vec<String>,
+    /// Extra environment variables for `command`.
+    pub env: BTreeMap<String, String>,
+    /// The initial request list filter.
+    pub filter: Option<String>,
+    /// Request list filters. Matching requests are held for a decision as if paused.
+    pub breakpoints: Vec<String>,
+    pub rewrite: Vec<Rewrite>,
+    /// JSON paths into `params`, `result`, and `error`, such as `params[0].privateKey` or
+    /// `$..password`, hidden before exchanges are shown or saved.
+    pub redact: Vec<String>,
+    /// Header names hidden the same way, matched without regard to case.
+    pub redact_headers: Vec<String>,
+    /// More proxy ports, each with its own target, recording into the same session.
+    pub listeners: Vec<ListenerProfile>,
+}
+
+/// A named proxy port beside the main one.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ListenerProfile {
+    /// Shown in the source column and matched by `source:<name>` filters.
+    pub name: String,
+    pub port: Option<u16>,
+    pub target: Option<String>,
+    /// As for the profile: `http` or a stdio framing.
+    pub transport: Option<String>,
+    pub command: Vec<String>,
+    pub env: BTreeMap<String, String>,
+}
+
+/// Values set on matching requests before they are logged and forwarded.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rewrite {
+    /// A request list filter such as `eth_call`. Empty matches every request.
+    #[serde(default, rename = "match")]
+    pub filter: String,
+    /// JSON paths into the request body and the values to put there.
+    pub set: BTreeMap<String, Value>,
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let config = toml::from_str::<Self>(text).map_err(|error| error.to_string())?;
+        for (name, profile) in &config.profiles {
+            profile
+                .rules()
+                .and(profile.framing().map(|_| ()))
+                .and(profile.listeners().map(|_| ()))
+                .map_err(|error| format!("profile {name}: {error}"))?;
+        }
+        Ok(config)
+    }
+
+    /// The user file, then the project file; a project profile replaces a user profile of
+    /// the same name. Missing files are skipped.
+    pub fn load() -> Result<Self, String> {
+        let mut paths = Vec::new();
+        if let Ok(dir) = crate::history::config_dir() {
+            paths.push(dir.join(USER_FILE));
+        }
+        paths.push(PathBuf::from(PROJECT_FILE));
+        Self::load_from(&paths)
+    }
+
+    pub fn load_from(paths: &[PathBuf]) -> Result<Self, String> {
+        let mut config = Self::default();
+        for path in paths {
+            let text = match std::fs::read_to_string(path) {
+                Ok(text) => text,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(format!("failed to read {}: {error}", path.display())),
+            };
+            let file =
+                Self::parse(&text).map_err(|error| format!("{}: {error}", path.display()))?;
+            config.default = file.default.or(config.default);
+            config.profiles.extend(file.profiles);
+        }
+        Ok(config)
+    }
+
+    /// The named profile, or the default one when `name` is `None`.
+    pub fn select(&self, name: Option<&str>) -> Result<Option<(&str, &Profile)>, String> {
+        let Some(name) = name.or(self.default.as_deref()) else {
+            return Ok(None);
+        };
+        self.profiles
+            .get_key_value(name)
+            .map(|(name, profile)| Some((name.as_str(), profile)))
+            .ok_or_else(|| {
+                let known = self.profiles.keys().cloned().collect::<Vec<_>>();
+                if known.is_empty() {
+                    format!("profile not found: {name}; no profiles are configured")
+                } else {
+                    format!("profile not found: {name}; known: {}", known.join(", "))
+                }
+            })
+    }
+}
+
+impl Profile {
+    /// The framing of a stdio profile, or `None` for HTTP.
+    pub fn framing(&self) -> Result<Option<Framing>, String> {
+        framing(self.transport.as_deref(), &self.command)
+    }
+
+    pub fn stdio(&self) -> Result<Option<StdioConfig>, String> {
+        stdio(self.framing()?, &self.command, &self.env)
+    }
+
+    pub fn listeners(&self) -> Result<Vec<Listener>, String> {
+        let listeners = self
+            .listeners
+            .iter()
+            .map(|listener| {
+                let context = |error: String| format!("listener {}: {error}", listener.name);
+                let framing =
+                    framing(listener.transport.as_deref(), &listener.command).map_err(context)?;
+                let stdio = stdio(framing, &listener.command, &listener.env).map_err(context)?;
+                let target_url = match (&stdio, &listener.target) {
+                    (Some(stdio), _) => crate::stdio::display_command(&stdio.command),
+                    (None, Some(target)) => target.clone(),
+                    (None, None) => return Err(context("needs a target or command".to_string())),
+                };
+                Ok(Listener {
+                    name: listener.name.clone(),
+                    listen_port: listener
+                        .port
+                        .ok_or_else(|| context("needs a port".to_string()))?,
+                    target_url,
+                    stdio,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        check_listener_names(&listeners)?;
+        Ok(listeners)
+    }
+
+    pub fn rules(&self) -> Result<Rules, String> {
+        let query = |path: &str| {
+            Query::parse(path).map_err(|error| format!("invalid path {path}: {error}"))
+        };
+        Ok(Rules {
+            breakpoints: self.breakpoints.clone(),
+            rewrites: self
+                .rewrite
+                .iter()
+                .map(|rewrite| {
+                    let set = rewrite
+                        .set
+                        .iter()
+                        .map(|(path, value)| Ok((query(path)?, value.clone())))
+                        .collect::<Result<Vec<_>, String>>()?;
+                    Ok((rewrite.filter.clone(), set))
+                })
+                .collect::<Result<_, String>>()?,
+            redactions: self
+                .redact
+                .iter()
+                .map(|path| query(path))
+                .collect::<Result<_, _>>()?,
+            redacted_headers: self
+                .redact_headers
+                .iter()
+                .map(|name| name.to_ascii_lowercase())
+                .collect(),
+        })
+    }
+}
+
+fn framing(transport: Option<&str>, command: &[String]) -> Result<Option<Framing>, String> {
+    let framing = match transport {
+        None if command.is_empty() => None,
+        None | Some("stdio-json-lines") => Some(Framing::JsonLines),
+        Some("stdio-content-length") => Some(Framing::ContentLength),
+        Some("stdio-dap") => Some(Framing::Dap),
+        Some("http") => None,
+        Some(name) => return Err(format!("unsupported transport: {name}")),
+    };
+    match (framing, command.is_empty()) {
+        (Some(_), true) => Err("a stdio transport needs a command".to_string()),
+        (None, false) => Err("an http transport cannot have a command".to_string()),
+        _ => Ok(framing),
+    }
+}
+
+fn stdio(
+    framing: Option<Framing>,
+    command: &[String],
+    env: &BTreeMap<String, String>,
+) -> Result<Option<StdioConfig>, String> {
+    Ok(framing.map(|framing| StdioConfig {
+        command: command.iter().map(OsString::from).collect(),
+        framing,
+        env: env
+            .iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect(),
+    }))
+}
+
+/// Listener names must be set, unique, and other than the main listener's.
+pub fn check_listener_names(listeners: &[Listener]) -> Result<(), String> {
+    for (index, listener) in listeners.iter().enumerate() {
+        if listener.name.trim().is_empty() {
+            return Err("every listener needs a name".to_string());
+        }
+        if listener.name == MAIN_LISTENER {
+            return Err(format!(
+                "{MAIN_LISTENER} is the name of the --port listener"
+            ));
+        }
+        if listeners[..index]
+            .iter()
+            .any(|other| other.name == listener.name)
+        {
+            return Err(format!("listener {} is defined twice", listener.name));
+        }
+    }
+    Ok(())
+}
+
+/// Each listener, and the main one on `port`, needs its own port.
+pub fn check_listener_ports(port: u16, listeners: &[Listener]) -> Result<(), String> {
+    for (index, listener) in listeners.iter().enumerate() {
+        if listener.listen_port == port
+            || listeners[..index]
+                .iter()
+                .any(|other| other.listen_port == listener.listen_port)
+        {
+            return Err(format!(
+                "listener {} reuses port {}",
+                listener.name, listener.listen_port
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The breakpoints, rewrites, and redactions of the active profile.
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    pub breakpoints: Vec<String>,
+    rewrites: Vec<(String, Vec<(Query, Value)>)>,
+    redactions: Vec<Query>,
+    redacted_headers: Vec<String>,
+}
+
+impl Rules {
+    /// Applies the rewrites to each request in a body, which may be a batch. Returns whether
+    /// anything matched.
+    pub fn rewrite(&self, body: &mut Value, connection: &Connection) -> bool {
+        if self.rewrites.is_empty() {
+            return false;
+        }
+        let mut rewritten = false;
+        let items = match body {
+            Value::Array(items) => items.iter_mut().collect::<Vec<_>>(),
+            body => vec![body],
+        };
+        for item in items {
+            let Some(mut request) = json_rpc_messages_by_shape(item, TransportType::Http, None)
+                .into_iter()
+                .next()
+                .filter(|message| message.method.is_some())
+            else {
+                continue;
+            };
+            request.connection = Some(connection.clone());
+            for (filter, set) in &self.rewrites {
+                if !request_matches_filter(&request, filter) {
+                    continue;
+                }
+                for (path, value) in set {
+                    path.assign(item, value);
+                }
+                rewritten = true;
+            }
+        }
+        rewritten
+    }
+
+    /// Whether any request among `messages` matches a breakpoint.
+    pub fn breaks(&self, messages: &[JsonRpcMessage]) -> bool {
+        messages
+            .iter()
+            .filter(|message| message.method.is_some())
+            .any(|message| {
+                self.breakpoints
+                    .iter()
+                    .any(|filter| request_matches_filter(message, filter))
+            })
+    }
+
+    pub fn redact(&self, message: &mut JsonRpcMessage) {
+        if let Some(headers) = &mut message.headers {
+            for (name, value) in headers.iter_mut() {
+                if self.redacted_headers.contains(&name.to_ascii_lowercase()) {
+                    *value = REDACTED.to_string();
+                }
+            }
+        }
+        if self.redactions.is_empty() {
+            return;
+        }
+        let mut document = Map::new();
+        for (key, value) in [
+            ("params", &message.params),
+            ("result", &message.result),
+            ("error", &message.error),
+        ] {
+            if let Some(value) = value {
+                document.insert(key.to_string(), value.clone());
+            }
+        }
+        let mut document = Value::Object(document);
+        for path in &self.redactions {
+            path.modify(&mut document, &mut |value| *value = REDACTED.into());
+        }
+        message.params = document.get("params").cloned();
+        message.result = document.get("result").cloned();
+        message.error = document.get("error").cloned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::MessageDirection;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    const CONFIG: &str = r#"
+default = "mainnet"
+
+[profiles.mainnet]
+target = "https://rpc.example/"
+port = 9545
+filter = "eth_"
+breakpoints = ["eth_sendRawTransaction"]
+redact = ["$..privateKey"]
+redact-headers = ["Authorization"]
+
+[[profiles.mainnet.rewrite]]
+match = "eth_call"
+set = { "params[1]" = "latest", "params[0].gas" = "0x5208" }
+
+[profiles.mcp]
+transport = "stdio-content-length"
+command = ["node", "server.js"]
+env = { DEBUG = "1" }
+"#;
+
+    #[test]
+    fn project_profiles_replace_user_profiles_of_the_same_name() {
+        let dir = std::env::temp_dir().join(format!("profile-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let user = dir.join(USER_FILE);
+        let project = dir.join(PROJECT_FILE);
+        std::fs::write(&user, CONFIG).unwrap();
+        std::fs::write(
+            &project,
+            "[profiles.mcp]\ntarget = \"http://localhost:3000/\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from(&[user, project, dir.join("missing.toml")]).unwrap();
+        let (name, profile) = config.select(None).unwrap().unwrap();
+        assert_eq!(name, "mainnet");
+        assert_eq!(profile.port, Some(9545));
+        assert_eq!(profile.framing().unwrap(), None);
+        let (_, mcp) = config.select(Some("mcp")).unwrap().unwrap();
+        assert_eq!(mcp.target.as_deref(), Some("http://localhost:3000/"));
+        assert!(mcp.stdio().unwrap().is_none());
+        assert_eq!(
+            config.select(Some("devnet")).unwrap_err(),
+            "profile not found: devnet; known: mainnet, mcp"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+
+        let stdio = Config::parse(CONFIG).unwrap().profiles["mcp"]
+            .stdio()
+            .unwrap()
+            .unwrap();
+        assert_eq!(stdio.framing, Framing::ContentLength);
+        assert_eq!(stdio.env, [(OsString::from("DEBUG"), OsString::from("1"))]);
+
+        assert_eq!(
+            Config::parse("[profiles.bad]\nredact = [\"params[\"]\n").unwrap_err(),
+            "profile bad: invalid path params[: expected an index at column 8"
+        );
+        assert!(Config::parse("[profiles.bad]\ntransport = \"stdio-dap\"\n").is_err());
+        assert!(Config::parse("[profiles.bad]\ntargte = \"typo\"\n").is_err());
+    }
+
+    #[test]
+    fn listeners_need_a_unique_name_port_and_target() {
+        let config = Config::parse(
+            r#"
+[profiles.rollup]
+target = "http://localhost:8545"
+
+[[profiles.rollup.listeners]]
+name = "l2"
+port = 8082
+target = "http://localhost:9545"
+
+[[profiles.rollup.listeners]]
+name = "mcp"
+port = 8084
+transport = "stdio-content-length"
+command = ["node", "server.js"]
+"#,
+        )
+        .unwrap();
+        let listeners = config.profiles["rollup"].listeners().unwrap();
+        assert_eq!(listeners[0].target_url, "http://localhost:9545");
+        assert_eq!(listeners[0].transport(), TransportType::Http);
+        assert_eq!(listeners[1].target_url, "node server.js");
+        assert_eq!(
+            listeners[1].transport(),
+            TransportType::Stdio(Framing::ContentLength)
+        );
+        assert_eq!(
+            check_listener_ports(8082, &listeners).unwrap_err(),
+            "listener l2 reuses port 8082"
+        );
+        assert!(check_listener_ports(8080, &listeners).is_ok());
+
+        let invalid = |listener: &str| {
+            Config::parse(&format!(
+                "[profiles.bad]\n[[profiles.bad.listeners]]\n{listener}\n"
+            ))
+            .unwrap_err()
+        };
+        assert_eq!(
+            invalid("name = \"l2\"\nport = 8082"),
+            "profile bad: listener l2: needs a target or command"
+        );
+        assert_eq!(
+            invalid("name = \"main\"\nport = 8082\ntarget = \"http://a/\""),
+            "profile bad: main is the name of the --port listener"
+        );
+        assert_eq!(
+            invalid("name = \"l2\"\ntarget = \"http://a/\""),
+            "profile bad: listener l2: needs a port"
+        );
+    }
+
+    #[test]
+    fn rules_rewrite_break_and_redact_requests() {
+        let rules = Config::parse(CONFIG).unwrap().profiles["mainnet"]
+            .rules()
+            .unwrap();
+        let connection = Connection {
+            id: "1".to_string(),
+            client: "127.0.0.1".to_string(),
+            source: None,
+        };
+        let mut body = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_call", "params": [{"to": "0xa"}]},
+            {"jsonrpc": "2.0", "id": 2, "method": "eth_chainId"},
+        ]);
+        assert!(rules.rewrite(&mut body, &connection));
+        assert_eq!(
+            body[0]["params"],
+            json!([{"to": "0xa", "gas": "0x5208"}, "latest"])
+        );
+        assert!(body[1].get("params").is_none());
+
+        let send = json!({"jsonrpc": "2.0", "id": 3, "method": "eth_sendRawTransaction"});
+        let messages = json_rpc_messages_by_shape(&send, TransportType::Http, None);
+        assert!(rules.breaks(&messages));
+        let messages = json_rpc_messages_by_shape(&body, TransportType::Http, None);
+        assert!(!rules.breaks(&messages));
+
+        let headers = HashMap::from([("authorization".to_string(), "Bearer key".to_string())]);
+        let mut message = json_rpc_messages_by_shape(
+            &json!({"jsonrpc": "2.0", "id": 4, "method": "sign", "params": [{"privateKey": "0x1"}]}),
+            TransportType::Http,
+            Some(&headers),
+        )
+        .remove(0);
+        rules.redact(&mut message);
+        assert_eq!(message.direction, MessageDirection::Request);
+        assert_eq!(message.params, Some(json!([{"privateKey": "[redacted]"}])));
+        assert_eq!(message.headers.unwrap()["authorization"], "[redacted]");
+        assert!(message.result.is_none());
+    }
+}
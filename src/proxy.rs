@@ -1,26 +1,68 @@
 use crate::app::{
-    json_rpc_messages, json_rpc_messages_by_shape, AppMode, Framing, JsonRpcMessage,
-    MessageDirection, PendingRequest, ProxyConfig, ProxyDecision, TransportType,
+    json_rpc_messages, json_rpc_messages_by_shape, AppMode, Connection, Delivery, Framing,
+    JsonRpcMessage, Listener, MessageDirection, PendingRequest, ProxyConfig, ProxyDecision,
+    StdioConfig, Timing, TransportType,
 };
+use crate::profile::Rules;
 use crate::stdio::StdioTransport;
 use anyhow::{Context, Result};
+use reqwest::dns::{Addrs, Resolve, Resolving};
 use reqwest::Client;
 use serde_json::Value;
 use std::{
     collections::HashMap,
     future::Future,
+    net::SocketAddr,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use uuid::Uuid;
 
 use tokio::sync::{mpsc, oneshot};
+use warp::http::{header, HeaderValue, StatusCode};
+use warp::hyper::{client::connect::dns::Name, Body};
 use warp::Filter;
 
+// How long a delivered response may wait for a stalled client before its delivery time is
+// given up on.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+tokio::task_local! {
+    // Set while a forwarded request waits for upstream headers, so a lookup made to open a
+    // connection for it is charged to it.
+    static LOOKUP: Arc<Mutex<Option<Duration>>>;
+}
+
+/// The system resolver, timed for the request that asked for the lookup.
+struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let lookup = LOOKUP.try_with(Arc::clone).ok();
+        Box::pin(async move {
+            let started = Instant::now();
+            let addresses = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .collect::<Vec<_>>();
+            if let Some(lookup) = lookup {
+                if let Ok(mut lookup) = lookup.lock() {
+                    *lookup = Some(started.elapsed());
+                }
+            }
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
 // Shared state between app and proxy
 #[derive(Clone)]
 pub struct ProxyState {
     pub app_mode: Arc<Mutex<AppMode>>,
     pub pending_sender: mpsc::UnboundedSender<PendingRequest>,
+    /// The active profile's breakpoints and rewrites.
+    pub rules: Arc<Mutex<Rules>>,
+    /// Where the time each client took to receive its responses is reported.
+    pub deliveries: mpsc::UnboundedSender<Delivery>,
 }
 
 pub struct ProxyServer {
@@ -28,6 +70,8 @@ pub struct ProxyServer {
     target: ProxyTarget,
     message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
     proxy_state: Option<ProxyState>,
+    /// The listener name recorded on each connection when several share a session.
+    source: Option<String>,
 }
 
 #[derive(Clone)]
@@ -60,9 +104,10 @@ impl ProxyServer {
         // Configure client for higher concurrency
         let client = Client::builder()
             .pool_max_idle_per_host(50) // More idle connections
-            .pool_idle_timeout(std::time::Duration::from_secs(30))
+            .pool_idle_timeout(Duration::from_secs(30))
             .http2_max_frame_size(Some(16384)) // Larger frame size
-            .http2_keep_alive_interval(Some(std::time::Duration::from_secs(10)))
+            .http2_keep_alive_interval(Some(Duration::from_secs(10)))
+            .dns_resolver(Arc::new(TimedResolver))
             .build()
             .unwrap_or_else(|_| Client::new()); // Fallback to default if config fails
 
@@ -74,31 +119,69 @@ impl ProxyServer {
             },
             message_sender,
             proxy_state: None,
+            source: None,
         }
     }
 
+    /// The main listener of `config`.
     pub fn from_config(
         config: &ProxyConfig,
         message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
     ) -> Result<Self> {
-        let Some(stdio) = &config.stdio else {
-            return Ok(Self::new(
-                config.listen_port,
-                config.target_url.clone(),
-                message_sender,
-            ));
+        Self::build(
+            config.listen_port,
+            &config.target_url,
+            config.stdio.as_ref(),
+            config.source(),
+            message_sender,
+        )
+    }
+
+    /// One of the named listeners beside the main one.
+    pub fn from_listener(
+        listener: &Listener,
+        message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
+    ) -> Result<Self> {
+        Self::build(
+            listener.listen_port,
+            &listener.target_url,
+            listener.stdio.as_ref(),
+            Some(&listener.name),
+            message_sender,
+        )
+    }
+
+    fn build(
+        listen_port: u16,
+        target_url: &str,
+        stdio: Option<&StdioConfig>,
+        source: Option<&str>,
+        message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
+    ) -> Result<Self> {
+        let source = source.map(str::to_string);
+        let Some(stdio) = stdio else {
+            let mut server = Self::new(listen_port, target_url.to_string(), message_sender);
+            server.source = source;
+            return Ok(server);
+        };
+        // Server notifications arrive without a client connection, so they are tagged on
+        // their way to the session.
+        let stdio_sender = match &source {
+            Some(source) => tag_source(source.clone(), message_sender.clone()),
+            None => message_sender.clone(),
         };
         let transport =
-            StdioTransport::spawn(&stdio.command, stdio.framing, message_sender.clone())
+            StdioTransport::spawn(&stdio.command, &stdio.env, stdio.framing, stdio_sender)
                 .map_err(anyhow::Error::msg)?;
         Ok(Self {
-            listen_port: config.listen_port,
+            listen_port,
             target: ProxyTarget::Stdio {
                 transport,
                 framing: stdio.framing,
             },
             message_sender,
             proxy_state: None,
+            source,
         })
     }
 
@@ -116,16 +199,22 @@ impl ProxyServer {
         let target = self.target.clone();
         let message_sender = self.message_sender.clone();
         let proxy_state = self.proxy_state.clone();
+        let source = self.source.clone();
 
         let proxy_route = warp::path::full()
             .and(warp::post())
+            .and(warp::addr::remote())
             .and(warp::header::headers_cloned())
             .and(warp::body::json())
             .and_then(
-                move |path: warp::path::FullPath, headers: warp::http::HeaderMap, body: Value| {
+                move |path: warp::path::FullPath,
+                      remote: Option<SocketAddr>,
+                      headers: warp::http::HeaderMap,
+                      body: Value| {
                     let target = target.clone();
                     let message_sender = message_sender.clone();
                     let proxy_state = proxy_state.clone();
+                    let connection = http_connection(remote, source.clone());
 
                     async move {
                         handle_proxy_request(
@@ -133,6 +222,7 @@ impl ProxyServer {
                             headers,
                             body,
                             target,
+                            connection,
                             message_sender,
                             proxy_state,
                         )
@@ -159,8 +249,9 @@ impl ProxyServer {
 async fn handle_proxy_request(
     path: warp::path::FullPath,
     headers: warp::http::HeaderMap,
-    body: Value,
+    mut body: Value,
     target: ProxyTarget,
+    connection: Connection,
     message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
     proxy_state: Option<ProxyState>,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
@@ -172,9 +263,16 @@ async fn handle_proxy_request(
         }
     }
 
+    // Profile rewrites change both what is logged and what is forwarded.
+    if let Some(state) = &proxy_state {
+        if let Ok(rules) = state.rules.lock() {
+            rules.rewrite(&mut body, &connection);
+        }
+    }
+
     // Log each JSON-RPC request in the HTTP body.
     let transport = target.transport(&body);
-    let request_messages = if matches!(transport, TransportType::Stdio(_)) {
+    let mut request_messages = if matches!(transport, TransportType::Stdio(_)) {
         json_rpc_messages_by_shape(&body, transport, Some(&header_map))
     } else {
         json_rpc_messages(
@@ -184,6 +282,9 @@ async fn handle_proxy_request(
             Some(&header_map),
         )
     };
+    for message in &mut request_messages {
+        message.connection = Some(connection.clone());
+    }
     let request_message = request_messages.first().cloned().unwrap_or(JsonRpcMessage {
         id: None,
         method: None,
@@ -198,15 +299,25 @@ async fn handle_proxy_request(
         },
         transport,
         headers: Some(header_map.clone()),
+        connection: Some(connection.clone()),
+        batch: None,
+        timing: None,
+        seq: None,
+    });
+    let breakpoint = proxy_state.as_ref().is_some_and(|state| {
+        state
+            .rules
+            .lock()
+            .is_ok_and(|rules| rules.breaks(&request_messages))
     });
     for message in request_messages {
         let _ = message_sender.send(message);
     }
 
-    // Check if we're in pause mode and should intercept the request
+    // Check if we're in pause mode or at a breakpoint and should intercept the request
     if let Some(ref state) = proxy_state {
         let should_intercept = if let Ok(app_mode) = state.app_mode.lock() {
-            matches!(*app_mode, AppMode::Paused)
+            (matches!(*app_mode, AppMode::Paused) || breakpoint)
                 && matches!(request_message.direction, MessageDirection::Request)
         } else {
             false
@@ -226,6 +337,7 @@ async fn handle_proxy_request(
             };
 
             // Send to app for interception
+            let queued_at = Instant::now();
             let _ = state.pending_sender.send(pending_request);
 
             // Wait for user decision with timeout
@@ -234,6 +346,10 @@ async fn handle_proxy_request(
                 decision_receiver,
             )
             .await;
+            let timing = Timing {
+                queued: Some(queued_at.elapsed()),
+                ..Timing::default()
+            };
 
             return match decision {
                 Ok(Ok(ProxyDecision::Allow(modified_json, modified_headers))) => {
@@ -262,7 +378,12 @@ async fn handle_proxy_request(
                         request_body,
                         path.as_str(),
                         target,
-                        message_sender,
+                        connection,
+                        timing,
+                        Reports {
+                            messages: message_sender,
+                            deliveries: Some(state.deliveries.clone()),
+                        },
                     )
                     .await
                 }
@@ -295,6 +416,10 @@ async fn handle_proxy_request(
                             ("content-type".to_string(), "application/json".to_string()),
                             ("x-proxy-completed".to_string(), "true".to_string()),
                         ])),
+                        connection: Some(connection),
+                        batch: None,
+                        timing: Some(timing),
+                        seq: None,
                     };
 
                     let _ = message_sender.send(response_message);
@@ -324,7 +449,25 @@ async fn handle_proxy_request(
     }
 
     // Normal forwarding (not intercepted)
-    forward_request(headers, body, path.as_str(), target, message_sender).await
+    forward_request(
+        headers,
+        body,
+        path.as_str(),
+        target,
+        connection,
+        Timing::default(),
+        Reports {
+            messages: message_sender,
+            deliveries: proxy_state.map(|state| state.deliveries),
+        },
+    )
+    .await
+}
+
+/// Where a forwarded call reports what it logs and when the client took the response.
+struct Reports {
+    messages: mpsc::UnboundedSender<JsonRpcMessage>,
+    deliveries: Option<mpsc::UnboundedSender<Delivery>>,
 }
 
 async fn forward_request(
@@ -332,7 +475,9 @@ async fn forward_request(
     body: Value,
     path: &str,
     target: ProxyTarget,
-    message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
+    connection: Connection,
+    timing: Timing,
+    reports: Reports,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
     match target {
         ProxyTarget::Http { url, client } => {
@@ -341,12 +486,14 @@ async fn forward_request(
                 body,
                 format!("{url}{path}"),
                 client,
-                message_sender,
+                connection,
+                timing,
+                reports,
             )
             .await
         }
         ProxyTarget::Stdio { transport, framing } => {
-            forward_stdio_request(body, transport, framing, message_sender).await
+            forward_stdio_request(body, transport, framing, connection, reports.messages).await
         }
     }
 }
@@ -356,8 +503,11 @@ async fn forward_http_request(
     body: Value,
     target_url: String,
     client: Client,
-    message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
+    connection: Connection,
+    mut timing: Timing,
+    reports: Reports,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let message_sender = reports.messages.clone();
     let transport = http_transport(&body);
     // Forward the request to the target
     let mut request_builder = client.post(&target_url).json(&body);
@@ -369,8 +519,16 @@ async fn forward_http_request(
         }
     }
 
-    match request_builder.send().await {
+    let lookup = Arc::new(Mutex::new(None));
+    let sent_at = Instant::now();
+    let sent = LOOKUP.scope(lookup.clone(), request_builder.send()).await;
+    timing.dns = lookup.lock().ok().and_then(|lookup| *lookup);
+    match sent {
         Ok(response) => {
+            let headers_at = Instant::now();
+            // reqwest hides the connect and TLS phases, so they are counted here.
+            timing.first_byte =
+                Some((headers_at - sent_at).saturating_sub(timing.dns.unwrap_or_default()));
             let status = response.status();
             let response_headers = response.headers().clone();
 
@@ -383,26 +541,32 @@ async fn forward_http_request(
             }
 
             // Get the response text - reqwest should handle decompression automatically
-            match response.text().await {
+            let text = response.text().await;
+            timing.download = Some(headers_at.elapsed());
+            match text {
                 Ok(response_text) => {
                     // Try to parse as JSON
                     match serde_json::from_str::<Value>(&response_text) {
                         Ok(response_body) => {
                             // Valid JSON response
-                            for message in json_rpc_messages(
+                            let mut messages = json_rpc_messages(
                                 &response_body,
                                 MessageDirection::Response,
                                 transport,
                                 Some(&response_header_map),
-                            ) {
-                                let _ = message_sender.send(message);
+                            );
+                            for message in &mut messages {
+                                message.connection = Some(connection.clone());
                             }
 
                             // Return the original response as-is
-                            Ok(Box::new(warp::reply::with_status(
-                                warp::reply::json(&response_body),
+                            Ok(delivered_reply(
+                                serde_json::to_vec(&response_body).unwrap_or_default(),
                                 status,
-                            )))
+                                timing,
+                                messages,
+                                reports,
+                            ))
                         }
                         Err(parse_error) => {
                             // Not valid JSON - analyze the response to provide better error info
@@ -470,6 +634,10 @@ async fn forward_http_request(
                                 direction: MessageDirection::Response,
                                 transport,
                                 headers: Some(response_header_map.clone()),
+                                connection: Some(connection),
+                                batch: None,
+                                timing: Some(timing),
+                                seq: None,
                             };
 
                             let _ = message_sender.send(error_message);
@@ -509,6 +677,10 @@ async fn forward_http_request(
                         direction: MessageDirection::Response,
                         transport,
                         headers: Some(response_header_map),
+                        connection: Some(connection),
+                        batch: None,
+                        timing: Some(timing),
+                        seq: None,
                     };
 
                     let _ = message_sender.send(error_message);
@@ -542,6 +714,10 @@ async fn forward_http_request(
                 direction: MessageDirection::Response,
                 transport,
                 headers: None,
+                connection: Some(connection),
+                batch: None,
+                timing: Some(timing),
+                seq: None,
             };
 
             let _ = message_sender.send(error_message);
@@ -565,9 +741,10 @@ async fn forward_stdio_request(
     body: Value,
     target: StdioTransport,
     framing: Framing,
+    connection: Connection,
     message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
-    match target.send(body.clone()).await {
+    match target.send(body.clone(), Some(connection.clone())).await {
         Ok(response) => Ok(Box::new(warp::reply::with_status(
             warp::reply::json(&response),
             warp::http::StatusCode::OK,
@@ -581,12 +758,13 @@ async fn forward_stdio_request(
                     "message": message,
                 }
             });
-            for message in json_rpc_messages(
+            for mut message in json_rpc_messages(
                 &response,
                 MessageDirection::Response,
                 TransportType::Stdio(framing),
                 None,
             ) {
+                message.connection = Some(connection.clone());
                 let _ = message_sender.send(message);
             }
             Ok(Box::new(warp::reply::with_status(
@@ -597,6 +775,89 @@ async fn forward_stdio_request(
     }
 }
 
+// Logs `messages` and streams `body` to the client, then reports to `deliveries` once the
+// client's connection has taken it.
+fn delivered_reply(
+    body: Vec<u8>,
+    status: StatusCode,
+    timing: Timing,
+    messages: Vec<JsonRpcMessage>,
+    reports: Reports,
+) -> Box<dyn warp::Reply> {
+    let connection = messages
+        .first()
+        .and_then(|message| Some(message.connection.as_ref()?.id.clone()));
+    for mut message in messages {
+        message.timing = Some(timing);
+        let _ = reports.messages.send(message);
+    }
+
+    let length = body.len();
+    let (mut sender, reply_body) = Body::channel();
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let delivered = tokio::time::timeout(DELIVERY_TIMEOUT, async {
+            sender.send_data(body.into()).await?;
+            // The channel holds one chunk, so it is ready again once the client's connection
+            // took it. With the whole body taken, the connection may drop its end instead.
+            let _ = std::future::poll_fn(|cx| sender.poll_ready(cx)).await;
+            Ok::<_, warp::hyper::Error>(())
+        })
+        .await;
+        let delivery = started.elapsed();
+        drop(sender);
+        if let (Ok(Ok(())), Some(connection), Some(deliveries)) =
+            (delivered, connection, reports.deliveries)
+        {
+            let _ = deliveries.send(Delivery {
+                connection,
+                delivery,
+            });
+        }
+    });
+
+    let mut reply = warp::http::Response::new(reply_body);
+    *reply.status_mut() = status;
+    reply.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    reply
+        .headers_mut()
+        .insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+    Box::new(reply)
+}
+
+// Every proxied HTTP request is its own connection for pairing; the client is its peer.
+fn http_connection(remote: Option<SocketAddr>, source: Option<String>) -> Connection {
+    Connection {
+        id: Uuid::new_v4().to_string(),
+        client: remote.map_or_else(|| "unknown".to_string(), |address| address.to_string()),
+        source,
+    }
+}
+
+/// A sender that records `source` on each message before passing it on. Messages without a
+/// connection are put on the stdio stream.
+fn tag_source(
+    source: String,
+    message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
+) -> mpsc::UnboundedSender<JsonRpcMessage> {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<JsonRpcMessage>();
+    tokio::spawn(async move {
+        while let Some(mut message) = receiver.recv().await {
+            message
+                .connection
+                .get_or_insert_with(Connection::stdio)
+                .source = Some(source.clone());
+            if message_sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+    sender
+}
+
 fn should_forward_header(header_name: &str) -> bool {
     !matches!(
         header_name.to_lowercase().as_str(),
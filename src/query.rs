@@ -0,0 +1,433 @@
+use crate::app::{message_body, JsonRpcExchange};
+use serde_json::{Map, Value};
+
+/// A JSONPath expression, also accepting jq's leading dot and `[]`: `$.result.logs[*].topics`,
+/// `.result.number`, `result.transactions[-1]`, and `..from` all work.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    expression: String,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Wildcard,
+    /// The inner step applied at every depth, as in `..key`.
+    Descend(Box<Step>),
+}
+
+impl Query {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        if expression.is_empty() {
+            return Err("query is empty".to_string());
+        }
+        let steps = Parser {
+            chars: expression.chars().collect(),
+            position: 0,
+        }
+        .steps()?;
+        Ok(Self {
+            expression: expression.to_string(),
+            steps,
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Every value the expression selects, in document order.
+    pub fn evaluate(&self, value: &Value) -> Vec<Value> {
+        let mut current = vec![value];
+        for step in &self.steps {
+            let mut next = Vec::new();
+            for value in current {
+                apply(step, value, &mut next);
+            }
+            current = next;
+        }
+        current.into_iter().cloned().collect()
+    }
+
+    /// Evaluates against the request and response bodies of `exchange` merged into one
+    /// object, so `params` and `result` are both top-level keys.
+    pub fn evaluate_exchange(&self, exchange: &JsonRpcExchange) -> Vec<Value> {
+        self.evaluate(&document(exchange))
+    }
+
+    /// Calls `change` on every value the expression selects, in document order.
+    pub fn modify(&self, value: &mut Value, change: &mut dyn FnMut(&mut Value)) {
+        visit(&self.steps, value, change);
+    }
+
+    /// Replaces every selected value with `new`. A final key missing from an object it
+    /// reaches is added, so `params[0].gas` sets gas on a call that left it out, and a final
+    /// index one past the end of an array appends.
+    pub fn assign(&self, value: &mut Value, new: &Value) {
+        match self.steps.split_last() {
+            Some((Step::Key(key), parents)) => visit(parents, value, &mut |parent| {
+                if let Value::Object(map) = parent {
+                    map.insert(key.clone(), new.clone());
+                }
+            }),
+            Some((Step::Index(index), parents)) => visit(parents, value, &mut |parent| {
+                if let Value::Array(items) = parent {
+                    match position(*index, items.len()) {
+                        Some(index) if index == items.len() => items.push(new.clone()),
+                        Some(index) if index < items.len() => items[index] = new.clone(),
+                        _ => {}
+                    }
+                }
+            }),
+            _ => self.modify(value, &mut |value| *value = new.clone()),
+        }
+    }
+}
+
+pub fn document(exchange: &JsonRpcExchange) -> Value {
+    let mut document = Map::new();
+    for message in [&exchange.request, &exchange.response]
+        .into_iter()
+        .flatten()
+    {
+        if let Value::Object(body) = message_body(message) {
+            document.extend(body);
+        }
+    }
+    Value::Object(document)
+}
+
+/// One match as itself and several as an array; `None` when nothing matched.
+pub fn combined(matches: &[Value]) -> Option<Value> {
+    match matches {
+        [] => None,
+        [value] => Some(value.clone()),
+        values => Some(Value::Array(values.to_vec())),
+    }
+}
+
+/// A one-line rendering for a table cell. Strings drop their quotes.
+pub fn cell(matches: &[Value]) -> String {
+    match combined(matches) {
+        None => String::new(),
+        Some(Value::String(text)) => text,
+        Some(value) => value.to_string(),
+    }
+}
+
+fn apply<'a>(step: &Step, value: &'a Value, out: &mut Vec<&'a Value>) {
+    match (step, value) {
+        (Step::Key(key), Value::Object(map)) => out.extend(map.get(key)),
+        (Step::Index(index), Value::Array(items)) => {
+            out.extend(position(*index, items.len()).and_then(|index| items.get(index)))
+        }
+        (Step::Slice(start, end), Value::Array(items)) => {
+            let (start, end) = bounds(*start, *end, items.len());
+            out.extend(items.iter().take(end).skip(start));
+        }
+        (Step::Wildcard, Value::Object(map)) => out.extend(map.values()),
+        (Step::Wildcard, Value::Array(items)) => out.extend(items),
+        (Step::Descend(step), value) => descend(step, value, out),
+        _ => {}
+    }
+}
+
+fn descend<'a>(step: &Step, value: &'a Value, out: &mut Vec<&'a Value>) {
+    apply(step, value, out);
+    match value {
+        Value::Object(map) => map.values().for_each(|child| descend(step, child, out)),
+        Value::Array(items) => items.iter().for_each(|child| descend(step, child, out)),
+        _ => {}
+    }
+}
+
+fn visit(steps: &[Step], value: &mut Value, change: &mut dyn FnMut(&mut Value)) {
+    let Some((step, rest)) = steps.split_first() else {
+        change(value);
+        return;
+    };
+    match (step, value) {
+        (Step::Key(key), Value::Object(map)) => {
+            if let Some(child) = map.get_mut(key) {
+                visit(rest, child, change);
+            }
+        }
+        (Step::Index(index), Value::Array(items)) => {
+            let len = items.len();
+            if let Some(child) = position(*index, len).and_then(|index| items.get_mut(index)) {
+                visit(rest, child, change);
+            }
+        }
+        (Step::Slice(start, end), Value::Array(items)) => {
+            let (start, end) = bounds(*start, *end, items.len());
+            for child in items.iter_mut().take(end).skip(start) {
+                visit(rest, child, change);
+            }
+        }
+        (Step::Wildcard, Value::Object(map)) => map
+            .values_mut()
+            .for_each(|child| visit(rest, child, change)),
+        (Step::Wildcard, Value::Array(items)) => items
+            .iter_mut()
+            .for_each(|child| visit(rest, child, change)),
+        (Step::Descend(inner), value) => {
+            // The same order as `descend`: this level's matches, then each child's.
+            let mut here = vec![inner.as_ref().clone()];
+            here.extend_from_slice(rest);
+            visit(&here, value, change);
+            match value {
+                Value::Object(map) => map
+                    .values_mut()
+                    .for_each(|child| visit(steps, child, change)),
+                Value::Array(items) => items
+                    .iter_mut()
+                    .for_each(|child| visit(steps, child, change)),
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn bounds(start: Option<i64>, end: Option<i64>, len: usize) -> (usize, usize) {
+    let bound = |bound: Option<i64>, default: usize| {
+        bound.map_or(default, |bound| position(bound, len).unwrap_or(0).min(len))
+    };
+    (bound(start, 0), bound(end, len))
+}
+
+// Negative positions count from the end, as in Python and jq.
+fn position(index: i64, len: usize) -> Option<usize> {
+    if index >= 0 {
+        usize::try_from(index).ok()
+    } else {
+        len.checked_sub(usize::try_from(index.unsigned_abs()).ok()?)
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn steps(mut self) -> Result<Vec<Step>, String> {
+        let mut steps = Vec::new();
+        self.eat('$');
+        if self.peek().is_some_and(is_key_char) {
+            steps.push(Step::Key(self.key()));
+        }
+        while let Some(character) = self.peek() {
+            match character {
+                '.' if self.chars.get(self.position + 1) == Some(&'.') => {
+                    self.position += 2;
+                    let step = self
+                        .member()?
+                        .ok_or_else(|| self.error("expected a key after `..`"))?;
+                    steps.push(Step::Descend(Box::new(step)));
+                }
+                '.' => {
+                    self.position += 1;
+                    match self.member()? {
+                        Some(step) => steps.push(step),
+                        // A lone `.` is jq's whole document.
+                        None if self.chars.len() == 1 => {}
+                        None => return Err(self.error("expected a key after `.`")),
+                    }
+                }
+                '[' => steps.push(self.bracket()?),
+                _ => return Err(self.error(&format!("unexpected `{character}`"))),
+            }
+        }
+        Ok(steps)
+    }
+
+    fn member(&mut self) -> Result<Option<Step>, String> {
+        match self.peek() {
+            Some('*') => {
+                self.position += 1;
+                Ok(Some(Step::Wildcard))
+            }
+            Some('[') => self.bracket().map(Some),
+            Some(character) if is_key_char(character) => Ok(Some(Step::Key(self.key()))),
+            _ => Ok(None),
+        }
+    }
+
+    fn bracket(&mut self) -> Result<Step, String> {
+        self.position += 1;
+        let step = match self.peek() {
+            Some(']') => Step::Wildcard,
+            Some('*') => {
+                self.position += 1;
+                Step::Wildcard
+            }
+            Some(quote @ ('"' | '\'')) => Step::Key(self.quoted(quote)?),
+            _ => {
+                let start = self.integer()?;
+                if self.eat(':') {
+                    Step::Slice(start, self.integer()?)
+                } else {
+                    Step::Index(start.ok_or_else(|| self.error("expected an index"))?)
+                }
+            }
+        };
+        if !self.eat(']') {
+            return Err(self.error("expected `]`"));
+        }
+        Ok(step)
+    }
+
+    fn key(&mut self) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(is_key_char) {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<String, String> {
+        self.position += 1;
+        let mut key = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(character) if character == quote => break,
+                Some('\\') => {
+                    self.position += 1;
+                    key.extend(self.peek());
+                }
+                Some(character) => key.push(character),
+            }
+            self.position += 1;
+        }
+        self.position += 1;
+        Ok(key)
+    }
+
+    fn integer(&mut self) -> Result<Option<i64>, String> {
+        let start = self.position;
+        self.eat('-');
+        while self
+            .peek()
+            .is_some_and(|character| character.is_ascii_digit())
+        {
+            self.position += 1;
+        }
+        if self.position == start {
+            return Ok(None);
+        }
+        let text = self.chars[start..self.position].iter().collect::<String>();
+        text.parse()
+            .map(Some)
+            .map_err(|_| self.error(&format!("invalid index `{text}`")))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn eat(&mut self, character: char) -> bool {
+        let matched = self.peek() == Some(character);
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{message} at column {}", self.position + 1)
+    }
+}
+
+fn is_key_char(character: char) -> bool {
+    character.is_alphanumeric() || matches!(character, '_' | '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn jsonpath_and_jq_forms_select_the_same_values() {
+        let body = json!({
+            "result": {
+                "number": "0x10",
+                "transactions": [
+                    {"from": "0xa", "to": "0xb"},
+                    {"from": "0xc", "to": null},
+                    {"from": "0xd", "to": "0xe", "x-tag": "t"}
+                ]
+            }
+        });
+        let select = |expression: &str| Query::parse(expression).unwrap().evaluate(&body);
+
+        for expression in ["result.number", "$.result.number", ".result.number"] {
+            assert_eq!(select(expression), [json!("0x10")], "{expression}");
+        }
+        assert_eq!(select("$['result'][\"number\"]"), [json!("0x10")]);
+        assert_eq!(select(".result.transactions[-1].to"), [json!("0xe")]);
+        assert_eq!(
+            select("$..from"),
+            [json!("0xa"), json!("0xc"), json!("0xd")]
+        );
+        assert_eq!(select(".result.transactions[].to").len(), 3);
+        assert_eq!(select("result.transactions[*].x-tag"), [json!("t")]);
+        assert_eq!(
+            select("result.transactions[1:].from"),
+            [json!("0xc"), json!("0xd")]
+        );
+        assert_eq!(select("."), vec![body.clone()]);
+        assert!(select("result.missing[0]").is_empty());
+
+        assert_eq!(cell(&select("result.number")), "0x10");
+        assert_eq!(cell(&select("$..to")), r#"["0xb",null,"0xe"]"#);
+        assert_eq!(
+            Query::parse("result.[").unwrap_err(),
+            "expected an index at column 9"
+        );
+        assert!(Query::parse("result..").is_err());
+        assert!(Query::parse("result | length").is_err());
+    }
+
+    #[test]
+    fn modify_and_assign_change_selected_values_in_place() {
+        let mut body = json!({
+            "params": [{"to": "0xa", "auth": {"password": "hunter2"}}, "latest"],
+            "password": "top",
+        });
+        let redact = |expression: &str, body: &mut Value| {
+            Query::parse(expression)
+                .unwrap()
+                .modify(body, &mut |value| *value = json!("[redacted]"))
+        };
+
+        redact("$..password", &mut body);
+        assert_eq!(body["password"], "[redacted]");
+        assert_eq!(body["params"][0]["auth"]["password"], "[redacted]");
+        redact("params[-1]", &mut body);
+        assert_eq!(body["params"][1], "[redacted]");
+
+        let gas = Query::parse("params[0].gas").unwrap();
+        gas.assign(&mut body, &json!("0x5208"));
+        assert_eq!(body["params"][0]["gas"], "0x5208");
+        Query::parse("params[0].to")
+            .unwrap()
+            .assign(&mut body, &json!("0xb"));
+        assert_eq!(body["params"][0]["to"], "0xb");
+        Query::parse("params[2]")
+            .unwrap()
+            .assign(&mut body, &json!(true));
+        assert_eq!(body["params"][2], true);
+        // Missing parents are not created.
+        Query::parse("result.gas")
+            .unwrap()
+            .assign(&mut body, &json!(1));
+        assert!(body.get("result").is_none());
+    }
+}
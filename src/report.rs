@@ -0,0 +1,512 @@
+use crate::app::{App, DetailTab, ExchangeStatus, Focus, JsonRpcExchange};
+use crate::control::timestamp_ms;
+use crate::time::iso8601;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub const ALL: [Self; 2] = [Self::Markdown, Self::Html];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// An investigation report: what was captured, what failed, and every exchange with the
+/// annotations written on it.
+struct Report {
+    title: String,
+    summary: Vec<(&'static str, String)>,
+    errors: Vec<String>,
+    sections: Vec<Section>,
+}
+
+struct Section {
+    index: usize,
+    title: String,
+    status: ExchangeStatus,
+    facts: Vec<(&'static str, String)>,
+    panels: Vec<Panel>,
+}
+
+impl Section {
+    fn is_notable(&self) -> bool {
+        self.status == ExchangeStatus::Error
+            || self.panels.iter().any(|panel| !panel.notes.is_empty())
+    }
+}
+
+struct Panel {
+    title: String,
+    json: bool,
+    lines: Vec<String>,
+    notes: Vec<Note>,
+}
+
+/// An annotation numbered across the report, with lines counted from the top of the panel
+/// contents.
+struct Note {
+    number: usize,
+    start_line: usize,
+    end_line: usize,
+    message: String,
+}
+
+/// Renders the exchanges at `indices` of the active session. `scope` says which ones were
+/// picked, such as the request list filter.
+pub fn render(app: &App, indices: &[usize], scope: Option<&str>, format: Format) -> String {
+    let report = build(app, indices, scope);
+    match format {
+        Format::Markdown => markdown(&report),
+        Format::Html => html(&report),
+    }
+}
+
+fn build(app: &App, indices: &[usize], scope: Option<&str>) -> Report {
+    let mut indices = indices
+        .iter()
+        .copied()
+        .filter(|index| *index < app.exchanges.len())
+        .collect::<Vec<_>>();
+    indices.sort_unstable();
+    indices.dedup();
+    let context = app.status_context();
+    let exchanges = indices
+        .iter()
+        .map(|index| (*index, &app.exchanges[*index]))
+        .collect::<Vec<_>>();
+
+    let mut counts = Vec::<(ExchangeStatus, usize)>::new();
+    for (_, exchange) in &exchanges {
+        let status = context.status(exchange);
+        match counts.iter_mut().find(|(counted, _)| *counted == status) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((status, 1)),
+        }
+    }
+    let times = exchanges
+        .iter()
+        .flat_map(|(_, exchange)| [&exchange.request, &exchange.response])
+        .flatten()
+        .map(|message| timestamp_ms(message.timestamp))
+        .collect::<Vec<_>>();
+    let span = match (times.iter().min(), times.iter().max()) {
+        (Some(first), Some(last)) => format!(
+            "{} to {} ({})",
+            iso8601(*first),
+            iso8601(*last),
+            crate::timeline::format_duration(std::time::Duration::from_millis(last - first))
+        ),
+        _ => "-".to_string(),
+    };
+
+    let mut summary = vec![("Target", app.proxy_config.target_url.clone())];
+    if let Some(session) = &app.session {
+        summary.push(("Session", session.name.clone()));
+    }
+    summary.extend([
+        ("Scope", scope.unwrap_or("all exchanges").to_string()),
+        ("Time span", span),
+        ("Exchanges", exchanges.len().to_string()),
+        (
+            "Status",
+            counts
+                .iter()
+                .map(|(status, count)| format!("{count} {}", status.label().to_lowercase()))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    ]);
+
+    let errors = exchanges
+        .iter()
+        .filter(|(_, exchange)| context.status(exchange) == ExchangeStatus::Error)
+        .map(|(index, exchange)| {
+            let error = exchange
+                .response
+                .as_ref()
+                .and_then(|response| response.error.as_ref());
+            let code = error
+                .and_then(|error| error.get("code"))
+                .map_or("-".to_string(), ToString::to_string);
+            let message = error
+                .and_then(|error| error.get("message"))
+                .and_then(|message| message.as_str())
+                .unwrap_or("");
+            format!("#{index} {}: {code} {message}", method(exchange))
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let mut number = 0;
+    let sections = exchanges
+        .iter()
+        .map(|(index, exchange)| {
+            let status = context.status(exchange);
+            let title = app.bookmark(*index).map_or_else(
+                || method(exchange),
+                |bookmark| bookmark.decorate(&method(exchange)),
+            );
+            let mut facts = vec![
+                ("Status", status.label().to_string()),
+                ("ID", crate::app::display_id(exchange.id.as_ref())),
+                ("Transport", exchange.transport.label().to_string()),
+                ("Started", iso8601(timestamp_ms(exchange.timestamp))),
+            ];
+            let duration = crate::app::exchange_duration(exchange);
+            if duration != "-" {
+                facts.push(("Duration", duration));
+            }
+            Section {
+                index: *index,
+                title,
+                status,
+                facts,
+                panels: panels(app, *index, &mut number),
+            }
+        })
+        .collect();
+
+    Report {
+        title: app.session.as_ref().map_or_else(
+            || "JSON-RPC report".to_string(),
+            |session| format!("{} report", session.name),
+        ),
+        summary,
+        errors,
+        sections,
+    }
+}
+
+/// Both bodies, plus any other tab that carries annotations.
+fn panels(app: &App, index: usize, number: &mut usize) -> Vec<Panel> {
+    let mut shown = vec![
+        (Focus::RequestSection, DetailTab::Body),
+        (Focus::ResponseSection, DetailTab::Body),
+    ];
+    for annotation in app
+        .annotations
+        .iter()
+        .filter(|annotation| annotation.exchange_index == index)
+    {
+        if !shown.contains(&(annotation.panel, annotation.tab)) {
+            shown.push((annotation.panel, annotation.tab));
+        }
+    }
+
+    shown
+        .into_iter()
+        .filter_map(|(panel, tab)| {
+            let (offset, lines) = crate::ui::detail_tab_contents(app, panel, index, tab)?;
+            let mut notes = app
+                .annotations
+                .iter()
+                .filter(|annotation| {
+                    annotation.exchange_index == index
+                        && annotation.panel == panel
+                        && annotation.tab == tab
+                })
+                .map(|annotation| {
+                    let line =
+                        |line: usize| line.saturating_sub(offset).clamp(1, lines.len().max(1));
+                    (
+                        line(annotation.start_line),
+                        line(annotation.end_line),
+                        annotation.message.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            notes.sort_by_key(|(start, end, _)| (*start, *end));
+            let notes = notes
+                .into_iter()
+                .map(|(start_line, end_line, message)| {
+                    *number += 1;
+                    Note {
+                        number: *number,
+                        start_line,
+                        end_line,
+                        message,
+                    }
+                })
+                .collect();
+            let side = if panel == Focus::RequestSection {
+                "Request"
+            } else {
+                "Response"
+            };
+            let tab_name = match tab {
+                DetailTab::Headers => "headers",
+                DetailTab::Body => "body",
+                DetailTab::Decoded => "decoded",
+            };
+            Some(Panel {
+                title: format!("{side} {tab_name}"),
+                json: tab == DetailTab::Body,
+                lines,
+                notes,
+            })
+        })
+        .collect()
+}
+
+fn markdown(report: &Report) -> String {
+    let mut out = format!("# {}\n\n", report.title);
+    for (label, value) in &report.summary {
+        out.push_str(&format!("- {label}: {value}\n"));
+    }
+    if !report.errors.is_empty() {
+        out.push_str("\n## Errors\n\n");
+        for error in &report.errors {
+            out.push_str(&format!("- {error}\n"));
+        }
+    }
+
+    for section in &report.sections {
+        out.push_str(&format!("\n## #{} {}\n\n", section.index, section.title));
+        for (label, value) in &section.facts {
+            out.push_str(&format!("- {label}: {value}\n"));
+        }
+        for panel in &section.panels {
+            let language = if panel.json && panel.notes.is_empty() {
+                "json"
+            } else {
+                "text"
+            };
+            out.push_str(&format!("\n### {}\n\n```{language}\n", panel.title));
+            for (index, line) in panel.lines.iter().enumerate() {
+                out.push_str(line);
+                for note in notes_ending_at(panel, index + 1) {
+                    out.push_str(&format!("  ◆ {}", note.number));
+                }
+                out.push('\n');
+            }
+            out.push_str("```\n");
+            if !panel.notes.is_empty() {
+                out.push('\n');
+                for note in &panel.notes {
+                    out.push_str(&format!(
+                        "> ◆ {} ({}): {}\n",
+                        note.number,
+                        line_range(note),
+                        note.message
+                    ));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn html(report: &Report) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n\
+         <table class=\"summary\">\n",
+        title = escape(&report.title)
+    );
+    for (label, value) in &report.summary {
+        out.push_str(&format!(
+            "<tr><th>{label}</th><td>{}</td></tr>\n",
+            escape(value)
+        ));
+    }
+    out.push_str("</table>\n");
+    if !report.errors.is_empty() {
+        out.push_str("<h2>Errors</h2>\n<ul>\n");
+        for error in &report.errors {
+            out.push_str(&format!("<li>{}</li>\n", escape(error)));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    out.push_str("<h2>Exchanges</h2>\n");
+    for section in &report.sections {
+        out.push_str(&format!(
+            "<details{}>\n<summary><span class=\"status {}\">{}</span> #{} {}</summary>\n<dl>\n",
+            if section.is_notable() { " open" } else { "" },
+            section.status.name(),
+            section.status.label(),
+            section.index,
+            escape(&section.title)
+        ));
+        for (label, value) in &section.facts {
+            out.push_str(&format!("<dt>{label}</dt><dd>{}</dd>\n", escape(value)));
+        }
+        out.push_str("</dl>\n");
+        for panel in &section.panels {
+            out.push_str(&format!(
+                "<h3>{}</h3>\n<table class=\"code\">\n",
+                escape(&panel.title)
+            ));
+            for (index, line) in panel.lines.iter().enumerate() {
+                let number = index + 1;
+                let noted = panel
+                    .notes
+                    .iter()
+                    .any(|note| (note.start_line..=note.end_line).contains(&number));
+                let notes = notes_ending_at(panel, number)
+                    .map(|note| {
+                        format!(
+                            "<span class=\"note\">◆ {} {}</span>",
+                            note.number,
+                            escape(&note.message)
+                        )
+                    })
+                    .collect::<String>();
+                out.push_str(&format!(
+                    "<tr{}><td class=\"line\">{number}</td><td><pre>{}</pre></td><td>{notes}</td></tr>\n",
+                    if noted { " class=\"noted\"" } else { "" },
+                    escape(line)
+                ));
+            }
+            out.push_str("</table>\n");
+        }
+        out.push_str("</details>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+const STYLE: &str = "body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }
+table.summary th { text-align: left; padding-right: 1rem; }
+details { border: 1px solid #ddd; border-radius: 4px; margin: 0.5rem 0; padding: 0.25rem 0.75rem; }
+summary { cursor: pointer; font-weight: 600; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0 1rem; }
+dt { color: #666; }
+dd { margin: 0; }
+table.code { border-collapse: collapse; font-family: ui-monospace, monospace; font-size: 0.85rem; }
+table.code td { padding: 0 0.5rem; vertical-align: top; }
+table.code pre { margin: 0; }
+td.line { color: #999; text-align: right; user-select: none; }
+tr.noted { background: #fff4d6; }
+.note { color: #a15c00; display: block; }
+.status { border-radius: 3px; padding: 0 0.4rem; font-size: 0.8rem; background: #eee; }
+.status.success { background: #d9f2dd; }
+.status.error, .status.timedOut { background: #fbdcdc; }
+.status.pending, .status.orphan { background: #fff1c2; }
+";
+
+fn notes_ending_at(panel: &Panel, line: usize) -> impl Iterator<Item = &Note> {
+    panel.notes.iter().filter(move |note| note.end_line == line)
+}
+
+fn line_range(note: &Note) -> String {
+    if note.start_line == note.end_line {
+        format!("line {}", note.start_line)
+    } else {
+        format!("lines {}-{}", note.start_line, note.end_line)
+    }
+}
+
+fn method(exchange: &JsonRpcExchange) -> String {
+    exchange
+        .method
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{testing, LineAnnotation, TransportType};
+    use crate::bookmark::Bookmark;
+    use serde_json::json;
+
+    fn app() -> App {
+        let mut app = testing::app(
+            TransportType::Http,
+            [
+                (
+                    json!({"jsonrpc": "2.0", "id": 1, "method": "eth_call", "params": ["<to>"]}),
+                    0,
+                ),
+                (
+                    json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "reverted"}}),
+                    20,
+                ),
+                (
+                    json!({"jsonrpc": "2.0", "id": 2, "method": "eth_chainId"}),
+                    30,
+                ),
+            ],
+        );
+        app.proxy_config.target_url = "https://rpc.example/".to_string();
+        app.set_bookmark(
+            0,
+            Bookmark {
+                starred: true,
+                tags: vec!["bug".to_string()],
+            },
+        );
+        let (offset, lines) =
+            crate::ui::detail_tab_contents(&app, Focus::RequestSection, 0, DetailTab::Body)
+                .unwrap();
+        let line = lines.iter().position(|line| line.contains("<to>")).unwrap() + 1;
+        app.annotations.push(LineAnnotation {
+            id: "note".to_string(),
+            exchange_index: 0,
+            panel: Focus::RequestSection,
+            tab: DetailTab::Body,
+            start_line: offset + line,
+            end_line: offset + line,
+            message: "wrong <contract>".to_string(),
+            text: Vec::new(),
+        });
+        app
+    }
+
+    #[test]
+    fn markdown_reports_summarize_and_place_annotations() {
+        let app = app();
+        let report = render(&app, &[0, 1], None, Format::Markdown);
+
+        assert!(report.contains("- Target: https://rpc.example/\n"));
+        assert!(report.contains("- Exchanges: 2\n"));
+        assert!(report.contains("- Status: 1 error, 1 pending\n"));
+        assert!(report.contains("## Errors\n\n- #0 eth_call: -32000 reverted\n"));
+        assert!(report.contains("## #0 ★ eth_call #bug\n"));
+        assert!(report.contains("\"<to>\"  ◆ 1\n"), "{report}");
+        assert!(report.contains("> ◆ 1 (line "));
+        assert!(report.contains("wrong <contract>"));
+    }
+
+    #[test]
+    fn html_reports_escape_and_open_notable_exchanges() {
+        let app = app();
+        let report = render(&app, &[0, 1], Some("tag:bug"), Format::Html);
+
+        assert!(report.starts_with("<!DOCTYPE html>"));
+        assert!(report.contains("<th>Scope</th><td>tag:bug</td>"));
+        assert!(report.contains("<details open>\n<summary><span class=\"status error\">"));
+        assert!(report.contains("<details>\n<summary><span class=\"status pending\">"));
+        assert!(report.contains("◆ 1 wrong &lt;contract&gt;"));
+        assert!(!report.contains("<contract>"));
+        assert!(report.contains("<tr class=\"noted\">"));
+    }
+}
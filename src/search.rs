@@ -0,0 +1,147 @@
+use crate::app::Focus;
+use regex::{Regex, RegexBuilder};
+
+/// A search within one panel. Patterns are regular expressions, and fall back to plain text
+/// while they do not parse, so typing `eth_call(` still finds something. A pattern without
+/// capitals ignores case.
+#[derive(Debug, Clone)]
+pub struct Search {
+    pub panel: Focus,
+    pub pattern: String,
+    regex: Option<Regex>,
+    /// The match the cursor last jumped to, as its line and byte offset.
+    pub current: Option<(usize, usize)>,
+}
+
+/// Where a pattern matched: a one-based line and a byte range within its text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Search {
+    pub fn new(panel: Focus, pattern: &str) -> Self {
+        let ignore_case = !pattern.chars().any(char::is_uppercase);
+        let build = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .build()
+                .ok()
+        };
+        let regex = (!pattern.is_empty())
+            .then(|| build(pattern).or_else(|| build(&regex::escape(pattern))))
+            .flatten();
+        Self {
+            panel,
+            pattern: pattern.to_string(),
+            regex,
+            current: None,
+        }
+    }
+
+    /// Every non-empty match in `lines`, in reading order.
+    pub fn matches(&self, lines: &[String]) -> Vec<Match> {
+        let Some(regex) = &self.regex else {
+            return Vec::new();
+        };
+        lines
+            .iter()
+            .enumerate()
+            .flat_map(|(index, text)| {
+                regex
+                    .find_iter(text)
+                    .filter(|found| !found.is_empty())
+                    .map(move |found| Match {
+                        line: index + 1,
+                        start: found.start(),
+                        end: found.end(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// The match to jump to from `from`, wrapping around at either end. `inclusive` also accepts
+/// a match at `from` itself, for the first jump from a line.
+pub fn step(
+    matches: &[Match],
+    from: (usize, usize),
+    forward: bool,
+    inclusive: bool,
+) -> Option<usize> {
+    let position = |found: &Match| (found.line, found.start);
+    if forward {
+        matches
+            .iter()
+            .position(|found| position(found) > from || (inclusive && position(found) == from))
+            .or((!matches.is_empty()).then_some(0))
+    } else {
+        matches
+            .iter()
+            .rposition(|found| position(found) < from || (inclusive && position(found) == from))
+            .or(matches.len().checked_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_use_regex_smart_case_and_wrap_around() {
+        let lines = [
+            "\"method\": \"eth_call\",",
+            "\"params\": [",
+            "  \"0xAb\", \"0xab\"",
+        ]
+        .map(str::to_string);
+
+        let search = Search::new(Focus::ResponseSection, "0xab");
+        let found = search.matches(&lines);
+        assert_eq!(
+            found,
+            [
+                Match {
+                    line: 3,
+                    start: 3,
+                    end: 7
+                },
+                Match {
+                    line: 3,
+                    start: 11,
+                    end: 15
+                },
+            ]
+        );
+        assert_eq!(
+            Search::new(Focus::ResponseSection, "0xAb")
+                .matches(&lines)
+                .len(),
+            1
+        );
+        assert_eq!(
+            Search::new(Focus::ResponseSection, r#""(method|params)""#)
+                .matches(&lines)
+                .len(),
+            2
+        );
+        assert_eq!(
+            Search::new(Focus::ResponseSection, "eth_call(")
+                .matches(&lines)
+                .len(),
+            0
+        );
+        assert_eq!(
+            Search::new(Focus::ResponseSection, "[").matches(&lines)[0].line,
+            2
+        );
+
+        assert_eq!(step(&found, (3, 0), true, true), Some(0));
+        assert_eq!(step(&found, (3, 3), true, false), Some(1));
+        assert_eq!(step(&found, (3, 11), true, false), Some(0));
+        assert_eq!(step(&found, (3, 3), false, false), Some(1));
+        assert_eq!(step(&[], (1, 0), true, false), None);
+    }
+}
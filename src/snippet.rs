@@ -0,0 +1,476 @@
+use crate::app::{message_body, App, JsonRpcExchange, MAIN_LISTENER};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+/// What a snippet reproduces: one request as a command, or the whole session as a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Curl,
+    Httpie,
+    Shell,
+    Python,
+    TypeScript,
+    Rust,
+}
+
+impl Format {
+    pub const ALL: [Self; 6] = [
+        Self::Curl,
+        Self::Httpie,
+        Self::Shell,
+        Self::Python,
+        Self::TypeScript,
+        Self::Rust,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Curl => "curl",
+            Self::Httpie => "httpie",
+            Self::Shell => "shell",
+            Self::Python => "python",
+            Self::TypeScript => "typescript",
+            Self::Rust => "rust",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Curl => "curl command",
+            Self::Httpie => "HTTPie command",
+            Self::Shell => "shell script",
+            Self::Python => "Python script",
+            Self::TypeScript => "TypeScript script",
+            Self::Rust => "Rust program",
+        }
+    }
+
+    /// Scripts replay the whole session; commands reproduce one request.
+    pub fn is_script(self) -> bool {
+        !matches!(self, Self::Curl | Self::Httpie)
+    }
+}
+
+/// One HTTP request to send again: the wire body and the headers worth repeating.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub body: Value,
+    pub headers: Vec<(String, String)>,
+    /// The listener that carried the request when the debugger runs several.
+    pub source: Option<String>,
+}
+
+impl Call {
+    /// The listener a script sends this call through.
+    pub fn listener(&self) -> &str {
+        self.source.as_deref().unwrap_or(MAIN_LISTENER)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    pub url: String,
+    pub text: String,
+}
+
+/// Where snippets send requests: the recorded target, or this debugger's HTTP proxy so the
+/// replay shows up in the request list. `source` picks a named listener's target or port;
+/// unknown names fall back to the main listener.
+pub fn url(app: &App, source: Option<&str>, via_proxy: bool) -> Result<String, String> {
+    let listener = source.and_then(|name| app.proxy_config.listener(name));
+    if via_proxy {
+        if app.proxy_config.transparent {
+            return Err("This debugger has no HTTP proxy".to_string());
+        }
+        let port = listener.map_or(app.proxy_config.listen_port, |listener| {
+            listener.listen_port
+        });
+        return Ok(format!("http://127.0.0.1:{port}"));
+    }
+    let target = listener.map_or(&app.proxy_config.target_url, |listener| {
+        &listener.target_url
+    });
+    if target.starts_with("http://") || target.starts_with("https://") {
+        Ok(target.clone())
+    } else {
+        Err("The target is not an HTTP URL; send through the proxy instead".to_string())
+    }
+}
+
+/// A command for the exchange at `index`, or a script replaying every request of the session
+/// in arrival order.
+pub fn export(app: &App, format: Format, index: usize, via_proxy: bool) -> Result<Snippet, String> {
+    // Scripts are shown with the main listener's URL and send each call through its own.
+    let source = app
+        .exchanges
+        .get(index)
+        .filter(|_| !format.is_script())
+        .and_then(JsonRpcExchange::source);
+    let url = url(app, source, via_proxy)?;
+    let text = if format.is_script() {
+        let calls = calls(&app.exchanges);
+        if calls.is_empty() {
+            return Err("The session has no requests".to_string());
+        }
+        let mut urls = Vec::<(&str, String)>::new();
+        for call in &calls {
+            let listener = call.listener();
+            if !urls.iter().any(|(name, _)| *name == listener) {
+                urls.push((listener, self::url(app, Some(listener), via_proxy)?));
+            }
+        }
+        script(format, &urls, &calls)
+    } else {
+        let call = call(&app.exchanges, index)
+            .ok_or_else(|| format!("Exchange {index} has no request"))?;
+        command(format, &shell_quote(&url), &call)
+    };
+    Ok(Snippet { url, text })
+}
+
+/// Every request of a session in arrival order, with the calls of a batch sent together.
+pub fn calls(exchanges: &[JsonRpcExchange]) -> Vec<Call> {
+    let mut sent_batches = HashSet::new();
+    (0..exchanges.len())
+        .filter(|index| {
+            let batch = exchanges[*index]
+                .request
+                .as_ref()
+                .and_then(|request| request.batch.as_ref());
+            match batch {
+                Some(batch) => sent_batches.insert(batch.id.as_str()),
+                None => true,
+            }
+        })
+        .filter_map(|index| call(exchanges, index))
+        .collect()
+}
+
+/// The request of one exchange, or of its whole batch.
+pub fn call(exchanges: &[JsonRpcExchange], index: usize) -> Option<Call> {
+    let exchange = exchanges.get(index)?;
+    let request = exchange.request.as_ref()?;
+    let body = match &request.batch {
+        Some(batch) => {
+            let mut members = exchanges
+                .iter()
+                .filter_map(|exchange| exchange.request.as_ref())
+                .filter(|member| {
+                    member
+                        .batch
+                        .as_ref()
+                        .is_some_and(|position| position.id == batch.id)
+                })
+                .collect::<Vec<_>>();
+            members.sort_by_key(|member| member.batch.as_ref().map(|batch| batch.position));
+            Value::Array(members.into_iter().map(message_body).collect())
+        }
+        None => message_body(request),
+    };
+
+    let mut headers = request
+        .headers
+        .iter()
+        .flatten()
+        .filter(|(name, _)| should_replay_header(name))
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+        .collect::<Vec<_>>();
+    if !headers.iter().any(|(name, _)| name == "content-type") {
+        headers.push(("content-type".to_string(), "application/json".to_string()));
+    }
+    headers.sort();
+    Some(Call {
+        body,
+        headers,
+        source: exchange.source().map(str::to_string),
+    })
+}
+
+/// Whether a recorded header belongs to the request rather than to the connection it came
+/// over, such as `Host` and `Content-Length`.
+pub fn should_replay_header(name: &str) -> bool {
+    !matches!(
+        name.to_ascii_lowercase().as_str(),
+        "host" | "content-length" | "transfer-encoding" | "connection" | "accept-encoding"
+    )
+}
+
+fn command(format: Format, url: &str, call: &Call) -> String {
+    let body = shell_quote(&call.body.to_string());
+    match format {
+        Format::Httpie => {
+            let headers = call
+                .headers
+                .iter()
+                .map(|(name, value)| format!(" \\\n  {}", shell_quote(&format!("{name}:{value}"))))
+                .collect::<String>();
+            format!("http POST {url}{headers} \\\n  --raw {body}")
+        }
+        _ => {
+            let headers = call
+                .headers
+                .iter()
+                .map(|(name, value)| {
+                    format!(" \\\n  -H {}", shell_quote(&format!("{name}: {value}")))
+                })
+                .collect::<String>();
+            format!("curl -sS {url}{headers} \\\n  --data-raw {body}")
+        }
+    }
+}
+
+/// A script sending each call to the URL of its listener, from `urls` by listener name.
+fn script(format: Format, urls: &[(&str, String)], calls: &[Call]) -> String {
+    let summary = format!(
+        "Replays {} JSON-RPC request{} recorded by jsonrpc-debugger, in order.",
+        calls.len(),
+        if calls.len() == 1 { "" } else { "s" }
+    );
+    let requests = Value::Array(
+        calls
+            .iter()
+            .map(|call| {
+                json!({
+                    "listener": call.listener(),
+                    "headers": call
+                        .headers
+                        .iter()
+                        .map(|(name, value)| (name.clone(), json!(value)))
+                        .collect::<serde_json::Map<_, _>>(),
+                    "body": call.body,
+                })
+            })
+            .collect(),
+    );
+    let pretty = serde_json::to_string_pretty(&requests).unwrap_or_else(|_| requests.to_string());
+    let urls_literal = Value::Object(
+        urls.iter()
+            .map(|(name, url)| (name.to_string(), json!(url)))
+            .collect(),
+    )
+    .to_string();
+
+    match format {
+        Format::Python => format!(
+            "#!/usr/bin/env python3\n\
+             \"\"\"{summary}\"\"\"\n\
+             import json\n\
+             import urllib.request\n\
+             \n\
+             URLS = {urls_literal}\n\
+             REQUESTS = json.loads({requests})\n\
+             \n\
+             for request in REQUESTS:\n\
+             \x20   sent = urllib.request.Request(\n\
+             \x20       URLS[request[\"listener\"]],\n\
+             \x20       data=json.dumps(request[\"body\"]).encode(),\n\
+             \x20       headers=request[\"headers\"],\n\
+             \x20       method=\"POST\",\n\
+             \x20   )\n\
+             \x20   with urllib.request.urlopen(sent) as response:\n\
+             \x20       print(response.read().decode())\n",
+            requests = json!(requests.to_string()),
+        ),
+        Format::TypeScript => format!(
+            "// {summary}\n\
+             // Run with Node 18 or later, for example `npx tsx replay.ts`.\n\
+             const urls: Record<string, string> = {urls_literal};\n\
+             const requests: {{ listener: string; headers: Record<string, string>; body: unknown }}[] = {pretty};\n\
+             \n\
+             for (const {{ listener, headers, body }} of requests) {{\n\
+             \x20 const response = await fetch(urls[listener], {{\n\
+             \x20   method: \"POST\",\n\
+             \x20   headers,\n\
+             \x20   body: JSON.stringify(body),\n\
+             \x20 }});\n\
+             \x20 console.log(await response.text());\n\
+             }}\n\
+             \n\
+             export {{}};\n"
+        ),
+        Format::Rust => {
+            let text = requests.to_string();
+            let hashes = "#".repeat(raw_string_hashes(&text).max(raw_string_hashes(&urls_literal)));
+            format!(
+                "//! {summary}\n\
+                 //!\n\
+                 //! Dependencies: reqwest = {{ version = \"0.12\", features = [\"blocking\"] }},\n\
+                 //! serde_json = \"1\".\n\
+                 \n\
+                 const URLS: &str = r{hashes}\"{urls_literal}\"{hashes};\n\
+                 const REQUESTS: &str = r{hashes}\"{text}\"{hashes};\n\
+                 \n\
+                 fn main() -> Result<(), Box<dyn std::error::Error>> {{\n\
+                 \x20   let client = reqwest::blocking::Client::new();\n\
+                 \x20   let urls: serde_json::Value = serde_json::from_str(URLS)?;\n\
+                 \x20   let requests: Vec<serde_json::Value> = serde_json::from_str(REQUESTS)?;\n\
+                 \x20   for request in requests {{\n\
+                 \x20       let url = urls[request[\"listener\"].as_str().unwrap_or_default()].as_str();\n\
+                 \x20       let mut sent = client.post(url.unwrap_or_default()).body(request[\"body\"].to_string());\n\
+                 \x20       for (name, value) in request[\"headers\"].as_object().into_iter().flatten() {{\n\
+                 \x20           sent = sent.header(name.as_str(), value.as_str().unwrap_or_default());\n\
+                 \x20       }}\n\
+                 \x20       println!(\"{{}}\", sent.send()?.text()?);\n\
+                 \x20   }}\n\
+                 \x20   Ok(())\n\
+                 }}\n"
+            )
+        }
+        Format::Shell | Format::Curl | Format::Httpie => {
+            let variables = urls
+                .iter()
+                .map(|(name, url)| format!("{}={}\n", url_variable(name), shell_quote(url)))
+                .collect::<String>();
+            let commands = calls
+                .iter()
+                .map(|call| {
+                    let url = format!("\"${}\"", url_variable(call.listener()));
+                    format!("{}\necho\n", command(Format::Curl, &url, call))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("#!/bin/sh\n# {summary}\nset -eu\n\n{variables}\n{commands}")
+        }
+    }
+}
+
+/// The shell variable holding a listener's URL: `URL` for the main one, `URL_ETH` for `eth`.
+fn url_variable(listener: &str) -> String {
+    if listener == MAIN_LISTENER {
+        return "URL".to_string();
+    }
+    let name = listener
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("URL_{name}")
+}
+
+/// Enough `#`s that a raw string holding `text` cannot end early.
+fn raw_string_hashes(text: &str) -> usize {
+    (1..)
+        .find(|count| !text.contains(&format!("\"{}", "#".repeat(*count))))
+        .unwrap_or(1)
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{json_rpc_messages_by_shape, testing, TransportType};
+    use std::collections::HashMap;
+
+    fn app() -> App {
+        let mut app = testing::app(
+            TransportType::Http,
+            [(
+                json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"}),
+                0,
+            )],
+        );
+        app.proxy_config.target_url = "https://rpc.example/".to_string();
+        app.exchanges[0].request.as_mut().unwrap().headers = Some(HashMap::from([
+            ("Authorization".to_string(), "Bearer it's".to_string()),
+            ("Host".to_string(), "rpc.example".to_string()),
+        ]));
+        testing::record(
+            &mut app,
+            &json!([
+                {"jsonrpc": "2.0", "id": 2, "method": "eth_blockNumber"},
+                {"jsonrpc": "2.0", "id": 3, "method": "net_version"},
+            ]),
+            TransportType::HttpBatch,
+            0,
+        );
+        app
+    }
+
+    #[test]
+    fn commands_quote_headers_and_bodies_for_the_shell() {
+        let app = app();
+
+        let curl = export(&app, Format::Curl, 0, false).unwrap();
+        assert_eq!(
+            curl.text,
+            "curl -sS 'https://rpc.example/' \\\n  \
+             -H 'authorization: Bearer it'\\''s' \\\n  \
+             -H 'content-type: application/json' \\\n  \
+             --data-raw '{\"id\":1,\"jsonrpc\":\"2.0\",\"method\":\"eth_chainId\"}'"
+        );
+        let httpie = export(&app, Format::Httpie, 2, true).unwrap();
+        assert!(httpie.text.starts_with("http POST 'http://127.0.0.1:8080'"));
+        assert!(httpie.text.contains("--raw '[{"));
+        assert!(httpie.text.contains("net_version"));
+    }
+
+    #[test]
+    fn scripts_replay_each_request_once_in_order() {
+        let app = app();
+        let calls = calls(&app.exchanges);
+        assert_eq!(calls.len(), 2);
+        assert!(calls[1].body.is_array());
+
+        let shell = export(&app, Format::Shell, 0, false).unwrap().text;
+        assert_eq!(shell.matches("curl -sS \"$URL\"").count(), 2);
+        for format in [Format::Python, Format::TypeScript, Format::Rust] {
+            let script = export(&app, format, 0, false).unwrap().text;
+            assert!(script.contains("Replays 2 JSON-RPC requests"), "{script}");
+            assert!(script.contains("eth_blockNumber"), "{script}");
+        }
+        assert_eq!(raw_string_hashes("a\"#b"), 2);
+    }
+
+    #[test]
+    fn scripts_send_each_call_through_its_listener() {
+        let mut app = app();
+        app.proxy_config.listeners.push(crate::app::Listener {
+            name: "l2-node".to_string(),
+            listen_port: 8090,
+            target_url: "https://l2.example/".to_string(),
+            stdio: None,
+        });
+        let request = json!({"jsonrpc": "2.0", "id": 4, "method": "eth_gasPrice"});
+        for mut message in json_rpc_messages_by_shape(&request, TransportType::Http, None) {
+            message.connection = Some(crate::app::Connection {
+                id: "l2".to_string(),
+                client: "127.0.0.1:5000".to_string(),
+                source: Some("l2-node".to_string()),
+            });
+            app.add_message(message);
+        }
+
+        let shell = export(&app, Format::Shell, 0, false).unwrap();
+        assert_eq!(shell.url, "https://rpc.example/");
+        assert!(shell.text.contains("URL='https://rpc.example/'\n"));
+        assert!(shell.text.contains("URL_L2_NODE='https://l2.example/'\n"));
+        assert_eq!(shell.text.matches("curl -sS \"$URL\"").count(), 2);
+        assert_eq!(shell.text.matches("curl -sS \"$URL_L2_NODE\"").count(), 1);
+        let python = export(&app, Format::Python, 0, true).unwrap().text;
+        assert!(python.contains(
+            r#"URLS = {"l2-node":"http://127.0.0.1:8090","main":"http://127.0.0.1:8080"}"#
+        ));
+    }
+
+    #[test]
+    fn stdio_targets_need_the_proxy() {
+        let mut app = app();
+        app.proxy_config.target_url = "node server.js".to_string();
+        assert!(export(&app, Format::Curl, 0, false).is_err());
+        assert!(export(&app, Format::Curl, 0, true).is_ok());
+        app.proxy_config.transparent = true;
+        assert!(export(&app, Format::Curl, 0, true).is_err());
+    }
+}
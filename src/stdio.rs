@@ -1,6 +1,7 @@
 pub use crate::app::Framing;
 use crate::app::{
-    incoming_json_rpc_messages, json_rpc_messages_by_shape, JsonRpcMessage, TransportType,
+    incoming_json_rpc_messages, json_rpc_messages_by_shape, Connection, JsonRpcMessage,
+    MessageDirection, TransportType,
 };
 use serde_json::Value;
 use std::{
@@ -33,7 +34,7 @@ impl Framer {
         let body = serde_json::to_vec(message).map_err(|error| error.to_string())?;
         Ok(match self.framing {
             Framing::JsonLines => [body.as_slice(), b"\n"].concat(),
-            Framing::ContentLength => [
+            Framing::ContentLength | Framing::Dap => [
                 format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes(),
                 body.as_slice(),
             ]
@@ -45,7 +46,7 @@ impl Framer {
         self.buffer.extend_from_slice(chunk);
         match self.framing {
             Framing::JsonLines => self.decode_json_lines(),
-            Framing::ContentLength => self.decode_content_length(),
+            Framing::ContentLength | Framing::Dap => self.decode_content_length(),
         }
     }
 
@@ -129,6 +130,7 @@ where
         server_writer,
         framing,
         transport,
+        false,
         message_sender.clone(),
     );
     let server_to_client = forward_frames(
@@ -136,6 +138,7 @@ where
         client_writer,
         framing,
         transport,
+        true,
         message_sender,
     );
     tokio::pin!(client_to_server, server_to_client);
@@ -205,11 +208,14 @@ pub async fn wrap(
     Ok(())
 }
 
+/// Copies frames from one peer to the other, logging what they carry. `from_server` says which
+/// peer is writing, so requests each side starts pair only with the other side's responses.
 async fn forward_frames<Reader, Writer>(
     mut reader: Reader,
     mut writer: Writer,
     framing: Framing,
     transport: TransportType,
+    from_server: bool,
     message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
 ) -> Result<(), String>
 where
@@ -239,7 +245,14 @@ where
         match decoder.decode(&chunk[..count]) {
             Ok(bodies) => {
                 for body in bodies {
-                    for message in json_rpc_messages_by_shape(&body, transport, None) {
+                    for mut message in json_rpc_messages_by_shape(&body, transport, None) {
+                        let server_started =
+                            (message.direction == MessageDirection::Request) == from_server;
+                        message.connection = Some(if server_started {
+                            Connection::stdio_server()
+                        } else {
+                            Connection::stdio()
+                        });
                         let _ = message_sender.send(message);
                     }
                 }
@@ -274,6 +287,7 @@ impl Drop for StreamTransportInner {
 enum StreamCommand {
     Send {
         message: Value,
+        connection: Option<Connection>,
         reply: oneshot::Sender<Result<Value, String>>,
     },
 }
@@ -284,8 +298,18 @@ enum Incoming {
     Error(String),
 }
 
+/// A request written to the stream, with the id its client chose. It goes out under another
+/// id only when a different connection already has that id in flight.
+struct WireRequest {
+    call: u64,
+    id: Value,
+    wire_id: Value,
+    connection: Option<String>,
+}
+
 struct PendingCall {
     batch: bool,
+    connection: Option<Connection>,
     remaining: usize,
     responses: Vec<Value>,
     reply: Option<oneshot::Sender<Result<Value, String>>>,
@@ -320,11 +344,20 @@ impl StreamTransport {
         }
     }
 
-    pub async fn send(&self, message: Value) -> Result<Value, String> {
+    /// Writes `message` and waits for its responses, which are recorded on `connection`.
+    pub async fn send(
+        &self,
+        message: Value,
+        connection: Option<Connection>,
+    ) -> Result<Value, String> {
         let (reply, response) = oneshot::channel();
         self.inner
             .commands
-            .send(StreamCommand::Send { message, reply })
+            .send(StreamCommand::Send {
+                message,
+                connection,
+                reply,
+            })
             .map_err(|_| "stdio stream is closed".to_string())?;
         response
             .await
@@ -347,15 +380,23 @@ async fn run_stream<R, W>(
     let reader = tokio::spawn(read_stream(reader, framing, incoming_sender));
     let encoder = Framer::new(framing);
     let mut next_call_id = 0_u64;
-    let mut call_by_rpc_id = HashMap::<String, u64>::new();
+    let mut next_wire_id = 0_u64;
+    let mut requests = HashMap::<String, WireRequest>::new();
     let mut calls = HashMap::<u64, PendingCall>::new();
 
     loop {
         tokio::select! {
             command = commands.recv() => {
-                let Some(StreamCommand::Send { message, reply }) = command else {
+                let Some(StreamCommand::Send { mut message, connection, reply }) = command else {
                     break;
                 };
+                if let Err(error) = check_request_ids(&message, framing) {
+                    let _ = reply.send(Err(error));
+                    continue;
+                }
+                let connection_id = connection.as_ref().map(|connection| connection.id.clone());
+                translate_cancellations(&mut message, framing, connection_id.as_deref(), &requests);
+                let ids = assign_wire_ids(&mut message, framing, &requests, &mut next_wire_id);
                 let frame = match encoder.encode(&message) {
                     Ok(frame) => frame,
                     Err(error) => {
@@ -363,13 +404,6 @@ async fn run_stream<R, W>(
                         continue;
                     }
                 };
-                let ids = match request_ids(&message, &call_by_rpc_id) {
-                    Ok(ids) => ids,
-                    Err(error) => {
-                        let _ = reply.send(Err(error));
-                        continue;
-                    }
-                };
                 if let Err(error) = writer.write_all(&frame).await {
                     let _ = reply.send(Err(error.to_string()));
                     continue;
@@ -385,22 +419,33 @@ async fn run_stream<R, W>(
 
                 let call_id = next_call_id;
                 next_call_id = next_call_id.wrapping_add(1);
-                for id in &ids {
-                    call_by_rpc_id.insert(id.clone(), call_id);
-                }
                 calls.insert(call_id, PendingCall {
                     batch: message.is_array(),
+                    connection,
                     remaining: ids.len(),
                     responses: Vec::with_capacity(ids.len()),
                     reply: Some(reply),
                 });
+                for (id, wire_id) in ids {
+                    requests.insert(wire_id.to_string(), WireRequest {
+                        call: call_id,
+                        id,
+                        wire_id,
+                        connection: connection_id.clone(),
+                    });
+                }
             }
             event = incoming.recv() => match event {
-                Some(Incoming::Message(message)) => {
-                    for rpc_message in incoming_json_rpc_messages(&message, transport) {
+                Some(Incoming::Message(mut message)) => {
+                    let answered = restore_ids(&mut message, framing, &mut requests);
+                    let rpc_messages = incoming_json_rpc_messages(&message, transport);
+                    for (mut rpc_message, call_id) in rpc_messages.into_iter().zip(&answered) {
+                        if let Some(call) = call_id.and_then(|call_id| calls.get(&call_id)) {
+                            rpc_message.connection = call.connection.clone();
+                        }
                         let _ = message_sender.send(rpc_message);
                     }
-                    resolve_responses(&message, &mut call_by_rpc_id, &mut calls);
+                    resolve_responses(&message, &answered, &mut calls);
                 }
                 Some(Incoming::Error(error)) => {
                     fail_pending(&mut calls, error);
@@ -451,44 +496,151 @@ where
     }
 }
 
-fn request_ids(message: &Value, pending: &HashMap<String, u64>) -> Result<Vec<String>, String> {
-    let messages = message
+// DAP requests are keyed by `seq` and answered with a matching `request_seq`.
+fn request_key(message: &Value, framing: Framing) -> Option<String> {
+    if framing == Framing::Dap {
+        return (message.get("type") == Some(&Value::from("request")))
+            .then(|| message.get("seq").map(Value::to_string))
+            .flatten();
+    }
+    message.get("method")?;
+    message.get("id").map(Value::to_string)
+}
+
+fn response_key(message: &Value, framing: Framing) -> Option<String> {
+    if framing == Framing::Dap {
+        return (message.get("type") == Some(&Value::from("response")))
+            .then(|| message.get("request_seq").map(Value::to_string))
+            .flatten();
+    }
+    if message.get("method").is_some() {
+        return None;
+    }
+    message.get("id").map(Value::to_string)
+}
+
+fn frames(message: &Value) -> &[Value] {
+    message
         .as_array()
         .map(Vec::as_slice)
-        .unwrap_or_else(|| std::slice::from_ref(message));
-    let ids = messages
+        .unwrap_or_else(|| std::slice::from_ref(message))
+}
+
+fn frames_mut(message: &mut Value) -> &mut [Value] {
+    match message {
+        Value::Array(messages) => messages,
+        message => std::slice::from_mut(message),
+    }
+}
+
+fn check_request_ids(message: &Value, framing: Framing) -> Result<(), String> {
+    let ids = frames(message)
         .iter()
-        .filter(|message| message.get("method").is_some())
-        .filter_map(|message| message.get("id"))
-        .map(Value::to_string)
+        .filter_map(|message| request_key(message, framing))
         .collect::<Vec<_>>();
     let unique = ids.iter().collect::<HashSet<_>>();
     if unique.len() != ids.len() {
         return Err("request IDs must be unique within a batch".to_string());
     }
-    if let Some(id) = ids.iter().find(|id| pending.contains_key(*id)) {
-        return Err(format!("request ID is already pending: {id}"));
+    Ok(())
+}
+
+// Clients on different connections may reuse ids. A request whose id is already in flight
+// goes to the stream under a free one instead. Returns each client id with its wire id.
+fn assign_wire_ids(
+    message: &mut Value,
+    framing: Framing,
+    requests: &HashMap<String, WireRequest>,
+    next_wire_id: &mut u64,
+) -> Vec<(Value, Value)> {
+    let field = if framing == Framing::Dap { "seq" } else { "id" };
+    let mut taken = frames(message)
+        .iter()
+        .filter_map(|message| request_key(message, framing))
+        .collect::<HashSet<_>>();
+    frames_mut(message)
+        .iter_mut()
+        .filter(|message| request_key(message, framing).is_some())
+        .map(|request| {
+            let id = request[field].clone();
+            if !requests.contains_key(&id.to_string()) {
+                return (id.clone(), id);
+            }
+            let wire_id = loop {
+                let wire_id = Value::from(*next_wire_id);
+                *next_wire_id = next_wire_id.wrapping_add(1);
+                let key = wire_id.to_string();
+                if !requests.contains_key(&key) && taken.insert(key) {
+                    break wire_id;
+                }
+            };
+            request[field] = wire_id.clone();
+            (id, wire_id)
+        })
+        .collect()
+}
+
+// Cancellations name the request they cancel by the client's id, so they follow the request
+// when it went out under another id.
+fn translate_cancellations(
+    message: &mut Value,
+    framing: Framing,
+    connection: Option<&str>,
+    requests: &HashMap<String, WireRequest>,
+) {
+    for frame in frames_mut(message) {
+        let reference = if framing == Framing::Dap {
+            (frame.get("command").and_then(Value::as_str) == Some("cancel"))
+                .then(|| frame.pointer_mut("/arguments/requestId"))
+                .flatten()
+        } else {
+            match frame.get("method").and_then(Value::as_str) {
+                Some("$/cancelRequest") => frame.pointer_mut("/params/id"),
+                Some("notifications/cancelled") => frame.pointer_mut("/params/requestId"),
+                _ => None,
+            }
+        };
+        let Some(reference) = reference else {
+            continue;
+        };
+        if let Some(request) = requests
+            .values()
+            .find(|request| request.connection.as_deref() == connection && request.id == *reference)
+        {
+            *reference = request.wire_id.clone();
+        }
     }
-    Ok(ids)
+}
+
+// Puts the client's ids back on responses to requests written under wire ids, returning the
+// call each frame answers.
+fn restore_ids(
+    message: &mut Value,
+    framing: Framing,
+    requests: &mut HashMap<String, WireRequest>,
+) -> Vec<Option<u64>> {
+    let field = if framing == Framing::Dap {
+        "request_seq"
+    } else {
+        "id"
+    };
+    frames_mut(message)
+        .iter_mut()
+        .map(|response| {
+            let request = requests.remove(&response_key(response, framing)?)?;
+            response[field] = request.id;
+            Some(request.call)
+        })
+        .collect()
 }
 
 fn resolve_responses(
     message: &Value,
-    call_by_rpc_id: &mut HashMap<String, u64>,
+    answered: &[Option<u64>],
     calls: &mut HashMap<u64, PendingCall>,
 ) {
-    let responses = message
-        .as_array()
-        .map(Vec::as_slice)
-        .unwrap_or_else(|| std::slice::from_ref(message));
-    for response in responses {
-        if response.get("method").is_some() {
-            continue;
-        }
-        let Some(id) = response.get("id").map(Value::to_string) else {
-            continue;
-        };
-        let Some(call_id) = call_by_rpc_id.remove(&id) else {
+    for (response, call_id) in frames(message).iter().zip(answered) {
+        let Some(call_id) = *call_id else {
             continue;
         };
         let Some(call) = calls.get_mut(&call_id) else {
@@ -542,6 +694,7 @@ impl Drop for ChildProcess {
 impl StdioTransport {
     pub fn spawn(
         command: &[OsString],
+        env: &[(OsString, OsString)],
         framing: Framing,
         message_sender: mpsc::UnboundedSender<JsonRpcMessage>,
     ) -> Result<Self, String> {
@@ -550,6 +703,7 @@ impl StdioTransport {
             .ok_or_else(|| "stdio command cannot be empty".to_string())?;
         let mut child = Command::new(program)
             .args(args)
+            .envs(env.iter().map(|(name, value)| (name, value)))
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -588,8 +742,12 @@ impl StdioTransport {
         })
     }
 
-    pub async fn send(&self, message: Value) -> Result<Value, String> {
-        self.stream.send(message).await
+    pub async fn send(
+        &self,
+        message: Value,
+        connection: Option<Connection>,
+    ) -> Result<Value, String> {
+        self.stream.send(message, connection).await
     }
 }
 
@@ -0,0 +1,183 @@
+use crate::app::JsonRpcExchange;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A `*_subscribe` call and the notifications delivered for the id it returned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subscription {
+    pub id: String,
+    pub method: String,
+    pub kind: Option<String>,
+    pub request: usize,
+    pub events: Vec<usize>,
+    pub unsubscribe: Option<usize>,
+}
+
+impl Subscription {
+    pub fn is_active(&self) -> bool {
+        self.unsubscribe.is_none()
+    }
+
+    pub fn label(&self) -> String {
+        match &self.kind {
+            Some(kind) => format!("{} · {kind}", self.method),
+            None => self.method.clone(),
+        }
+    }
+
+    /// Events per second between the first and latest event.
+    pub fn rate(&self, exchanges: &[JsonRpcExchange]) -> Option<f64> {
+        let timestamp = |index: &usize| {
+            exchanges
+                .get(*index)
+                .and_then(|exchange| exchange.request.as_ref())
+                .map(|request| request.timestamp)
+        };
+        let first = timestamp(self.events.first()?)?;
+        let last = timestamp(self.events.last()?)?;
+        let seconds = last.duration_since(first).ok()?.as_secs_f64();
+        (seconds > 0.0).then(|| (self.events.len() - 1) as f64 / seconds)
+    }
+}
+
+/// Subscriptions in the order they were requested.
+pub fn subscriptions(exchanges: &[JsonRpcExchange]) -> Vec<Subscription> {
+    let mut subscriptions: Vec<Subscription> = Vec::new();
+    let mut open: HashMap<String, usize> = HashMap::new();
+
+    for (index, exchange) in exchanges.iter().enumerate() {
+        let method = exchange.method.as_deref().unwrap_or_default();
+        if exchange.is_notification() {
+            let Some(id) = notification_subscription(exchange) else {
+                continue;
+            };
+            if let Some(position) = open.get(&id) {
+                subscriptions[*position].events.push(index);
+            }
+        } else if method.ends_with("_subscribe") {
+            let Some(id) = exchange
+                .response
+                .as_ref()
+                .and_then(|response| response.result.as_ref())
+                .and_then(subscription_id)
+            else {
+                continue;
+            };
+            let kind = exchange
+                .request
+                .as_ref()
+                .and_then(|request| request.params.as_ref())
+                .and_then(|params| params.get(0))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            open.insert(id.clone(), subscriptions.len());
+            subscriptions.push(Subscription {
+                id,
+                method: method.to_string(),
+                kind,
+                request: index,
+                events: Vec::new(),
+                unsubscribe: None,
+            });
+        } else if method.ends_with("_unsubscribe") {
+            let id = exchange
+                .request
+                .as_ref()
+                .and_then(|request| request.params.as_ref())
+                .and_then(|params| params.get(0))
+                .and_then(subscription_id);
+            if let Some(position) = id.and_then(|id| open.remove(&id)) {
+                subscriptions[position].unsubscribe = Some(index);
+            }
+        }
+    }
+
+    subscriptions
+}
+
+/// The subscription id carried by a notification in `params.subscription`.
+pub fn notification_subscription(exchange: &JsonRpcExchange) -> Option<String> {
+    exchange
+        .request
+        .as_ref()?
+        .params
+        .as_ref()?
+        .get("subscription")
+        .and_then(subscription_id)
+}
+
+/// The subscription that `index` starts, receives, or ends.
+pub fn containing(subscriptions: &[Subscription], index: usize) -> Option<&Subscription> {
+    subscriptions.iter().find(|subscription| {
+        subscription.request == index
+            || subscription.unsubscribe == Some(index)
+            || subscription.events.binary_search(&index).is_ok()
+    })
+}
+
+fn subscription_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{testing, TransportType};
+    use serde_json::json;
+
+    fn notification(subscription: &str) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "eth_subscription",
+            "params": {"subscription": subscription, "result": {}},
+        })
+    }
+
+    #[test]
+    fn notifications_group_under_their_subscribe_call() {
+        let app = testing::app(
+            TransportType::WebSocket,
+            [
+                (
+                    json!({"jsonrpc": "2.0", "id": 1, "method": "eth_subscribe", "params": ["newHeads"]}),
+                    0,
+                ),
+                (json!({"jsonrpc": "2.0", "id": 1, "result": "0xa"}), 0),
+                (notification("0xa"), 1_000),
+                (notification("0xb"), 1_000),
+                (notification("0xa"), 2_000),
+                (
+                    json!({"jsonrpc": "2.0", "id": 2, "method": "eth_unsubscribe", "params": ["0xa"]}),
+                    3_000,
+                ),
+                (notification("0xa"), 4_000),
+            ],
+        );
+
+        let subscriptions = subscriptions(&app.exchanges);
+
+        assert_eq!(
+            subscriptions,
+            vec![Subscription {
+                id: "0xa".to_string(),
+                method: "eth_subscribe".to_string(),
+                kind: Some("newHeads".to_string()),
+                request: 0,
+                events: vec![1, 3],
+                unsubscribe: Some(4),
+            }]
+        );
+        assert_eq!(subscriptions[0].label(), "eth_subscribe · newHeads");
+        assert_eq!(subscriptions[0].rate(&app.exchanges), Some(1.0));
+        assert!(!subscriptions[0].is_active());
+        assert_eq!(
+            containing(&subscriptions, 3).map(|subscription| subscription.request),
+            Some(0)
+        );
+        assert_eq!(containing(&subscriptions, 5), None);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::app::{display_id, exchange_duration, App, ExchangeStatus, JsonRpcExchange};
+use crate::control::{timestamp_ms, SessionExchange};
+use crate::time::iso8601;
+use serde_json::Value;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The compact line, then the request and response bodies.
+    Pretty,
+    Compact,
+    /// The portable exchange with its index and status, one per line.
+    Ndjson,
+}
+
+/// Writes settled exchanges as they arrive, for headless runs and `tail`.
+#[derive(Debug, Clone)]
+pub struct Printer {
+    pub format: Format,
+    /// Request list filter syntax, such as `status:error` or `eth_`.
+    pub filter: String,
+    pub color: bool,
+}
+
+impl Printer {
+    /// Plain compact lines for every exchange.
+    pub fn plain() -> Self {
+        Self {
+            format: Format::Compact,
+            filter: String::new(),
+            color: false,
+        }
+    }
+
+    /// The text to print for each exchange at `indices` the filter matches. Pretty entries
+    /// end with an empty line to keep them apart.
+    pub fn render(&self, app: &App, indices: &[usize]) -> Vec<String> {
+        let context = app.status_context();
+        indices
+            .iter()
+            .filter_map(|index| Some((*index, app.exchanges.get(*index)?)))
+            .filter(|(index, exchange)| {
+                self.filter.trim().is_empty()
+                    || context.matches_filter(exchange, app.bookmarks.get(index), &self.filter)
+            })
+            .map(|(index, exchange)| {
+                let status = context.status(exchange);
+                match self.format {
+                    Format::Compact => compact(index, exchange, status, self.color),
+                    Format::Pretty => pretty(index, exchange, status, self.color),
+                    Format::Ndjson => ndjson(index, exchange, status),
+                }
+            })
+            .collect()
+    }
+}
+
+/// `2024-05-01T12:30:00.250Z #3 eth_call id=7 success 42ms`, with the listener and client
+/// when known and the code and message of an error.
+pub fn compact(
+    index: usize,
+    exchange: &JsonRpcExchange,
+    status: ExchangeStatus,
+    color: bool,
+) -> String {
+    let paint = |style: &str, text: &str| {
+        if color {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+    let mut line = format!(
+        "{} #{index} {}",
+        paint(DIM, &iso8601(timestamp_ms(exchange.timestamp))),
+        paint(
+            BOLD,
+            &printable(exchange.method.as_deref().unwrap_or("unknown"))
+        )
+    );
+    if exchange.id.is_some() {
+        line.push_str(&format!(" id={}", display_id(exchange.id.as_ref())));
+    }
+    if let Some(source) = exchange.source() {
+        line.push_str(&format!(" source={}", printable(source)));
+    }
+    if let Some(client) = exchange.client() {
+        line.push_str(&format!(" client={}", printable(client)));
+    }
+    line.push(' ');
+    line.push_str(&paint(status_color(status), status.name()));
+    let duration = exchange_duration(exchange);
+    if duration != "-" {
+        // Whole seconds are slow enough to stand out.
+        let style = if duration.ends_with('s') && !duration.ends_with("ms") {
+            "\x1b[33m"
+        } else {
+            DIM
+        };
+        line.push(' ');
+        line.push_str(&paint(style, &duration));
+    }
+    if let Some(error) = exchange
+        .response
+        .as_ref()
+        .and_then(|response| response.error.as_ref())
+    {
+        if let Some(code) = error.get("code") {
+            line.push_str(&format!(" {code}"));
+        }
+        if let Some(message) = error.get("message").and_then(|message| message.as_str()) {
+            line.push(' ');
+            line.push_str(&printable(message));
+        }
+    }
+    line
+}
+
+/// `text` with control characters escaped, so what a peer sent cannot drive the terminal.
+fn printable(text: &str) -> String {
+    text.chars()
+        .map(|character| {
+            if character.is_control() {
+                character.escape_default().to_string()
+            } else {
+                character.to_string()
+            }
+        })
+        .collect()
+}
+
+fn pretty(index: usize, exchange: &JsonRpcExchange, status: ExchangeStatus, color: bool) -> String {
+    let session = SessionExchange::from(exchange);
+    let mut text = compact(index, exchange, status, color);
+    for (arrow, message) in [("→", &session.request), ("←", &session.response)] {
+        let Some(message) = message else {
+            continue;
+        };
+        let body = serde_json::to_string_pretty(&message.body).unwrap_or_default();
+        for (number, line) in body.lines().enumerate() {
+            let marker = if number == 0 { arrow } else { " " };
+            text.push_str(&format!("\n  {marker} {line}"));
+        }
+    }
+    text.push('\n');
+    text
+}
+
+fn ndjson(index: usize, exchange: &JsonRpcExchange, status: ExchangeStatus) -> String {
+    let mut value = serde_json::to_value(SessionExchange::from(exchange)).unwrap_or_default();
+    if let Value::Object(object) = &mut value {
+        object.insert("index".to_string(), index.into());
+        object.insert("status".to_string(), status.name().into());
+    }
+    value.to_string()
+}
+
+/// The ANSI colour of a status, matching the request list.
+fn status_color(status: ExchangeStatus) -> &'static str {
+    match status {
+        ExchangeStatus::Notification => "\x1b[36m",
+        ExchangeStatus::Pending => "\x1b[33m",
+        ExchangeStatus::TimedOut => "\x1b[91m",
+        ExchangeStatus::Orphan => "\x1b[35m",
+        ExchangeStatus::Error => "\x1b[31m",
+        ExchangeStatus::Success => "\x1b[32m",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{testing, TransportType};
+    use serde_json::json;
+
+    fn app() -> App {
+        testing::app(
+            TransportType::Http,
+            [
+                (
+                    json!({"jsonrpc": "2.0", "id": 1, "method": "eth_call"}),
+                    1_700_000_000_000,
+                ),
+                (
+                    json!({"jsonrpc": "2.0", "id": 1, "result": "0x"}),
+                    1_700_000_001_500,
+                ),
+                (
+                    json!({"jsonrpc": "2.0", "method": "ping"}),
+                    1_700_000_002_000,
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn formats_settled_exchanges_through_the_filter() {
+        let app = app();
+        let mut printer = Printer::plain();
+        assert_eq!(
+            printer.render(&app, &[0, 1]),
+            [
+                "2023-11-14T22:13:20.000Z #0 eth_call id=1 success 1.50s",
+                "2023-11-14T22:13:22.000Z #1 ping notification",
+            ]
+        );
+
+        printer.filter = "status:success".to_string();
+        printer.format = Format::Pretty;
+        assert_eq!(
+            printer.render(&app, &[0, 1]),
+            [concat!(
+                "2023-11-14T22:13:20.000Z #0 eth_call id=1 success 1.50s\n",
+                "  → {\n      \"id\": 1,\n      \"jsonrpc\": \"2.0\",\n      \"method\": \"eth_call\"\n    }\n",
+                "  ← {\n      \"id\": 1,\n      \"jsonrpc\": \"2.0\",\n      \"result\": \"0x\"\n    }\n",
+            )]
+        );
+
+        printer.format = Format::Ndjson;
+        let line: Value = serde_json::from_str(&printer.render(&app, &[0])[0]).unwrap();
+        assert_eq!(line["index"], 0);
+        assert_eq!(line["status"], "success");
+        assert_eq!(line["response"]["body"]["result"], "0x");
+    }
+
+    #[test]
+    fn colours_method_status_and_slow_durations() {
+        let app = app();
+        let line = compact(0, &app.exchanges[0], ExchangeStatus::Success, true);
+
+        assert!(line.contains("\x1b[1meth_call\x1b[0m"));
+        assert!(line.contains("\x1b[32msuccess\x1b[0m"));
+        assert!(line.contains("\x1b[33m1.50s\x1b[0m"));
+    }
+
+    #[test]
+    fn escapes_control_characters_from_the_wire() {
+        let mut app = app();
+        let exchange = &mut app.exchanges[0];
+        exchange.method = Some("eth_\x1b]0;owned\x07call".to_string());
+        exchange.response.as_mut().unwrap().error =
+            Some(json!({"code": -32000, "message": "boom\x1b[2J\nnext"}));
+
+        let line = compact(0, exchange, ExchangeStatus::Error, false);
+
+        assert!(!line.chars().any(char::is_control));
+        assert!(line.contains("eth_\\u{1b}]0;owned\\u{7}call"));
+        assert!(line.ends_with(" -32000 boom\\u{1b}[2J\\nnext"));
+    }
+}
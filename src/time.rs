@@ -0,0 +1,95 @@
+/// `2024-05-01T12:30:00.250Z` for milliseconds since the epoch.
+pub fn iso8601(ms: u64) -> String {
+    let seconds = ms / 1000;
+    let (year, month, day) =
+        civil_from_days(i64::try_from(seconds / 86_400).unwrap_or(i64::MAX / 2));
+    let time = seconds % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60,
+        ms % 1000
+    )
+}
+
+/// Milliseconds since the epoch for an ISO 8601 date-time with a `Z` or `±hh:mm` offset,
+/// as browsers write `startedDateTime` and many loggers prefix their lines.
+pub fn parse_iso8601(text: &str) -> Option<u64> {
+    let (date, time) = text.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+
+    let (clock, offset_seconds) = if let Some(clock) = time.strip_suffix('Z') {
+        (clock, 0)
+    } else {
+        let split = time.rfind(['+', '-'])?;
+        let (clock, offset) = time.split_at(split);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = offset[1..].split_once(':').unwrap_or((&offset[1..], "0"));
+        let offset = hours.parse::<i64>().ok()? * 3_600 + minutes.parse::<i64>().ok()? * 60;
+        (clock, sign * offset)
+    };
+    let (clock, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+    let mut clock = clock.splitn(3, ':').map(str::parse::<i64>);
+    let (hour, minute, second) = (
+        clock.next()?.ok()?,
+        clock.next()?.ok()?,
+        clock.next()?.ok()?,
+    );
+    let millis = format!("{fraction:0<3}")
+        .get(..3)
+        .and_then(|millis| millis.parse::<i64>().ok())?;
+
+    let days = days_from_civil(year, month, day);
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second - offset_seconds;
+    u64::try_from(seconds * 1000 + millis).ok()
+}
+
+/// The year, month and day of a count of days since 1970-01-01 (Howard Hinnant's algorithm).
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Days since 1970-01-01 for a civil date, the inverse of `civil_from_days`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_round_trip_through_iso8601() {
+        assert_eq!(iso8601(1_714_566_600_250), "2024-05-01T12:30:00.250Z");
+        assert_eq!(
+            parse_iso8601("2024-05-01T12:30:00.250Z"),
+            Some(1_714_566_600_250)
+        );
+        assert_eq!(
+            parse_iso8601("2024-05-01T14:30:00.25+02:00"),
+            Some(1_714_566_600_250)
+        );
+        assert_eq!(parse_iso8601("yesterday"), None);
+    }
+}
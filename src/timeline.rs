@@ -0,0 +1,158 @@
+use std::time::{Duration, SystemTime};
+
+pub const MAX_ZOOM: u32 = 1024;
+
+/// When an exchange started and when it was answered; `end` is `None` while it is in flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: SystemTime,
+    pub end: Option<SystemTime>,
+}
+
+impl Span {
+    fn end_or(&self, now: SystemTime) -> SystemTime {
+        self.end.unwrap_or(now).max(self.start)
+    }
+}
+
+/// The slice of the capture drawn across the waterfall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    /// Start of the earliest exchange; offsets and axis labels are relative to it.
+    pub origin: SystemTime,
+    pub offset: Duration,
+    pub length: Duration,
+}
+
+impl Window {
+    /// Fits every span, then narrows to `1/zoom` of it. Without an explicit `offset` the
+    /// window centres on `focus`.
+    pub fn fit(
+        spans: &[Span],
+        now: SystemTime,
+        zoom: u32,
+        offset: Option<Duration>,
+        focus: Option<SystemTime>,
+    ) -> Option<Self> {
+        let origin = spans.iter().map(|span| span.start).min()?;
+        let end = spans.iter().map(|span| span.end_or(now)).max()?;
+        let extent = end
+            .duration_since(origin)
+            .unwrap_or_default()
+            .max(Duration::from_millis(1));
+        let length = (extent / zoom.clamp(1, MAX_ZOOM)).max(Duration::from_millis(1));
+        let offset = offset.unwrap_or_else(|| {
+            focus
+                .and_then(|focus| focus.duration_since(origin).ok())
+                .map_or(Duration::ZERO, |focus| focus.saturating_sub(length / 2))
+        });
+
+        Some(Self {
+            origin,
+            offset: offset.min(extent.saturating_sub(length)),
+            length,
+        })
+    }
+
+    /// The window moved by `quarters` quarter-widths; `fit` clamps it to the capture.
+    pub fn panned(&self, quarters: i64) -> Duration {
+        let step = self.length / 4 * u32::try_from(quarters.unsigned_abs()).unwrap_or(u32::MAX);
+        if quarters >= 0 {
+            self.offset.saturating_add(step)
+        } else {
+            self.offset.saturating_sub(step)
+        }
+    }
+
+    /// A `width`-column bar for `span`, solid once answered and shaded while in flight.
+    /// Spans outside the window leave an arrow at the edge they are past.
+    pub fn bar(&self, span: Span, now: SystemTime, width: usize) -> String {
+        if width == 0 {
+            return String::new();
+        }
+        let from = self.column(span.start, width);
+        let to = self.column(span.end_or(now), width);
+        if to < 0.0 {
+            return "◂".to_string();
+        }
+        if from >= width as f64 {
+            return format!("{}▸", " ".repeat(width - 1));
+        }
+
+        let first = from.floor().max(0.0) as usize;
+        let last = (to.ceil() as usize).clamp(first + 1, width);
+        let fill = if span.end.is_some() { "█" } else { "░" };
+        format!("{}{}", " ".repeat(first), fill.repeat(last - first))
+    }
+
+    /// The window's start and end relative to the origin, spread across `width` columns.
+    pub fn axis(&self, width: usize) -> String {
+        let start = format!("+{}", format_duration(self.offset));
+        let end = format!("+{}", format_duration(self.offset + self.length));
+        let gap = width.saturating_sub(start.chars().count() + end.chars().count());
+        if gap == 0 {
+            return start;
+        }
+        format!("{start}{}{end}", " ".repeat(gap))
+    }
+
+    fn column(&self, time: SystemTime, width: usize) -> f64 {
+        let elapsed = match time.duration_since(self.origin) {
+            Ok(elapsed) => elapsed.as_secs_f64(),
+            Err(error) => -error.duration().as_secs_f64(),
+        };
+        (elapsed - self.offset.as_secs_f64()) / self.length.as_secs_f64() * width as f64
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    if duration.as_millis() < 1000 {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn at(millis: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(millis)
+    }
+
+    fn span(start: u64, end: Option<u64>) -> Span {
+        Span {
+            start: at(start),
+            end: end.map(at),
+        }
+    }
+
+    #[test]
+    fn bars_scale_to_the_window_and_mark_what_is_out_of_view() {
+        let spans = [span(0, Some(1000)), span(500, Some(600)), span(900, None)];
+        let now = at(2000);
+
+        let window = Window::fit(&spans, now, 1, None, None).unwrap();
+        assert_eq!(window.length, Duration::from_secs(2));
+        assert_eq!(window.bar(spans[0], now, 20), "█".repeat(10));
+        assert_eq!(window.bar(spans[1], now, 20), format!("{}█", " ".repeat(5)));
+        assert_eq!(
+            window.bar(spans[2], now, 20),
+            format!("{}{}", " ".repeat(9), "░".repeat(11))
+        );
+        assert_eq!(window.axis(12), "+0ms  +2.00s");
+
+        let zoomed = Window::fit(&spans, now, 4, None, Some(at(1500))).unwrap();
+        assert_eq!(zoomed.offset, Duration::from_millis(1250));
+        assert_eq!(zoomed.bar(spans[1], now, 10), "◂");
+        assert_eq!(zoomed.bar(spans[2], now, 10), "░".repeat(10));
+
+        let end = Window::fit(&spans, now, 4, Some(zoomed.panned(8)), None).unwrap();
+        assert_eq!(end.offset, Duration::from_millis(1500));
+        let start = Window::fit(&spans, now, 4, Some(zoomed.panned(-12)), None).unwrap();
+        assert_eq!(start.offset, Duration::ZERO);
+        assert_eq!(start.bar(span(1900, Some(1950)), now, 10), "         ▸");
+    }
+}
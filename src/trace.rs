@@ -0,0 +1,535 @@
+use crate::bookmark::Bookmark;
+use crate::control::{
+    Session, SessionConnection, SessionExchange, SessionMessage, SessionTransport,
+};
+use crate::time::parse_iso8601;
+use serde_json::{json, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAY_MS: u64 = 86_400_000;
+
+/// The log formats [`import`] recognizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The text trace VS Code language clients write with `trace.server` set to `verbose`.
+    LspText,
+    /// One JSON-RPC message per line, bare or wrapped in a log record such as the JSON trace
+    /// of a VS Code language client or the stderr log of an MCP client.
+    JsonLines,
+}
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::LspText => "lsp-text",
+            Self::JsonLines => "json-lines",
+        }
+    }
+}
+
+/// A trace log read as a portable session. `skipped` counts lines that carried no message.
+#[derive(Debug)]
+pub struct Import {
+    pub session: Session,
+    pub format: Format,
+    pub skipped: usize,
+}
+
+/// Which peer wrote a message. Editors and MCP hosts are the client; language and MCP
+/// servers send requests of their own too, such as `workspace/configuration`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sender {
+    Client,
+    Server,
+}
+
+impl Sender {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Client => "client",
+            Self::Server => "server",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Record {
+    sender: Option<Sender>,
+    body: Value,
+    timestamp_ms: u64,
+    /// How long the request took, as the text trace reports on its response.
+    elapsed_ms: Option<u64>,
+}
+
+/// Reads a trace log into exchanges in the order their first message was logged. Requests
+/// pair with the next response of the same id from the other peer, so the overlapping ids
+/// of client and server requests stay apart.
+pub fn import(text: &str, target: &str) -> Result<Import, String> {
+    let (format, records, skipped) = if text.lines().any(|line| lsp_header(line).is_some()) {
+        let (records, skipped) = lsp_records(text);
+        (Format::LspText, records, skipped)
+    } else {
+        let (records, skipped) = json_records(text);
+        (Format::JsonLines, records, skipped)
+    };
+    if records.is_empty() {
+        return Err("No JSON-RPC messages found in the trace".to_string());
+    }
+
+    let transport = match format {
+        Format::LspText => SessionTransport::StdioContentLength,
+        Format::JsonLines => SessionTransport::StdioJsonLines,
+    };
+    let mut exchanges: Vec<SessionExchange> = Vec::new();
+    let mut pending: Vec<(Option<Sender>, Value, usize)> = Vec::new();
+    for record in records {
+        let message = |timestamp_ms, sender: Option<Sender>| SessionMessage {
+            body: record.body.clone(),
+            headers: None,
+            timestamp_ms,
+            connection: sender.map(|sender| SessionConnection {
+                id: sender.name().to_string(),
+                client: sender.name().to_string(),
+                source: None,
+            }),
+            batch: None,
+            timing: None,
+        };
+        let id = record.body.get("id").filter(|id| !id.is_null()).cloned();
+        if record.body.get("method").is_some() {
+            if let Some(id) = id {
+                pending.push((record.sender, id, exchanges.len()));
+            }
+            exchanges.push(SessionExchange {
+                transport,
+                request: Some(message(record.timestamp_ms, record.sender)),
+                response: None,
+                bookmark: Bookmark::default(),
+            });
+            continue;
+        }
+
+        let answered = id.and_then(|id| {
+            pending.iter().position(|(sender, pending_id, _)| {
+                *pending_id == id
+                    && (sender.is_none() || record.sender.is_none() || *sender != record.sender)
+            })
+        });
+        match answered.map(|position| pending.remove(position)) {
+            Some((_, _, index)) => {
+                let exchange = &mut exchanges[index];
+                let sent = exchange
+                    .request
+                    .as_ref()
+                    .map_or(record.timestamp_ms, |request| request.timestamp_ms);
+                let timestamp_ms = record
+                    .elapsed_ms
+                    .map_or(record.timestamp_ms.max(sent), |elapsed| sent + elapsed);
+                exchange.response = Some(message(timestamp_ms, None));
+            }
+            None => exchanges.push(SessionExchange {
+                transport,
+                request: None,
+                response: Some(message(record.timestamp_ms, None)),
+                bookmark: Bookmark::default(),
+            }),
+        }
+    }
+
+    Ok(Import {
+        session: Session {
+            schema_version: 1,
+            exported_at_ms: now_ms(),
+            target: target.to_string(),
+            exchanges,
+        },
+        format,
+        skipped,
+    })
+}
+
+/// A `[Trace - 10:22:01 AM] Sending request 'initialize - (0)'.` header: the clock time,
+/// the sender, the message kind, the quoted name, and the text after it.
+fn lsp_header(line: &str) -> Option<(&str, Sender, &str, &str, &str)> {
+    let rest = line.trim_end().strip_prefix("[Trace - ")?;
+    let (clock, rest) = rest.split_once("] ")?;
+    let (sender, rest) = if let Some(rest) = rest.strip_prefix("Sending ") {
+        (Sender::Client, rest)
+    } else {
+        (Sender::Server, rest.strip_prefix("Received ")?)
+    };
+    let (kind, rest) = rest.split_once(" '")?;
+    let (name, tail) = rest.split_once('\'')?;
+    Some((clock, sender, kind, name, tail))
+}
+
+fn lsp_records(text: &str) -> (Vec<Record>, usize) {
+    let day = now_ms() / DAY_MS * DAY_MS;
+    let mut clock = Clock::default();
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some((time, sender, kind, name, tail)) = lsp_header(line) else {
+            skipped += usize::from(!line.trim().is_empty());
+            continue;
+        };
+        let mut block = Vec::new();
+        while let Some(next) = lines.next_if(|next| lsp_header(next).is_none()) {
+            block.push(next);
+        }
+        let block = block.join("\n");
+        let timestamp_ms = day + clock.advance(parse_clock(time).unwrap_or(clock.last));
+
+        let (method, id) = match name.rsplit_once(" - (") {
+            Some((method, id)) => (method, id.strip_suffix(')').map(parse_id)),
+            None => (name, None),
+        };
+        let mut body = json!({"jsonrpc": "2.0"});
+        if let Some(id) = &id {
+            body["id"] = id.clone();
+        }
+        let elapsed_ms = match kind {
+            "request" | "notification" => {
+                body["method"] = json!(method);
+                if let Some(params) = block_value(&block, "Params:") {
+                    body["params"] = params;
+                }
+                None
+            }
+            "response" => {
+                if let Some((_, failure)) = tail.split_once("Request failed: ") {
+                    let (message, code) = failure
+                        .trim_end_matches('.')
+                        .rsplit_once(" (")
+                        .and_then(|(message, code)| {
+                            Some((message, code.strip_suffix(')')?.parse::<i64>().ok()?))
+                        })
+                        .unwrap_or((failure.trim_end_matches('.'), -32603));
+                    body["error"] = json!({"code": code, "message": message});
+                    if let Some(data) = block_value(&block, "Error data:") {
+                        body["error"]["data"] = data;
+                    }
+                } else {
+                    body["result"] = block_value(&block, "Result:").unwrap_or(Value::Null);
+                }
+                tail.split_once(" in ")
+                    .and_then(|(_, elapsed)| elapsed.split_once("ms"))
+                    .and_then(|(elapsed, _)| elapsed.trim().parse().ok())
+            }
+            _ => {
+                skipped += 1;
+                continue;
+            }
+        };
+        records.push(Record {
+            sender: Some(sender),
+            body,
+            timestamp_ms,
+            elapsed_ms,
+        });
+    }
+    (records, skipped)
+}
+
+/// The JSON after a `Params:` or `Result:` label, which may span many lines.
+fn block_value(block: &str, label: &str) -> Option<Value> {
+    let start = block.find(label)? + label.len();
+    let mut values = serde_json::Deserializer::from_str(&block[start..]).into_iter::<Value>();
+    values.next()?.ok()
+}
+
+/// Milliseconds into the day for `10:22:01 AM`, `10:22:01.250 PM`, or `22:22:01`.
+fn parse_clock(text: &str) -> Option<u64> {
+    let (clock, meridiem) = match text.trim().split_once(' ') {
+        Some((clock, meridiem)) => (clock, Some(meridiem.to_ascii_uppercase())),
+        None => (text.trim(), None),
+    };
+    let (clock, fraction) = clock.split_once('.').unwrap_or((clock, "0"));
+    let mut parts = clock.splitn(3, ':').map(str::parse::<u64>);
+    let (mut hour, minute, second) = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next().unwrap_or(Ok(0)).ok()?,
+    );
+    match meridiem.as_deref() {
+        Some("AM") if hour == 12 => hour = 0,
+        Some("PM") if hour < 12 => hour += 12,
+        Some("AM" | "PM") | None => {}
+        Some(_) => return None,
+    }
+    let millis = format!("{fraction:0<3}").get(..3)?.parse::<u64>().ok()?;
+    Some(((hour * 60 + minute) * 60 + second) * 1000 + millis)
+}
+
+/// Turns times of day into a running offset, so a trace that passes midnight keeps its order.
+#[derive(Default)]
+struct Clock {
+    days: u64,
+    last: u64,
+}
+
+impl Clock {
+    fn advance(&mut self, time: u64) -> u64 {
+        if time + DAY_MS / 2 < self.last {
+            self.days += 1;
+        }
+        self.last = time;
+        self.days * DAY_MS + time
+    }
+}
+
+fn json_records(text: &str) -> (Vec<Record>, usize) {
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    let mut last_ms = None;
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        // Log prefixes such as `[info]` come before the message, so try each bracket.
+        let found = line.match_indices(['{', '[']).find_map(|(start, _)| {
+            let value = serde_json::Deserializer::from_str(&line[start..])
+                .into_iter::<Value>()
+                .next()?
+                .ok()?;
+            line_messages(value).map(|(messages, record)| (&line[..start], messages, record))
+        });
+        let Some((prefix, messages, record)) = found else {
+            skipped += 1;
+            continue;
+        };
+        let record = record.as_ref();
+        let timestamp_ms = record
+            .and_then(record_timestamp)
+            .or_else(|| line_timestamp(prefix));
+        last_ms = timestamp_ms.or(last_ms);
+        let marker = record.map_or_else(String::new, |record| {
+            ["type", "direction", "dir", "event"]
+                .iter()
+                .filter_map(|field| record.get(*field)?.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        let sender = direction(&format!("{prefix} {marker}"));
+        records.extend(messages.into_iter().map(|body| (sender, body, last_ms)));
+    }
+
+    // Lines before the first timestamp take it; a log without any uses the import time.
+    let first_ms = records
+        .iter()
+        .find_map(|(_, _, timestamp_ms)| *timestamp_ms)
+        .unwrap_or_else(now_ms);
+    let records = records
+        .into_iter()
+        .map(|(sender, body, timestamp_ms)| Record {
+            sender,
+            body,
+            timestamp_ms: timestamp_ms.unwrap_or(first_ms),
+            elapsed_ms: None,
+        })
+        .collect();
+    (records, skipped)
+}
+
+/// The messages of a line's JSON: a message, a batch, or a log record holding one in a
+/// `message`, `msg`, `data`, or `payload` field. The record is returned for its timestamp.
+fn line_messages(value: Value) -> Option<(Vec<Value>, Option<Value>)> {
+    match value {
+        Value::Array(messages) => {
+            let messages = messages.into_iter().filter(is_message).collect::<Vec<_>>();
+            (!messages.is_empty()).then_some((messages, None))
+        }
+        value if is_message(&value) => Some((vec![value], None)),
+        record => {
+            let message = ["message", "msg", "data", "payload"]
+                .iter()
+                .find_map(|field| record.get(*field).filter(|inner| is_message(inner)))?
+                .clone();
+            Some((vec![message], Some(record)))
+        }
+    }
+}
+
+fn is_message(value: &Value) -> bool {
+    value.get("jsonrpc").is_some()
+        || value.get("method").is_some()
+        || (value.get("id").is_some()
+            && (value.get("result").is_some() || value.get("error").is_some()))
+}
+
+/// Who wrote a line, from words like `Sending`, `send-request`, `Message from server`, or
+/// arrows. Without a marker, requests and responses still pair by id.
+fn direction(text: &str) -> Option<Sender> {
+    const CLIENT: [&str; 7] = [
+        "from client",
+        "to server",
+        "sending",
+        "send-",
+        "outgoing",
+        "-->",
+        ">>>",
+    ];
+    const SERVER: [&str; 7] = [
+        "from server",
+        "to client",
+        "received",
+        "receive-",
+        "incoming",
+        "<--",
+        "<<<",
+    ];
+    let text = text.to_ascii_lowercase();
+    if CLIENT.iter().any(|marker| text.contains(marker)) {
+        Some(Sender::Client)
+    } else if SERVER.iter().any(|marker| text.contains(marker)) {
+        Some(Sender::Server)
+    } else {
+        None
+    }
+}
+
+/// A `timestamp`, `time`, or `ts` field in milliseconds or seconds since the epoch, or as
+/// an ISO 8601 date-time.
+fn record_timestamp(record: &Value) -> Option<u64> {
+    ["timestamp", "time", "ts"]
+        .iter()
+        .filter_map(|field| record.get(*field))
+        .find_map(|value| match value {
+            Value::Number(number) => {
+                let number = number.as_f64()?;
+                // Seconds until the year 5138, milliseconds after.
+                let millis = if number < 1e11 {
+                    number * 1000.0
+                } else {
+                    number
+                };
+                Some(millis as u64)
+            }
+            Value::String(text) => parse_iso8601(text),
+            _ => None,
+        })
+}
+
+/// The ISO 8601 date-time that starts a log line, possibly in brackets.
+fn line_timestamp(prefix: &str) -> Option<u64> {
+    let token = prefix.split_whitespace().next()?;
+    parse_iso8601(token.trim_matches(['[', ']']))
+}
+
+fn parse_id(text: &str) -> Value {
+    text.parse::<i64>()
+        .map_or_else(|_| json!(text), |id| json!(id))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::TransportType;
+    use crate::control::replay_session;
+
+    #[test]
+    fn lsp_text_traces_pair_client_and_server_requests() {
+        let trace = "\
+[Info  - 10:22:00 AM] Starting language server
+[Trace - 10:22:01 AM] Sending request 'initialize - (0)'.
+Params: {
+    \"processId\": 42
+}
+
+
+[Trace - 10:22:01 AM] Received request 'workspace/configuration - (0)'.
+Params: {
+    \"items\": []
+}
+
+
+[Trace - 10:22:01 AM] Sending response 'workspace/configuration - (0)'. Processing request took 0ms
+Result: [
+    null
+]
+
+
+[Trace - 10:22:01 AM] Received response 'initialize - (0)' in 15ms.
+Result: {
+    \"capabilities\": {}
+}
+
+
+[Trace - 10:22:02 AM] Sending notification 'initialized'.
+Params: {}
+
+
+[Trace - 10:22:03 PM] Received response 'textDocument/hover - (1)' in 3ms. Request failed: Unhandled method textDocument/hover (-32601).
+";
+        let imported = import(trace, "rust-analyzer").unwrap();
+        assert_eq!(imported.format, Format::LspText);
+        assert_eq!(imported.skipped, 1);
+
+        let exchanges = replay_session(imported.session).unwrap();
+        let methods = exchanges
+            .iter()
+            .map(|exchange| exchange.method.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            methods,
+            [
+                Some("initialize"),
+                Some("workspace/configuration"),
+                Some("initialized"),
+                None,
+            ]
+        );
+        assert!(exchanges.iter().all(|exchange| exchange.transport
+            == TransportType::Stdio(crate::app::Framing::ContentLength)));
+
+        let initialize = &exchanges[0];
+        let request = initialize.request.as_ref().unwrap();
+        let response = initialize.response.as_ref().unwrap();
+        assert_eq!(
+            response
+                .timestamp
+                .duration_since(request.timestamp)
+                .unwrap()
+                .as_millis(),
+            15
+        );
+        assert_eq!(response.result, Some(json!({"capabilities": {}})));
+        let configuration = exchanges[1].request.as_ref().unwrap();
+        assert_eq!(configuration.connection.as_ref().unwrap().client, "server");
+        assert_eq!(
+            exchanges[1].response.as_ref().unwrap().result,
+            Some(json!([null]))
+        );
+        let failure = exchanges[3].response.as_ref().unwrap();
+        assert_eq!(failure.error.as_ref().unwrap()["code"], -32601);
+    }
+
+    #[test]
+    fn json_lines_accept_bare_and_wrapped_messages() {
+        let log = "\
+{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"tools/list\"}
+2025-01-02T03:04:05.000Z [fs] [info] Message from server: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"tools\":[]}} { metadata: undefined }
+[LSP - 10:00:00 AM] {\"type\":\"send-notification\",\"message\":{\"jsonrpc\":\"2.0\",\"method\":\"exit\"},\"timestamp\":1735787046000}
+server started
+";
+        let imported = import(log, "mcp").unwrap();
+        assert_eq!(imported.format, Format::JsonLines);
+        assert_eq!(imported.skipped, 1);
+        let session = &imported.session;
+        assert_eq!(session.exchanges.len(), 2);
+        let response = session.exchanges[0].response.as_ref().unwrap();
+        assert_eq!(response.timestamp_ms, 1_735_787_045_000);
+        assert_eq!(response.body["result"], json!({"tools": []}));
+        let exit = session.exchanges[1].request.as_ref().unwrap();
+        assert_eq!(exit.timestamp_ms, 1_735_787_046_000);
+        assert_eq!(exit.connection.as_ref().unwrap().client, "client");
+
+        assert!(import("no messages here\n", "mcp").is_err());
+        assert_eq!(parse_clock("12:05:00 AM"), Some(300_000));
+    }
+}
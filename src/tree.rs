@@ -0,0 +1,258 @@
+use crate::diff;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Arrays longer than this start folded.
+pub const COLLAPSED_ARRAY_LEN: usize = 20;
+
+/// An object or array that spans several lines of a pretty-printed body. `line` is the
+/// detail line that opens it and `last` the one that closes it, both one-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fold {
+    pub path: String,
+    pub line: usize,
+    pub last: usize,
+    pub len: usize,
+    pub array: bool,
+    pub folded: bool,
+}
+
+impl Fold {
+    /// Shown after the opening line while folded, such as `…] 1200 items`.
+    pub fn summary(&self) -> String {
+        let (close, noun) = if self.array {
+            (']', "item")
+        } else {
+            ('}', "key")
+        };
+        let plural = if self.len == 1 { "" } else { "s" };
+        format!("…{close} {} {noun}{plural}", self.len)
+    }
+}
+
+/// The folds of one JSON body and which of its lines they hide. Lines outside the body are
+/// never hidden, so an empty `Folds` leaves a panel as it is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Folds {
+    /// Every foldable object and array, by opening line.
+    folds: Vec<Fold>,
+    /// Detail lines before the body.
+    offset: usize,
+    /// For each body line, the body line that stands for it on screen.
+    shown: Vec<usize>,
+    /// How many body lines before each one are hidden.
+    hidden_before: Vec<usize>,
+}
+
+impl Folds {
+    /// Folds for `value` pretty-printed after `offset` detail lines. `overrides` holds the
+    /// state chosen per path; other arrays longer than [`COLLAPSED_ARRAY_LEN`] start folded.
+    pub fn new(value: &Value, offset: usize, overrides: &HashMap<String, bool>) -> Self {
+        let mut folds = Vec::new();
+        let len = outline(value, "$".to_string(), &mut 0, &mut folds);
+        for fold in &mut folds {
+            fold.folded = overrides
+                .get(&fold.path)
+                .copied()
+                .unwrap_or(fold.array && fold.len > COLLAPSED_ARRAY_LEN);
+            fold.line += offset + 1;
+            fold.last += offset + 1;
+        }
+
+        let mut shown = (0..len).collect::<Vec<_>>();
+        for fold in folds.iter().filter(|fold| fold.folded) {
+            let start = fold.line - offset - 1;
+            if shown[start] != start {
+                continue;
+            }
+            for line in &mut shown[start + 1..=fold.last - offset - 1] {
+                *line = start;
+            }
+        }
+        let mut hidden_before = Vec::with_capacity(len + 1);
+        let mut hidden = 0;
+        for (line, owner) in shown.iter().enumerate() {
+            hidden_before.push(hidden);
+            hidden += usize::from(*owner != line);
+        }
+        hidden_before.push(hidden);
+
+        Self {
+            folds,
+            offset,
+            shown,
+            hidden_before,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.folds.is_empty()
+    }
+
+    /// The fold opening on `line`, folded or not.
+    pub fn at(&self, line: usize) -> Option<&Fold> {
+        self.folds
+            .binary_search_by_key(&line, |fold| fold.line)
+            .ok()
+            .map(|index| &self.folds[index])
+    }
+
+    /// The innermost fold whose lines include `line`.
+    pub fn enclosing(&self, line: usize) -> Option<&Fold> {
+        self.folds
+            .iter()
+            .rev()
+            .find(|fold| (fold.line..=fold.last).contains(&line))
+    }
+
+    /// Folded folds that hide any line of `start..=end`, outermost first.
+    pub fn hiding(&self, start: usize, end: usize) -> Vec<&Fold> {
+        self.folds
+            .iter()
+            .filter(|fold| fold.folded && fold.line < end && start <= fold.last)
+            .collect()
+    }
+
+    pub fn is_hidden(&self, line: usize) -> bool {
+        self.shown(line) != line
+    }
+
+    /// The line drawn in place of `line`: the opening line of a fold that hides it.
+    pub fn shown(&self, line: usize) -> usize {
+        match line
+            .checked_sub(self.offset + 1)
+            .and_then(|index| self.shown.get(index))
+        {
+            Some(owner) => owner + self.offset + 1,
+            None => line,
+        }
+    }
+
+    /// How many lines are drawn before `line`.
+    pub fn rank(&self, line: usize) -> usize {
+        let index = line.saturating_sub(self.offset + 1).min(self.shown.len());
+        line.saturating_sub(1) - self.hidden_before.get(index).copied().unwrap_or(0)
+    }
+
+    /// The drawn line `lines` drawn lines away from `line`, within `1..=total`.
+    pub fn step(&self, line: usize, lines: i64, total: usize) -> usize {
+        let total = total.max(1);
+        let mut line = self.shown(line.clamp(1, total));
+        for _ in 0..lines.unsigned_abs() {
+            let next = if lines >= 0 {
+                let after = self
+                    .at(line)
+                    .filter(|fold| fold.folded)
+                    .map_or(line, |fold| fold.last);
+                (after + 1).min(total)
+            } else {
+                self.shown(line.saturating_sub(1).max(1))
+            };
+            if self.shown(next) == line {
+                break;
+            }
+            line = self.shown(next);
+        }
+        line
+    }
+}
+
+// Mirrors the line layout of `serde_json::to_string_pretty`: scalars and empty containers
+// take one line, and other containers open and close on lines of their own.
+fn outline(value: &Value, path: String, line: &mut usize, folds: &mut Vec<Fold>) -> usize {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (diff::key_path(&path, key), child))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (format!("{path}[{index}]"), child))
+            .collect(),
+        _ => Vec::new(),
+    };
+    if children.is_empty() {
+        *line += 1;
+        return *line;
+    }
+
+    let index = folds.len();
+    folds.push(Fold {
+        path,
+        line: *line,
+        last: 0,
+        len: children.len(),
+        array: value.is_array(),
+        folded: false,
+    });
+    *line += 1;
+    for (path, child) in children {
+        outline(child, path, line, folds);
+    }
+    folds[index].last = *line;
+    *line += 1;
+    *line
+}
+
+/// The path of the value on `line` of `value` pretty-printed, counting from zero. Closing
+/// lines belong to the object or array they close.
+pub fn path_at(value: &Value, line: usize) -> Option<String> {
+    let mut paths = Vec::new();
+    line_paths(value, "$".to_string(), &mut paths);
+    paths.into_iter().nth(line)
+}
+
+fn line_paths(value: &Value, path: String, paths: &mut Vec<String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            paths.push(path.clone());
+            for (key, child) in map {
+                line_paths(child, diff::key_path(&path, key), paths);
+            }
+            paths.push(path);
+        }
+        Value::Array(items) if !items.is_empty() => {
+            paths.push(path.clone());
+            for (index, child) in items.iter().enumerate() {
+                line_paths(child, format!("{path}[{index}]"), paths);
+            }
+            paths.push(path);
+        }
+        _ => paths.push(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn folded_lines_are_skipped_but_keep_their_numbers() {
+        let value = json!({"a": [1, 2, 3], "b": {"c": []}});
+        let text = serde_json::to_string_pretty(&value).unwrap();
+        assert_eq!(text.lines().count(), 10);
+
+        let overrides = HashMap::from([("$.a".to_string(), true)]);
+        let folds = Folds::new(&value, 2, &overrides);
+        let a = folds.at(4).unwrap();
+        assert_eq!((a.path.as_str(), a.last, a.folded), ("$.a", 8, true));
+        assert_eq!(a.summary(), "…] 3 items");
+        assert_eq!(folds.enclosing(10).unwrap().path, "$.b");
+        assert_eq!(folds.shown(6), 4);
+        assert_eq!(folds.rank(9), 4);
+        assert_eq!(folds.step(3, 2, 12), 9);
+        assert_eq!(folds.step(9, -1, 12), 4);
+        assert_eq!(folds.step(12, 20, 12), 12);
+        assert_eq!(folds.hiding(7, 10), [a]);
+
+        assert!(!Folds::new(&value, 0, &HashMap::new()).at(2).unwrap().folded);
+        let long = json!((0..=COLLAPSED_ARRAY_LEN).collect::<Vec<_>>());
+        assert!(Folds::new(&long, 0, &HashMap::new()).at(1).unwrap().folded);
+
+        assert_eq!(path_at(&value, 3).as_deref(), Some("$.a[1]"));
+        assert_eq!(path_at(&value, 7).as_deref(), Some("$.b.c"));
+        assert_eq!(path_at(&value, 9).as_deref(), Some("$"));
+    }
+}
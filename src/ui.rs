@@ -10,9 +10,21 @@ use ratatui::{
 };
 
 use crate::app::{
-    request_matches_filter, App, AppMode, EditorMode, Focus, InputMode, JsonRpcExchange,
-    LineAnnotation, Overlay,
+    message_body, request_matches_filter, App, AppMode, EditorMode, ExchangeStatus, Focus,
+    InputMode, JsonRpcExchange, LineAnnotation, Overlay, StatusContext,
 };
+use crate::batch;
+use crate::columns::{self, Column};
+use crate::diff::{self, ChangeKind, Comparison};
+use crate::ethereum;
+use crate::mcp;
+use crate::profile;
+use crate::query::{self, Query};
+use crate::search;
+use crate::snippet;
+use crate::subscription;
+use crate::timeline;
+use crate::tree::{self, Folds};
 
 const ANNOTATION_AMBER: Color = Color::Rgb(245, 166, 35);
 
@@ -26,6 +38,7 @@ pub enum MouseAction {
     SelectRequestTab(usize),
     SelectResponseTab(usize),
     SelectLine { panel: Focus, line: usize },
+    ToggleFold { panel: Focus, line: usize },
     SelectAnnotation { id: String },
     SelectSession(usize),
     CloseOverlay,
@@ -103,20 +116,32 @@ pub fn detail_max_source_scroll(
     visible_lines: usize,
 ) -> usize {
     let annotations = detail_annotations(app, panel);
+    let folds = detail_folds(app, panel);
     let block_count = annotations
         .iter()
         .filter(|annotation| annotation.start_line != annotation.end_line)
         .count();
-    let max_display_scroll = (total_lines + block_count).saturating_sub(visible_lines);
+    let max_display_scroll =
+        (folds.rank(total_lines + 1) + block_count).saturating_sub(visible_lines);
     (0..total_lines)
-        .take_while(|source| detail_display_scroll(*source, &annotations) <= max_display_scroll)
+        .filter(|source| !folds.is_hidden(source + 1))
+        .take_while(|source| {
+            detail_display_scroll(*source, &annotations, &folds) <= max_display_scroll
+        })
         .last()
         .unwrap_or(0)
 }
 
 pub fn mouse_action(area: Rect, app: &App, column: u16, row: u16) -> Option<MouseAction> {
     match app.overlay {
-        Overlay::Help => return Some(MouseAction::CloseOverlay),
+        Overlay::Help
+        | Overlay::Mcp
+        | Overlay::Subscription
+        | Overlay::Batch
+        | Overlay::Diff
+        | Overlay::Columns
+        | Overlay::Snippets
+        | Overlay::Profiles => return Some(MouseAction::CloseOverlay),
         Overlay::Sessions => {
             return session_at_row(area, app, column, row)
                 .map(MouseAction::SelectSession)
@@ -313,13 +338,7 @@ fn pending_list_action(area: Rect, app: &App, row: u16) -> Option<MouseAction> {
         .pending_requests
         .iter()
         .enumerate()
-        .filter(|(_, pending)| {
-            request_matches_filter(
-                pending.original_request.method.as_deref(),
-                pending.original_request.id.as_ref(),
-                &app.filter_text,
-            )
-        })
+        .filter(|(_, pending)| request_matches_filter(&pending.original_request, &app.filter_text))
         .nth(row.saturating_sub(first_row) as usize)
         .map(|(index, _)| index);
 
@@ -338,6 +357,7 @@ fn request_details_action(area: Rect, app: &App, column: u16, row: u16) -> Optio
 
     let content = request_detail_lines(app);
     let annotations = detail_annotations(app, Focus::RequestSection);
+    let folds = detail_folds(app, Focus::RequestSection);
     let clicked = clicked_detail_row(
         area,
         column,
@@ -345,6 +365,7 @@ fn request_details_action(area: Rect, app: &App, column: u16, row: u16) -> Optio
         app.request_details_scroll,
         &content,
         &annotations,
+        &folds,
     );
     let has_request = exchange.and_then(|value| value.request.as_ref()).is_some();
     if has_request && clicked == Some(ClickedDetail::Line(tab_line + 1)) {
@@ -358,6 +379,10 @@ fn request_details_action(area: Rect, app: &App, column: u16, row: u16) -> Optio
                 panel: Focus::RequestSection,
                 line,
             },
+            ClickedDetail::Fold(line) => MouseAction::ToggleFold {
+                panel: Focus::RequestSection,
+                line,
+            },
             ClickedDetail::Annotation(id) => MouseAction::SelectAnnotation { id },
         })
         .or(Some(MouseAction::Focus(Focus::RequestSection)))
@@ -366,6 +391,7 @@ fn request_details_action(area: Rect, app: &App, column: u16, row: u16) -> Optio
 fn response_details_action(area: Rect, app: &App, column: u16, row: u16) -> Option<MouseAction> {
     let content = response_detail_lines(app);
     let annotations = detail_annotations(app, Focus::ResponseSection);
+    let folds = detail_folds(app, Focus::ResponseSection);
     let clicked = clicked_detail_row(
         area,
         column,
@@ -373,6 +399,7 @@ fn response_details_action(area: Rect, app: &App, column: u16, row: u16) -> Opti
         app.response_details_scroll,
         &content,
         &annotations,
+        &folds,
     );
     let has_response = app
         .get_selected_exchange()
@@ -389,6 +416,10 @@ fn response_details_action(area: Rect, app: &App, column: u16, row: u16) -> Opti
                 panel: Focus::ResponseSection,
                 line,
             },
+            ClickedDetail::Fold(line) => MouseAction::ToggleFold {
+                panel: Focus::ResponseSection,
+                line,
+            },
             ClickedDetail::Annotation(id) => MouseAction::SelectAnnotation { id },
         })
         .or(Some(MouseAction::Focus(Focus::ResponseSection)))
@@ -397,6 +428,7 @@ fn response_details_action(area: Rect, app: &App, column: u16, row: u16) -> Opti
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum ClickedDetail {
     Line(usize),
+    Fold(usize),
     Annotation(String),
 }
 
@@ -461,12 +493,17 @@ fn truncate_to_width(value: &str, max_width: usize) -> String {
     result
 }
 
+// Lines hidden by a fold are skipped; notes on them move to the fold's opening line.
 fn detail_rows<'a>(
     content_len: usize,
     annotations: &'a [&'a LineAnnotation],
+    folds: &Folds,
 ) -> Vec<DetailRow<'a>> {
     let mut rows = Vec::with_capacity(content_len + annotations.len());
     for source_index in 0..content_len {
+        if folds.is_hidden(source_index + 1) {
+            continue;
+        }
         rows.push(DetailRow::Line(source_index));
         rows.extend(
             annotations
@@ -474,7 +511,7 @@ fn detail_rows<'a>(
                 .copied()
                 .filter(|annotation| {
                     annotation.start_line != annotation.end_line
-                        && annotation.end_line == source_index + 1
+                        && folds.shown(annotation.end_line) == source_index + 1
                 })
                 .map(DetailRow::Annotation),
         );
@@ -482,6 +519,27 @@ fn detail_rows<'a>(
     rows
 }
 
+fn inline_annotations<'a>(
+    annotations: &[&'a LineAnnotation],
+    folds: &Folds,
+    line: usize,
+) -> Vec<&'a LineAnnotation> {
+    annotations
+        .iter()
+        .copied()
+        .filter(|annotation| {
+            annotation.start_line == annotation.end_line && folds.shown(annotation.end_line) == line
+        })
+        .collect()
+}
+
+fn fold_summary(folds: &Folds, line: usize) -> Option<String> {
+    folds
+        .at(line)
+        .filter(|fold| fold.folded)
+        .map(|fold| format!(" {}", fold.summary()))
+}
+
 fn clicked_detail_row(
     area: Rect,
     column: u16,
@@ -489,6 +547,7 @@ fn clicked_detail_row(
     scroll: usize,
     content: &[Line<'_>],
     annotations: &[&LineAnnotation],
+    folds: &Folds,
 ) -> Option<ClickedDetail> {
     if row <= area.y || row >= area.y.saturating_add(area.height).saturating_sub(1) {
         return None;
@@ -498,13 +557,17 @@ fn clicked_detail_row(
     let gutter_width = detail_gutter_width(content.len());
     let clicked_column = usize::from(column.saturating_sub(area.x.saturating_add(1)));
     let mut visible_row = usize::from(row.saturating_sub(area.y + 1));
-    let display_scroll = detail_display_scroll(scroll, annotations);
-    for detail_row in detail_rows(content.len(), annotations)
+    let display_scroll = detail_display_scroll(scroll, annotations, folds);
+    for detail_row in detail_rows(content.len(), annotations, folds)
         .into_iter()
         .skip(display_scroll)
     {
         let line_width = match detail_row {
-            DetailRow::Line(index) => content[index].width(),
+            DetailRow::Line(index) => {
+                content[index].width()
+                    + fold_summary(folds, index + 1)
+                        .map_or(0, |summary| Line::from(summary).width())
+            }
             DetailRow::Annotation(annotation) => {
                 Line::from(format!("◆ {}", annotation.message)).width()
             }
@@ -513,14 +576,15 @@ fn clicked_detail_row(
         let height = rendered_width.max(1).div_ceil(width);
         if visible_row < height {
             if let DetailRow::Line(index) = detail_row {
-                let inline = annotations
-                    .iter()
-                    .copied()
-                    .filter(|annotation| {
-                        annotation.start_line == annotation.end_line
-                            && annotation.end_line == index + 1
-                    })
-                    .collect::<Vec<_>>();
+                let marker = gutter_width - 2;
+                let summary = (gutter_width + content[index].width())..rendered_width;
+                if visible_row == 0
+                    && folds.at(index + 1).is_some()
+                    && (clicked_column == marker || summary.contains(&clicked_column))
+                {
+                    return Some(ClickedDetail::Fold(index + 1));
+                }
+                let inline = inline_annotations(annotations, folds, index + 1);
                 let mut start = rendered_width;
                 for note in inline_annotation_layout(start, &inline, width) {
                     let end = start + note.width;
@@ -547,12 +611,18 @@ fn detail_annotations(app: &App, panel: Focus) -> Vec<&LineAnnotation> {
     app.visible_annotations(panel).collect()
 }
 
-fn detail_display_scroll(source_scroll: usize, annotations: &[&LineAnnotation]) -> usize {
-    source_scroll
+fn detail_display_scroll(
+    source_scroll: usize,
+    annotations: &[&LineAnnotation],
+    folds: &Folds,
+) -> usize {
+    let line = folds.shown(source_scroll + 1);
+    folds.rank(line)
         + annotations
             .iter()
             .filter(|annotation| {
-                annotation.start_line != annotation.end_line && annotation.end_line <= source_scroll
+                annotation.start_line != annotation.end_line
+                    && folds.shown(annotation.end_line) < line
             })
             .count()
 }
@@ -563,6 +633,7 @@ fn tab_action(area: Rect, column: u16, gutter_width: usize) -> Option<usize> {
     match column {
         0..=8 => Some(0),
         9..=14 => Some(1),
+        15..=23 => Some(2),
         _ => None,
     }
 }
@@ -577,16 +648,7 @@ fn format_json_with_highlighting(json_value: &serde_json::Value) -> Vec<Line<'st
 
     let mut lines = Vec::new();
 
-    for (line_num, line) in json_str.lines().enumerate() {
-        // Limit total lines to prevent UI issues
-        if line_num > 1000 {
-            lines.push(Line::from(Span::styled(
-                "... (content truncated)",
-                Style::default().fg(Color::Gray),
-            )));
-            break;
-        }
-
+    for line in json_str.lines() {
         // Don't trim the line - work with it as-is to preserve indentation
         let mut spans = Vec::new();
         let mut chars = line.chars().peekable();
@@ -796,6 +858,9 @@ pub fn draw(f: &mut Frame, app: &App) {
             }
             InputMode::NamingSession => draw_input_dialog(f, app, "New Session", "Name (optional)"),
             InputMode::RenamingSession => draw_input_dialog(f, app, "Rename Session", "Name"),
+            InputMode::Querying => draw_input_dialog(f, app, "Query", "JSONPath"),
+            InputMode::Searching => draw_input_dialog(f, app, "Search", "Pattern"),
+            InputMode::Tagging => draw_input_dialog(f, app, "Tag Exchange", "Tags"),
             InputMode::Normal | InputMode::EditingTarget => {}
         }
     }
@@ -803,6 +868,15 @@ pub fn draw(f: &mut Frame, app: &App) {
     match app.overlay {
         Overlay::Help => draw_keybind_help(f, app),
         Overlay::Sessions => draw_sessions(f, app),
+        Overlay::Mcp => draw_scrolling_overlay(f, app, "MCP", mcp_summary_lines(app)),
+        Overlay::Subscription => {
+            draw_scrolling_overlay(f, app, "Subscription events", subscription_event_lines(app))
+        }
+        Overlay::Batch => draw_scrolling_overlay(f, app, "Batch", batch_lines(app)),
+        Overlay::Diff => draw_scrolling_overlay(f, app, "Diff", diff_lines(app)),
+        Overlay::Columns => draw_columns(f, app),
+        Overlay::Snippets => draw_snippets(f, app),
+        Overlay::Profiles => draw_profiles(f, app),
         Overlay::None | Overlay::Prefix => {}
     }
 }
@@ -817,12 +891,26 @@ fn draw_keybind_help(f: &mut Frame, app: &App) {
             )),
             Line::from("^B z  fullscreen panel"),
             Line::from("^B y  copy focused panel as Markdown"),
+            Line::from("^B Y  copy as curl, HTTPie, or a replay script"),
+            Line::from("^B r  save Markdown and HTML reports of the listed exchanges"),
+            Line::from("^B m  MCP summary    ^B e  subscription events"),
+            Line::from("^B b  batch request and response"),
+            Line::from("^B w  timeline of request-to-response bars"),
+            Line::from("^B f  toggle the JSON tree in body tabs"),
+            Line::from("^B =  diff the two marked exchanges"),
+            Line::from("^B o  choose, resize, and sort request list columns"),
             Line::from("^B q  quit           ^B ?  this help"),
             Line::from(""),
             Line::from(Span::styled("Navigation", Style::default().fg(Color::Cyan))),
             Line::from("↑/↓ or j/k navigate   Tab focus   h/l tabs   / filter"),
             Line::from("d/u page   g/G top/bottom"),
-            Line::from("Requests: Enter response   Details: Enter copy Markdown"),
+            Line::from("Requests: Enter response   Space fold batch or subscription"),
+            Line::from("Requests: m mark for diff (two marks, any sessions)"),
+            Line::from("Requests: * star   # tags   filter tag:<name> or is:starred"),
+            Line::from("Timeline: +/- zoom around selection   [/] pan"),
+            Line::from("Details: Enter copy Markdown   Space fold object or array"),
+            Line::from("Details: . query   P pin query as a column   Esc clear query"),
+            Line::from("Details: / search (regex)   n/N next/previous match"),
             Line::from("The external client owns the stdio data plane."),
         ]
     } else {
@@ -836,9 +924,18 @@ fn draw_keybind_help(f: &mut Frame, app: &App) {
             Line::from("^B a  annotate visual selection"),
             Line::from("^B c  create request ^B p  pause interception"),
             Line::from("^B t  target         ^B x  start/stop proxy"),
+            Line::from("^B P  switch configuration profile"),
             Line::from("^B z  fullscreen panel"),
             Line::from("^B y  copy focused panel as Markdown"),
+            Line::from("^B Y  copy as curl, HTTPie, or a replay script"),
+            Line::from("^B r  save Markdown and HTML reports of the listed exchanges"),
             Line::from("^B d  delete focused annotation"),
+            Line::from("^B m  MCP summary    ^B e  subscription events"),
+            Line::from("^B b  batch request and response"),
+            Line::from("^B w  timeline of request-to-response bars"),
+            Line::from("^B f  toggle the JSON tree in body tabs"),
+            Line::from("^B =  diff the two marked exchanges"),
+            Line::from("^B o  choose, resize, and sort request list columns"),
             Line::from("^B q  quit           ^B ?  this help"),
             Line::from(""),
             Line::from(Span::styled(
@@ -851,7 +948,14 @@ fn draw_keybind_help(f: &mut Frame, app: &App) {
             Line::from(Span::styled("Navigation", Style::default().fg(Color::Cyan))),
             Line::from("↑/↓ or j/k navigate   Tab focus   h/l tabs   / filter"),
             Line::from("d/u page   g/G top/bottom"),
-            Line::from("Requests: Enter response   Details: Enter copy Markdown"),
+            Line::from("Requests: Enter response   Space fold batch or subscription"),
+            Line::from("Requests: m mark for diff (two marks, any sessions)"),
+            Line::from("Requests: * star   # tags   filter tag:<name> or is:starred"),
+            Line::from("Requests: filter source:<name> for one listener"),
+            Line::from("Timeline: +/- zoom around selection   [/] pan"),
+            Line::from("Details: Enter copy Markdown   Space fold object or array"),
+            Line::from("Details: . query   P pin query as a column   Esc clear query"),
+            Line::from("Details: / search (regex)   n/N next/previous match"),
             Line::from("Details: v visual select   j/k extend   Esc clear"),
         ]
     };
@@ -892,7 +996,77 @@ fn draw_sessions(f: &mut Frame, app: &App) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Sessions — Enter/click open · Esc close"),
+                .title("Sessions — Enter/click open · = diff with active · Esc close"),
+        )
+        .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black))
+        .highlight_symbol("› ");
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut state);
+}
+
+fn draw_profiles(f: &mut Frame, app: &App) {
+    let popup = centered_popup(f.size(), 60, 50);
+    let items = app
+        .config
+        .profiles
+        .iter()
+        .map(|(name, profile)| {
+            let marker = if app.profile.as_deref() == Some(name.as_str()) {
+                "●"
+            } else {
+                " "
+            };
+            let target = if profile.command.is_empty() {
+                profile.target.clone().unwrap_or_default()
+            } else {
+                profile.command.join(" ")
+            };
+            ListItem::new(format!("{marker} {name:<16} {target}"))
+        })
+        .collect::<Vec<_>>();
+    let title = if items.is_empty() {
+        format!(
+            "Profiles — none in {} or ./{} · Esc close",
+            profile::USER_FILE,
+            profile::PROJECT_FILE
+        )
+    } else {
+        "Profiles — Enter apply · Esc close".to_string()
+    };
+    let mut state = ratatui::widgets::ListState::default();
+    state.select((!items.is_empty()).then_some(app.selected_profile));
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black))
+        .highlight_symbol("› ");
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut state);
+}
+
+fn draw_columns(f: &mut Frame, app: &App) {
+    let popup = centered_popup(f.size(), 60, 60);
+    let items = Column::ALL
+        .iter()
+        .map(|column| {
+            let width = app.columns.width(*column);
+            let check = if width.is_some() { "[x]" } else { "[ ]" };
+            let width = width.map_or("-".to_string(), |width| width.to_string());
+            let sort = match app.columns.sort {
+                Some(sort) if sort.column == *column && sort.descending => "▼ sorted",
+                Some(sort) if sort.column == *column => "▲ sorted",
+                _ => "",
+            };
+            ListItem::new(format!("{check} {:<12} {width:>3}  {sort}", column.title()))
+        })
+        .collect::<Vec<_>>();
+    let mut state = ratatui::widgets::ListState::default();
+    state.select(Some(app.selected_column));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Columns — Space show · </> width · s sort · Esc close")
+                .border_style(Style::default().fg(Color::Yellow)),
         )
         .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black))
         .highlight_symbol("› ");
@@ -900,6 +1074,379 @@ fn draw_sessions(f: &mut Frame, app: &App) {
     f.render_stateful_widget(list, popup, &mut state);
 }
 
+fn draw_snippets(f: &mut Frame, app: &App) {
+    let popup = centered_popup(f.size(), 50, 50);
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let source = app
+        .exchanges
+        .get(app.selected_exchange)
+        .and_then(JsonRpcExchange::source);
+    let destination = match snippet::url(app, source, app.snippet_via_proxy) {
+        Ok(url) => Span::raw(url),
+        Err(error) => Span::styled(error, Style::default().fg(Color::Red)),
+    };
+    let mut lines = vec![
+        Line::from(vec![Span::styled("Sends to ", bold), destination]),
+        Line::from(""),
+    ];
+    for format in snippet::Format::ALL {
+        let scope = if format.is_script() {
+            "whole session"
+        } else {
+            "selected exchange"
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {}  ", snippet_key(format)), bold.fg(Color::Cyan)),
+            Span::raw(format!("{:<18} {scope}", format.title())),
+        ]));
+    }
+    lines.push(Line::from(""));
+    let other = if app.snippet_via_proxy {
+        "the target"
+    } else {
+        "the proxy"
+    };
+    lines.push(Line::from(vec![
+        Span::styled(" v  ", bold.fg(Color::Cyan)),
+        Span::raw(format!("send to {other} instead")),
+    ]));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Copy as — Esc close")
+        .border_style(Style::default().fg(Color::Yellow));
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+/// The key that copies `format` in the copy-as overlay.
+pub fn snippet_key(format: snippet::Format) -> char {
+    match format {
+        snippet::Format::Curl => 'c',
+        snippet::Format::Httpie => 'h',
+        snippet::Format::Shell => 's',
+        snippet::Format::Python => 'p',
+        snippet::Format::TypeScript => 't',
+        snippet::Format::Rust => 'r',
+    }
+}
+
+fn draw_scrolling_overlay(f: &mut Frame, app: &App, title: &str, lines: Vec<Line<'static>>) {
+    let popup = centered_popup(f.size(), 82, 80);
+    let visible = usize::from(popup.height.saturating_sub(2));
+    let scroll = app.overlay_scroll.min(lines.len().saturating_sub(visible));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{title} — j/k scroll · Esc close"))
+        .border_style(Style::default().fg(Color::Yellow));
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((u16::try_from(scroll).unwrap_or(u16::MAX), 0)),
+        popup,
+    );
+}
+
+pub fn batch_lines(app: &App) -> Vec<Line<'static>> {
+    let batches = batch::batches(&app.exchanges);
+    let Some(batch) = batch::containing(&batches, app.selected_exchange) else {
+        return vec![Line::from("No batch selected.")];
+    };
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let counts = batch.counts(&app.exchanges, &app.status_context());
+    let duration = batch
+        .duration(&app.exchanges)
+        .map(|duration| format!(" · {}ms", duration.as_millis()))
+        .unwrap_or_default();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("Batch of {}", batch.members.len()),
+            bold.fg(Color::Cyan),
+        )),
+        Line::from(format!(
+            "{} ok · {} failed · {} waiting{duration}",
+            counts.success, counts.error, counts.waiting
+        )),
+        Line::from(""),
+    ];
+
+    for (title, body) in [
+        ("Request", batch.request_body(&app.exchanges)),
+        (
+            "Response (server order)",
+            batch.response_body(&app.exchanges),
+        ),
+    ] {
+        lines.push(Line::from(Span::styled(title, bold)));
+        let json = serde_json::to_string_pretty(&body).unwrap_or_else(|_| body.to_string());
+        lines.extend(json.lines().map(|line| Line::from(line.to_string())));
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
+pub fn diff_lines(app: &App) -> Vec<Line<'static>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    match &app.comparison {
+        None => vec![Line::from("Nothing to compare.")],
+        Some(Comparison::Exchanges {
+            left,
+            right,
+            differences,
+        }) => {
+            let mut lines = vec![
+                Line::from(vec![
+                    Span::styled(format!("- {left}"), bold.fg(Color::Red)),
+                    Span::raw("  "),
+                    Span::styled(format!("+ {right}"), bold.fg(Color::Green)),
+                ]),
+                Line::from(match differences.len() {
+                    0 => "Identical".to_string(),
+                    1 => "1 difference".to_string(),
+                    count => format!("{count} differences"),
+                }),
+                Line::from(""),
+            ];
+            lines.extend(
+                differences
+                    .iter()
+                    .map(|difference| difference_line(difference, "")),
+            );
+            lines
+        }
+        Some(Comparison::Sessions { left, right, pairs }) => {
+            let tally = diff::tally(pairs);
+            let mut lines = vec![
+                Line::from(vec![
+                    Span::styled(format!("- {left}"), bold.fg(Color::Red)),
+                    Span::raw("  "),
+                    Span::styled(format!("+ {right}"), bold.fg(Color::Green)),
+                ]),
+                Line::from(format!(
+                    "{} identical · {} changed · {} only left · {} only right",
+                    tally.identical, tally.changed, tally.only_left, tally.only_right
+                )),
+                Line::from(""),
+            ];
+            for pair in pairs {
+                let (symbol, color) = match (pair.left, pair.right) {
+                    (Some(_), None) => ('-', Color::Red),
+                    (None, _) => ('+', Color::Green),
+                    _ if pair.differences.is_empty() => continue,
+                    _ => ('~', Color::Yellow),
+                };
+                let index = |index: Option<usize>| {
+                    index.map_or_else(|| "—".to_string(), |index| format!("#{index}"))
+                };
+                let id = pair
+                    .id
+                    .as_ref()
+                    .map(|id| format!("  id {id}"))
+                    .unwrap_or_default();
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "{symbol} {} ↔ {}  {}{id}",
+                        index(pair.left),
+                        index(pair.right),
+                        pair.method.as_deref().unwrap_or("unknown")
+                    ),
+                    bold.fg(color),
+                )));
+                lines.extend(
+                    pair.differences
+                        .iter()
+                        .map(|difference| difference_line(difference, "    ")),
+                );
+            }
+            lines
+        }
+    }
+}
+
+fn difference_line(difference: &diff::Difference, indent: &str) -> Line<'static> {
+    let color = match difference.kind {
+        ChangeKind::Added => Color::Green,
+        ChangeKind::Removed => Color::Red,
+        ChangeKind::Changed => Color::Yellow,
+    };
+    let value = |value: &Option<serde_json::Value>| {
+        value
+            .as_ref()
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+    };
+    let change = match difference.kind {
+        ChangeKind::Added => value(&difference.after),
+        ChangeKind::Removed => value(&difference.before),
+        ChangeKind::Changed => format!(
+            "{} → {}",
+            value(&difference.before),
+            value(&difference.after)
+        ),
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("{indent}{} {}", difference.kind.symbol(), difference.path),
+            Style::default().fg(color),
+        ),
+        Span::raw(format!("  {change}")),
+    ])
+}
+
+pub fn subscription_event_lines(app: &App) -> Vec<Line<'static>> {
+    let subscriptions = subscription::subscriptions(&app.exchanges);
+    let Some(subscription) = subscription::containing(&subscriptions, app.selected_exchange) else {
+        return vec![Line::from("No subscription selected.")];
+    };
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let rate = subscription
+        .rate(&app.exchanges)
+        .map(|rate| format!(" · {rate:.1}/s"))
+        .unwrap_or_default();
+    let state = match subscription.unsubscribe {
+        Some(index) => format!(" · unsubscribed at #{index}"),
+        None => " · active".to_string(),
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(subscription.label(), bold.fg(Color::Cyan)),
+            Span::raw(format!("  id {}", subscription.id)),
+        ]),
+        Line::from(format!("{} events{rate}{state}", subscription.events.len())),
+        Line::from(""),
+    ];
+
+    let started = app.exchanges[subscription.request].timestamp;
+    for index in &subscription.events {
+        let Some(request) = app.exchanges[*index].request.as_ref() else {
+            continue;
+        };
+        let offset = request
+            .timestamp
+            .duration_since(started)
+            .map(|offset| format!("+{:.3}s", offset.as_secs_f64()))
+            .unwrap_or_else(|_| "-".to_string());
+        let payload = request
+            .params
+            .as_ref()
+            .and_then(|params| params.get("result"))
+            .map(serde_json::Value::to_string)
+            .unwrap_or_default();
+        let payload = if payload.chars().count() > 100 {
+            format!("{}…", payload.chars().take(99).collect::<String>())
+        } else {
+            payload
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("#{index:<6}"), Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{offset:>10}  "),
+                Style::default().fg(Color::Magenta),
+            ),
+            Span::raw(payload),
+        ]));
+    }
+    lines
+}
+
+pub fn mcp_summary_lines(app: &App) -> Vec<Line<'static>> {
+    let Some(summary) = mcp::summary(&app.exchanges) else {
+        return vec![Line::from(
+            "No MCP initialize handshake or catalog in this session.",
+        )];
+    };
+    let heading = |text: &'static str| {
+        Line::from(Span::styled(
+            text,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+    };
+    let peer = |info: &Option<serde_json::Value>| {
+        info.as_ref()
+            .map(|info| {
+                let name = info.get("name").and_then(serde_json::Value::as_str);
+                let version = info.get("version").and_then(serde_json::Value::as_str);
+                format!("{} {}", name.unwrap_or("unknown"), version.unwrap_or(""))
+                    .trim_end()
+                    .to_string()
+            })
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+    let capabilities = |capabilities: &Option<serde_json::Value>| {
+        capabilities
+            .as_ref()
+            .and_then(serde_json::Value::as_object)
+            .filter(|capabilities| !capabilities.is_empty())
+            .map(|capabilities| capabilities.keys().cloned().collect::<Vec<_>>().join(", "))
+            .unwrap_or_else(|| "none".to_string())
+    };
+
+    let mut lines = vec![
+        heading("Handshake"),
+        Line::from(format!(
+            "Protocol {}",
+            summary.protocol_version.as_deref().unwrap_or("unknown")
+        )),
+        Line::from(format!(
+            "Client {}: {}",
+            peer(&summary.client_info),
+            capabilities(&summary.client_capabilities)
+        )),
+        Line::from(format!(
+            "Server {}: {}",
+            peer(&summary.server_info),
+            capabilities(&summary.server_capabilities)
+        )),
+    ];
+    if let Some(instructions) = &summary.instructions {
+        lines.push(Line::from(format!("Instructions: {instructions}")));
+    }
+
+    for (title, entries, key) in [
+        ("Tools", &summary.tools, "name"),
+        ("Resources", &summary.resources, "uri"),
+        (
+            "Resource templates",
+            &summary.resource_templates,
+            "uriTemplate",
+        ),
+        ("Prompts", &summary.prompts, "name"),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("{title} ({})", entries.len()),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for entry in entries {
+            let name = entry
+                .get(key)
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("unnamed");
+            let mut spans = vec![Span::styled(
+                format!("  {name}"),
+                Style::default().fg(Color::Green),
+            )];
+            if let Some(description) = entry.get("description").and_then(serde_json::Value::as_str)
+            {
+                spans.push(Span::styled(
+                    format!("  {}", description.lines().next().unwrap_or("")),
+                    Style::default().fg(Color::Gray),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    lines
+}
+
 fn session_popup(area: Rect) -> Rect {
     centered_popup(area, 82, 70)
 }
@@ -1175,11 +1722,16 @@ fn draw_status_header(f: &mut Frame, area: Rect, app: &App) {
 
     let data_plane = if app.proxy_config.transparent {
         vec![Span::styled("Data:", label_style), Span::raw(" STDIO")]
-    } else {
+    } else if app.proxy_config.listeners.is_empty() {
         vec![
             Span::styled("Port:", label_style),
             Span::raw(format!(" {}", app.proxy_config.listen_port)),
         ]
+    } else {
+        vec![
+            Span::styled("Ports:", label_style),
+            Span::raw(format!(" {}", app.listener_ports())),
+        ]
     };
     let info_line = Line::from(
         [
@@ -1199,6 +1751,20 @@ fn draw_status_header(f: &mut Frame, area: Rect, app: &App) {
             "Editing target (Enter to save, Esc to cancel)",
             Style::default().fg(Color::Yellow),
         )));
+    } else if let Some(timing) = app
+        .get_selected_exchange()
+        .and_then(|exchange| exchange.timing())
+    {
+        let phases = timing
+            .phases()
+            .into_iter()
+            .map(|(name, duration)| format!("{name} {}", timeline::format_duration(duration)))
+            .collect::<Vec<_>>()
+            .join(" · ");
+        lines.push(Line::from(vec![
+            Span::styled("Timing:", label_style),
+            Span::styled(format!(" {phases}"), Style::default().fg(Color::Magenta)),
+        ]));
     }
 
     let mut block = Block::default().borders(Borders::ALL).title(Span::styled(
@@ -1251,6 +1817,174 @@ fn draw_fullscreen_panel(f: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+// Subscribe rows carry a fold marker and event stats; expanded events are indented.
+fn subscription_row_label(
+    app: &App,
+    subscriptions: &[subscription::Subscription],
+    index: usize,
+    exchange: &JsonRpcExchange,
+) -> Option<String> {
+    let subscription = subscription::containing(subscriptions, index)?;
+    if subscription.request != index {
+        return subscription
+            .events
+            .contains(&index)
+            .then(|| format!("  └ {}", exchange.method.as_deref().unwrap_or("unknown")));
+    }
+
+    let marker = if app.expanded_subscriptions.contains(&index) {
+        "▾"
+    } else {
+        "▸"
+    };
+    let rate = subscription
+        .rate(&app.exchanges)
+        .map(|rate| format!(", {rate:.1}/s"))
+        .unwrap_or_default();
+    let state = if subscription.is_active() {
+        ""
+    } else {
+        ", closed"
+    };
+    Some(format!(
+        "{marker} {} ({} events{rate}{state})",
+        subscription.label(),
+        subscription.events.len()
+    ))
+}
+
+fn batch_row_label(
+    app: &App,
+    batches: &[batch::Batch],
+    index: usize,
+    exchange: &JsonRpcExchange,
+    context: &StatusContext,
+) -> Option<String> {
+    let batch = batch::containing(batches, index)?;
+    let method = exchange.method.as_deref().unwrap_or("unknown");
+    if batch.head() != index {
+        return Some(format!("  └ {method}"));
+    }
+
+    let marker = if app.expanded_batches.contains(&index) {
+        "▾"
+    } else {
+        "▸"
+    };
+    let counts = batch.counts(&app.exchanges, context);
+    let summary = [
+        (counts.success, "ok"),
+        (counts.error, "failed"),
+        (counts.waiting, "waiting"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count} {label}"))
+    .collect::<Vec<_>>()
+    .join(", ");
+    Some(format!(
+        "{marker} batch ×{} · {method} ({summary})",
+        batch.members.len()
+    ))
+}
+
+fn row_method(
+    app: &App,
+    batches: &[batch::Batch],
+    subscriptions: &[subscription::Subscription],
+    index: usize,
+    exchange: &JsonRpcExchange,
+    context: &StatusContext,
+) -> String {
+    let method = batch_row_label(app, batches, index, exchange, context)
+        .or_else(|| subscription_row_label(app, subscriptions, index, exchange))
+        .or_else(|| mcp::label(exchange))
+        .unwrap_or_else(|| "unknown".to_string());
+    let method = match app.bookmark(index) {
+        Some(bookmark) => bookmark.decorate(&method),
+        None => method,
+    };
+    if app.is_diff_marked(index) {
+        format!("◆ {method}")
+    } else {
+        method
+    }
+}
+
+// A collapsed batch row reports the whole batch.
+fn collapsed_batch<'a>(
+    app: &App,
+    batches: &'a [batch::Batch],
+    index: usize,
+) -> Option<&'a batch::Batch> {
+    batch::containing(batches, index)
+        .filter(|batch| batch.head() == index && !app.expanded_batches.contains(&index))
+}
+
+fn row_status(
+    app: &App,
+    batches: &[batch::Batch],
+    index: usize,
+    exchange: &JsonRpcExchange,
+    context: &StatusContext,
+) -> (&'static str, Color) {
+    let collapsed_batch = collapsed_batch(app, batches, index);
+    let status = collapsed_batch.map_or_else(
+        || context.status(exchange),
+        |batch| batch.status(&app.exchanges, context),
+    );
+    let late = collapsed_batch.is_none() && context.lateness(exchange).is_some();
+    match status {
+        ExchangeStatus::Notification => ("• Notify", Color::Cyan),
+        ExchangeStatus::Pending => ("⏳ Pending", Color::Yellow),
+        ExchangeStatus::TimedOut => ("⌛ Timed out", Color::LightRed),
+        ExchangeStatus::Orphan => ("? Orphan", Color::Magenta),
+        ExchangeStatus::Error if late => ("✗ Late", Color::LightYellow),
+        ExchangeStatus::Success if late => ("✓ Late", Color::LightYellow),
+        ExchangeStatus::Error => ("✗ Error", Color::Red),
+        ExchangeStatus::Success => ("✓ Success", Color::Green),
+    }
+}
+
+fn row_duration(
+    app: &App,
+    batches: &[batch::Batch],
+    index: usize,
+    exchange: &JsonRpcExchange,
+) -> Option<std::time::Duration> {
+    if let Some(batch) = collapsed_batch(app, batches, index) {
+        return batch.duration(&app.exchanges);
+    }
+    let (request, response) = (exchange.request.as_ref()?, exchange.response.as_ref()?);
+    response.timestamp.duration_since(request.timestamp).ok()
+}
+
+fn list_block(title: String, app: &App) -> Block<'static> {
+    let block = Block::default().borders(Borders::ALL).title(title);
+    if matches!(app.focus, Focus::MessageList) {
+        block.border_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        block.border_style(Style::default().fg(Color::DarkGray))
+    }
+}
+
+fn list_highlight_style(app: &App) -> Style {
+    if matches!(app.focus, Focus::MessageList) {
+        Style::default()
+            .bg(Color::Cyan)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    }
+}
+
+const QUERY_COLUMN_WIDTH: u16 = 18;
+
 fn draw_message_list(f: &mut Frame, area: Rect, app: &App) {
     let filtered: Vec<(usize, &JsonRpcExchange)> = app
         .filtered_exchange_indices()
@@ -1275,25 +2009,18 @@ fn draw_message_list(f: &mut Frame, area: Rect, app: &App) {
             "Press Ctrl-B x to start the proxy and begin capturing messages".to_string()
         };
 
-        let mut block = Block::default().borders(Borders::ALL).title("Requests");
-        if matches!(app.focus, Focus::MessageList) {
-            block = block.border_style(
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            );
-        } else {
-            block = block.border_style(Style::default().fg(Color::DarkGray));
-        }
-
         let paragraph = Paragraph::new(empty_message.as_str())
-            .block(block)
+            .block(list_block("Requests".to_string(), app))
             .style(Style::default().fg(Color::Gray))
             .wrap(Wrap { trim: true });
 
         f.render_widget(paragraph, area);
         return;
     }
+    if app.timeline_visible {
+        draw_timeline(f, area, app, &filtered);
+        return;
+    }
 
     let selected_position = filtered
         .iter()
@@ -1302,109 +2029,189 @@ fn draw_message_list(f: &mut Frame, area: Rect, app: &App) {
     let visible_rows = area.height.saturating_sub(3) as usize;
     let offset = app.history_scroll_offset(visible_rows);
 
-    let highlight_style = if matches!(app.focus, Focus::MessageList) {
-        Style::default()
-            .bg(Color::Cyan)
-            .fg(Color::Black)
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
+    let header = Row::new(
+        app.columns
+            .columns
+            .iter()
+            .map(|shown| {
+                let arrow = match app.columns.sort {
+                    Some(sort) if sort.column == shown.column && sort.descending => " ▼",
+                    Some(sort) if sort.column == shown.column => " ▲",
+                    _ => "",
+                };
+                Cell::from(format!("{}{arrow}", shown.column.title()))
+            })
+            .chain(
+                app.query_columns
+                    .iter()
+                    .map(|column| Cell::from(column.expression().to_string())),
+            )
+            .collect::<Vec<_>>(),
+    )
+    .style(Style::default().add_modifier(Modifier::BOLD))
+    .height(1);
+
+    let subscriptions = subscription::subscriptions(&app.exchanges);
+    let batches = batch::batches(&app.exchanges);
+    let context = app.status_context();
+    let origin = columns::session_origin(&app.exchanges);
+    let rows: Vec<Row> = filtered
+        .iter()
+        .skip(offset)
+        .take(visible_rows)
+        .map(|(index, exchange)| {
+            let cells = app.columns.columns.iter().map(|shown| match shown.column {
+                Column::Status => {
+                    let (symbol, color) = row_status(app, &batches, *index, exchange, &context);
+                    Cell::from(symbol).style(Style::default().fg(color))
+                }
+                Column::Method => Cell::from(row_method(
+                    app,
+                    &batches,
+                    &subscriptions,
+                    *index,
+                    exchange,
+                    &context,
+                ))
+                .style(Style::default().fg(Color::Red)),
+                Column::Duration => Cell::from(
+                    row_duration(app, &batches, *index, exchange)
+                        .map(timeline::format_duration)
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+                .style(Style::default().fg(Color::Magenta)),
+                column => {
+                    let color = match column {
+                        Column::Transport => Color::Blue,
+                        Column::Client => Color::Cyan,
+                        Column::Source => Color::LightBlue,
+                        Column::ErrorCode => Color::LightRed,
+                        Column::FirstParam => Color::White,
+                        _ => Color::Gray,
+                    };
+                    Cell::from(column.text(exchange, &context, origin))
+                        .style(Style::default().fg(color))
+                }
+            });
+
+            let document = query::document(exchange);
+            let derived = app.query_columns.iter().map(|column| {
+                Cell::from(query::cell(&column.evaluate(&document)))
+                    .style(Style::default().fg(Color::Yellow))
+            });
+
+            Row::new(cells.chain(derived).collect::<Vec<_>>()).height(1)
+        })
+        .collect();
+
+    // The method column takes whatever width is left.
+    let widths = app
+        .columns
+        .columns
+        .iter()
+        .map(|shown| match shown.column {
+            Column::Method => Constraint::Min(shown.width),
+            _ => Constraint::Length(shown.width),
+        })
+        .chain(
+            app.query_columns
+                .iter()
+                .map(|_| Constraint::Length(QUERY_COLUMN_WIDTH)),
+        )
+        .collect::<Vec<_>>();
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(list_block("Requests".to_string(), app))
+        .highlight_style(list_highlight_style(app))
+        .highlight_symbol("  ")
+        .highlight_spacing(HighlightSpacing::Always);
+
+    let mut table_state = TableState::default();
+    table_state.select(
+        selected_position
+            .checked_sub(offset)
+            .filter(|position| *position < visible_rows),
+    );
+    f.render_stateful_widget(table, area, &mut table_state);
+
+    draw_list_scrollbar(f, area, filtered.len(), offset, visible_rows);
+}
+
+// Bars share the list's rows, scrolling, and selection so clicks map the same way.
+fn draw_timeline(f: &mut Frame, area: Rect, app: &App, filtered: &[(usize, &JsonRpcExchange)]) {
+    const METHOD_WIDTH: u16 = 24;
+    const DURATION_WIDTH: u16 = 9;
+
+    let indices = filtered.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+    let selected_position = indices
+        .iter()
+        .position(|index| *index == app.selected_exchange)
+        .unwrap_or(0);
+    let visible_rows = area.height.saturating_sub(3) as usize;
+    let offset = app.history_scroll_offset(visible_rows);
+    // Borders, highlight symbol, and the spacing between three columns.
+    let bar_width = area
+        .width
+        .saturating_sub(2 + 2 + METHOD_WIDTH + DURATION_WIDTH + 2) as usize;
+
+    let now = std::time::SystemTime::now();
+    let spans = app.timeline_spans(&indices);
+    let window = app.timeline_window(&indices, now);
+    let subscriptions = subscription::subscriptions(&app.exchanges);
+    let batches = batch::batches(&app.exchanges);
+    let context = app.status_context();
 
     let header = Row::new(vec![
-        Cell::from("Status"),
-        Cell::from("Transport"),
         Cell::from("Method"),
-        Cell::from("ID"),
         Cell::from("Duration"),
+        Cell::from(
+            window
+                .map(|window| window.axis(bar_width))
+                .unwrap_or_default(),
+        ),
     ])
     .style(Style::default().add_modifier(Modifier::BOLD))
     .height(1);
 
     let rows: Vec<Row> = filtered
         .iter()
+        .zip(&spans)
         .skip(offset)
         .take(visible_rows)
-        .map(|(_, exchange)| {
-            let transport_symbol = exchange.transport.label();
-
-            let method = exchange.method.as_deref().unwrap_or("unknown");
-            let id = exchange
-                .id
-                .as_ref()
-                .map(|v| match v {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    _ => v.to_string(),
-                })
-                .unwrap_or_else(|| "null".to_string());
-
-            let (status_symbol, status_color) = if exchange.is_notification() {
-                ("• Notify", Color::Cyan)
-            } else if exchange.response.is_none() {
-                ("⏳ Pending", Color::Yellow)
-            } else if let Some(response) = &exchange.response {
-                if response.error.is_some() {
-                    ("✗ Error", Color::Red)
-                } else {
-                    ("✓ Success", Color::Green)
-                }
-            } else {
-                ("? Unknown", Color::Gray)
-            };
-
-            let duration_text =
-                if let (Some(request), Some(response)) = (&exchange.request, &exchange.response) {
-                    match response.timestamp.duration_since(request.timestamp) {
-                        Ok(duration) => {
-                            let millis = duration.as_millis();
-                            if millis < 1000 {
-                                format!("{}ms", millis)
-                            } else {
-                                format!("{:.2}s", duration.as_secs_f64())
-                            }
-                        }
-                        Err(_) => "-".to_string(),
-                    }
-                } else {
-                    "-".to_string()
-                };
+        .map(|((index, exchange), span)| {
+            let method = row_method(app, &batches, &subscriptions, *index, exchange, &context);
+            let (_, status_color) = row_status(app, &batches, *index, exchange, &context);
+            let duration_text = row_duration(app, &batches, *index, exchange)
+                .map(timeline::format_duration)
+                .unwrap_or_else(|| "-".to_string());
+            let bar = window
+                .zip(*span)
+                .map(|(window, span)| window.bar(span, now, bar_width))
+                .unwrap_or_default();
 
             Row::new(vec![
-                Cell::from(status_symbol).style(Style::default().fg(status_color)),
-                Cell::from(transport_symbol).style(Style::default().fg(Color::Blue)),
                 Cell::from(method).style(Style::default().fg(Color::Red)),
-                Cell::from(id).style(Style::default().fg(Color::Gray)),
                 Cell::from(duration_text).style(Style::default().fg(Color::Magenta)),
+                Cell::from(bar).style(Style::default().fg(status_color)),
             ])
             .height(1)
         })
         .collect();
 
-    let mut table_block = Block::default().borders(Borders::ALL).title("Requests");
-    if matches!(app.focus, Focus::MessageList) {
-        table_block = table_block.border_style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        );
-    } else {
-        table_block = table_block.border_style(Style::default().fg(Color::DarkGray));
-    }
-
     let table = Table::new(
         rows,
         [
-            Constraint::Length(12), // Status
-            Constraint::Length(11), // Transport
-            Constraint::Min(15),    // Method (flexible)
-            Constraint::Length(12), // ID
-            Constraint::Length(10), // Duration
+            Constraint::Length(METHOD_WIDTH),
+            Constraint::Length(DURATION_WIDTH),
+            Constraint::Min(10),
         ],
     )
     .header(header)
-    .block(table_block)
-    .highlight_style(highlight_style)
+    .block(list_block(
+        format!("Requests · timeline {}×", app.timeline_zoom),
+        app,
+    ))
+    .highlight_style(list_highlight_style(app))
     .highlight_symbol("  ")
     .highlight_spacing(HighlightSpacing::Always);
 
@@ -1415,27 +2222,31 @@ fn draw_message_list(f: &mut Frame, area: Rect, app: &App) {
             .filter(|position| *position < visible_rows),
     );
     f.render_stateful_widget(table, area, &mut table_state);
+    draw_list_scrollbar(f, area, filtered.len(), offset, visible_rows);
+}
 
-    if filtered.len() > 1 {
-        let mut scrollbar_state = ScrollbarState::new(filtered.len())
-            .position(offset)
-            .viewport_content_length(visible_rows);
-
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(None)
-            .end_symbol(None)
-            .track_symbol(None)
-            .thumb_symbol("▐");
-
-        f.render_stateful_widget(
-            scrollbar,
-            area.inner(&Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scrollbar_state,
-        );
+fn draw_list_scrollbar(f: &mut Frame, area: Rect, rows: usize, offset: usize, visible_rows: usize) {
+    if rows <= 1 {
+        return;
     }
+    let mut scrollbar_state = ScrollbarState::new(rows)
+        .position(offset)
+        .viewport_content_length(visible_rows);
+
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_symbol(None)
+        .thumb_symbol("▐");
+
+    f.render_stateful_widget(
+        scrollbar,
+        area.inner(&Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut scrollbar_state,
+    );
 }
 
 pub fn detail_line_count(app: &App, panel: Focus) -> Option<usize> {
@@ -1459,16 +2270,36 @@ pub fn detail_lines_text_at(
     tab: crate::app::DetailTab,
 ) -> Option<Vec<String>> {
     let exchange = app.exchanges.get(exchange_index);
-    let tab = usize::from(tab == crate::app::DetailTab::Body);
+    let tab = tab.index();
     let lines = match panel {
-        Focus::RequestSection => request_detail_lines_for(exchange, tab, false),
-        Focus::ResponseSection => response_detail_lines_for(exchange, tab, false),
+        Focus::RequestSection => request_detail_lines_for(exchange, tab, false, &app.abi),
+        Focus::ResponseSection => response_detail_lines_for(exchange, tab, false, &app.abi),
         Focus::MessageList | Focus::StatusHeader => return None,
     };
 
     Some(lines.iter().map(line_text).collect())
 }
 
+/// The contents of a panel tab below its heading, and how many detail lines come before
+/// them, so annotations can be placed without the panel chrome.
+pub fn detail_tab_contents(
+    app: &App,
+    panel: Focus,
+    exchange_index: usize,
+    tab: crate::app::DetailTab,
+) -> Option<(usize, Vec<String>)> {
+    let exchange = app.exchanges.get(exchange_index)?;
+    let heading = match panel {
+        Focus::RequestSection => request_heading_lines(exchange, tab.index(), false).len(),
+        Focus::ResponseSection => response_heading_lines(exchange, tab.index(), false).len(),
+        Focus::MessageList | Focus::StatusHeader => return None,
+    };
+    let mut lines = detail_lines_text_at(app, panel, exchange_index, tab)?;
+    // A blank line separates the tabs from the contents.
+    let offset = (heading + 1).min(lines.len());
+    Some((offset, lines.split_off(offset)))
+}
+
 pub fn detail_line_text(
     app: &App,
     panel: Focus,
@@ -1519,6 +2350,113 @@ fn highlight_selected_lines(
         .collect()
 }
 
+/// The text a search of `panel` runs over: the detail lines, or the intercepted request
+/// outside normal mode.
+pub fn search_lines(app: &App, panel: Focus) -> Option<Vec<String>> {
+    match (app.app_mode, panel) {
+        (AppMode::Normal, _) => detail_lines_text(app, panel),
+        (_, Focus::RequestSection) => {
+            Some(intercept_detail_lines(app).iter().map(line_text).collect())
+        }
+        _ => None,
+    }
+}
+
+fn search_matches(app: &App, panel: Focus) -> Vec<search::Match> {
+    app.panel_search(panel)
+        .zip(search_lines(app, panel))
+        .map(|(search, lines)| search.matches(&lines))
+        .unwrap_or_default()
+}
+
+// Shown in the panel title, such as `/0xab 2/5`.
+fn search_label(app: &App, panel: Focus) -> Option<String> {
+    let search = app.panel_search(panel)?;
+    let matches = search_matches(app, panel);
+    let count = match search.current.and_then(|current| {
+        matches
+            .iter()
+            .position(|found| (found.line, found.start) == current)
+    }) {
+        _ if matches.is_empty() => "no matches".to_string(),
+        Some(index) => format!("{}/{}", index + 1, matches.len()),
+        None if matches.len() == 1 => "1 match".to_string(),
+        None => format!("{} matches", matches.len()),
+    };
+    Some(format!("/{} {count}", search.pattern))
+}
+
+fn highlight_search_matches(
+    lines: Vec<Line<'static>>,
+    app: &App,
+    panel: Focus,
+) -> Vec<Line<'static>> {
+    let Some(search) = app.panel_search(panel) else {
+        return lines;
+    };
+    let texts = lines.iter().map(line_text).collect::<Vec<_>>();
+    let matches = search.matches(&texts);
+    if matches.is_empty() {
+        return lines;
+    }
+
+    let match_style = Style::default().bg(Color::Rgb(92, 76, 0)).fg(Color::White);
+    let current_style = Style::default()
+        .bg(Color::Yellow)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD);
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let found = matches
+                .iter()
+                .filter(|found| found.line == index + 1)
+                .collect::<Vec<_>>();
+            if found.is_empty() {
+                return line;
+            }
+            let style_at = |offset: usize| {
+                found
+                    .iter()
+                    .find(|found| (found.start..found.end).contains(&offset))
+                    .map(|found| {
+                        if search.current == Some((found.line, found.start)) {
+                            current_style
+                        } else {
+                            match_style
+                        }
+                    })
+            };
+            let mut spans = Vec::new();
+            let mut offset = 0;
+            for span in line.spans {
+                let text = span.content.as_ref();
+                // Cut the span wherever a match starts or ends inside it.
+                let mut cuts = found
+                    .iter()
+                    .flat_map(|found| [found.start, found.end])
+                    .filter(|cut| (offset + 1..offset + text.len()).contains(cut))
+                    .map(|cut| cut - offset)
+                    .collect::<Vec<_>>();
+                cuts.sort_unstable();
+                cuts.dedup();
+                let mut from = 0;
+                for to in cuts.into_iter().chain([text.len()]) {
+                    if to > from {
+                        let style = style_at(offset + from)
+                            .map_or(span.style, |style| span.style.patch(style));
+                        spans.push(Span::styled(text[from..to].to_string(), style));
+                    }
+                    from = to;
+                }
+                offset += text.len();
+            }
+            Line::from(spans).style(line.style)
+        })
+        .collect()
+}
+
 fn annotate_detail_lines(lines: Vec<Line<'static>>, app: &App, panel: Focus) -> Vec<Line<'static>> {
     let annotations = detail_annotations(app, panel);
     if annotations.is_empty() {
@@ -1541,39 +2479,39 @@ fn annotate_detail_lines(lines: Vec<Line<'static>>, app: &App, panel: Focus) ->
         .collect()
 }
 
+// Drops lines hidden by folds and adds each annotation beside or below its line.
 fn insert_annotation_lines(
     lines: Vec<Line<'static>>,
     app: &App,
     panel: Focus,
     width: usize,
+    folds: &Folds,
 ) -> Vec<Line<'static>> {
     let annotations = detail_annotations(app, panel);
-    if annotations.is_empty() {
+    if annotations.is_empty() && folds.is_empty() {
         return lines;
     }
 
     let number_width = lines.len().max(1).to_string().len();
-    let mut displayed = Vec::with_capacity(lines.len() + annotations.len());
-    for (source_index, mut line) in lines.into_iter().enumerate() {
-        let inline = annotations
-            .iter()
-            .copied()
-            .filter(|annotation| {
-                annotation.start_line == annotation.end_line
-                    && annotation.end_line == source_index + 1
-            })
-            .collect::<Vec<_>>();
-        for note in inline_annotation_layout(line.width(), &inline, width) {
-            line.spans.extend([
-                Span::styled("  ◆ ", Style::default().fg(ANNOTATION_AMBER)),
-                Span::styled(note.message, Style::default().fg(ANNOTATION_AMBER)),
-            ]);
-        }
-        displayed.push(line);
-        for annotation in annotations.iter().filter(|annotation| {
-            annotation.start_line != annotation.end_line && annotation.end_line == source_index + 1
-        }) {
-            displayed.push(Line::from(vec![
+    let rows = detail_rows(lines.len(), &annotations, folds);
+    let mut lines = lines.into_iter().map(Some).collect::<Vec<_>>();
+    let mut displayed = Vec::with_capacity(rows.len());
+    for row in rows {
+        match row {
+            DetailRow::Line(source_index) => {
+                let Some(mut line) = lines[source_index].take() else {
+                    continue;
+                };
+                let inline = inline_annotations(&annotations, folds, source_index + 1);
+                for note in inline_annotation_layout(line.width(), &inline, width) {
+                    line.spans.extend([
+                        Span::styled("  ◆ ", Style::default().fg(ANNOTATION_AMBER)),
+                        Span::styled(note.message, Style::default().fg(ANNOTATION_AMBER)),
+                    ]);
+                }
+                displayed.push(line);
+            }
+            DetailRow::Annotation(annotation) => displayed.push(Line::from(vec![
                 Span::styled(
                     format!(" {:>number_width$} ╰─", ""),
                     Style::default().fg(Color::DarkGray),
@@ -1588,17 +2526,21 @@ fn insert_annotation_lines(
                     annotation.message.clone(),
                     Style::default().fg(ANNOTATION_AMBER),
                 ),
-            ]));
+            ])),
         }
     }
     displayed
 }
 
+// Opening lines of objects and arrays carry a fold marker in place of the separator, and
+// folded ones end with a summary of what they hide.
 fn number_detail_lines(
     lines: Vec<Line<'static>>,
     cursor_line: Option<usize>,
+    folds: &Folds,
 ) -> Vec<Line<'static>> {
     let number_width = lines.len().max(1).to_string().len();
+    let cursor_line = cursor_line.map(|line| folds.shown(line));
     lines
         .into_iter()
         .enumerate()
@@ -1614,14 +2556,22 @@ fn number_detail_lines(
             } else {
                 Span::raw(" ")
             };
+            let separator = match folds.at(line_number) {
+                Some(fold) if fold.folded => "▸",
+                Some(_) => "▾",
+                None => "│",
+            };
             let mut spans = vec![
                 cursor,
                 Span::styled(
-                    format!("{line_number:>number_width$} │ "),
+                    format!("{line_number:>number_width$} {separator} "),
                     Style::default().fg(Color::DarkGray),
                 ),
             ];
             spans.extend(line.spans);
+            if let Some(summary) = fold_summary(folds, line_number) {
+                spans.push(Span::styled(summary, Style::default().fg(Color::DarkGray)));
+            }
             Line::from(spans).style(line.style)
         })
         .collect()
@@ -1632,6 +2582,20 @@ fn detail_gutter_width(line_count: usize) -> usize {
 }
 
 fn detail_title(title: &str, app: &App, panel: Focus) -> String {
+    let path = app
+        .detail_cursor_line(panel)
+        .filter(|_| app.focus == panel)
+        .zip(detail_body(app, panel))
+        .and_then(|(line, (offset, body))| tree::path_at(&body, line.checked_sub(offset + 1)?));
+    let title = match path {
+        Some(path) => format!("{title} • {path}"),
+        None => title.to_string(),
+    };
+    let title = match search_label(app, panel) {
+        Some(label) => format!("{title} • {label}"),
+        None => title,
+    };
+    let title = title.as_str();
     let Some(selection) = &app.line_selection else {
         return title.to_string();
     };
@@ -1649,58 +2613,152 @@ fn detail_title(title: &str, app: &App, panel: Focus) -> String {
 }
 
 pub fn request_detail_lines(app: &App) -> Vec<Line<'static>> {
-    request_detail_lines_for(
+    if let Some((query, exchange)) = app.request_query.as_ref().zip(app.get_selected_exchange()) {
+        let focused = matches!(app.focus, Focus::RequestSection);
+        let mut lines = request_heading_lines(exchange, app.request_tab, focused);
+        lines.extend(query_lines(app, query, exchange));
+        return lines;
+    }
+    let mut lines = request_detail_lines_for(
         app.get_selected_exchange(),
         app.request_tab,
         matches!(app.focus, Focus::RequestSection),
-    )
+        &app.abi,
+    );
+    if app.request_tab == 1 {
+        lines.extend(mcp_detail_lines(app));
+    }
+    lines
 }
 
-fn request_detail_lines_for(
-    exchange: Option<&crate::app::JsonRpcExchange>,
-    tab: usize,
-    focused: bool,
-) -> Vec<Line<'static>> {
-    if let Some(exchange) = exchange {
-        let mut lines = Vec::new();
+// Shown in place of the tab content while a query is set for the panel.
+fn query_lines(app: &App, query: &Query, exchange: &JsonRpcExchange) -> Vec<Line<'static>> {
+    let matches = query.evaluate_exchange(exchange);
+    let pinned = app
+        .query_columns
+        .iter()
+        .any(|column| column.expression() == query.expression());
+    let count = match matches.len() {
+        1 => "1 match".to_string(),
+        count => format!("{count} matches"),
+    };
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Query ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                query.expression().to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                format!("  {count}{}", if pinned { " · pinned" } else { "" }),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::from(""),
+    ];
+    match query::combined(&matches) {
+        Some(value) => lines.extend(format_json_with_highlighting(&value)),
+        None => lines.push(Line::from("  No match")),
+    }
+    lines
+}
+
+fn decoded_lines(values: Vec<ethereum::Decoded>) -> Vec<Line<'static>> {
+    if values.is_empty() {
+        return vec![Line::from("  Nothing to decode")];
+    }
+    values
+        .into_iter()
+        .map(|value| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}: ", value.path),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(value.detail),
+            ])
+        })
+        .collect()
+}
 
-        // Basic exchange info
+// MCP context is appended below the body so line numbers of the body stay stable.
+fn mcp_detail_lines(app: &App) -> Vec<Line<'static>> {
+    let index = app.selected_exchange;
+    let Some(exchange) = app.exchanges.get(index) else {
+        return Vec::new();
+    };
+    let mut lines = Vec::new();
+    if let Some(target) = mcp::target(exchange) {
         lines.push(Line::from(vec![
-            Span::styled("Transport: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(exchange.transport.label()),
+            Span::styled("Target: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(target.to_string()),
         ]));
-
-        if let Some(method) = &exchange.method {
-            lines.push(Line::from(vec![
-                Span::styled("Method: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(method.clone()),
-            ]));
+    }
+    if let Some(issues) =
+        mcp::summary(&app.exchanges).and_then(|summary| mcp::argument_issues(&summary, exchange))
+    {
+        if issues.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "✓ Arguments match inputSchema",
+                Style::default().fg(Color::Green),
+            )));
         }
-
-        if let Some(id) = &exchange.id {
-            lines.push(Line::from(vec![
-                Span::styled("ID: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(id.to_string()),
-            ]));
+        for issue in issues {
+            lines.push(Line::from(Span::styled(
+                format!("✗ {issue}"),
+                Style::default().fg(Color::Red),
+            )));
         }
+    }
+    if let Some((relation, related)) = mcp::related_request(&app.exchanges, index) {
+        let method = app.exchanges[related]
+            .method
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string());
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{}: ", relation.label()),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("#{related} {method}")),
+        ]));
+    }
+    let updates = mcp::progress_updates(&app.exchanges, index);
+    if updates > 0 {
+        lines.push(Line::from(format!("Progress updates: {updates}")));
+    }
+    if lines.is_empty() {
+        return lines;
+    }
 
-        // Request section with tabs
-        lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            "REQUEST:",
+    lines.insert(0, Line::from(""));
+    lines.insert(
+        1,
+        Line::from(Span::styled(
+            "MCP:",
             Style::default()
                 .add_modifier(Modifier::BOLD)
-                .fg(Color::Green),
-        )));
-        lines.push(build_tab_line(
-            &["Headers", "Body"],
-            tab,
-            focused,
-            exchange.request.is_some(),
-        ));
+                .fg(Color::Magenta),
+        )),
+    );
+    lines
+}
+
+fn request_detail_lines_for(
+    exchange: Option<&crate::app::JsonRpcExchange>,
+    tab: usize,
+    focused: bool,
+    abi: &ethereum::Abi,
+) -> Vec<Line<'static>> {
+    if let Some(exchange) = exchange {
+        let mut lines = request_heading_lines(exchange, tab, focused);
 
         if let Some(request) = &exchange.request {
-            if tab == 0 {
+            if tab == 2 {
+                lines.push(Line::from(""));
+                lines.extend(decoded_lines(ethereum::request_values(exchange, abi)));
+            } else if tab == 0 {
                 // Show headers regardless of focus state
                 lines.push(Line::from(""));
                 match &request.headers {
@@ -1719,27 +2777,7 @@ fn request_detail_lines_for(
             } else {
                 // Show body regardless of focus state
                 lines.push(Line::from(""));
-                let mut request_json = serde_json::Map::new();
-                request_json.insert(
-                    "jsonrpc".to_string(),
-                    serde_json::Value::String("2.0".to_string()),
-                );
-
-                if let Some(id) = &request.id {
-                    request_json.insert("id".to_string(), id.clone());
-                }
-                if let Some(method) = &request.method {
-                    request_json.insert(
-                        "method".to_string(),
-                        serde_json::Value::String(method.clone()),
-                    );
-                }
-                if let Some(params) = &request.params {
-                    request_json.insert("params".to_string(), params.clone());
-                }
-
-                let request_json_value = serde_json::Value::Object(request_json);
-                let request_json_lines = format_json_with_highlighting(&request_json_value);
+                let request_json_lines = format_json_with_highlighting(&message_body(request));
                 for line in request_json_lines {
                     lines.push(line);
                 }
@@ -1755,6 +2793,51 @@ fn request_detail_lines_for(
     }
 }
 
+// Everything above the tab contents, so folds know where a body starts.
+fn request_heading_lines(
+    exchange: &JsonRpcExchange,
+    tab: usize,
+    focused: bool,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    // Basic exchange info
+    lines.push(Line::from(vec![
+        Span::styled("Transport: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(exchange.transport.label()),
+    ]));
+
+    if let Some(method) = &exchange.method {
+        lines.push(Line::from(vec![
+            Span::styled("Method: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(method.clone()),
+        ]));
+    }
+
+    if let Some(id) = &exchange.id {
+        lines.push(Line::from(vec![
+            Span::styled("ID: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(id.to_string()),
+        ]));
+    }
+
+    // Request section with tabs
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "REQUEST:",
+        Style::default()
+            .add_modifier(Modifier::BOLD)
+            .fg(Color::Green),
+    )));
+    lines.push(build_tab_line(
+        &["Headers", "Body", "Decoded"],
+        tab,
+        focused,
+        exchange.request.is_some(),
+    ));
+    lines
+}
+
 fn draw_request_details(f: &mut Frame, area: Rect, app: &App) {
     let inner_area = area.inner(&Margin {
         vertical: 1,
@@ -1765,13 +2848,16 @@ fn draw_request_details(f: &mut Frame, area: Rect, app: &App) {
         .filter(|_| app.focus == Focus::RequestSection);
     let content = annotate_detail_lines(request_detail_lines(app), app, Focus::RequestSection);
     let content = highlight_selected_lines(content, app, Focus::RequestSection);
-    let content = number_detail_lines(content, cursor_line);
+    let content = highlight_search_matches(content, app, Focus::RequestSection);
+    let folds = detail_folds(app, Focus::RequestSection);
+    let content = number_detail_lines(content, cursor_line, &folds);
     let source_lines = content.len();
     let content = insert_annotation_lines(
         content,
         app,
         Focus::RequestSection,
         usize::from(inner_area.width),
+        &folds,
     );
 
     // Calculate visible area for scrolling
@@ -1782,7 +2868,7 @@ fn draw_request_details(f: &mut Frame, area: Rect, app: &App) {
     let max_scroll = total_lines.saturating_sub(visible_lines);
     let annotations = detail_annotations(app, Focus::RequestSection);
     let start_line =
-        detail_display_scroll(app.request_details_scroll, &annotations).min(max_scroll);
+        detail_display_scroll(app.request_details_scroll, &annotations, &folds).min(max_scroll);
     let end_line = std::cmp::min(start_line + visible_lines, total_lines);
     let visible_content = if start_line < total_lines {
         content[start_line..end_line].to_vec()
@@ -1854,10 +2940,17 @@ fn draw_details_split(f: &mut Frame, area: Rect, app: &App) {
 }
 
 pub fn response_detail_lines(app: &App) -> Vec<Line<'static>> {
+    if let Some((query, exchange)) = app.response_query.as_ref().zip(app.get_selected_exchange()) {
+        let focused = matches!(app.focus, Focus::ResponseSection);
+        let mut lines = response_heading_lines(exchange, app.response_tab, focused);
+        lines.extend(query_lines(app, query, exchange));
+        return lines;
+    }
     response_detail_lines_for(
         app.get_selected_exchange(),
         app.response_tab,
         matches!(app.focus, Focus::ResponseSection),
+        &app.abi,
     )
 }
 
@@ -1865,26 +2958,16 @@ fn response_detail_lines_for(
     exchange: Option<&crate::app::JsonRpcExchange>,
     tab: usize,
     focused: bool,
+    abi: &ethereum::Abi,
 ) -> Vec<Line<'static>> {
     if let Some(exchange) = exchange {
-        let mut lines = Vec::new();
-
-        // Response section with tabs
-        lines.push(Line::from(Span::styled(
-            "RESPONSE:",
-            Style::default()
-                .add_modifier(Modifier::BOLD)
-                .fg(Color::Blue),
-        )));
-        lines.push(build_tab_line(
-            &["Headers", "Body"],
-            tab,
-            focused,
-            exchange.response.is_some(),
-        ));
+        let mut lines = response_heading_lines(exchange, tab, focused);
 
         if let Some(response) = &exchange.response {
-            if tab == 0 {
+            if tab == 2 {
+                lines.push(Line::from(""));
+                lines.extend(decoded_lines(ethereum::response_values(exchange, abi)));
+            } else if tab == 0 {
                 // Show headers regardless of focus state
                 lines.push(Line::from(""));
                 match &response.headers {
@@ -1903,24 +2986,7 @@ fn response_detail_lines_for(
             } else {
                 // Show body regardless of focus state
                 lines.push(Line::from(""));
-                let mut response_json = serde_json::Map::new();
-                response_json.insert(
-                    "jsonrpc".to_string(),
-                    serde_json::Value::String("2.0".to_string()),
-                );
-
-                if let Some(id) = &response.id {
-                    response_json.insert("id".to_string(), id.clone());
-                }
-                if let Some(result) = &response.result {
-                    response_json.insert("result".to_string(), result.clone());
-                }
-                if let Some(error) = &response.error {
-                    response_json.insert("error".to_string(), error.clone());
-                }
-
-                let response_json_value = serde_json::Value::Object(response_json);
-                let response_json_lines = format_json_with_highlighting(&response_json_value);
+                let response_json_lines = format_json_with_highlighting(&message_body(response));
                 for line in response_json_lines {
                     lines.push(line);
                 }
@@ -1939,6 +3005,58 @@ fn response_detail_lines_for(
     }
 }
 
+fn response_heading_lines(
+    exchange: &JsonRpcExchange,
+    tab: usize,
+    focused: bool,
+) -> Vec<Line<'static>> {
+    vec![
+        Line::from(Span::styled(
+            "RESPONSE:",
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(Color::Blue),
+        )),
+        build_tab_line(
+            &["Headers", "Body", "Decoded"],
+            tab,
+            focused,
+            exchange.response.is_some(),
+        ),
+    ]
+}
+
+// The body on a panel's Body tab and how many detail lines come before it.
+fn detail_body(app: &App, panel: Focus) -> Option<(usize, serde_json::Value)> {
+    if app.panel_query(panel).is_some() {
+        return None;
+    }
+    let exchange = app.get_selected_exchange()?;
+    let (heading, message) = match panel {
+        Focus::RequestSection if app.request_tab == 1 => (
+            request_heading_lines(exchange, app.request_tab, false).len(),
+            exchange.request.as_ref()?,
+        ),
+        Focus::ResponseSection if app.response_tab == 1 => (
+            response_heading_lines(exchange, app.response_tab, false).len(),
+            exchange.response.as_ref()?,
+        ),
+        _ => return None,
+    };
+    // A blank line separates the tabs from the body.
+    Some((heading + 1, message_body(message)))
+}
+
+/// Folds of the body shown in `panel`; empty unless the JSON tree is on.
+pub fn detail_folds(app: &App, panel: Focus) -> Folds {
+    if !app.json_tree {
+        return Folds::default();
+    }
+    detail_body(app, panel)
+        .map(|(offset, body)| Folds::new(&body, offset, app.fold_overrides(panel)))
+        .unwrap_or_default()
+}
+
 fn draw_response_details(f: &mut Frame, area: Rect, app: &App) {
     let inner_area = area.inner(&Margin {
         vertical: 1,
@@ -1949,13 +3067,16 @@ fn draw_response_details(f: &mut Frame, area: Rect, app: &App) {
         .filter(|_| app.focus == Focus::ResponseSection);
     let content = annotate_detail_lines(response_detail_lines(app), app, Focus::ResponseSection);
     let content = highlight_selected_lines(content, app, Focus::ResponseSection);
-    let content = number_detail_lines(content, cursor_line);
+    let content = highlight_search_matches(content, app, Focus::ResponseSection);
+    let folds = detail_folds(app, Focus::ResponseSection);
+    let content = number_detail_lines(content, cursor_line, &folds);
     let source_lines = content.len();
     let content = insert_annotation_lines(
         content,
         app,
         Focus::ResponseSection,
         usize::from(inner_area.width),
+        &folds,
     );
 
     // Calculate visible area for scrolling
@@ -1966,7 +3087,7 @@ fn draw_response_details(f: &mut Frame, area: Rect, app: &App) {
     let max_scroll = total_lines.saturating_sub(visible_lines);
     let annotations = detail_annotations(app, Focus::ResponseSection);
     let start_line =
-        detail_display_scroll(app.response_details_scroll, &annotations).min(max_scroll);
+        detail_display_scroll(app.response_details_scroll, &annotations, &folds).min(max_scroll);
     let end_line = std::cmp::min(start_line + visible_lines, total_lines);
     let visible_content = if start_line < total_lines {
         content[start_line..end_line].to_vec()
@@ -2117,6 +3238,31 @@ fn get_keybinds_for_mode(app: &App) -> Vec<KeybindInfo> {
             return vec![
                 KeybindInfo::new("?", "keybinds", 1),
                 KeybindInfo::new("y", "copy markdown", 1),
+                KeybindInfo::new("m", "mcp summary", 1),
+                KeybindInfo::new("e", "subscription events", 1),
+                KeybindInfo::new("b", "batch", 1),
+                KeybindInfo::new(
+                    "w",
+                    if app.timeline_visible {
+                        "request table"
+                    } else {
+                        "timeline"
+                    },
+                    1,
+                ),
+                KeybindInfo::new(
+                    "f",
+                    if app.json_tree {
+                        "flat JSON"
+                    } else {
+                        "JSON tree"
+                    },
+                    1,
+                ),
+                KeybindInfo::new("=", "diff marked", 1),
+                KeybindInfo::new("o", "columns", 1),
+                KeybindInfo::new("Y", "copy as", 1),
+                KeybindInfo::new("r", "report", 1),
                 KeybindInfo::new(
                     "z",
                     if app.panel_fullscreen {
@@ -2138,8 +3284,34 @@ fn get_keybinds_for_mode(app: &App) -> Vec<KeybindInfo> {
             KeybindInfo::new("c", "create request", 1),
             KeybindInfo::new("p", "pause", 1),
             KeybindInfo::new("t", "target", 1),
+            KeybindInfo::new("P", "profiles", 1),
             KeybindInfo::new("x", "start/stop", 1),
             KeybindInfo::new("y", "copy markdown", 1),
+            KeybindInfo::new("m", "mcp summary", 1),
+            KeybindInfo::new("e", "subscription events", 1),
+            KeybindInfo::new("b", "batch", 1),
+            KeybindInfo::new(
+                "w",
+                if app.timeline_visible {
+                    "request table"
+                } else {
+                    "timeline"
+                },
+                1,
+            ),
+            KeybindInfo::new(
+                "f",
+                if app.json_tree {
+                    "flat JSON"
+                } else {
+                    "JSON tree"
+                },
+                1,
+            ),
+            KeybindInfo::new("=", "diff marked", 1),
+            KeybindInfo::new("o", "columns", 1),
+            KeybindInfo::new("Y", "copy as", 1),
+            KeybindInfo::new("r", "report", 1),
             KeybindInfo::new(
                 "z",
                 if app.panel_fullscreen {
@@ -2165,9 +3337,43 @@ fn get_keybinds_for_mode(app: &App) -> Vec<KeybindInfo> {
         }
         return keybinds;
     }
-    if matches!(app.overlay, Overlay::Help | Overlay::Sessions) {
+    if app.overlay == Overlay::Sessions {
+        return vec![
+            KeybindInfo::new("Enter", "open", 1),
+            KeybindInfo::new("=", "diff with active", 1),
+            KeybindInfo::new("Esc", "close", 1),
+        ];
+    }
+    if matches!(
+        app.overlay,
+        Overlay::Help | Overlay::Mcp | Overlay::Subscription | Overlay::Diff
+    ) {
         return vec![KeybindInfo::new("Esc", "close", 1)];
     }
+    if app.overlay == Overlay::Profiles {
+        return vec![
+            KeybindInfo::new("Enter", "apply", 1),
+            KeybindInfo::new("Esc", "close", 1),
+        ];
+    }
+    if app.overlay == Overlay::Columns {
+        return vec![
+            KeybindInfo::new("Space", "show/hide", 1),
+            KeybindInfo::new("</>", "width", 1),
+            KeybindInfo::new("s", "sort", 1),
+            KeybindInfo::new("Esc", "close", 1),
+        ];
+    }
+    if app.overlay == Overlay::Batch {
+        let mut keybinds = vec![
+            KeybindInfo::new("y", "copy markdown", 1),
+            KeybindInfo::new("Esc", "close", 1),
+        ];
+        if !app.proxy_config.transparent {
+            keybinds.insert(0, KeybindInfo::new("r", "resend", 1));
+        }
+        return keybinds;
+    }
 
     let enter_description =
         if app.app_mode == AppMode::Normal && matches!(app.focus, Focus::MessageList) {
@@ -2180,17 +3386,63 @@ fn get_keybinds_for_mode(app: &App) -> Vec<KeybindInfo> {
         KeybindInfo::new("↑↓/j/k", "navigate", 1),
         KeybindInfo::new("Tab", "focus", 1),
         KeybindInfo::new("Enter", enter_description, 1),
-        KeybindInfo::new("/", "filter", 2),
+        KeybindInfo::new(
+            "/",
+            if app.is_searchable() {
+                "search"
+            } else {
+                "filter"
+            },
+            2,
+        ),
         KeybindInfo::new("h/l", "tabs", 2),
         KeybindInfo::new("d/u/g/G", "scroll", 2),
     ];
 
+    if app.app_mode == AppMode::Normal && matches!(app.focus, Focus::MessageList) {
+        keybinds.push(KeybindInfo::new("Space", "fold group", 2));
+        keybinds.push(KeybindInfo::new("m", "mark for diff", 2));
+        if !app.proxy_config.transparent || app.proxy_config.stdio.is_some() {
+            keybinds.extend([
+                KeybindInfo::new("*", "star", 2),
+                KeybindInfo::new("#", "tags", 2),
+            ]);
+        }
+        if app.timeline_visible {
+            keybinds.extend([
+                KeybindInfo::new("+/-", "zoom", 2),
+                KeybindInfo::new("[/]", "pan", 2),
+            ]);
+        }
+    }
+    let searching = app.panel_search(app.focus).is_some();
+    if searching {
+        keybinds.push(KeybindInfo::new("n/N", "next/prev match", 1));
+    }
     if app.app_mode == AppMode::Normal
         && matches!(app.focus, Focus::RequestSection | Focus::ResponseSection)
     {
+        if app.json_tree {
+            keybinds.push(KeybindInfo::new("Space", "fold", 2));
+        }
+        keybinds.push(KeybindInfo::new(".", "query", 2));
+        let querying = app.panel_query(app.focus).is_some();
+        if querying {
+            keybinds.push(KeybindInfo::new("P", "pin column", 2));
+        }
         keybinds.extend([
             KeybindInfo::new("v", "visual select", 1),
-            KeybindInfo::new("Esc", "clear selection", 2),
+            KeybindInfo::new(
+                "Esc",
+                if searching {
+                    "clear search"
+                } else if querying {
+                    "clear query"
+                } else {
+                    "clear selection"
+                },
+                2,
+            ),
         ]);
     }
 
@@ -2376,21 +3628,11 @@ fn draw_pending_requests(f: &mut Frame, area: Rect, app: &App) {
         .pending_requests
         .iter()
         .enumerate()
-        .filter(|(_, pending)| {
-            request_matches_filter(
-                pending.original_request.method.as_deref(),
-                pending.original_request.id.as_ref(),
-                &app.filter_text,
-            )
-        })
+        .filter(|(_, pending)| request_matches_filter(&pending.original_request, &app.filter_text))
         .map(|(i, pending)| {
-            let method = pending
-                .original_request
-                .method
-                .as_deref()
-                .unwrap_or("unknown");
-            let id = pending
-                .original_request
+            let original = app.shown_pending_request(pending);
+            let method = original.method.as_deref().unwrap_or("unknown");
+            let id = original
                 .id
                 .as_ref()
                 .map(|v| v.to_string())
@@ -2470,8 +3712,9 @@ fn draw_pending_requests(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(requests_list, area);
 }
 
-fn draw_intercept_request_details(f: &mut Frame, area: Rect, app: &App) {
-    let content = if let Some(pending) = app.get_selected_pending() {
+pub fn intercept_detail_lines(app: &App) -> Vec<Line<'static>> {
+    if let Some(pending) = app.get_selected_pending() {
+        let shown = app.shown_pending_request(pending);
         let mut lines = Vec::new();
 
         if pending.modified_request.is_some() || pending.modified_headers.is_some() {
@@ -2496,10 +3739,7 @@ fn draw_intercept_request_details(f: &mut Frame, area: Rect, app: &App) {
                 .add_modifier(Modifier::BOLD)
                 .fg(Color::Green),
         )));
-        let headers_to_show = pending
-            .modified_headers
-            .as_ref()
-            .or(pending.original_request.headers.as_ref());
+        let headers_to_show = shown.headers.as_ref();
 
         if let Some(headers) = headers_to_show {
             for (key, value) in headers {
@@ -2527,55 +3767,7 @@ fn draw_intercept_request_details(f: &mut Frame, area: Rect, app: &App) {
         )));
 
         // Show the modified request if available, otherwise show original
-        let json_to_show = if let Some(ref modified_json) = pending.modified_request {
-            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(modified_json) {
-                parsed
-            } else {
-                // Fallback to original if modified JSON is invalid
-                let mut request_json = serde_json::Map::new();
-                request_json.insert(
-                    "jsonrpc".to_string(),
-                    serde_json::Value::String("2.0".to_string()),
-                );
-
-                if let Some(id) = &pending.original_request.id {
-                    request_json.insert("id".to_string(), id.clone());
-                }
-                if let Some(method) = &pending.original_request.method {
-                    request_json.insert(
-                        "method".to_string(),
-                        serde_json::Value::String(method.clone()),
-                    );
-                }
-                if let Some(params) = &pending.original_request.params {
-                    request_json.insert("params".to_string(), params.clone());
-                }
-
-                serde_json::Value::Object(request_json)
-            }
-        } else {
-            // Show original request
-            let mut request_json = serde_json::Map::new();
-            request_json.insert(
-                "jsonrpc".to_string(),
-                serde_json::Value::String("2.0".to_string()),
-            );
-
-            if let Some(id) = &pending.original_request.id {
-                request_json.insert("id".to_string(), id.clone());
-            }
-            if let Some(method) = &pending.original_request.method {
-                request_json.insert(
-                    "method".to_string(),
-                    serde_json::Value::String(method.clone()),
-                );
-            }
-            if let Some(params) = &pending.original_request.params {
-                request_json.insert("params".to_string(), params.clone());
-            }
-
-            serde_json::Value::Object(request_json)
-        };
+        let json_to_show = message_body(&shown);
 
         let request_json_lines = format_json_with_highlighting(&json_to_show);
         for line in request_json_lines {
@@ -2597,7 +3789,11 @@ fn draw_intercept_request_details(f: &mut Frame, area: Rect, app: &App) {
         lines
     } else {
         vec![Line::from("No request selected")]
-    };
+    }
+}
+
+fn draw_intercept_request_details(f: &mut Frame, area: Rect, app: &App) {
+    let content = highlight_search_matches(intercept_detail_lines(app), app, Focus::RequestSection);
 
     // Calculate visible area for scrolling
     let inner_area = area.inner(&Margin {
@@ -2617,13 +3813,17 @@ fn draw_intercept_request_details(f: &mut Frame, area: Rect, app: &App) {
     };
 
     // Create title with scroll indicator
+    let base_title = match search_label(app, Focus::RequestSection) {
+        Some(label) => format!("Request Details • {label}"),
+        None => "Request Details".to_string(),
+    };
     let scroll_info = if total_lines > visible_lines {
         let progress = ((app.intercept_details_scroll as f32
             / (total_lines - visible_lines) as f32)
             * 100.0) as u8;
-        format!("Request Details ({}% - vim: j/k/d/u/G/g)", progress)
+        format!("{} ({}% - vim: j/k/d/u/G/g)", base_title, progress)
     } else {
-        "Request Details".to_string()
+        base_title
     };
 
     let details_block = if matches!(app.focus, Focus::RequestSection) {
@@ -2684,6 +3884,10 @@ mod tests {
             direction: MessageDirection::Request,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         app.add_message(JsonRpcMessage {
             id: Some(serde_json::json!(1)),
@@ -2695,10 +3899,117 @@ mod tests {
             direction: MessageDirection::Response,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
         app
     }
 
+    #[test]
+    fn long_arrays_fold_without_renumbering_the_body() {
+        let mut app = app_with_request();
+        app.exchanges[0].response.as_mut().unwrap().result =
+            Some(serde_json::json!({"logs": (0..30).collect::<Vec<_>>(), "n": 1}));
+        app.focus = Focus::ResponseSection;
+        app.response_tab = 1;
+        let render = |app: &App| {
+            let area = Rect::new(0, 0, 60, 12);
+            let mut terminal = Terminal::new(TestBackend::new(area.width, area.height)).unwrap();
+            terminal
+                .draw(|frame| draw_response_details(frame, area, app))
+                .unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            (0..area.height)
+                .map(|row| {
+                    (0..area.width)
+                        .map(|column| buffer.get(column, row).symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let total = detail_line_count(&app, Focus::ResponseSection).unwrap();
+        let folds = detail_folds(&app, Focus::ResponseSection);
+        let logs = folds.at(8).unwrap().clone();
+        assert_eq!((logs.path.as_str(), logs.last), ("$.result.logs", 39));
+        assert_eq!(folds.rank(total + 1), total - 31);
+
+        app.response_details_cursor_line = 8;
+        let rows = render(&app);
+        assert!(rows[0].contains("$.result.logs"), "{rows:?}");
+        assert!(
+            rows[8].contains("8 ▸     \"logs\": [ …] 30 items"),
+            "{rows:?}"
+        );
+        assert!(rows[9].contains(" 40 │     \"n\": 1"), "{rows:?}");
+
+        app.set_fold(Focus::ResponseSection, &logs, false);
+        assert!(render(&app)[9].contains("  9 │       0,"));
+        assert_eq!(detail_line_count(&app, Focus::ResponseSection), Some(total));
+    }
+
+    #[test]
+    fn queries_show_in_place_and_as_list_columns() {
+        let mut app = app_with_request();
+        let query = Query::parse("$..result").unwrap();
+        app.set_query(Focus::ResponseSection, Some(query.clone()));
+        app.pin_query_column(query);
+
+        let details = response_detail_lines(&app)
+            .iter()
+            .map(line_text)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            details[2..],
+            ["", "Query $..result  1 match · pinned", "", "\"0x1\""]
+        );
+        assert!(detail_folds(&app, Focus::ResponseSection).is_empty());
+
+        let area = Rect::new(0, 0, 120, 5);
+        let mut terminal = Terminal::new(TestBackend::new(area.width, area.height)).unwrap();
+        terminal
+            .draw(|frame| draw_message_list(frame, area, &app))
+            .unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        let row = |row: u16| {
+            (0..area.width)
+                .map(|column| buffer.get(column, row).symbol())
+                .collect::<String>()
+        };
+        assert!(row(1).contains("Duration   $..result"), "{}", row(1));
+        assert!(
+            row(2).trim_end_matches(['│', ' ']).ends_with("0x1"),
+            "{}",
+            row(2)
+        );
+    }
+
+    #[test]
+    fn search_matches_are_highlighted_and_counted_in_the_title() {
+        let mut app = app_with_request();
+        app.focus = Focus::ResponseSection;
+        let title = |app: &App| detail_title("Response Details", app, Focus::ResponseSection);
+        app.search = Some(search::Search::new(Focus::ResponseSection, "0x"));
+        let lines = search_lines(&app, Focus::ResponseSection).unwrap();
+        let found = app.search.as_ref().unwrap().matches(&lines);
+        assert_eq!(found.len(), 1);
+        assert_eq!(title(&app), "Response Details • /0x 1 match");
+
+        app.search.as_mut().unwrap().current = Some((found[0].line, found[0].start));
+        assert_eq!(title(&app), "Response Details • /0x 1/1");
+        let highlighted =
+            highlight_search_matches(response_detail_lines(&app), &app, Focus::ResponseSection);
+        let line = &highlighted[found[0].line - 1];
+        assert_eq!(line_text(line), lines[found[0].line - 1]);
+        let current = line.spans.iter().find(|span| span.content == "0x").unwrap();
+        assert_eq!(current.style.bg, Some(Color::Yellow));
+
+        app.search = Some(search::Search::new(Focus::ResponseSection, "missing"));
+        assert_eq!(title(&app), "Response Details • /missing no matches");
+    }
+
     #[test]
     fn batch_members_are_labeled_http_batch() {
         let mut app = App::new();
@@ -2712,6 +4023,10 @@ mod tests {
             direction: MessageDirection::Request,
             transport: TransportType::HttpBatch,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
 
         let details = request_detail_lines(&app)
@@ -2749,6 +4064,10 @@ mod tests {
             direction: MessageDirection::Request,
             transport: TransportType::Stdio(crate::app::Framing::JsonLines),
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
 
         let area = Rect::new(0, 0, 100, 5);
@@ -2766,6 +4085,180 @@ mod tests {
         assert!(rendered.contains("STDIO/JSONL"));
     }
 
+    #[test]
+    fn decoded_tab_renders_ethereum_values() {
+        let mut app = app_with_request();
+        app.response_tab = 2;
+
+        let details = response_detail_lines(&app)
+            .iter()
+            .map(line_text)
+            .collect::<Vec<_>>();
+        assert_eq!(details[1], " Headers  Body  Decoded ");
+        assert_eq!(details[3], "  result: 1");
+
+        let area = Rect::new(0, 0, 120, 40);
+        let (_, _, response) = normal_panels(area, &app);
+        let gutter = detail_gutter_width(response_detail_lines(&app).len()) as u16;
+        assert_eq!(
+            mouse_action(area, &app, response.x + 17 + gutter, response.y + 2),
+            Some(MouseAction::SelectResponseTab(2))
+        );
+    }
+
+    #[test]
+    fn subscriptions_render_as_a_folded_row_with_an_events_view() {
+        let mut app = App::new();
+        let transport = TransportType::WebSocket;
+        for body in [
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_subscribe", "params": ["logs"]}),
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0xa"}),
+            serde_json::json!({"jsonrpc": "2.0", "method": "eth_subscription", "params": {
+                "subscription": "0xa", "result": {"n": 1}
+            }}),
+        ] {
+            for message in crate::app::incoming_json_rpc_messages(&body, transport) {
+                app.add_message(message);
+            }
+        }
+        app.select_exchange(0);
+
+        let render = |app: &App| {
+            let area = Rect::new(0, 0, 120, 5);
+            let mut terminal = Terminal::new(TestBackend::new(area.width, area.height)).unwrap();
+            terminal
+                .draw(|frame| draw_message_list(frame, area, app))
+                .unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+        assert!(render(&app).contains("▸ eth_subscribe · logs (1 events)"));
+        assert!(!render(&app).contains("└ eth_subscription"));
+
+        app.toggle_selected_subscription();
+        assert!(render(&app).contains("└ eth_subscription"));
+
+        let events = subscription_event_lines(&app)
+            .iter()
+            .map(line_text)
+            .collect::<Vec<_>>();
+        assert_eq!(events[0], "eth_subscribe · logs  id 0xa");
+        assert!(events[3].starts_with("#1") && events[3].ends_with(r#"{"n":1}"#));
+    }
+
+    #[test]
+    fn timeline_draws_filtered_exchanges_as_bars_on_their_list_rows() {
+        let mut app = App::new();
+        let started = std::time::SystemTime::now() - std::time::Duration::from_secs(1);
+        let at = |millis| started + std::time::Duration::from_millis(millis);
+        for (id, method, sent, answered) in [
+            (1, "eth_slow", 0, Some(1000)),
+            (2, "eth_fast", 100, Some(200)),
+            (3, "net_version", 300, Some(400)),
+            (4, "eth_stuck", 500, None),
+        ] {
+            let body = serde_json::json!({"jsonrpc": "2.0", "id": id, "method": method});
+            let mut messages = crate::app::json_rpc_messages(
+                &body,
+                MessageDirection::Request,
+                TransportType::Http,
+                None,
+            );
+            if let Some(answered) = answered {
+                let body = serde_json::json!({"jsonrpc": "2.0", "id": id, "result": "0x1"});
+                let mut response = crate::app::json_rpc_messages(
+                    &body,
+                    MessageDirection::Response,
+                    TransportType::Http,
+                    None,
+                );
+                response[0].timestamp = at(answered);
+                messages.extend(response);
+            }
+            messages[0].timestamp = at(sent);
+            for message in messages {
+                app.add_message(message);
+            }
+        }
+        app.filter_text = "eth_".to_string();
+        app.set_timeline(true, None);
+
+        let area = Rect::new(0, 0, 80, 7);
+        let mut terminal = Terminal::new(TestBackend::new(area.width, area.height)).unwrap();
+        terminal
+            .draw(|frame| draw_message_list(frame, area, &app))
+            .unwrap();
+        let rows = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| terminal.backend().buffer().get(x, y).symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(rows[0].contains("timeline 1×"));
+        assert!(rows[2].contains("eth_slow") && rows[2].contains('█'));
+        assert!(rows[3].contains("eth_fast") && rows[3].contains(" █"));
+        assert!(rows[4].contains("eth_stuck") && rows[4].contains('░'));
+        assert!(!rows.iter().any(|row| row.contains("net_version")));
+        assert_eq!(
+            mouse_action(
+                Rect::new(0, 0, 120, 40),
+                &app,
+                2,
+                normal_panels(Rect::new(0, 0, 120, 40), &app).0.y + 4
+            ),
+            Some(MouseAction::SelectExchange(3))
+        );
+    }
+
+    #[test]
+    fn mcp_tool_calls_show_the_tool_and_schema_issues() {
+        let mut app = App::new();
+        let transport = TransportType::Stdio(crate::app::Framing::JsonLines);
+        for body in [
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {"tools": [{
+                "name": "search",
+                "inputSchema": {"type": "object", "required": ["query"]}
+            }]}}),
+            serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {
+                "name": "search", "arguments": {}
+            }}),
+        ] {
+            for message in crate::app::incoming_json_rpc_messages(&body, transport) {
+                app.add_message(message);
+            }
+        }
+        app.select_exchange(1);
+
+        let details = request_detail_lines(&app)
+            .iter()
+            .map(line_text)
+            .collect::<Vec<_>>();
+        assert!(details.contains(&"Target: search".to_string()));
+        assert!(details.contains(&"✗ arguments.query: required property is missing".to_string()));
+
+        let area = Rect::new(0, 0, 100, 5);
+        let mut terminal = Terminal::new(TestBackend::new(area.width, area.height)).unwrap();
+        terminal
+            .draw(|frame| draw_message_list(frame, area, &app))
+            .unwrap();
+        let rendered = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        assert!(rendered.contains("tools/call · search"));
+    }
+
     fn normal_panels(area: Rect, app: &App) -> (Rect, Rect, Rect) {
         let screen = screen_chunks(area, app);
         let main = Layout::default()
@@ -2932,8 +4425,14 @@ mod tests {
         let annotated =
             annotate_detail_lines(request_detail_lines(&app), &app, Focus::RequestSection);
         let selected = highlight_selected_lines(annotated.clone(), &app, Focus::RequestSection);
-        let numbered = number_detail_lines(selected.clone(), Some(3));
-        let displayed = insert_annotation_lines(numbered, &app, Focus::RequestSection, 120);
+        let numbered = number_detail_lines(selected.clone(), Some(3), &Folds::default());
+        let displayed = insert_annotation_lines(
+            numbered,
+            &app,
+            Focus::RequestSection,
+            120,
+            &Folds::default(),
+        );
 
         assert_eq!(
             detail_lines_text(&app, Focus::RequestSection).unwrap()[2],
@@ -2976,8 +4475,14 @@ mod tests {
 
         let annotated =
             annotate_detail_lines(request_detail_lines(&app), &app, Focus::RequestSection);
-        let numbered = number_detail_lines(annotated.clone(), Some(4));
-        let displayed = insert_annotation_lines(numbered, &app, Focus::RequestSection, 120);
+        let numbered = number_detail_lines(annotated.clone(), Some(4), &Folds::default());
+        let displayed = insert_annotation_lines(
+            numbered,
+            &app,
+            Focus::RequestSection,
+            120,
+            &Folds::default(),
+        );
 
         assert_eq!(app.annotations.len(), 2);
         assert_eq!(app.active_annotation_id, None);
@@ -3005,8 +4510,9 @@ mod tests {
             "Compare the name",
             vec!["Method: eth_call".to_string()],
         ));
-        let numbered = number_detail_lines(request_detail_lines(&app), Some(2));
-        let displayed = insert_annotation_lines(numbered, &app, Focus::RequestSection, 80);
+        let numbered = number_detail_lines(request_detail_lines(&app), Some(2), &Folds::default());
+        let displayed =
+            insert_annotation_lines(numbered, &app, Focus::RequestSection, 80, &Folds::default());
         let line = line_text(&displayed[1]);
 
         assert_eq!(displayed.len(), request_detail_lines(&app).len());
@@ -3027,7 +4533,7 @@ mod tests {
         ));
         app.request_details_scroll = 0;
         let area = Rect::new(0, 0, 80, 8);
-        let numbered = number_detail_lines(request_detail_lines(&app), Some(2));
+        let numbered = number_detail_lines(request_detail_lines(&app), Some(2), &Folds::default());
         let marker_x = 1 + numbered[1].width() as u16 + 2;
         let mut terminal = Terminal::new(TestBackend::new(area.width, area.height)).unwrap();
 
@@ -3192,23 +4698,23 @@ mod tests {
         let annotations = vec![&annotation];
 
         assert_eq!(
-            clicked_detail_row(area, 1, 1, 0, &content, &annotations),
+            clicked_detail_row(area, 1, 1, 0, &content, &annotations, &Folds::default()),
             Some(ClickedDetail::Line(1))
         );
         assert_eq!(
-            clicked_detail_row(area, 1, 2, 0, &content, &annotations),
+            clicked_detail_row(area, 1, 2, 0, &content, &annotations, &Folds::default()),
             Some(ClickedDetail::Line(2))
         );
         assert_eq!(
-            clicked_detail_row(area, 1, 3, 0, &content, &annotations),
+            clicked_detail_row(area, 1, 3, 0, &content, &annotations, &Folds::default()),
             Some(ClickedDetail::Annotation("annotation-1".to_string()))
         );
         assert_eq!(
-            clicked_detail_row(area, 1, 4, 0, &content, &annotations),
+            clicked_detail_row(area, 1, 4, 0, &content, &annotations, &Folds::default()),
             Some(ClickedDetail::Line(3))
         );
         assert_eq!(
-            clicked_detail_row(area, 1, 1, 2, &content, &annotations),
+            clicked_detail_row(area, 1, 1, 2, &content, &annotations, &Folds::default()),
             Some(ClickedDetail::Line(3))
         );
     }
@@ -3228,11 +4734,11 @@ mod tests {
         let annotations = vec![&annotation];
 
         assert_eq!(
-            clicked_detail_row(area, 9, 2, 0, &content, &annotations),
+            clicked_detail_row(area, 9, 2, 0, &content, &annotations, &Folds::default()),
             Some(ClickedDetail::Annotation("annotation-1".to_string()))
         );
         assert_eq!(
-            clicked_detail_row(area, 2, 2, 0, &content, &annotations),
+            clicked_detail_row(area, 2, 2, 0, &content, &annotations, &Folds::default()),
             Some(ClickedDetail::Line(2))
         );
     }
@@ -3259,7 +4765,7 @@ mod tests {
     fn visible_line_numbers_match_get_panel_without_changing_its_text() {
         let app = app_with_request();
         let raw = detail_lines_text(&app, Focus::RequestSection).unwrap();
-        let numbered = number_detail_lines(request_detail_lines(&app), Some(2));
+        let numbered = number_detail_lines(request_detail_lines(&app), Some(2), &Folds::default());
 
         assert_eq!(raw[1], "Method: eth_call");
         assert_eq!(line_text(&numbered[1]), "› 2 │ Method: eth_call");
@@ -3372,6 +4878,10 @@ mod tests {
                 direction: MessageDirection::Request,
                 transport: TransportType::Http,
                 headers: None,
+                connection: None,
+                batch: None,
+                timing: None,
+                seq: None,
             });
         }
         app.history_scroll = Some(3);
@@ -3397,6 +4907,10 @@ mod tests {
                 direction: MessageDirection::Request,
                 transport: TransportType::Http,
                 headers: None,
+                connection: None,
+                batch: None,
+                timing: None,
+                seq: None,
             });
         }
         let area = Rect::new(0, 0, 80, 5);
@@ -1,5 +1,14 @@
 use jsonrpc_debugger::app::*;
 use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// Logs the messages in `body` as arriving over `transport` at `at`.
+fn record(app: &mut App, body: &serde_json::Value, transport: TransportType, at: SystemTime) {
+    for mut message in json_rpc_messages_by_shape(body, transport, None) {
+        message.timestamp = at;
+        app.add_message(message);
+    }
+}
 
 #[test]
 fn test_app_new_creates_empty() {
@@ -28,6 +37,10 @@ fn test_add_message() {
         direction: MessageDirection::Request,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     app.add_message(test_message);
@@ -56,6 +69,10 @@ fn idless_requests_are_notifications() {
         direction: MessageDirection::Request,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     });
 
     assert!(app.exchanges[0].is_notification());
@@ -77,6 +94,10 @@ fn test_navigation() {
             direction: MessageDirection::Request,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         };
         app.add_message(test_message);
     }
@@ -120,6 +141,10 @@ fn test_get_selected_exchange() {
         direction: MessageDirection::Request,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
     app.add_message(test_message);
 
@@ -174,6 +199,10 @@ fn test_request_response_pairing() {
             h.insert("Content-Type".to_string(), "application/json".to_string());
             h
         }),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
     app.add_message(http_request);
 
@@ -192,6 +221,10 @@ fn test_request_response_pairing() {
             h.insert("Content-Type".to_string(), "application/json".to_string());
             h
         }),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
     app.add_message(http_response);
 
@@ -206,6 +239,10 @@ fn test_request_response_pairing() {
         direction: MessageDirection::Request,
         transport: TransportType::WebSocket,
         headers: None, // WebSocket shouldn't have headers
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
     app.add_message(ws_request);
 
@@ -223,6 +260,10 @@ fn test_request_response_pairing() {
         direction: MessageDirection::Response,
         transport: TransportType::WebSocket,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
     app.add_message(error_response);
 
@@ -252,6 +293,43 @@ fn test_request_response_pairing() {
     assert!(ws_response.result.is_none());
 }
 
+#[test]
+fn deliveries_fill_in_the_logged_response() {
+    let mut app = App::new();
+    let connection = Connection {
+        id: "a".to_string(),
+        client: "127.0.0.1:5000".to_string(),
+        source: None,
+    };
+    for (body, direction) in [
+        (
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"}),
+            MessageDirection::Request,
+        ),
+        (
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x1"}),
+            MessageDirection::Response,
+        ),
+    ] {
+        for mut message in json_rpc_messages(&body, direction, TransportType::Http, None) {
+            message.connection = Some(connection.clone());
+            message.timing = Some(Timing::default());
+            app.add_message(message);
+        }
+    }
+
+    let delivery = std::time::Duration::from_millis(3);
+    app.record_delivery(&Delivery {
+        connection: connection.id,
+        delivery,
+    });
+
+    assert_eq!(app.exchanges.len(), 1);
+    let response = app.exchanges[0].response.as_ref().unwrap();
+    assert_eq!(response.result, Some(serde_json::json!("0x1")));
+    assert_eq!(response.timing.unwrap().delivery, Some(delivery));
+}
+
 #[test]
 fn test_json_rpc_message_creation() {
     let message = JsonRpcMessage {
@@ -264,6 +342,10 @@ fn test_json_rpc_message_creation() {
         direction: MessageDirection::Request,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     assert_eq!(
@@ -283,6 +365,7 @@ fn test_proxy_config() {
         transport: TransportType::Http,
         stdio: None,
         transparent: false,
+        listeners: Vec::new(),
     };
 
     assert_eq!(config.listen_port, 9090);
@@ -315,6 +398,10 @@ fn test_filtering_functionality() {
             direction: MessageDirection::Request,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         };
         app.add_message(test_message);
     }
@@ -438,6 +525,10 @@ fn filters_requests_by_their_visible_id() {
             direction: MessageDirection::Request,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         });
     }
 
@@ -461,13 +552,17 @@ fn focused_request_list_copies_as_markdown_table() {
         direction: MessageDirection::Request,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     });
 
     assert_eq!(
         app.focused_markdown().unwrap(),
-        "| Status | Transport | Method | ID | Duration |\n\
-         | --- | --- | --- | --- | --- |\n\
-         | Pending | HTTP | eth\\|call | 1 | - |"
+        "| Status | Transport | Client | Method | ID | Duration |\n\
+         | --- | --- | --- | --- | --- | --- |\n\
+         | Pending | HTTP | - | eth\\|call | 1 | - |"
     );
 }
 
@@ -487,6 +582,10 @@ fn focused_details_copy_the_selected_tab_as_markdown() {
             "Content-Type".to_string(),
             "application/json".to_string(),
         )])),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     });
 
     app.focus = Focus::RequestSection;
@@ -641,6 +740,7 @@ fn stdio_accepts_responses_to_server_requests() {
     app.proxy_config.stdio = Some(StdioConfig {
         command: vec!["example-server".into()],
         framing: Framing::JsonLines,
+        env: Vec::new(),
     });
 
     assert!(app
@@ -674,6 +774,7 @@ fn active_session_tracks_new_exchanges() {
         },
         Vec::new(),
         Vec::new(),
+        HashMap::new(),
     );
 
     app.add_message(JsonRpcMessage {
@@ -686,6 +787,10 @@ fn active_session_tracks_new_exchanges() {
         direction: MessageDirection::Request,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     });
 
     assert_eq!(app.session.unwrap().exchange_count, 1);
@@ -705,6 +810,7 @@ fn session_name_prompts_use_the_shared_input_buffer() {
         },
         Vec::new(),
         Vec::new(),
+        HashMap::new(),
     );
 
     app.start_naming_session();
@@ -736,6 +842,7 @@ fn activating_a_session_clears_the_previous_filter() {
         },
         Vec::new(),
         Vec::new(),
+        HashMap::new(),
     );
 
     assert!(app.filter_text.is_empty());
@@ -821,6 +928,10 @@ fn unpausing_with_pending_requests_keeps_them_visible() {
             direction: MessageDirection::Request,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         },
         modified_request: None,
         modified_headers: None,
@@ -834,3 +945,589 @@ fn unpausing_with_pending_requests_keeps_them_visible() {
     app.toggle_pause_mode();
     assert_eq!(app.app_mode, AppMode::Paused);
 }
+
+#[test]
+fn dap_responses_pair_with_requests_by_request_seq() {
+    let transport = TransportType::Stdio(Framing::Dap);
+    let mut app = App::new();
+    for body in [
+        serde_json::json!({"seq": 1, "type": "request", "command": "initialize", "arguments": {}}),
+        serde_json::json!({"seq": 2, "type": "request", "command": "launch", "arguments": {}}),
+        serde_json::json!({"seq": 1, "type": "event", "event": "initialized"}),
+        serde_json::json!({
+            "seq": 2,
+            "type": "response",
+            "request_seq": 1,
+            "success": true,
+            "command": "initialize",
+            "body": {"supportsConfigurationDoneRequest": true}
+        }),
+    ] {
+        record(&mut app, &body, transport, SystemTime::now());
+    }
+
+    assert_eq!(app.exchanges.len(), 3);
+    assert_eq!(app.exchanges[0].method.as_deref(), Some("initialize"));
+    assert_eq!(
+        app.exchanges[0].response.as_ref().unwrap().result,
+        Some(serde_json::json!({"supportsConfigurationDoneRequest": true}))
+    );
+    assert!(app.exchanges[1].response.is_none());
+    assert!(app.exchanges[2].is_notification());
+    assert_eq!(
+        message_body(app.exchanges[2].request.as_ref().unwrap()),
+        serde_json::json!({"seq": 1, "type": "event", "event": "initialized"})
+    );
+    assert_eq!(
+        message_body(app.exchanges[0].response.as_ref().unwrap()),
+        serde_json::json!({
+            "seq": 2,
+            "type": "response",
+            "request_seq": 1,
+            "success": true,
+            "command": "initialize",
+            "body": {"supportsConfigurationDoneRequest": true}
+        })
+    );
+}
+
+#[test]
+fn dap_targets_validate_dap_requests() {
+    let mut app = App::new();
+    app.proxy_config.target_url = "debug-adapter".to_string();
+    app.proxy_config.transport = TransportType::Stdio(Framing::Dap);
+    app.proxy_config.stdio = Some(StdioConfig {
+        command: vec!["debug-adapter".into()],
+        framing: Framing::Dap,
+        env: Vec::new(),
+    });
+
+    assert!(app
+        .prepare_new_request(r#"{"seq":1,"type":"request","command":"threads"}"#.to_string())
+        .is_ok());
+    assert_eq!(
+        app.prepare_new_request(r#"{"seq":1,"type":"request"}"#.to_string())
+            .unwrap_err(),
+        "Missing 'command' field"
+    );
+}
+
+#[test]
+fn subscription_events_fold_under_their_subscribe_row() {
+    let mut app = App::new();
+    for body in [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_subscribe", "params": ["newHeads"]}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0xa"}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "eth_subscription", "params": {"subscription": "0xa", "result": {}}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "eth_blockNumber"}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "eth_subscription", "params": {"subscription": "0xa", "result": {}}}),
+    ] {
+        record(&mut app, &body, TransportType::WebSocket, SystemTime::now());
+    }
+
+    assert_eq!(app.filtered_exchange_indices(), vec![0, 2]);
+    app.select_exchange(0);
+    app.select_next();
+    assert_eq!(app.selected_exchange, 2);
+
+    app.select_exchange(0);
+    app.toggle_selected_subscription();
+    assert_eq!(app.filtered_exchange_indices(), vec![0, 1, 2, 3]);
+
+    app.select_exchange(3);
+    app.toggle_selected_subscription();
+    assert_eq!(app.selected_exchange, 0);
+    assert_eq!(app.filtered_exchange_indices(), vec![0, 2]);
+
+    // A filter that hides the subscribe row shows its events on their own.
+    app.filter_text = "eth_subscription".to_string();
+    assert_eq!(app.filtered_exchange_indices(), vec![1, 3]);
+}
+
+#[test]
+fn stale_unanswered_and_late_exchanges_get_their_own_status() {
+    let start = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000);
+    let mut app = App::new();
+    for (seconds, body) in [
+        (
+            0,
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "slow"}),
+        ),
+        (
+            0,
+            serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "late"}),
+        ),
+        (
+            40,
+            serde_json::json!({"jsonrpc": "2.0", "id": 2, "result": true}),
+        ),
+        (
+            0,
+            serde_json::json!({"jsonrpc": "2.0", "id": 3, "method": "cancelled"}),
+        ),
+        (
+            1,
+            serde_json::json!({"jsonrpc": "2.0", "method": "$/cancelRequest", "params": {"id": 3}}),
+        ),
+        (
+            2,
+            serde_json::json!({"jsonrpc": "2.0", "id": 3, "result": null}),
+        ),
+        (
+            2,
+            serde_json::json!({"jsonrpc": "2.0", "id": 9, "result": true}),
+        ),
+        (
+            50,
+            serde_json::json!({"jsonrpc": "2.0", "id": 4, "method": "fresh"}),
+        ),
+    ] {
+        record(
+            &mut app,
+            &body,
+            TransportType::Http,
+            start + std::time::Duration::from_secs(seconds),
+        );
+    }
+
+    let context = StatusContext::new(
+        &app.exchanges,
+        Some(DEFAULT_REQUEST_TIMEOUT),
+        start + std::time::Duration::from_secs(60),
+    );
+    let statuses: Vec<_> = app
+        .exchanges
+        .iter()
+        .map(|exchange| (context.status(exchange), context.lateness(exchange)))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (ExchangeStatus::TimedOut, None),
+            (ExchangeStatus::Success, Some(Lateness::AfterTimeout)),
+            (ExchangeStatus::Success, Some(Lateness::AfterCancellation)),
+            (ExchangeStatus::Notification, None),
+            (ExchangeStatus::Orphan, None),
+            (ExchangeStatus::Pending, None),
+        ]
+    );
+    assert!(context.matches_filter(&app.exchanges[0], None, "status:timedOut"));
+    assert!(context.matches_filter(&app.exchanges[1], None, "status:late late"));
+    assert!(!context.matches_filter(&app.exchanges[5], None, "status:orphan"));
+}
+
+#[test]
+fn responses_pair_with_requests_from_their_own_connection() {
+    let connection = |id: &str, client: &str| Connection {
+        id: id.to_string(),
+        client: client.to_string(),
+        source: None,
+    };
+    let on = |connection: Connection, body: serde_json::Value| {
+        let mut message = incoming_json_rpc_messages(&body, TransportType::Http).remove(0);
+        message.connection = Some(connection);
+        message
+    };
+    let first = connection("a", "127.0.0.1:5001");
+    let second = connection("b", "127.0.0.1:5002");
+    let mut app = App::new();
+    for message in [
+        on(
+            first.clone(),
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "first"}),
+        ),
+        on(
+            second.clone(),
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "second"}),
+        ),
+        on(
+            first,
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "a"}),
+        ),
+        on(
+            second,
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "b"}),
+        ),
+    ] {
+        app.add_message(message);
+    }
+
+    let results: Vec<_> = app
+        .exchanges
+        .iter()
+        .map(|exchange| {
+            (
+                exchange.method.as_deref(),
+                exchange.client(),
+                exchange
+                    .response
+                    .as_ref()
+                    .and_then(|response| response.result.clone()),
+            )
+        })
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            (
+                Some("first"),
+                Some("127.0.0.1:5001"),
+                Some(serde_json::json!("a"))
+            ),
+            (
+                Some("second"),
+                Some("127.0.0.1:5002"),
+                Some(serde_json::json!("b"))
+            ),
+        ]
+    );
+
+    app.filter_text = "client:5002".to_string();
+    assert_eq!(app.filtered_exchange_indices(), vec![1]);
+}
+
+#[test]
+fn batch_calls_fold_under_their_first_call() {
+    let mut app = App::new();
+    for message in json_rpc_messages(
+        &serde_json::json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"},
+            {"jsonrpc": "2.0", "id": 2, "method": "eth_blockNumber"}
+        ]),
+        MessageDirection::Request,
+        TransportType::HttpBatch,
+        None,
+    )
+    .into_iter()
+    .chain(incoming_json_rpc_messages(
+        &serde_json::json!({"jsonrpc": "2.0", "id": 3, "method": "eth_gasPrice"}),
+        TransportType::Http,
+    )) {
+        app.add_message(message);
+    }
+
+    assert_eq!(app.filtered_exchange_indices(), vec![0, 2]);
+    app.select_exchange(0);
+    app.toggle_selected_group();
+    assert_eq!(app.filtered_exchange_indices(), vec![0, 1, 2]);
+
+    app.select_exchange(1);
+    app.toggle_selected_group();
+    assert_eq!(app.selected_exchange, 0);
+    assert_eq!(app.filtered_exchange_indices(), vec![0, 2]);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&app.batch_request_json().unwrap()).unwrap()[1]
+            ["method"],
+        "eth_blockNumber"
+    );
+
+    app.select_exchange(2);
+    app.show_batch();
+    assert_eq!(app.overlay, Overlay::None);
+    assert_eq!(app.notice.as_deref(), Some("No batch selected"));
+}
+
+#[test]
+fn diff_marks_survive_switching_sessions() {
+    let session = |id: &str| SessionSummary {
+        id: id.to_string(),
+        name: id.to_string(),
+        target: "http://node".to_string(),
+        created_at_ms: 1,
+        updated_at_ms: 1,
+        exchange_count: 0,
+    };
+    let request = |method: &str, params: serde_json::Value| JsonRpcMessage {
+        id: Some(serde_json::json!(1)),
+        method: Some(method.to_string()),
+        params: Some(params),
+        result: None,
+        error: None,
+        timestamp: std::time::SystemTime::now(),
+        direction: MessageDirection::Request,
+        transport: TransportType::Http,
+        headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
+    };
+    let mut app = App::new();
+    app.show_marked_diff();
+    assert_eq!(app.overlay, Overlay::None);
+
+    app.activate_session(session("first"), Vec::new(), Vec::new(), HashMap::new());
+    app.add_message(request("eth_getBalance", serde_json::json!(["0xa"])));
+    app.toggle_diff_mark();
+    assert!(app.is_diff_marked(0));
+
+    app.activate_session(session("second"), Vec::new(), Vec::new(), HashMap::new());
+    assert!(!app.is_diff_marked(0));
+    app.add_message(request("eth_getBalance", serde_json::json!(["0xb"])));
+    app.toggle_diff_mark();
+    app.show_marked_diff();
+
+    assert_eq!(app.overlay, Overlay::Diff);
+    let Some(jsonrpc_debugger::diff::Comparison::Exchanges {
+        left,
+        right,
+        differences,
+    }) = &app.comparison
+    else {
+        panic!("expected an exchange comparison");
+    };
+    assert_eq!((left.as_str(), right.as_str()), ("first #0", "second #0"));
+    assert_eq!(differences.len(), 1);
+    assert_eq!(differences[0].path, "params[0]");
+}
+
+#[test]
+fn queries_replace_the_panel_and_pin_as_list_columns() {
+    let message =
+        |direction, method: Option<&str>, result: Option<serde_json::Value>| JsonRpcMessage {
+            id: Some(serde_json::json!(1)),
+            method: method.map(str::to_string),
+            params: method.map(|_| serde_json::json!(["latest", false])),
+            result,
+            error: None,
+            timestamp: std::time::SystemTime::now(),
+            direction,
+            transport: TransportType::Http,
+            headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
+        };
+    let mut app = App::new();
+    app.add_message(message(
+        MessageDirection::Request,
+        Some("eth_getBlockByNumber"),
+        None,
+    ));
+    app.add_message(message(
+        MessageDirection::Response,
+        None,
+        Some(serde_json::json!({"number": "0x10", "hash": "0xab"})),
+    ));
+
+    app.set_focus(Focus::ResponseSection);
+    app.start_query();
+    assert_eq!(app.input_mode, InputMode::Querying);
+    assert_eq!(app.input_buffer, ".");
+    app.input_buffer = "result[".to_string();
+    app.apply_query();
+    assert_eq!(app.input_mode, InputMode::Querying);
+    assert!(app.notice.as_deref().unwrap().starts_with("Error:"));
+
+    app.input_buffer = "result.number".to_string();
+    app.apply_query();
+    assert_eq!(app.input_mode, InputMode::Normal);
+    let markdown = app.focused_markdown().unwrap();
+    assert!(markdown.contains("## Query `result.number`\n\n```json\n\"0x10\"\n```"));
+
+    app.toggle_query_column();
+    app.set_focus(Focus::RequestSection);
+    app.toggle_query_column();
+    assert_eq!(
+        app.notice.as_deref(),
+        Some("No query to pin; press . to write one")
+    );
+    app.set_focus(Focus::MessageList);
+    let table = app.focused_markdown().unwrap();
+    assert!(table
+        .lines()
+        .next()
+        .unwrap()
+        .ends_with("| Duration | result.number |"));
+    assert!(table.lines().nth(2).unwrap().ends_with("| 0x10 |"));
+
+    app.set_query(Focus::ResponseSection, None);
+    assert!(app.focused_markdown().unwrap().contains("result.number"));
+}
+
+#[test]
+fn column_layout_orders_the_list_and_its_markdown() {
+    use jsonrpc_debugger::columns::{Column, Layout, Sort};
+
+    let mut app = App::new();
+    for (id, method) in [(1, "eth_chainId"), (2, "eth_call"), (3, "eth_blockNumber")] {
+        app.add_message(JsonRpcMessage {
+            id: Some(serde_json::json!(id)),
+            method: Some(method.to_string()),
+            params: Some(serde_json::json!([])),
+            result: None,
+            error: None,
+            timestamp: std::time::SystemTime::now(),
+            direction: MessageDirection::Request,
+            transport: TransportType::Http,
+            headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
+        });
+    }
+
+    let mut layout = Layout::default();
+    layout.set_shown(Column::Transport, false);
+    layout.set_shown(Column::Client, false);
+    layout.set_shown(Column::FirstParam, true);
+    layout.sort = Some(Sort {
+        column: Column::Method,
+        descending: false,
+    });
+    app.set_columns(layout);
+    assert_eq!(app.filtered_exchange_indices(), [2, 1, 0]);
+
+    app.set_focus(Focus::MessageList);
+    let table = app.focused_markdown().unwrap();
+    let lines = table.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[0],
+        "| Status | Method | ID | Duration | First param |"
+    );
+    assert_eq!(lines[1], "| --- | --- | --- | --- | --- |");
+    assert_eq!(lines[2], "| Pending | eth_blockNumber | 3 | - | - |");
+
+    app.selected_column = Column::ALL
+        .iter()
+        .position(|column| *column == Column::Method)
+        .unwrap();
+    app.sort_by_picked_column();
+    assert_eq!(app.filtered_exchange_indices(), [0, 1, 2]);
+}
+
+#[test]
+fn bookmarks_filter_the_list_and_decorate_its_markdown() {
+    use jsonrpc_debugger::bookmark::Bookmark;
+
+    let mut app = App::new();
+    for (id, method) in [(1, "eth_chainId"), (2, "eth_call"), (3, "eth_blockNumber")] {
+        app.add_message(JsonRpcMessage {
+            id: Some(serde_json::json!(id)),
+            method: Some(method.to_string()),
+            params: None,
+            result: None,
+            error: None,
+            timestamp: std::time::SystemTime::now(),
+            direction: MessageDirection::Request,
+            transport: TransportType::Http,
+            headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
+        });
+    }
+    let mut bug = Bookmark::default();
+    bug.add_tags(["bug"]);
+    app.set_bookmark(1, bug);
+    app.set_bookmark(
+        2,
+        Bookmark {
+            starred: true,
+            ..Bookmark::default()
+        },
+    );
+
+    app.filter_text = "tag:bug".to_string();
+    assert_eq!(app.filtered_exchange_indices(), [1]);
+    app.filter_text = "is:starred eth_block".to_string();
+    assert_eq!(app.filtered_exchange_indices(), [2]);
+
+    app.filter_text.clear();
+    app.set_focus(Focus::MessageList);
+    let table = app.focused_markdown().unwrap();
+    assert!(table.contains("| eth_call #bug |"));
+    assert!(table.contains("| ★ eth_blockNumber |"));
+
+    app.set_bookmark(1, Bookmark::default());
+    assert!(app.bookmark(1).is_none());
+}
+
+#[test]
+fn timeout_checks_follow_requests_until_they_are_answered() {
+    let mut app = App::new();
+    let start = SystemTime::now() - std::time::Duration::from_secs(60);
+    for body in [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "slow"}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "ping"}),
+    ] {
+        record(&mut app, &body, TransportType::Http, start);
+    }
+
+    assert!(app.check_timeouts());
+    assert!(!app.check_timeouts());
+
+    let response = serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": true});
+    record(&mut app, &response, TransportType::Http, SystemTime::now());
+    assert!(app.check_timeouts());
+    assert!(!app.check_timeouts());
+}
+
+#[test]
+fn spliced_exchanges_are_checked_for_timeouts_again() {
+    let start = SystemTime::now() - std::time::Duration::from_secs(60);
+    let mut app = App::new();
+    for body in [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "fast"}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": true}),
+    ] {
+        record(&mut app, &body, TransportType::Http, start);
+    }
+    assert!(!app.check_timeouts());
+
+    // A refreshed copy of the same row that is still waiting for its response.
+    let mut refreshed = App::new();
+    let request = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "slow"});
+    record(&mut refreshed, &request, TransportType::Http, start);
+    app.splice_exchanges(0, refreshed.exchanges);
+
+    assert_eq!(app.exchanges[0].method.as_deref(), Some("slow"));
+    assert!(app.check_timeouts());
+    assert!(!app.check_timeouts());
+}
+
+#[test]
+fn held_requests_are_shown_redacted_but_kept_intact() {
+    let mut app = App::new();
+    *app.rules.lock().unwrap() = jsonrpc_debugger::profile::Config::parse(
+        "[profiles.keys]\nredact = [\"$..privateKey\"]\nredact-headers = [\"Authorization\"]\n",
+    )
+    .unwrap()
+    .profiles["keys"]
+        .rules()
+        .unwrap();
+    let headers = HashMap::from([("authorization".to_string(), "Bearer key".to_string())]);
+    let (decision_sender, _decision_receiver) = tokio::sync::oneshot::channel();
+    app.hold_pending(PendingRequest {
+        id: "pending".to_string(),
+        original_request: json_rpc_messages_by_shape(
+            &serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "sign", "params": [{"privateKey": "0x1"}]}),
+            TransportType::Http,
+            Some(&headers),
+        )
+        .remove(0),
+        modified_request: None,
+        modified_headers: None,
+        decision_sender,
+    });
+
+    let pending = &app.pending_requests[0];
+    let shown = app.shown_pending_request(pending);
+    assert_eq!(
+        shown.params,
+        Some(serde_json::json!([{"privateKey": "[redacted]"}]))
+    );
+    assert_eq!(
+        shown.headers.as_ref().unwrap()["authorization"],
+        "[redacted]"
+    );
+    let shown = serde_json::to_string(&jsonrpc_debugger::control::pending(&app)).unwrap();
+    assert!(!shown.contains("0x1") && !shown.contains("Bearer key"));
+    assert_eq!(
+        pending.original_request.headers.as_ref().unwrap()["authorization"],
+        "Bearer key"
+    );
+}
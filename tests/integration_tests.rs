@@ -39,6 +39,10 @@ fn test_full_exchange_flow() {
             h.insert("Content-Type".to_string(), "application/json".to_string());
             h
         }),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     app.add_message(request);
@@ -59,6 +63,10 @@ fn test_full_exchange_flow() {
             h.insert("Content-Length".to_string(), "25".to_string());
             h
         }),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     app.add_message(response);
@@ -111,6 +119,10 @@ fn test_websocket_vs_http_exchanges() {
             h.insert("Authorization".to_string(), "Bearer token123".to_string());
             h
         }),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     // Add WebSocket request
@@ -124,6 +136,10 @@ fn test_websocket_vs_http_exchanges() {
         direction: MessageDirection::Request,
         transport: TransportType::WebSocket,
         headers: None, // WebSocket messages shouldn't have HTTP headers
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     app.add_message(http_request);
@@ -159,6 +175,10 @@ fn test_error_handling() {
         direction: MessageDirection::Request,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     app.add_message(request);
@@ -178,6 +198,10 @@ fn test_error_handling() {
         direction: MessageDirection::Response,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     app.add_message(error_response);
@@ -226,6 +250,10 @@ fn test_proxy_state_management() {
         direction: MessageDirection::Request,
         transport: TransportType::Http,
         headers: None,
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     app.add_message(msg);
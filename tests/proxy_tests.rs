@@ -32,10 +32,12 @@ async fn stdio_target_round_trips_through_the_http_frontend() {
     let port = listener.local_addr().unwrap().port();
     drop(listener);
     let (message_sender, mut message_receiver) = mpsc::unbounded_channel();
+    // Answers with the id the proxy wrote, which need not be the client's.
     let script = concat!(
         "while IFS= read -r line; do ",
+        "id=$(printf '%s' \"$line\" | sed 's/.*\"id\":\\([0-9]*\\).*/\\1/'); ",
         "printf '%s\\n' '{\"jsonrpc\":\"2.0\",\"method\":\"example/changed\"}'; ",
-        "printf '%s\\n' '{\"jsonrpc\":\"2.0\",\"id\":7,\"result\":\"ok\"}'; ",
+        "printf '{\"jsonrpc\":\"2.0\",\"id\":%s,\"result\":\"ok\"}\\n' \"$id\"; ",
         "done"
     );
     let config = ProxyConfig {
@@ -49,8 +51,10 @@ async fn stdio_target_round_trips_through_the_http_frontend() {
                 OsString::from(script),
             ],
             framing: Framing::JsonLines,
+            env: Vec::new(),
         }),
         transparent: false,
+        listeners: Vec::new(),
     };
     let proxy = ProxyServer::from_config(&config, message_sender).unwrap();
     let server = tokio::spawn(proxy.bind().unwrap());
@@ -76,6 +80,7 @@ async fn stdio_target_round_trips_through_the_http_frontend() {
     assert_eq!(request.method.as_deref(), Some("example/run"));
     assert_eq!(notification.method.as_deref(), Some("example/changed"));
     assert_eq!(response.result, Some(serde_json::json!("ok")));
+    assert_eq!(response.id, Some(serde_json::json!(7)));
     assert!(matches!(
         response.transport,
         TransportType::Stdio(Framing::JsonLines)
@@ -203,6 +208,390 @@ async fn test_proxy_handles_different_paths() {
     mock_server.abort();
 }
 
+#[tokio::test]
+async fn proxied_responses_record_where_the_time_went() {
+    use jsonrpc_debugger::proxy::ProxyState;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use warp::Filter;
+
+    let upstream =
+        warp::post()
+            .and(warp::body::json())
+            .and_then(|body: serde_json::Value| async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok::<_, warp::Rejection>(warp::reply::json(&serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "result": "0x1"
+                })))
+            });
+    let (upstream_address, upstream_server) =
+        warp::serve(upstream).bind_ephemeral(([127, 0, 0, 1], 0));
+    let upstream_server = tokio::spawn(upstream_server);
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let (message_sender, mut message_receiver) = mpsc::unbounded_channel();
+    let (pending_sender, mut pending_receiver) = mpsc::unbounded_channel();
+    let (deliveries, mut delivery_receiver) = mpsc::unbounded_channel();
+    let app_mode = Arc::new(Mutex::new(AppMode::Normal));
+    let proxy = ProxyServer::new(
+        port,
+        format!("http://localhost:{}", upstream_address.port()),
+        message_sender,
+    )
+    .with_state(ProxyState {
+        app_mode: app_mode.clone(),
+        pending_sender,
+        rules: Arc::default(),
+        deliveries,
+    });
+    let proxy_server = tokio::spawn(proxy.bind().unwrap());
+
+    let client = reqwest::Client::new();
+    let call = |id: u64| {
+        client
+            .post(format!("http://127.0.0.1:{port}"))
+            .json(&serde_json::json!({"jsonrpc": "2.0", "id": id, "method": "eth_chainId"}))
+            .send()
+    };
+
+    call(1).await.unwrap().text().await.unwrap();
+    let request = message_receiver.recv().await.unwrap();
+    assert!(request.timing.is_none());
+    let timing = message_receiver.recv().await.unwrap().timing.unwrap();
+    assert_eq!(timing.queued, None);
+    assert!(
+        timing.dns.is_some(),
+        "the first call opens a connection to localhost"
+    );
+    assert_eq!(
+        (timing.connect, timing.tls),
+        (None, None),
+        "connection setup is counted in the first byte"
+    );
+    assert!(timing.first_byte.unwrap() >= Duration::from_millis(50));
+    assert!(timing.download.is_some());
+    assert_eq!(
+        timing.delivery, None,
+        "the response is logged before delivery"
+    );
+    let delivery = delivery_receiver
+        .recv()
+        .await
+        .expect("a delivery follows the response");
+    assert_eq!(
+        Some(&delivery.connection),
+        request.connection.as_ref().map(|c| &c.id)
+    );
+    assert!(delivery.delivery < Duration::from_secs(1));
+
+    *app_mode.lock().unwrap() = AppMode::Paused;
+    let paused = tokio::spawn(call(2));
+    let pending = pending_receiver.recv().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    pending
+        .decision_sender
+        .send(ProxyDecision::Allow(None, None))
+        .unwrap();
+    paused.await.unwrap().unwrap();
+    message_receiver.recv().await.unwrap();
+    let timing = message_receiver.recv().await.unwrap().timing.unwrap();
+    assert!(timing.queued.unwrap() >= Duration::from_millis(30));
+    assert_eq!(
+        timing.dns, None,
+        "the second call reuses the pooled connection"
+    );
+    assert!(timing.first_byte.is_some());
+
+    proxy_server.abort();
+    upstream_server.abort();
+}
+
+#[tokio::test]
+async fn profile_rules_rewrite_requests_and_hold_breakpoints() {
+    use jsonrpc_debugger::profile::Config;
+    use jsonrpc_debugger::proxy::ProxyState;
+    use std::sync::{Arc, Mutex};
+    use warp::Filter;
+
+    // Answers with the params it received.
+    let upstream = warp::post()
+        .and(warp::body::json())
+        .map(|body: serde_json::Value| {
+            warp::reply::json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "result": body["params"],
+            }))
+        });
+    let (upstream_address, upstream_server) =
+        warp::serve(upstream).bind_ephemeral(([127, 0, 0, 1], 0));
+    let upstream_server = tokio::spawn(upstream_server);
+
+    let config = Config::parse(
+        r#"
+[profiles.local]
+breakpoints = ["eth_sendRawTransaction"]
+
+[[profiles.local.rewrite]]
+match = "eth_call"
+set = { "params[1]" = "latest" }
+"#,
+    )
+    .unwrap();
+    let rules = config.profiles["local"].rules().unwrap();
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let (message_sender, mut message_receiver) = mpsc::unbounded_channel();
+    let (pending_sender, mut pending_receiver) = mpsc::unbounded_channel();
+    let proxy = ProxyServer::new(
+        port,
+        format!("http://localhost:{}", upstream_address.port()),
+        message_sender,
+    )
+    .with_state(ProxyState {
+        app_mode: Arc::new(Mutex::new(AppMode::Normal)),
+        pending_sender,
+        rules: Arc::new(Mutex::new(rules)),
+        deliveries: mpsc::unbounded_channel().0,
+    });
+    let proxy_server = tokio::spawn(proxy.bind().unwrap());
+
+    let client = reqwest::Client::new();
+    let call = |method: &str, params: serde_json::Value| {
+        client
+            .post(format!("http://127.0.0.1:{port}"))
+            .json(
+                &serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}),
+            )
+            .send()
+    };
+
+    let response: serde_json::Value = call("eth_call", serde_json::json!([{"to": "0xa"}]))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        response["result"],
+        serde_json::json!([{"to": "0xa"}, "latest"])
+    );
+    let logged = message_receiver.recv().await.unwrap();
+    assert_eq!(
+        logged.params,
+        Some(serde_json::json!([{"to": "0xa"}, "latest"]))
+    );
+    message_receiver.recv().await.unwrap();
+
+    let held = tokio::spawn(call("eth_sendRawTransaction", serde_json::json!(["0x00"])));
+    let pending = pending_receiver.recv().await.unwrap();
+    assert_eq!(
+        pending.original_request.method.as_deref(),
+        Some("eth_sendRawTransaction")
+    );
+    pending.decision_sender.send(ProxyDecision::Block).unwrap();
+    let blocked: serde_json::Value = held.await.unwrap().unwrap().json().await.unwrap();
+    assert_eq!(blocked["error"]["message"], "Request blocked by user");
+
+    proxy_server.abort();
+    upstream_server.abort();
+}
+
+#[tokio::test]
+async fn edited_held_requests_forward_redacted_headers_unchanged() {
+    use jsonrpc_debugger::app::App;
+    use jsonrpc_debugger::profile::Config;
+    use jsonrpc_debugger::proxy::ProxyState;
+    use std::sync::{Arc, Mutex};
+    use warp::Filter;
+
+    // Answers with the authorization header and params it received.
+    let upstream = warp::post()
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .map(|authorization: String, body: serde_json::Value| {
+            warp::reply::json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "result": {"authorization": authorization, "params": body["params"]},
+            }))
+        });
+    let (upstream_address, upstream_server) =
+        warp::serve(upstream).bind_ephemeral(([127, 0, 0, 1], 0));
+    let upstream_server = tokio::spawn(upstream_server);
+
+    let config = Config::parse(
+        r#"
+[profiles.local]
+breakpoints = ["eth_sendRawTransaction"]
+redact-headers = ["Authorization"]
+"#,
+    )
+    .unwrap();
+    let rules = Arc::new(Mutex::new(config.profiles["local"].rules().unwrap()));
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let (message_sender, _message_receiver) = mpsc::unbounded_channel();
+    let (pending_sender, mut pending_receiver) = mpsc::unbounded_channel();
+    let proxy = ProxyServer::new(
+        port,
+        format!("http://localhost:{}", upstream_address.port()),
+        message_sender,
+    )
+    .with_state(ProxyState {
+        app_mode: Arc::new(Mutex::new(AppMode::Normal)),
+        pending_sender,
+        rules: rules.clone(),
+        deliveries: mpsc::unbounded_channel().0,
+    });
+    let proxy_server = tokio::spawn(proxy.bind().unwrap());
+
+    let held = tokio::spawn(
+        reqwest::Client::new()
+            .post(format!("http://127.0.0.1:{port}"))
+            .header("authorization", "Bearer secret")
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "eth_sendRawTransaction",
+                "params": ["0x00"],
+            }))
+            .send(),
+    );
+    let mut app = App::new();
+    app.rules = rules;
+    app.hold_pending(pending_receiver.recv().await.unwrap());
+
+    let headers = app.get_pending_request_headers().unwrap();
+    app.apply_edited_headers(format!("{headers}\nx-edited: yes"))
+        .unwrap();
+    let body = app
+        .get_pending_request_json()
+        .unwrap()
+        .replace("0x00", "0x01");
+    app.apply_edited_json(body).unwrap();
+    let shown = serde_json::to_string(&jsonrpc_debugger::control::pending(&app)).unwrap();
+    assert!(!shown.contains("Bearer secret"));
+    app.allow_selected_request();
+
+    let response: serde_json::Value = held.await.unwrap().unwrap().json().await.unwrap();
+    assert_eq!(
+        response["result"],
+        serde_json::json!({"authorization": "Bearer secret", "params": ["0x01"]})
+    );
+
+    proxy_server.abort();
+    upstream_server.abort();
+}
+
+#[tokio::test]
+async fn listeners_record_their_name_into_one_session() {
+    use warp::Filter;
+
+    // Each upstream answers with its own name.
+    let upstream = |name: &'static str| {
+        let route = warp::post()
+            .and(warp::body::json())
+            .map(move |body: serde_json::Value| {
+                warp::reply::json(&serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "result": name,
+                }))
+            });
+        warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0))
+    };
+    let (l1_address, l1_server) = upstream("l1");
+    let (l2_address, l2_server) = upstream("l2");
+    let upstream_servers = [tokio::spawn(l1_server), tokio::spawn(l2_server)];
+
+    let free_port = || {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let (main_port, l2_port) = (free_port(), free_port());
+    let config = ProxyConfig {
+        listen_port: main_port,
+        target_url: format!("http://localhost:{}", l1_address.port()),
+        transport: TransportType::Http,
+        stdio: None,
+        transparent: false,
+        listeners: vec![Listener {
+            name: "l2".to_string(),
+            listen_port: l2_port,
+            target_url: format!("http://localhost:{}", l2_address.port()),
+            stdio: None,
+        }],
+    };
+    let (message_sender, message_receiver) = mpsc::unbounded_channel();
+    let main = ProxyServer::from_config(&config, message_sender.clone()).unwrap();
+    let l2 = ProxyServer::from_listener(&config.listeners[0], message_sender).unwrap();
+    let proxy_servers = [
+        tokio::spawn(main.bind().unwrap()),
+        tokio::spawn(l2.bind().unwrap()),
+    ];
+
+    let client = reqwest::Client::new();
+    for port in [main_port, l2_port] {
+        let response: serde_json::Value = client
+            .post(format!("http://127.0.0.1:{port}"))
+            .json(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let expected = if port == main_port { "l1" } else { "l2" };
+        assert_eq!(response["result"], expected);
+    }
+
+    let mut app = App::new_with_receiver(message_receiver);
+    while app.exchanges.len() < 2
+        || app
+            .exchanges
+            .iter()
+            .any(|exchange| exchange.response.is_none())
+    {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        app.check_for_new_messages();
+    }
+    let sources = app
+        .exchanges
+        .iter()
+        .map(|exchange| {
+            (
+                exchange.source(),
+                exchange
+                    .response
+                    .as_ref()
+                    .and_then(|response| response.result.clone()),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sources,
+        [
+            (Some("main"), Some(serde_json::json!("l1"))),
+            (Some("l2"), Some(serde_json::json!("l2"))),
+        ]
+    );
+    app.filter_text = "source:L2 eth_".to_string();
+    assert_eq!(app.filtered_exchange_indices(), vec![1]);
+
+    for server in proxy_servers.into_iter().chain(upstream_servers) {
+        server.abort();
+    }
+}
+
 #[test]
 fn test_message_channel_integration() {
     let (sender, receiver) = mpsc::unbounded_channel();
@@ -222,6 +611,10 @@ fn test_message_channel_integration() {
             h.insert("Content-Type".to_string(), "application/json".to_string());
             h
         }),
+        connection: None,
+        batch: None,
+        timing: None,
+        seq: None,
     };
 
     sender.send(test_message.clone()).unwrap();
@@ -277,6 +670,10 @@ fn test_multiple_message_handling() {
             direction: MessageDirection::Request,
             transport: TransportType::Http,
             headers: None,
+            connection: None,
+            batch: None,
+            timing: None,
+            seq: None,
         };
         sender.send(message).unwrap();
     }
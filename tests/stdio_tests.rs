@@ -1,5 +1,5 @@
 use jsonrpc_debugger::{
-    app::TransportType,
+    app::{App, Connection, TransportType},
     stdio::{relay, Framer, Framing, StreamTransport},
 };
 use serde_json::json;
@@ -81,8 +81,16 @@ async fn stream_transport_correlates_responses_and_records_notifications() {
         server_writer.write_all(&response).await.unwrap();
     });
 
+    let connection = Connection {
+        id: "worker-1".to_string(),
+        client: "127.0.0.1:4000".to_string(),
+        source: None,
+    };
     let response = transport
-        .send(json!({"jsonrpc": "2.0", "id": 7, "method": "example/run"}))
+        .send(
+            json!({"jsonrpc": "2.0", "id": 7, "method": "example/run"}),
+            Some(connection.clone()),
+        )
         .await
         .unwrap();
     assert_eq!(response["result"], "ok");
@@ -90,8 +98,10 @@ async fn stream_transport_correlates_responses_and_records_notifications() {
     let notification = message_receiver.recv().await.unwrap();
     assert_eq!(notification.method.as_deref(), Some("example/changed"));
     assert!(notification.id.is_none());
+    assert_eq!(notification.connection, None);
     let response = message_receiver.recv().await.unwrap();
     assert_eq!(response.id, Some(json!(7)));
+    assert_eq!(response.connection, Some(connection));
 }
 
 #[tokio::test]
@@ -109,7 +119,10 @@ async fn stream_transport_writes_client_responses_without_waiting() {
     );
 
     let response = json!({"jsonrpc": "2.0", "id": 9, "result": {}});
-    assert_eq!(transport.send(response.clone()).await.unwrap(), json!(null));
+    assert_eq!(
+        transport.send(response.clone(), None).await.unwrap(),
+        json!(null)
+    );
 
     let mut bytes = [0; 1024];
     let count = server_reader.read(&mut bytes).await.unwrap();
@@ -159,16 +172,147 @@ async fn content_length_stream_supports_batch_responses() {
     });
 
     let response = transport
-        .send(json!([
-            {"jsonrpc": "2.0", "id": 1, "method": "example/first"},
-            {"jsonrpc": "2.0", "id": 2, "method": "example/second"}
-        ]))
+        .send(
+            json!([
+                {"jsonrpc": "2.0", "id": 1, "method": "example/first"},
+                {"jsonrpc": "2.0", "id": 2, "method": "example/second"}
+            ]),
+            None,
+        )
         .await
         .unwrap();
     assert_eq!(response[0]["id"], 2);
     assert_eq!(response[1]["id"], 1);
 }
 
+#[tokio::test]
+async fn stream_transport_lets_connections_reuse_request_ids() {
+    let (client_stream, server_stream) = tokio::io::duplex(4096);
+    let (client_reader, client_writer) = split(client_stream);
+    let (mut server_reader, mut server_writer) = split(server_stream);
+    let (message_sender, mut message_receiver) = mpsc::unbounded_channel();
+    let transport = StreamTransport::new(
+        client_reader,
+        client_writer,
+        Framing::JsonLines,
+        TransportType::Stdio(Framing::JsonLines),
+        message_sender,
+    );
+
+    // Answers both requests once they arrive, last first, echoing their params.
+    tokio::spawn(async move {
+        let mut decoder = Framer::new(Framing::JsonLines);
+        let encoder = Framer::new(Framing::JsonLines);
+        let mut bytes = [0; 1024];
+        let mut requests = Vec::new();
+        while requests.len() < 2 {
+            let count = server_reader.read(&mut bytes).await.unwrap();
+            requests.extend(decoder.decode(&bytes[..count]).unwrap());
+        }
+        assert_ne!(requests[0]["id"], requests[1]["id"]);
+        for request in requests.iter().rev() {
+            let response =
+                json!({"jsonrpc": "2.0", "id": request["id"], "result": request["params"]});
+            server_writer
+                .write_all(&encoder.encode(&response).unwrap())
+                .await
+                .unwrap();
+        }
+    });
+
+    let call = |worker: &str| {
+        let connection = Connection {
+            id: worker.to_string(),
+            client: worker.to_string(),
+            source: None,
+        };
+        transport.send(
+            json!({"jsonrpc": "2.0", "id": 1, "method": "example/run", "params": worker}),
+            Some(connection),
+        )
+    };
+    let (first, second) = tokio::join!(call("a"), call("b"));
+
+    assert_eq!(
+        first.unwrap(),
+        json!({"jsonrpc": "2.0", "id": 1, "result": "a"})
+    );
+    assert_eq!(
+        second.unwrap(),
+        json!({"jsonrpc": "2.0", "id": 1, "result": "b"})
+    );
+    for _ in 0..2 {
+        let response = message_receiver.recv().await.unwrap();
+        assert_eq!(response.id, Some(json!(1)));
+        assert_eq!(
+            response.connection.unwrap().id,
+            response.result.unwrap().as_str().unwrap()
+        );
+    }
+}
+
+#[tokio::test]
+async fn stream_transport_keeps_ids_and_routes_cancellations_of_renamed_requests() {
+    let (client_stream, server_stream) = tokio::io::duplex(4096);
+    let (client_reader, client_writer) = split(client_stream);
+    let (mut server_reader, mut server_writer) = split(server_stream);
+    let (message_sender, _message_receiver) = mpsc::unbounded_channel();
+    let transport = StreamTransport::new(
+        client_reader,
+        client_writer,
+        Framing::JsonLines,
+        TransportType::Stdio(Framing::JsonLines),
+        message_sender,
+    );
+    let connection = |worker: &str| {
+        Some(Connection {
+            id: worker.to_string(),
+            client: worker.to_string(),
+            source: None,
+        })
+    };
+
+    let first = transport.send(
+        json!({"jsonrpc": "2.0", "id": 1, "method": "example/run"}),
+        connection("a"),
+    );
+    let second = transport.send(
+        json!({"jsonrpc": "2.0", "id": 1, "method": "example/run"}),
+        connection("b"),
+    );
+    let cancel = transport.send(
+        json!({"jsonrpc": "2.0", "method": "$/cancelRequest", "params": {"id": 1}}),
+        connection("b"),
+    );
+    let server = async {
+        let mut decoder = Framer::new(Framing::JsonLines);
+        let mut bytes = [0; 1024];
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            let count = server_reader.read(&mut bytes).await.unwrap();
+            received.extend(decoder.decode(&bytes[..count]).unwrap());
+        }
+        let encoder = Framer::new(Framing::JsonLines);
+        for request in &received[..2] {
+            let response = json!({"jsonrpc": "2.0", "id": request["id"], "result": null});
+            server_writer
+                .write_all(&encoder.encode(&response).unwrap())
+                .await
+                .unwrap();
+        }
+        received
+    };
+    let (first, second, cancel, received) = tokio::join!(first, second, cancel, server);
+
+    // The first request keeps its id; only the one that collides goes out under another.
+    assert_eq!(received[0]["id"], 1);
+    assert_ne!(received[1]["id"], 1);
+    assert_eq!(received[2]["params"]["id"], received[1]["id"]);
+    assert_eq!(first.unwrap()["id"], 1);
+    assert_eq!(second.unwrap()["id"], 1);
+    assert_eq!(cancel.unwrap(), serde_json::Value::Null);
+}
+
 #[tokio::test]
 async fn transparent_json_lines_relay_preserves_bytes_both_ways() {
     let request = b"  {\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"example/run\"}  \r\n";
@@ -204,6 +348,119 @@ async fn transparent_content_length_relay_preserves_batches() {
     assert_eq!(messages[3].id, Some(json!(1)));
 }
 
+#[tokio::test]
+async fn transparent_dap_relay_pairs_by_request_seq() {
+    let encoder = Framer::new(Framing::Dap);
+    let request = encoder
+        .encode(&json!({
+            "seq": 3,
+            "type": "request",
+            "command": "setBreakpoints",
+            "arguments": {"lines": [12]}
+        }))
+        .unwrap();
+    let response = [
+        encoder
+            .encode(&json!({
+                "seq": 7,
+                "type": "event",
+                "event": "stopped",
+                "body": {"reason": "breakpoint"}
+            }))
+            .unwrap(),
+        encoder
+            .encode(&json!({
+                "seq": 8,
+                "type": "response",
+                "request_seq": 3,
+                "success": false,
+                "command": "setBreakpoints",
+                "message": "no source"
+            }))
+            .unwrap(),
+    ]
+    .concat();
+    let messages = transparent_round_trip(Framing::Dap, &request, &response).await;
+
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0].id, Some(json!(3)));
+    assert_eq!(messages[0].method.as_deref(), Some("setBreakpoints"));
+    assert_eq!(messages[0].params, Some(json!({"lines": [12]})));
+    assert_eq!(messages[1].id, None);
+    assert_eq!(messages[1].method.as_deref(), Some("stopped"));
+    assert_eq!(messages[2].id, Some(json!(3)));
+    assert_eq!(messages[2].error, Some(json!({"message": "no source"})));
+}
+
+#[tokio::test]
+async fn transparent_dap_relay_keeps_reverse_requests_apart() {
+    let (client, relay_client) = tokio::io::duplex(4096);
+    let (server, relay_server) = tokio::io::duplex(4096);
+    let (relay_client_reader, relay_client_writer) = split(relay_client);
+    let (relay_server_reader, relay_server_writer) = split(relay_server);
+    let (mut client_reader, mut client_writer) = split(client);
+    let (mut server_reader, mut server_writer) = split(server);
+    let (message_sender, mut message_receiver) = mpsc::unbounded_channel();
+    let relay = tokio::spawn(relay(
+        relay_client_reader,
+        relay_client_writer,
+        relay_server_reader,
+        relay_server_writer,
+        Framing::Dap,
+        message_sender,
+    ));
+
+    // The client and the adapter each number their messages from 1, so the adapter's
+    // `runInTerminal` request shares its seq with the client's `launch`.
+    let encoder = Framer::new(Framing::Dap);
+    let frames = [
+        (
+            true,
+            json!({"seq": 1, "type": "request", "command": "launch"}),
+        ),
+        (
+            false,
+            json!({"seq": 1, "type": "request", "command": "runInTerminal"}),
+        ),
+        (
+            true,
+            json!({"seq": 2, "type": "response", "request_seq": 1, "success": true, "command": "runInTerminal"}),
+        ),
+        (
+            false,
+            json!({"seq": 2, "type": "response", "request_seq": 1, "success": true, "command": "launch"}),
+        ),
+    ];
+    for (from_client, frame) in frames {
+        let frame = encoder.encode(&frame).unwrap();
+        let mut forwarded = vec![0; frame.len()];
+        if from_client {
+            client_writer.write_all(&frame).await.unwrap();
+            server_reader.read_exact(&mut forwarded).await.unwrap();
+        } else {
+            server_writer.write_all(&frame).await.unwrap();
+            client_reader.read_exact(&mut forwarded).await.unwrap();
+        }
+        assert_eq!(forwarded, frame);
+    }
+    server_writer.shutdown().await.unwrap();
+    client_writer.shutdown().await.unwrap();
+    relay.await.unwrap().unwrap();
+
+    let mut app = App::new();
+    while let Ok(message) = message_receiver.try_recv() {
+        app.add_message(message);
+    }
+    assert_eq!(app.exchanges.len(), 2);
+    for exchange in &app.exchanges {
+        let response = exchange
+            .response
+            .as_ref()
+            .expect("each request is answered");
+        assert_eq!(response.method, exchange.method);
+    }
+}
+
 #[tokio::test]
 async fn transparent_relay_forwards_bytes_it_cannot_decode() {
     let messages = transparent_round_trip(
@@ -1,6 +1,9 @@
 #[cfg(unix)]
 mod unix {
-    use jsonrpc_debugger::{app::App, attach::ControlClient};
+    use jsonrpc_debugger::{
+        app::App,
+        attach::{self, ControlClient},
+    };
     use serde_json::{json, Value};
     use std::{process::Stdio, time::Duration};
     use tokio::{
@@ -16,9 +19,11 @@ mod unix {
         drop(listener);
         let config_dir =
             std::env::temp_dir().join(format!("jsonrpc-debugger-wrap-test-{}", Uuid::new_v4()));
+        // Answers each request with its own id.
         let script = concat!(
             "while IFS= read -r line; do ",
-            "printf '%s\\n' '{\"jsonrpc\":\"2.0\",\"id\":\"wrap-1\",\"result\":\"ok\"}'; ",
+            "id=${line#*\\\"id\\\":\\\"}; id=${id%%\\\"*}; ",
+            "printf '{\"jsonrpc\":\"2.0\",\"id\":\"%s\",\"result\":\"ok\"}\\n' \"$id\"; ",
             "done"
         );
         let mut wrapper = Command::new(env!("CARGO_BIN_EXE_jsonrpc-debugger"))
@@ -80,6 +85,44 @@ mod unix {
         assert_eq!(attached.exchanges.len(), 1);
         assert_eq!(attached.exchanges[0].method.as_deref(), Some("example/run"));
 
+        stdin
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":\"wrap-2\",\"method\":\"example/run\"}\n")
+            .await
+            .unwrap();
+        response.clear();
+        tokio::time::timeout(Duration::from_secs(2), stdout.read_line(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        let state = client.state().await.unwrap();
+        let from = attach::refresh_from(&attached, &state);
+        assert_eq!(from, 1, "the settled exchange is not fetched again");
+        let mut session = client.session_from(from).await.unwrap();
+        assert!(session.exchanges.len() <= 1);
+        for _ in 0..100 {
+            if session
+                .exchanges
+                .first()
+                .is_some_and(|exchange| exchange.response.is_some())
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            session = client.session_from(from).await.unwrap();
+        }
+        attach::Snapshot {
+            state,
+            session,
+            from,
+        }
+        .apply(&mut attached)
+        .unwrap();
+        assert_eq!(attached.exchanges.len(), 2);
+        assert_eq!(
+            attached.exchanges[1].id,
+            Some(Value::String("wrap-2".to_string()))
+        );
+
         stdin.shutdown().await.unwrap();
         drop(stdin);
         let status = tokio::time::timeout(Duration::from_secs(2), wrapper.wait())